@@ -0,0 +1 @@
+-- roles は users から参照されており、削除するとユーザーも連鎖削除されるためロールバックでは何もしない
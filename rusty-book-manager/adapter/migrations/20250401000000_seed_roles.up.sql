@@ -0,0 +1,6 @@
+-- ロールは kernel::model::role::Role と対応するマスタデータなのでマイグレーションで投入する
+INSERT INTO
+    roles (name)
+VALUES
+    ('Admin'),
+    ('User') ON CONFLICT DO NOTHING;
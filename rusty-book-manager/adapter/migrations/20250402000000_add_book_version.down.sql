@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS books_version_trigger ON books;
+DROP FUNCTION IF EXISTS increment_version;
+ALTER TABLE books DROP COLUMN IF EXISTS version;
@@ -0,0 +1,14 @@
+-- 楽観的排他制御と ETag のためのバージョン。更新のたびにトリガーで 1 ずつ増やす
+ALTER TABLE books ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
+
+CREATE
+OR REPLACE FUNCTION increment_version() RETURNS trigger AS '
+BEGIN
+    NEW.version := OLD.version + 1;
+    RETURN NEW;
+END;
+' LANGUAGE plpgsql;
+
+CREATE TRIGGER books_version_trigger BEFORE
+UPDATE
+    ON books FOR EACH ROW EXECUTE PROCEDURE increment_version();
@@ -0,0 +1,2 @@
+-- ISBN から取得した書影の URL（取得できなかった場合は NULL）
+ALTER TABLE books ADD COLUMN IF NOT EXISTS cover_url TEXT;
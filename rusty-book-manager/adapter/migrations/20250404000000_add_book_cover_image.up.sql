@@ -0,0 +1,2 @@
+-- アップロードされた表紙画像の BlobStore 上のキー（サムネイルのキーはここから導出する）
+ALTER TABLE books ADD COLUMN IF NOT EXISTS cover_image TEXT;
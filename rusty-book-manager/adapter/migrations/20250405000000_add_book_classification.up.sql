@@ -0,0 +1,71 @@
+-- 利用者が自由に付けるタグ。名前は正規化（小文字・空白をハイフンに）して保存する
+CREATE TABLE IF NOT EXISTS tags (
+    tag_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE TABLE IF NOT EXISTS book_tags (
+    book_id UUID NOT NULL,
+    tag_id UUID NOT NULL,
+    PRIMARY KEY (book_id, tag_id),
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+        FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS book_tags_tag_id_idx ON book_tags(tag_id);
+
+-- 管理者が管理するジャンルの分類。parent_id で階層を表す
+CREATE TABLE IF NOT EXISTS genres (
+    genre_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    parent_id UUID,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    FOREIGN KEY (parent_id) REFERENCES genres(genre_id) ON
+    UPDATE
+        CASCADE ON DELETE RESTRICT
+);
+
+-- 使用中のジャンルは削除できない
+CREATE TABLE IF NOT EXISTS book_genres (
+    book_id UUID NOT NULL,
+    genre_id UUID NOT NULL,
+    PRIMARY KEY (book_id, genre_id),
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+        FOREIGN KEY (genre_id) REFERENCES genres(genre_id) ON
+    UPDATE
+        CASCADE ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS book_genres_genre_id_idx ON book_genres(genre_id);
+
+-- 利用者ごとの本棚（「読みたい」など）
+CREATE TABLE IF NOT EXISTS shelves (
+    shelf_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    UNIQUE (user_id, name),
+    FOREIGN KEY (user_id) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS shelf_books (
+    shelf_id UUID NOT NULL,
+    book_id UUID NOT NULL,
+    added_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    PRIMARY KEY (shelf_id, book_id),
+    FOREIGN KEY (shelf_id) REFERENCES shelves(shelf_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+        FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
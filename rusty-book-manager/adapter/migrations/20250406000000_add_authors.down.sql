@@ -0,0 +1,8 @@
+ALTER TABLE books
+    DROP COLUMN IF EXISTS publisher,
+    DROP COLUMN IF EXISTS publication_year,
+    DROP COLUMN IF EXISTS language,
+    DROP COLUMN IF EXISTS page_count,
+    DROP COLUMN IF EXISTS edition;
+DROP TABLE IF EXISTS book_authors;
+DROP TABLE IF EXISTS authors;
@@ -0,0 +1,53 @@
+-- 著者。同じ名前の著者は同一人物として扱う
+CREATE TABLE IF NOT EXISTS authors (
+    author_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+-- 蔵書と著者の関係。role は author / translator / editor のいずれか
+CREATE TABLE IF NOT EXISTS book_authors (
+    book_id UUID NOT NULL,
+    author_id UUID NOT NULL,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('author', 'translator', 'editor')),
+    position INTEGER NOT NULL,
+    PRIMARY KEY (book_id, author_id, role),
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+        FOREIGN KEY (author_id) REFERENCES authors(author_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS book_authors_author_id_idx ON book_authors(author_id);
+
+-- 書誌情報
+ALTER TABLE books
+    ADD COLUMN IF NOT EXISTS publisher VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS publication_year INTEGER,
+    ADD COLUMN IF NOT EXISTS language VARCHAR(35),
+    ADD COLUMN IF NOT EXISTS page_count INTEGER,
+    ADD COLUMN IF NOT EXISTS edition VARCHAR(64);
+
+-- 既存の author の文字列を区切り文字で分割して著者を作る（AuthorName::parse_list と同じ区切り）
+CREATE TEMPORARY TABLE split_authors AS
+SELECT
+    b.book_id,
+    btrim(s.name) AS name,
+    s.position
+FROM books AS b
+CROSS JOIN LATERAL regexp_split_to_table(b.author, '[;；、]') WITH ORDINALITY AS s(name, position)
+WHERE btrim(s.name) <> '';
+
+INSERT INTO authors (name)
+SELECT DISTINCT name FROM split_authors
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO book_authors (book_id, author_id, role, position)
+SELECT sa.book_id, a.author_id, 'author', (sa.position - 1)::INTEGER
+FROM split_authors AS sa
+INNER JOIN authors AS a USING(name)
+ON CONFLICT DO NOTHING;
+
+DROP TABLE split_authors;
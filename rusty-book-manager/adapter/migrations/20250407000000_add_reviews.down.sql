@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS reviews_updated_at_trigger ON reviews;
+DROP TABLE IF EXISTS reviews;
@@ -0,0 +1,23 @@
+-- 蔵書のレビュー。返却済みの貸出がある利用者だけが、蔵書ごとに 1 件投稿できる
+CREATE TABLE IF NOT EXISTS reviews (
+    review_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    book_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    body TEXT NOT NULL DEFAULT '',
+    -- 管理者が非表示にしたレビューは一覧や評価の集計に含めない
+    hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    UNIQUE (book_id, user_id),
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
+
+CREATE TRIGGER reviews_updated_at_trigger BEFORE
+UPDATE
+    ON reviews FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
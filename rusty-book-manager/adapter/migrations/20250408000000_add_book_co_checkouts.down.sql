@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS returned_checkouts_user_id_idx;
+DROP MATERIALIZED VIEW IF EXISTS book_co_checkouts;
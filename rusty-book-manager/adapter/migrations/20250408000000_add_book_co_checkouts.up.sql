@@ -0,0 +1,19 @@
+-- 「この本を借りた人はこんな本も借りています」の集計。
+-- 返却済みの貸出から、同じ利用者が借りた蔵書の組ごとに借りた利用者の数を数える。
+-- 集計は重いので定期的に REFRESH MATERIALIZED VIEW CONCURRENTLY で更新する
+CREATE MATERIALIZED VIEW IF NOT EXISTS book_co_checkouts AS
+WITH borrowers AS (
+    SELECT DISTINCT book_id, user_id FROM returned_checkouts
+)
+SELECT
+    a.book_id,
+    b.book_id AS related_book_id,
+    COUNT(*) AS borrower_count
+FROM borrowers AS a
+INNER JOIN borrowers AS b ON a.user_id = b.user_id AND a.book_id <> b.book_id
+GROUP BY a.book_id, b.book_id;
+
+-- CONCURRENTLY で更新するには一意なインデックスが必要
+CREATE UNIQUE INDEX IF NOT EXISTS book_co_checkouts_pkey ON book_co_checkouts(book_id, related_book_id);
+
+CREATE INDEX IF NOT EXISTS returned_checkouts_user_id_idx ON returned_checkouts(user_id);
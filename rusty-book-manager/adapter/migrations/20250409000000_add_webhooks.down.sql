@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS webhook_deliveries_updated_at_trigger ON webhook_deliveries;
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP TRIGGER IF EXISTS webhook_subscriptions_updated_at_trigger ON webhook_subscriptions;
+DROP TABLE IF EXISTS webhook_subscriptions;
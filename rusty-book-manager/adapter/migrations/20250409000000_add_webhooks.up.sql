@@ -0,0 +1,41 @@
+-- 外部サービスへ通知する Webhook の購読。event_types に含まれるイベントだけを送る
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    subscription_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    url TEXT NOT NULL,
+    -- ペイロードの HMAC 署名に使う共有鍵
+    secret TEXT NOT NULL,
+    event_types TEXT[] NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE TRIGGER webhook_subscriptions_updated_at_trigger BEFORE
+UPDATE
+    ON webhook_subscriptions FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- 購読ごとの配信キュー。失敗した配信は next_attempt_at まで待って再送し、
+-- 上限回数まで失敗したものは dead として残す
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subscription_id UUID NOT NULL,
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'succeeded', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    last_error TEXT,
+    delivered_at TIMESTAMP(3) WITH TIME ZONE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    FOREIGN KEY (subscription_id) REFERENCES webhook_subscriptions(subscription_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_due_idx ON webhook_deliveries (next_attempt_at)
+WHERE
+    status = 'pending';
+
+CREATE TRIGGER webhook_deliveries_updated_at_trigger BEFORE
+UPDATE
+    ON webhook_deliveries FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
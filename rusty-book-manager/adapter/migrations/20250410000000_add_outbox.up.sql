@@ -0,0 +1,32 @@
+-- 蔵書・貸出・利用者の変更と同じトランザクションで書き込むドメインイベント。
+-- 登録されたハンドラがすべて処理し終えたら published_at を記録する
+CREATE TABLE IF NOT EXISTS outbox (
+    event_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    published_at TIMESTAMP(3) WITH TIME ZONE,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS outbox_unpublished_idx ON outbox (next_attempt_at)
+WHERE
+    published_at IS NULL;
+
+-- イベントを処理し終えたハンドラ。再送の際、処理済みのハンドラには同じイベントを渡さない
+CREATE TABLE IF NOT EXISTS outbox_handled_events (
+    event_id UUID NOT NULL,
+    handler TEXT NOT NULL,
+    handled_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    PRIMARY KEY (event_id, handler),
+    FOREIGN KEY (event_id) REFERENCES outbox(event_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
+
+-- 同じイベントから同じ購読先への配信を二度積まないよう、配信に元のイベントを記録する
+ALTER TABLE webhook_deliveries ADD COLUMN event_id UUID;
+
+CREATE UNIQUE INDEX IF NOT EXISTS webhook_deliveries_event_idx ON webhook_deliveries (subscription_id, event_id);
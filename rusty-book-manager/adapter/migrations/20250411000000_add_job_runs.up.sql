@@ -0,0 +1,26 @@
+-- スケジューラが実行したジョブの履歴
+CREATE TABLE IF NOT EXISTS job_runs (
+    job_run_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'succeeded', 'failed')),
+    -- 実行を予定していた時刻。ロックはこの時刻ごとに取る
+    scheduled_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    started_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    finished_at TIMESTAMP(3) WITH TIME ZONE,
+    -- 送ったメールや削除した行など、ジョブが処理した件数
+    processed BIGINT,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS job_runs_started_at_idx ON job_runs (started_at DESC);
+
+-- 返却の催促を送った記録。同じ貸出には種類ごとに一度しか送らない
+CREATE TABLE IF NOT EXISTS checkout_reminders (
+    checkout_id UUID NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('due_soon', 'overdue')),
+    sent_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    PRIMARY KEY (checkout_id, kind),
+    FOREIGN KEY (checkout_id) REFERENCES checkouts(checkout_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,33 @@
+-- 利用者の受信箱に届く通知
+CREATE TABLE IF NOT EXISTS notifications (
+    notification_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    -- 通知のもとになったイベントや貸出の ID。同じ種類の通知を同じものから二度作らない
+    source_id UUID NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    book_id UUID,
+    read_at TIMESTAMP(3) WITH TIME ZONE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    UNIQUE (user_id, kind, source_id),
+    FOREIGN KEY (user_id) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS notifications_user_id_created_at_idx ON notifications (user_id, created_at DESC);
+
+-- 通知の種類ごとに、メールでも受け取るかどうか。行がない種類は既定の設定に従う
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    email BOOLEAN NOT NULL,
+    PRIMARY KEY (user_id, kind),
+    FOREIGN KEY (user_id) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE
+);
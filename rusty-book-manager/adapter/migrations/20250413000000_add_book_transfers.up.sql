@@ -0,0 +1,32 @@
+-- 蔵書の所有者を変更する申し出と、その結果。受け入れられたものと管理者が変更したものが所有者の履歴になる
+CREATE TABLE IF NOT EXISTS book_transfers (
+    book_transfer_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    book_id UUID NOT NULL,
+    from_user_id UUID NOT NULL,
+    to_user_id UUID NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (
+        status IN ('pending', 'accepted', 'declined', 'cancelled', 'forced')
+    ),
+    -- 管理者が所有者を変更した場合の管理者
+    forced_by UUID,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    resolved_at TIMESTAMP(3) WITH TIME ZONE,
+    CHECK (from_user_id <> to_user_id),
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (from_user_id) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (to_user_id) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (forced_by) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE SET NULL
+);
+
+-- 返事を待つ申し出は蔵書ごとに一つまで
+CREATE UNIQUE INDEX IF NOT EXISTS book_transfers_pending_idx ON book_transfers (book_id)
+WHERE
+    status = 'pending';
@@ -0,0 +1,6 @@
+ALTER TABLE returned_checkouts
+DROP COLUMN IF EXISTS return_reason,
+DROP COLUMN IF EXISTS returned_on_behalf_by,
+DROP COLUMN IF EXISTS checked_out_on_behalf_by;
+
+ALTER TABLE checkouts DROP COLUMN IF EXISTS checked_out_on_behalf_by;
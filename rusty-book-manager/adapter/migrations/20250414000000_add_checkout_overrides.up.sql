@@ -0,0 +1,11 @@
+-- 管理者が利用者に代わって貸出・返却した場合の記録。利用者本人が操作した場合は NULL
+ALTER TABLE checkouts
+ADD COLUMN IF NOT EXISTS checked_out_on_behalf_by UUID REFERENCES users(user_id) ON
+UPDATE
+    CASCADE ON DELETE SET NULL;
+
+ALTER TABLE returned_checkouts
+ADD COLUMN IF NOT EXISTS checked_out_on_behalf_by UUID,
+ADD COLUMN IF NOT EXISTS returned_on_behalf_by UUID,
+-- 代わりに返却した理由。returned_on_behalf_by がある場合のみ
+ADD COLUMN IF NOT EXISTS return_reason TEXT;
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS book_conditions;
+
+ALTER TABLE returned_checkouts DROP COLUMN IF EXISTS outcome;
+
+ALTER TABLE books DROP COLUMN IF EXISTS condition;
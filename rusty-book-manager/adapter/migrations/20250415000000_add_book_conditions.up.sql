@@ -0,0 +1,35 @@
+-- 蔵書の状態。紛失中の蔵書は見つかるまで貸し出せない
+ALTER TABLE books
+ADD COLUMN IF NOT EXISTS condition TEXT NOT NULL DEFAULT 'good' CHECK (
+    condition IN ('good', 'damaged', 'lost')
+);
+
+-- 貸出の終わり方。通常の返却のほか、破損しての返却と紛失がある
+ALTER TABLE returned_checkouts
+ADD COLUMN IF NOT EXISTS outcome TEXT NOT NULL DEFAULT 'returned' CHECK (
+    outcome IN ('returned', 'damaged', 'lost')
+);
+
+-- 蔵書の状態の履歴と、破損・紛失に伴う弁償の記録
+CREATE TABLE IF NOT EXISTS book_conditions (
+    book_condition_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    book_id UUID NOT NULL,
+    condition TEXT NOT NULL CHECK (condition IN ('good', 'damaged', 'lost')),
+    -- 破損・紛失のきっかけになった貸出
+    checkout_id UUID,
+    note TEXT NOT NULL DEFAULT '',
+    -- 弁償の方法。fee は charge_amount の金額を支払い、replacement は同じ本を用意して弁償する
+    charge_kind TEXT CHECK (charge_kind IN ('fee', 'replacement')),
+    charge_amount INTEGER CHECK (charge_amount >= 0),
+    recorded_by UUID,
+    recorded_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    CHECK ((charge_kind IS NOT DISTINCT FROM 'fee') = (charge_amount IS NOT NULL)),
+    FOREIGN KEY (book_id) REFERENCES books(book_id) ON
+    UPDATE
+        CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (recorded_by) REFERENCES users(user_id) ON
+    UPDATE
+        CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS book_conditions_book_id_idx ON book_conditions (book_id, recorded_at DESC);
@@ -0,0 +1 @@
+ALTER TABLE books ALTER COLUMN author TYPE VARCHAR(255) USING left(author, 255);
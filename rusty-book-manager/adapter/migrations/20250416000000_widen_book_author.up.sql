@@ -0,0 +1,2 @@
+-- books.author は著者をつなげた表示用の文字列のため、著者が多いと 255 文字に収まらない
+ALTER TABLE books ALTER COLUMN author TYPE TEXT;
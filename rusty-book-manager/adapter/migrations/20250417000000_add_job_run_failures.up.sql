@@ -0,0 +1,2 @@
+-- 一部の処理に失敗しても最後まで続けたジョブの、失敗した件数
+ALTER TABLE job_runs ADD COLUMN IF NOT EXISTS failed BIGINT;
@@ -0,0 +1,18 @@
+DELETE FROM book_transfers WHERE from_user_id IS NULL OR to_user_id IS NULL;
+
+ALTER TABLE book_transfers DROP CONSTRAINT IF EXISTS book_transfers_from_user_id_fkey;
+ALTER TABLE book_transfers DROP CONSTRAINT IF EXISTS book_transfers_to_user_id_fkey;
+ALTER TABLE book_transfers
+ADD CONSTRAINT book_transfers_from_user_id_fkey FOREIGN KEY (from_user_id) REFERENCES users(user_id) ON
+UPDATE
+    CASCADE ON DELETE CASCADE;
+ALTER TABLE book_transfers
+ADD CONSTRAINT book_transfers_to_user_id_fkey FOREIGN KEY (to_user_id) REFERENCES users(user_id) ON
+UPDATE
+    CASCADE ON DELETE CASCADE;
+
+ALTER TABLE book_transfers ALTER COLUMN from_user_id SET NOT NULL;
+ALTER TABLE book_transfers ALTER COLUMN to_user_id SET NOT NULL;
+
+ALTER TABLE book_transfers DROP COLUMN IF EXISTS from_user_name;
+ALTER TABLE book_transfers DROP COLUMN IF EXISTS to_user_name;
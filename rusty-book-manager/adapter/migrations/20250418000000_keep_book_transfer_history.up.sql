@@ -0,0 +1,25 @@
+-- 利用者を削除しても所有者の履歴が残るよう、譲渡の記録には名前も残し、利用者への参照は NULL にする
+ALTER TABLE book_transfers ADD COLUMN IF NOT EXISTS from_user_name TEXT;
+ALTER TABLE book_transfers ADD COLUMN IF NOT EXISTS to_user_name TEXT;
+
+UPDATE book_transfers AS t
+SET
+    from_user_name = (SELECT name FROM users WHERE user_id = t.from_user_id),
+    to_user_name = (SELECT name FROM users WHERE user_id = t.to_user_id);
+
+ALTER TABLE book_transfers ALTER COLUMN from_user_name SET NOT NULL;
+ALTER TABLE book_transfers ALTER COLUMN to_user_name SET NOT NULL;
+
+ALTER TABLE book_transfers ALTER COLUMN from_user_id DROP NOT NULL;
+ALTER TABLE book_transfers ALTER COLUMN to_user_id DROP NOT NULL;
+
+ALTER TABLE book_transfers DROP CONSTRAINT IF EXISTS book_transfers_from_user_id_fkey;
+ALTER TABLE book_transfers DROP CONSTRAINT IF EXISTS book_transfers_to_user_id_fkey;
+ALTER TABLE book_transfers
+ADD CONSTRAINT book_transfers_from_user_id_fkey FOREIGN KEY (from_user_id) REFERENCES users(user_id) ON
+UPDATE
+    CASCADE ON DELETE SET NULL;
+ALTER TABLE book_transfers
+ADD CONSTRAINT book_transfers_to_user_id_fkey FOREIGN KEY (to_user_id) REFERENCES users(user_id) ON
+UPDATE
+    CASCADE ON DELETE SET NULL;
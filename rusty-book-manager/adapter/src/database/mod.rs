@@ -1,18 +1,57 @@
+use secrecy::ExposeSecret;
 use shared::{
-    config::DatabaseConfig,
+    config::{DatabaseConfig, DatabaseConnection, SslMode},
     error::{AppError, AppResult},
 };
-use sqlx::{postgres::PgConnectOptions, PgPool};
+use sqlx::{
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgPoolOptions, PgSslMode},
+    PgPool,
+};
+use std::str::FromStr;
 
 pub mod model;
 
-fn make_pg_connect_options(cfg: &DatabaseConfig) -> PgConnectOptions {
-    PgConnectOptions::new()
-        .host(&cfg.host)
-        .port(cfg.port)
-        .username(&cfg.username)
-        .password(&cfg.password)
-        .database(&cfg.database)
+// adapter/migrations 以下のマイグレーションをバイナリに埋め込む
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+fn make_pg_connect_options(cfg: &DatabaseConfig) -> AppResult<PgConnectOptions> {
+    let options = match &cfg.connection {
+        DatabaseConnection::Url(url) => PgConnectOptions::from_str(url.expose_secret())
+            .map_err(|e| AppError::ConversionEntityError(e.to_string())),
+        DatabaseConnection::Params {
+            host,
+            port,
+            username,
+            password,
+            database,
+        } => Ok(PgConnectOptions::new()
+            .host(host)
+            .port(*port)
+            .username(username)
+            .password(password.expose_secret())
+            .database(database)),
+    }?;
+    let options = match cfg.ssl_mode {
+        Some(mode) => options.ssl_mode(to_pg_ssl_mode(mode)),
+        None => options,
+    };
+    let options = match cfg.statement_timeout {
+        Some(timeout) => options.options([("statement_timeout", timeout.as_millis().to_string())]),
+        None => options,
+    };
+    Ok(options)
+}
+
+fn to_pg_ssl_mode(mode: SslMode) -> PgSslMode {
+    match mode {
+        SslMode::Disable => PgSslMode::Disable,
+        SslMode::Allow => PgSslMode::Allow,
+        SslMode::Prefer => PgSslMode::Prefer,
+        SslMode::Require => PgSslMode::Require,
+        SslMode::VerifyCa => PgSslMode::VerifyCa,
+        SslMode::VerifyFull => PgSslMode::VerifyFull,
+    }
 }
 
 #[derive(Clone)]
@@ -31,6 +70,50 @@ impl ConnectionPool {
     }
 }
 
-pub fn connect_database_with(cfg: &DatabaseConfig) -> ConnectionPool {
-    ConnectionPool(PgPool::connect_lazy_with(make_pg_connect_options(cfg)))
+pub fn connect_database_with(cfg: &DatabaseConfig) -> AppResult<ConnectionPool> {
+    let pool = PgPoolOptions::new()
+        .max_connections(cfg.max_connections)
+        .min_connections(cfg.min_connections)
+        .acquire_timeout(cfg.acquire_timeout)
+        .idle_timeout(cfg.idle_timeout)
+        .connect_lazy_with(make_pg_connect_options(cfg)?);
+    Ok(ConnectionPool(pool))
+}
+
+// 未適用のマイグレーションを適用する。
+// sqlx の Migrator は適用中に pg_advisory_lock を取得するので、
+// 複数のレプリカが同時に起動しても適用処理が競合することはない。
+pub async fn run_migrations(pool: &ConnectionPool) -> AppResult<()> {
+    MIGRATOR
+        .run(pool.inner_ref())
+        .await
+        .map_err(AppError::MigrationError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = false)]
+    async fn test_run_migrations(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let pool = ConnectionPool::new(pool);
+
+        // 2回目は適用済みなので何もしない
+        run_migrations(&pool).await?;
+        run_migrations(&pool).await?;
+
+        let applied: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success = TRUE")
+                .fetch_one(pool.inner_ref())
+                .await?;
+        assert_eq!(
+            applied,
+            MIGRATOR
+                .iter()
+                .filter(|m| !m.migration_type.is_down_migration())
+                .count() as i64
+        );
+
+        Ok(())
+    }
 }
@@ -6,7 +6,7 @@ use kernel::model::{
     id::UserId,
 };
 
-use crate::redis::model::{RedisKey, RedisValue};
+use crate::redis::model::{RedisKey, RedisSetKey, RedisValue};
 
 pub struct UserItem {
     pub user_id: UserId,
@@ -15,6 +15,30 @@ pub struct UserItem {
 
 pub struct AuthorizationKey(String);
 pub struct AuthorizedUserId(UserId);
+// ユーザーごとに発行済みのアクセストークンをまとめておくキー（一括失効に使う）
+pub struct UserTokensKey(UserId);
+
+impl UserTokensKey {
+    // すべての利用者の一覧に一致する SCAN のパターン
+    pub const PATTERN: &'static str = "user-tokens:*";
+}
+
+impl TryFrom<String> for UserTokensKey {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        let user_id = s
+            .strip_prefix("user-tokens:")
+            .ok_or_else(|| AppError::ConversionEntityError(format!("unexpected key: {s}")))?;
+        Ok(Self(UserId::from_str(user_id)?))
+    }
+}
+
+impl From<UserId> for UserTokensKey {
+    fn from(user_id: UserId) -> Self {
+        Self(user_id)
+    }
+}
 
 pub fn from(event: CreateToken) -> (AuthorizationKey, AuthorizedUserId) {
     (
@@ -49,6 +73,28 @@ impl RedisKey for AuthorizationKey {
     }
 }
 
+impl RedisValue for AuthorizationKey {
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl TryFrom<String> for AuthorizationKey {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        Ok(Self(s))
+    }
+}
+
+impl RedisSetKey for UserTokensKey {
+    type Member = AuthorizationKey;
+
+    fn inner(&self) -> String {
+        format!("user-tokens:{}", self.0)
+    }
+}
+
 impl RedisValue for AuthorizedUserId {
     fn inner(&self) -> String {
         self.0.to_string()
@@ -69,4 +115,8 @@ impl AuthorizedUserId {
     pub fn into_inner(self) -> UserId {
         self.0
     }
+
+    pub fn inner_ref(&self) -> &UserId {
+        &self.0
+    }
 }
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use kernel::model::{
+    author::{Author, AuthorBook, AuthorRole},
+    id::{AuthorId, BookId},
+};
+use shared::error::AppError;
+
+pub struct AuthorRow {
+    pub author_id: AuthorId,
+    pub name: String,
+    pub book_count: i64,
+}
+
+impl From<AuthorRow> for Author {
+    fn from(value: AuthorRow) -> Self {
+        let AuthorRow {
+            author_id,
+            name,
+            book_count,
+        } = value;
+        Self {
+            id: author_id,
+            name,
+            book_count,
+        }
+    }
+}
+
+pub struct AuthorBookRow {
+    pub book_id: BookId,
+    pub title: String,
+    pub role: String,
+    pub publication_year: Option<i32>,
+}
+
+impl TryFrom<AuthorBookRow> for AuthorBook {
+    type Error = AppError;
+
+    fn try_from(value: AuthorBookRow) -> Result<Self, Self::Error> {
+        let AuthorBookRow {
+            book_id,
+            title,
+            role,
+            publication_year,
+        } = value;
+        Ok(Self {
+            book_id,
+            title,
+            role: AuthorRole::from_str(&role)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            publication_year,
+        })
+    }
+}
@@ -1,9 +1,18 @@
 use chrono::{DateTime, Utc};
+use std::str::FromStr;
+
 use kernel::model::{
-    book::{Book, Checkout},
-    id::{BookId, CheckoutId, UserId},
+    author::{AuthorRole, BookAuthor},
+    book::{Bibliography, Book, BookSort, Checkout},
+    book_condition::BookCondition,
+    genre::Genre,
+    id::{AuthorId, BookId, CheckoutId, GenreId, UserId},
+    review::RatingSummary,
     user::{BookOwner, CheckoutUser},
 };
+use shared::error::{AppError, AppResult};
+
+use crate::redis::model::{JsonValue, RedisKey, RedisValue};
 
 pub struct BookRow {
     pub book_id: BookId,
@@ -11,31 +20,132 @@ pub struct BookRow {
     pub author: String,
     pub isbn: String,
     pub description: String,
+    pub cover_url: Option<String>,
+    pub cover_image: Option<String>,
+    pub publisher: Option<String>,
+    pub publication_year: Option<i32>,
+    pub language: Option<String>,
+    pub page_count: Option<i32>,
+    pub edition: Option<String>,
     pub owned_by: UserId,
     pub owner_name: String,
+    pub rating_average: Option<f64>,
+    pub review_count: i64,
+    pub condition: String,
+    pub version: i64,
 }
 impl BookRow {
-    pub fn into_book(self, checkout: Option<Checkout>) -> Book {
+    pub fn into_book(
+        self,
+        checkout: Option<Checkout>,
+        authors: Vec<BookAuthor>,
+        tags: Vec<String>,
+        genres: Vec<Genre>,
+    ) -> AppResult<Book> {
         let BookRow {
             book_id,
             title,
             author,
             isbn,
             description,
+            cover_url,
+            cover_image,
+            publisher,
+            publication_year,
+            language,
+            page_count,
+            edition,
             owned_by,
             owner_name,
+            rating_average,
+            review_count,
+            condition,
+            version,
         } = self;
-        Book {
+        Ok(Book {
             id: book_id,
             title,
             author,
+            authors,
             isbn,
             description,
+            cover_url,
+            cover_image,
+            bibliography: Bibliography {
+                publisher,
+                publication_year,
+                language,
+                page_count,
+                edition,
+            },
+            tags,
+            genres,
+            rating: RatingSummary {
+                average: rating_average,
+                count: review_count,
+            },
             owner: BookOwner {
                 id: owned_by,
                 name: owner_name,
             },
             checkout,
+            condition: BookCondition::from_str(&condition)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            version,
+        })
+    }
+}
+
+pub struct BookAuthorRow {
+    pub book_id: BookId,
+    pub author_id: AuthorId,
+    pub name: String,
+    pub role: String,
+}
+
+impl TryFrom<BookAuthorRow> for BookAuthor {
+    type Error = AppError;
+
+    fn try_from(value: BookAuthorRow) -> Result<Self, Self::Error> {
+        let BookAuthorRow {
+            author_id,
+            name,
+            role,
+            ..
+        } = value;
+        Ok(Self {
+            id: author_id,
+            name,
+            role: AuthorRole::from_str(&role)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+        })
+    }
+}
+
+pub struct BookTagRow {
+    pub book_id: BookId,
+    pub name: String,
+}
+
+pub struct BookGenreRow {
+    pub book_id: BookId,
+    pub genre_id: GenreId,
+    pub name: String,
+    pub parent_id: Option<GenreId>,
+}
+
+impl From<BookGenreRow> for Genre {
+    fn from(value: BookGenreRow) -> Self {
+        let BookGenreRow {
+            genre_id,
+            name,
+            parent_id,
+            ..
+        } = value;
+        Self {
+            id: genre_id,
+            name,
+            parent_id,
         }
     }
 }
@@ -71,3 +181,79 @@ impl From<BookCheckoutRow> for Checkout {
         }
     }
 }
+
+// 蔵書詳細のキャッシュのキー
+pub struct BookCacheKey(BookId);
+// 蔵書一覧のキャッシュのキー。一覧の世代番号を含めることで、世代を進めるだけで
+// それまでにキャッシュした一覧をまとめて無効にできる
+pub struct BookListCacheKey {
+    pub version: u64,
+    pub limit: i64,
+    pub offset: i64,
+    pub tag: Option<String>,
+    pub genre_id: Option<GenreId>,
+    pub sort: BookSort,
+}
+// 蔵書一覧の世代番号のキー
+pub struct BookListVersionKey;
+pub struct BookListVersion(pub u64);
+
+impl From<BookId> for BookCacheKey {
+    fn from(book_id: BookId) -> Self {
+        Self(book_id)
+    }
+}
+
+impl RedisKey for BookCacheKey {
+    type Value = JsonValue;
+
+    fn inner(&self) -> String {
+        format!("book:{}", self.0)
+    }
+}
+
+impl RedisKey for BookListCacheKey {
+    type Value = JsonValue;
+
+    fn inner(&self) -> String {
+        let mut key = format!(
+            "books:v{}:limit={}:offset={}",
+            self.version, self.limit, self.offset
+        );
+        // タグは正規化済みで ":" を含まない
+        if let Some(tag) = &self.tag {
+            key.push_str(&format!(":tag={tag}"));
+        }
+        if let Some(genre_id) = &self.genre_id {
+            key.push_str(&format!(":genre={genre_id}"));
+        }
+        if self.sort != BookSort::Newest {
+            key.push_str(&format!(":sort={}", self.sort.as_ref()));
+        }
+        key
+    }
+}
+
+impl RedisKey for BookListVersionKey {
+    type Value = BookListVersion;
+
+    fn inner(&self) -> String {
+        "books:version".into()
+    }
+}
+
+impl RedisValue for BookListVersion {
+    fn inner(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<String> for BookListVersion {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        s.parse()
+            .map(Self)
+            .map_err(|e: std::num::ParseIntError| AppError::ConversionEntityError(e.to_string()))
+    }
+}
@@ -0,0 +1,66 @@
+use kernel::model::{
+    book_condition::{BookCondition, BookConditionRecord, CheckoutCharge},
+    id::{BookConditionId, BookId, CheckoutId, UserId},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct BookConditionRow {
+    pub book_condition_id: BookConditionId,
+    pub book_id: BookId,
+    pub condition: String,
+    pub checkout_id: Option<CheckoutId>,
+    pub note: String,
+    pub charge_kind: Option<String>,
+    pub charge_amount: Option<i32>,
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl TryFrom<BookConditionRow> for BookConditionRecord {
+    type Error = AppError;
+    fn try_from(value: BookConditionRow) -> Result<Self, Self::Error> {
+        let BookConditionRow {
+            book_condition_id,
+            book_id,
+            condition,
+            checkout_id,
+            note,
+            charge_kind,
+            charge_amount,
+            recorded_by,
+            recorded_at,
+        } = value;
+        let charge = match (charge_kind.as_deref(), charge_amount) {
+            (None, _) => None,
+            (Some("fee"), Some(amount)) => Some(CheckoutCharge::Fee { amount }),
+            (Some("replacement"), _) => Some(CheckoutCharge::Replacement),
+            (Some(kind), _) => {
+                return Err(AppError::ConversionEntityError(format!(
+                    "invalid charge kind: {kind}"
+                )))
+            }
+        };
+        Ok(Self {
+            id: book_condition_id,
+            book_id,
+            condition: BookCondition::from_str(&condition)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            checkout_id,
+            note,
+            charge,
+            recorded_by,
+            recorded_at,
+        })
+    }
+}
+
+// 弁償の方法を charge_kind・charge_amount の列に分ける
+pub fn charge_columns(charge: Option<&CheckoutCharge>) -> (Option<&'static str>, Option<i32>) {
+    match charge {
+        None => (None, None),
+        Some(CheckoutCharge::Fee { amount }) => (Some("fee"), Some(*amount)),
+        Some(CheckoutCharge::Replacement) => (Some("replacement"), None),
+    }
+}
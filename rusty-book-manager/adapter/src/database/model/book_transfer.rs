@@ -0,0 +1,73 @@
+use kernel::model::{
+    book_transfer::{BookTransfer, BookTransferStatus},
+    id::{BookId, BookTransferId, UserId},
+    user::TransferUser,
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct BookTransferRow {
+    pub book_transfer_id: BookTransferId,
+    pub book_id: BookId,
+    pub book_title: String,
+    pub from_user_id: Option<UserId>,
+    pub from_user_name: String,
+    pub to_user_id: Option<UserId>,
+    pub to_user_name: String,
+    pub status: String,
+    pub forced_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<BookTransferRow> for BookTransfer {
+    type Error = AppError;
+    fn try_from(value: BookTransferRow) -> Result<Self, Self::Error> {
+        let BookTransferRow {
+            book_transfer_id,
+            book_id,
+            book_title,
+            from_user_id,
+            from_user_name,
+            to_user_id,
+            to_user_name,
+            status,
+            forced_by,
+            created_at,
+            resolved_at,
+        } = value;
+        Ok(Self {
+            id: book_transfer_id,
+            book_id,
+            book_title,
+            from_user: TransferUser {
+                id: from_user_id,
+                name: from_user_name,
+            },
+            to_user: TransferUser {
+                id: to_user_id,
+                name: to_user_name,
+            },
+            status: BookTransferStatus::from_str(&status)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            forced_by,
+            created_at,
+            resolved_at,
+        })
+    }
+}
+
+// 返事をする対象の申し出
+pub struct BookTransferStateRow {
+    pub book_id: BookId,
+    pub from_user_id: UserId,
+    pub to_user_id: UserId,
+    pub status: String,
+}
+
+// 所有者を変更できるかどうかの判断に使う蔵書の状態
+pub struct BookOwnershipRow {
+    pub owner_id: UserId,
+    pub checked_out: bool,
+}
@@ -1,13 +1,16 @@
 use kernel::model::{
-    checkout::{Checkout, CheckoutBook},
+    checkout::{reminder::CheckoutReminder, Checkout, CheckoutBook, CheckoutOutcome},
     id::{BookId, CheckoutId, UserId},
 };
+use shared::error::AppError;
 use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
 
 pub struct CheckoutStateRow {
     pub book_id: BookId,
     pub checkout_id: Option<CheckoutId>,
     pub user_id: Option<UserId>,
+    pub condition: String,
 }
 
 // sqlx::query_as!は結果をネストできないのでここではフラットな構造
@@ -16,6 +19,7 @@ pub struct CheckoutRow {
     pub book_id: BookId,
     pub user_id: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub checked_out_on_behalf_by: Option<UserId>,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -27,6 +31,7 @@ impl From<CheckoutRow> for Checkout {
             book_id,
             user_id,
             checked_out_at,
+            checked_out_on_behalf_by,
             title,
             author,
             isbn,
@@ -38,6 +43,10 @@ impl From<CheckoutRow> for Checkout {
             checked_out_at,
             // ここでは未返却なのでNone
             returned_at: None,
+            outcome: None,
+            checked_out_on_behalf_by,
+            returned_on_behalf_by: None,
+            return_reason: None,
             book: CheckoutBook {
                 book_id,
                 title,
@@ -54,34 +63,77 @@ pub struct ReturnedCheckoutRow {
     pub user_id: UserId,
     pub checked_out_at: DateTime<Utc>,
     pub returned_at: DateTime<Utc>,
+    pub outcome: String,
+    pub checked_out_on_behalf_by: Option<UserId>,
+    pub returned_on_behalf_by: Option<UserId>,
+    pub return_reason: Option<String>,
     pub title: String,
     pub author: String,
     pub isbn: String,
 }
-impl From<ReturnedCheckoutRow> for Checkout {
-    fn from(value: ReturnedCheckoutRow) -> Self {
+impl TryFrom<ReturnedCheckoutRow> for Checkout {
+    type Error = AppError;
+    fn try_from(value: ReturnedCheckoutRow) -> Result<Self, Self::Error> {
         let ReturnedCheckoutRow {
             checkout_id,
             book_id,
             user_id,
             checked_out_at,
             returned_at,
+            outcome,
+            checked_out_on_behalf_by,
+            returned_on_behalf_by,
+            return_reason,
             title,
             author,
             isbn,
         } = value;
-        Checkout {
+        Ok(Checkout {
             id: checkout_id,
             checked_out_by: user_id,
             checked_out_at,
             // ここでは返却済みなのでSome
             returned_at: Some(returned_at),
+            outcome: Some(
+                CheckoutOutcome::from_str(&outcome)
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            ),
+            checked_out_on_behalf_by,
+            returned_on_behalf_by,
+            return_reason,
             book: CheckoutBook {
                 book_id,
                 title,
                 author,
                 isbn,
             },
+        })
+    }
+}
+
+pub struct CheckoutReminderRow {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub title: String,
+    pub user_id: UserId,
+    pub due_at: DateTime<Utc>,
+}
+
+impl From<CheckoutReminderRow> for CheckoutReminder {
+    fn from(value: CheckoutReminderRow) -> Self {
+        let CheckoutReminderRow {
+            checkout_id,
+            book_id,
+            title,
+            user_id,
+            due_at,
+        } = value;
+        Self {
+            checkout_id,
+            book_id,
+            title,
+            user_id,
+            due_at,
         }
     }
 }
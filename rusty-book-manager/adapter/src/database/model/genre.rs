@@ -0,0 +1,22 @@
+use kernel::model::{genre::Genre, id::GenreId};
+
+pub struct GenreRow {
+    pub genre_id: GenreId,
+    pub name: String,
+    pub parent_id: Option<GenreId>,
+}
+
+impl From<GenreRow> for Genre {
+    fn from(value: GenreRow) -> Self {
+        let GenreRow {
+            genre_id,
+            name,
+            parent_id,
+        } = value;
+        Self {
+            id: genre_id,
+            name,
+            parent_id,
+        }
+    }
+}
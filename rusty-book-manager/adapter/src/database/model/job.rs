@@ -0,0 +1,49 @@
+use kernel::model::{
+    id::JobRunId,
+    job::{JobName, JobRun, JobRunStatus},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct JobRunRow {
+    pub job_run_id: JobRunId,
+    pub job_name: String,
+    pub status: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub processed: Option<i64>,
+    pub failed: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl TryFrom<JobRunRow> for JobRun {
+    type Error = AppError;
+    fn try_from(value: JobRunRow) -> Result<Self, Self::Error> {
+        let JobRunRow {
+            job_run_id,
+            job_name,
+            status,
+            scheduled_at,
+            started_at,
+            finished_at,
+            processed,
+            failed,
+            error,
+        } = value;
+        Ok(Self {
+            id: job_run_id,
+            job_name: JobName::from_str(&job_name)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            status: JobRunStatus::from_str(&status)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            scheduled_at,
+            started_at,
+            finished_at,
+            processed,
+            failed,
+            error,
+        })
+    }
+}
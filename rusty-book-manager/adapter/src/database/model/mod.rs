@@ -1,4 +1,17 @@
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod book_condition;
+pub mod book_transfer;
 pub mod checkout;
+pub mod genre;
+pub mod job;
+pub mod notification;
+pub mod outbox;
+pub mod recommendation;
+pub mod report;
+pub mod review;
+pub mod shelf;
+pub mod tag;
 pub mod user;
+pub mod webhook;
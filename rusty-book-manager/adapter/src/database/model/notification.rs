@@ -0,0 +1,50 @@
+use kernel::model::{
+    id::{BookId, NotificationId, UserId},
+    notification::{Notification, NotificationKind},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct NotificationRow {
+    pub notification_id: NotificationId,
+    pub user_id: UserId,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub book_id: Option<BookId>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<NotificationRow> for Notification {
+    type Error = AppError;
+    fn try_from(value: NotificationRow) -> Result<Self, Self::Error> {
+        let NotificationRow {
+            notification_id,
+            user_id,
+            kind,
+            title,
+            body,
+            book_id,
+            read_at,
+            created_at,
+        } = value;
+        Ok(Self {
+            id: notification_id,
+            user_id,
+            kind: NotificationKind::from_str(&kind)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            title,
+            body,
+            book_id,
+            read_at,
+            created_at,
+        })
+    }
+}
+
+pub struct NotificationPreferenceRow {
+    pub kind: String,
+    pub email: bool,
+}
@@ -0,0 +1,30 @@
+use kernel::model::{
+    domain_event::{DomainEvent, OutboxEvent},
+    id::DomainEventId,
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct OutboxEventRow {
+    pub event_id: DomainEventId,
+    pub payload: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl TryFrom<OutboxEventRow> for OutboxEvent {
+    type Error = AppError;
+    fn try_from(value: OutboxEventRow) -> Result<Self, Self::Error> {
+        let OutboxEventRow {
+            event_id,
+            payload,
+            occurred_at,
+        } = value;
+        let event: DomainEvent = serde_json::from_str(&payload)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        Ok(Self {
+            id: event_id,
+            event,
+            occurred_at,
+        })
+    }
+}
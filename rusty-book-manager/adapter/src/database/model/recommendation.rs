@@ -0,0 +1,37 @@
+use kernel::model::{id::BookId, recommendation::Recommendation};
+
+pub struct RecommendationRow {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub score: i64,
+    pub co_checkout_count: i64,
+    pub shared_author_count: i64,
+    pub shared_tag_count: i64,
+}
+
+impl From<RecommendationRow> for Recommendation {
+    fn from(value: RecommendationRow) -> Self {
+        let RecommendationRow {
+            book_id,
+            title,
+            author,
+            isbn,
+            score,
+            co_checkout_count,
+            shared_author_count,
+            shared_tag_count,
+        } = value;
+        Self {
+            book_id,
+            title,
+            author,
+            isbn,
+            score,
+            co_checkout_count,
+            shared_author_count,
+            shared_tag_count,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use kernel::model::{
+    id::{AuthorId, BookId, UserId},
+    report::{
+        AuthorCheckoutRanking, BookCheckoutRanking, BorrowerRanking, CheckoutCount, IdleBook,
+        LoanDuration,
+    },
+};
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct CheckoutCountRow {
+    pub period_start: DateTime<Utc>,
+    pub count: i64,
+}
+
+impl From<CheckoutCountRow> for CheckoutCount {
+    fn from(value: CheckoutCountRow) -> Self {
+        let CheckoutCountRow {
+            period_start,
+            count,
+        } = value;
+        Self {
+            period_start,
+            count,
+        }
+    }
+}
+
+pub struct BookCheckoutRankingRow {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub checkout_count: i64,
+}
+
+impl From<BookCheckoutRankingRow> for BookCheckoutRanking {
+    fn from(value: BookCheckoutRankingRow) -> Self {
+        let BookCheckoutRankingRow {
+            book_id,
+            title,
+            author,
+            checkout_count,
+        } = value;
+        Self {
+            book_id,
+            title,
+            author,
+            checkout_count,
+        }
+    }
+}
+
+pub struct AuthorCheckoutRankingRow {
+    pub author_id: AuthorId,
+    pub name: String,
+    pub checkout_count: i64,
+}
+
+impl From<AuthorCheckoutRankingRow> for AuthorCheckoutRanking {
+    fn from(value: AuthorCheckoutRankingRow) -> Self {
+        let AuthorCheckoutRankingRow {
+            author_id,
+            name,
+            checkout_count,
+        } = value;
+        Self {
+            author_id,
+            name,
+            checkout_count,
+        }
+    }
+}
+
+pub struct BorrowerRankingRow {
+    pub user_id: UserId,
+    pub name: String,
+    pub checkout_count: i64,
+}
+
+impl From<BorrowerRankingRow> for BorrowerRanking {
+    fn from(value: BorrowerRankingRow) -> Self {
+        let BorrowerRankingRow {
+            user_id,
+            name,
+            checkout_count,
+        } = value;
+        Self {
+            user_id,
+            name,
+            checkout_count,
+        }
+    }
+}
+
+pub struct LoanDurationRow {
+    pub returned_count: i64,
+    pub average_days: Option<f64>,
+}
+
+impl From<LoanDurationRow> for LoanDuration {
+    fn from(value: LoanDurationRow) -> Self {
+        let LoanDurationRow {
+            returned_count,
+            average_days,
+        } = value;
+        Self {
+            returned_count,
+            average_days,
+        }
+    }
+}
+
+pub struct IdleBookRow {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub registered_at: DateTime<Utc>,
+}
+
+impl From<IdleBookRow> for IdleBook {
+    fn from(value: IdleBookRow) -> Self {
+        let IdleBookRow {
+            book_id,
+            title,
+            author,
+            registered_at,
+        } = value;
+        Self {
+            book_id,
+            title,
+            author,
+            registered_at,
+        }
+    }
+}
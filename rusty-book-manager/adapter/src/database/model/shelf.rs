@@ -0,0 +1,22 @@
+use kernel::model::{id::ShelfId, shelf::Shelf};
+
+pub struct ShelfRow {
+    pub shelf_id: ShelfId,
+    pub name: String,
+    pub book_count: i64,
+}
+
+impl From<ShelfRow> for Shelf {
+    fn from(value: ShelfRow) -> Self {
+        let ShelfRow {
+            shelf_id,
+            name,
+            book_count,
+        } = value;
+        Self {
+            id: shelf_id,
+            name,
+            book_count,
+        }
+    }
+}
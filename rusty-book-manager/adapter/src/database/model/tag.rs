@@ -0,0 +1,13 @@
+use kernel::model::tag::Tag;
+
+pub struct TagRow {
+    pub name: String,
+    pub book_count: i64,
+}
+
+impl From<TagRow> for Tag {
+    fn from(value: TagRow) -> Self {
+        let TagRow { name, book_count } = value;
+        Self { name, book_count }
+    }
+}
@@ -1,8 +1,15 @@
-use kernel::model::{id::UserId, role::Role, user::User};
+use kernel::model::{
+    id::UserId,
+    role::Role,
+    user::{EmailVerification, EmailVerificationToken, User},
+};
+use serde::{Deserialize, Serialize};
 use shared::error::AppError;
 use sqlx::types::chrono::{DateTime, Utc};
 use std::str::FromStr;
 
+use crate::redis::model::{JsonValue, RedisKey};
+
 pub struct UserRow {
     pub user_id: UserId,
     pub name: String,
@@ -31,3 +38,33 @@ impl TryFrom<UserRow> for User {
         })
     }
 }
+
+// メールアドレス変更の確認用トークンのキー
+pub struct EmailVerificationKey(String);
+
+impl From<&EmailVerificationToken> for EmailVerificationKey {
+    fn from(token: &EmailVerificationToken) -> Self {
+        Self(token.0.clone())
+    }
+}
+
+impl RedisKey for EmailVerificationKey {
+    type Value = JsonValue;
+
+    fn inner(&self) -> String {
+        format!("email-verification:{}", self.0)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailVerificationItem {
+    pub user_id: UserId,
+    pub new_email: String,
+}
+
+impl From<EmailVerificationItem> for EmailVerification {
+    fn from(value: EmailVerificationItem) -> Self {
+        let EmailVerificationItem { user_id, new_email } = value;
+        Self { user_id, new_email }
+    }
+}
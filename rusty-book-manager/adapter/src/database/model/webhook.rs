@@ -0,0 +1,117 @@
+use kernel::model::{
+    id::{WebhookDeliveryId, WebhookSubscriptionId},
+    webhook::{
+        PendingWebhookDelivery, WebhookDelivery, WebhookDeliveryStatus, WebhookEventType,
+        WebhookSubscription,
+    },
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct WebhookSubscriptionRow {
+    pub subscription_id: WebhookSubscriptionId,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookSubscriptionRow> for WebhookSubscription {
+    type Error = AppError;
+    fn try_from(value: WebhookSubscriptionRow) -> Result<Self, Self::Error> {
+        let WebhookSubscriptionRow {
+            subscription_id,
+            url,
+            event_types,
+            created_at,
+        } = value;
+        Ok(Self {
+            id: subscription_id,
+            url,
+            event_types: event_types
+                .iter()
+                .map(|t| parse_event_type(t))
+                .collect::<Result<_, _>>()?,
+            created_at,
+        })
+    }
+}
+
+pub struct WebhookDeliveryRow {
+    pub delivery_id: WebhookDeliveryId,
+    pub subscription_id: WebhookSubscriptionId,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookDeliveryRow> for WebhookDelivery {
+    type Error = AppError;
+    fn try_from(value: WebhookDeliveryRow) -> Result<Self, Self::Error> {
+        let WebhookDeliveryRow {
+            delivery_id,
+            subscription_id,
+            event_type,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            last_error,
+            delivered_at,
+            created_at,
+        } = value;
+        Ok(Self {
+            id: delivery_id,
+            subscription_id,
+            event_type: parse_event_type(&event_type)?,
+            payload,
+            status: WebhookDeliveryStatus::from_str(&status)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            attempts,
+            next_attempt_at,
+            last_error,
+            delivered_at,
+            created_at,
+        })
+    }
+}
+
+pub struct PendingWebhookDeliveryRow {
+    pub delivery_id: WebhookDeliveryId,
+    pub url: String,
+    pub secret: String,
+    pub event_type: String,
+    pub payload: String,
+    pub attempts: i32,
+}
+
+impl TryFrom<PendingWebhookDeliveryRow> for PendingWebhookDelivery {
+    type Error = AppError;
+    fn try_from(value: PendingWebhookDeliveryRow) -> Result<Self, Self::Error> {
+        let PendingWebhookDeliveryRow {
+            delivery_id,
+            url,
+            secret,
+            event_type,
+            payload,
+            attempts,
+        } = value;
+        Ok(Self {
+            id: delivery_id,
+            url,
+            secret,
+            event_type: parse_event_type(&event_type)?,
+            payload,
+            attempts,
+        })
+    }
+}
+
+fn parse_event_type(value: &str) -> Result<WebhookEventType, AppError> {
+    WebhookEventType::from_str(value).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
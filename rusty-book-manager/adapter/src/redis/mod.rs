@@ -1,8 +1,51 @@
 pub mod model;
 
-use self::model::{RedisKey, RedisValue};
-use redis::{AsyncCommands, Client};
-use shared::{config::RedisConfig, error::AppResult};
+use std::time::Duration;
+
+use self::model::{RedisKey, RedisSetKey, RedisValue};
+use redis::{AsyncCommands, Client, Script};
+use shared::{
+    config::RedisConfig,
+    error::{AppError, AppResult},
+};
+use tokio_stream::{Stream, StreamExt};
+
+// スライディングウィンドウ方式のカウンタ。Sorted Set にリクエストごとの時刻を記録し、
+// ウィンドウ外のものを取り除いてから件数を数える。時刻は Redis サーバーのものを使うので、
+// 複数のレプリカから呼ばれても時計のずれの影響を受けない。
+// 戻り値は { 許可したか (0/1), ウィンドウ内の件数, 枠が空くまでのミリ秒 }
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+local window = tonumber(ARGV[1])
+local limit = tonumber(ARGV[2])
+local t = redis.call('TIME')
+local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+local count = redis.call('ZCARD', KEYS[1])
+local allowed = 0
+if count < limit then
+  redis.call('ZADD', KEYS[1], now, ARGV[3])
+  count = count + 1
+  allowed = 1
+end
+redis.call('PEXPIRE', KEYS[1], window)
+local reset = window
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+if oldest[2] then
+  reset = tonumber(oldest[2]) + window - now
+end
+return { allowed, count, reset }
+"#;
+
+// 一度だけ Stream に追加してチャンネルに知らせる。KEYS[1] に印が付いていれば何もしない。
+// 知らせるメッセージは「Stream の ID + 空白 + 値」
+const APPEND_ONCE_SCRIPT: &str = r#"
+if not redis.call('SET', KEYS[1], '1', 'NX', 'EX', ARGV[1]) then
+  return false
+end
+local id = redis.call('XADD', KEYS[2], 'MAXLEN', '~', ARGV[2], '*', 'data', ARGV[4])
+redis.call('PUBLISH', ARGV[3], id .. ' ' .. ARGV[4])
+return id
+"#;
 
 pub struct RedisClient {
     client: Client,
@@ -16,7 +59,8 @@ impl RedisClient {
 
     pub async fn set_ex<T: RedisKey>(&self, key: &T, value: &T::Value, ttl: u64) -> AppResult<()> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        conn.set_ex(key.inner(), value.inner(), ttl).await?;
+        conn.set_ex::<_, _, ()>(key.inner(), value.inner(), ttl)
+            .await?;
         Ok(())
     }
 
@@ -26,12 +70,189 @@ impl RedisClient {
         result.map(T::Value::try_from).transpose()
     }
 
+    pub async fn incr<T: RedisKey>(&self, key: &T) -> AppResult<u64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let value: u64 = conn.incr(key.inner(), 1).await?;
+        Ok(value)
+    }
+
+    // 値を取得すると同時に削除する（一度しか使えない値の取り出しに使う）
+    pub async fn get_delete<T: RedisKey>(&self, key: &T) -> AppResult<Option<T::Value>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Option<String> = conn.get_del(key.inner()).await?;
+        result.map(T::Value::try_from).transpose()
+    }
+
+    pub async fn exists<T: RedisKey>(&self, key: &T) -> AppResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let exists: bool = conn.exists(key.inner()).await?;
+        Ok(exists)
+    }
+
     pub async fn delete<T: RedisKey>(&self, key: &T) -> AppResult<()> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        conn.del(key.inner()).await?;
+        conn.del::<_, ()>(key.inner()).await?;
         Ok(())
     }
 
+    // Set にメンバーを追加し、Set 自体の有効期限を ttl 秒後に延長する
+    pub async fn add_to_set<T: RedisSetKey>(
+        &self,
+        key: &T,
+        member: &T::Member,
+        ttl: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        redis::pipe()
+            .atomic()
+            .sadd(key.inner(), member.inner())
+            .ignore()
+            .expire(key.inner(), ttl as i64)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_from_set<T: RedisSetKey>(
+        &self,
+        key: &T,
+        member: &T::Member,
+    ) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.srem::<_, _, ()>(key.inner(), member.inner()).await?;
+        Ok(())
+    }
+
+    pub async fn set_members<T: RedisSetKey>(&self, key: &T) -> AppResult<Vec<T::Member>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let members: Vec<String> = conn.smembers(key.inner()).await?;
+        members.into_iter().map(T::Member::try_from).collect()
+    }
+
+    // pattern に一致する Set のキーをすべて返す。SCAN で少しずつ辿るので Redis を長く止めない
+    pub async fn scan_set_keys<T>(&self, pattern: &str) -> AppResult<Vec<T>>
+    where
+        T: RedisSetKey + TryFrom<String, Error = AppError>,
+    {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut iter: redis::AsyncIter<String> = conn.scan_match(pattern).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(T::try_from(key)?);
+        }
+        Ok(keys)
+    }
+
+    pub async fn delete_set<T: RedisSetKey>(&self, key: &T) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.del::<_, ()>(key.inner()).await?;
+        Ok(())
+    }
+
+    // key がなければ ttl の間だけ作り、作れたかどうかを返す。複数のプロセスの間のロックに使う
+    pub async fn try_lock(&self, key: &str, ttl: Duration) -> AppResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let res: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(1)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut conn)
+            .await?;
+        Ok(res.is_some())
+    }
+
+    // key に対するリクエストを 1 件記録し、(許可したか, ウィンドウ内の件数, 枠が空くまでの時間) を返す
+    pub async fn hit_sliding_window(
+        &self,
+        key: &str,
+        limit: u64,
+        window: Duration,
+    ) -> AppResult<(bool, u64, Duration)> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let (allowed, count, reset_ms): (u8, u64, u64) = Script::new(SLIDING_WINDOW_SCRIPT)
+            .key(key)
+            .arg(window.as_millis() as u64)
+            .arg(limit)
+            // 同一ミリ秒のリクエストも別々に数えられるよう、メンバーは一意にする
+            .arg(uuid::Uuid::new_v4().to_string())
+            .invoke_async(&mut conn)
+            .await?;
+        Ok((allowed == 1, count, Duration::from_millis(reset_ms)))
+    }
+
+    // marker が付いていなければ、value を上限 max_len 件の Stream に追加して channel に知らせる。
+    // marker は ttl 秒だけ残す
+    pub async fn append_once(
+        &self,
+        marker: &str,
+        ttl: u64,
+        stream: &str,
+        max_len: usize,
+        channel: &str,
+        value: &str,
+    ) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        Script::new(APPEND_ONCE_SCRIPT)
+            .key(marker)
+            .key(stream)
+            .arg(ttl)
+            .arg(max_len)
+            .arg(channel)
+            .arg(value)
+            .invoke_async::<_, ()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    // Stream の最も古いエントリの ID
+    pub async fn first_stream_id(&self, stream: &str) -> AppResult<Option<String>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let entries: Vec<(String, Vec<String>)> = redis::cmd("XRANGE")
+            .arg(stream)
+            .arg("-")
+            .arg("+")
+            .arg("COUNT")
+            .arg(1)
+            .query_async(&mut conn)
+            .await?;
+        Ok(entries.into_iter().next().map(|(id, _)| id))
+    }
+
+    // Stream のうち after より後のエントリを古い順に (ID, 値) の組で返す
+    pub async fn stream_entries_after(
+        &self,
+        stream: &str,
+        after: &str,
+    ) -> AppResult<Vec<(String, String)>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let entries: Vec<(String, Vec<String>)> = redis::cmd("XRANGE")
+            .arg(stream)
+            .arg(format!("({after}"))
+            .arg("+")
+            .query_async(&mut conn)
+            .await?;
+        // フィールドと値が交互に並ぶ。append_once で追加した data の値を取り出す
+        Ok(entries
+            .into_iter()
+            .filter_map(|(id, fields)| {
+                let value = fields.chunks(2).find(|f| f[0] == "data")?.get(1)?.clone();
+                Some((id, value))
+            })
+            .collect())
+    }
+
+    // channel に届いたメッセージを受け取る。接続が切れるとストリームも終わる
+    pub async fn subscribe(&self, channel: &str) -> AppResult<impl Stream<Item = String>> {
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub.subscribe(channel).await?;
+        Ok(pubsub
+            .into_on_message()
+            .filter_map(|msg| msg.get_payload::<String>().ok()))
+    }
+
     pub async fn try_connect(&self) -> AppResult<()> {
         let _ = self.client.get_multiplexed_async_connection().await?;
         Ok(())
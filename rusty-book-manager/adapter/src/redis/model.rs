@@ -1,4 +1,5 @@
-use shared::error::AppError;
+use serde::{de::DeserializeOwned, Serialize};
+use shared::error::{AppError, AppResult};
 
 pub trait RedisKey {
     type Value: RedisValue + TryFrom<String, Error = AppError>;
@@ -8,3 +9,38 @@ pub trait RedisKey {
 pub trait RedisValue {
     fn inner(&self) -> String;
 }
+
+// Redis の Set 型を値に持つキー
+pub trait RedisSetKey {
+    type Member: RedisValue + TryFrom<String, Error = AppError>;
+    fn inner(&self) -> String;
+}
+
+// JSON にシリアライズして保存する値
+pub struct JsonValue(String);
+
+impl JsonValue {
+    pub fn encode<T: Serialize>(value: &T) -> AppResult<Self> {
+        serde_json::to_string(value)
+            .map(Self)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self) -> AppResult<T> {
+        serde_json::from_str(&self.0).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl RedisValue for JsonValue {
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl TryFrom<String> for JsonValue {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        Ok(Self(s))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     database::{
-        model::auth::{from, AuthorizationKey, AuthorizedUserId, UserItem},
+        model::auth::{from, AuthorizationKey, AuthorizedUserId, UserItem, UserTokensKey},
         ConnectionPool,
     },
     redis::RedisClient,
@@ -59,11 +59,80 @@ impl AuthRepository for AuthRepositoryImpl {
     async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken> {
         let (key, value) = from(event);
         self.kv.set_ex(&key, &value, self.ttl).await?;
+        // 一括失効できるように、ユーザーごとの発行済みトークンの一覧にも追加しておく
+        self.kv
+            .add_to_set(&UserTokensKey::from(*value.inner_ref()), &key, self.ttl)
+            .await?;
         Ok(key.into())
     }
 
     async fn delete_token(&self, access_token: AccessToken) -> AppResult<()> {
         let key: AuthorizationKey = access_token.into();
+        if let Some(user_id) = self.kv.get(&key).await? {
+            self.kv
+                .remove_from_set(&UserTokensKey::from(user_id.into_inner()), &key)
+                .await?;
+        }
         self.kv.delete(&key).await
     }
+
+    async fn delete_all_tokens(&self, user_id: UserId) -> AppResult<u64> {
+        let set_key = UserTokensKey::from(user_id);
+        let keys = self.kv.set_members(&set_key).await?;
+        for key in &keys {
+            self.kv.delete(key).await?;
+        }
+        self.kv.delete_set(&set_key).await?;
+        Ok(keys.len() as u64)
+    }
+
+    async fn delete_expired_tokens(&self) -> AppResult<u64> {
+        // トークン自体は有効期限で Redis から消えるが、一覧には残り続ける
+        let mut removed = 0;
+        for set_key in self
+            .kv
+            .scan_set_keys::<UserTokensKey>(UserTokensKey::PATTERN)
+            .await?
+        {
+            for key in self.kv.set_members(&set_key).await? {
+                if !self.kv.exists(&key).await? {
+                    self.kv.remove_from_set(&set_key, &key).await?;
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::config::RedisConfig;
+
+    // Redis が必要なため、Redis を起動したうえで `cargo test -- --ignored` で実行する
+    #[sqlx::test]
+    #[ignore = "requires a running Redis (REDIS_HOST / REDIS_PORT)"]
+    async fn test_delete_all_tokens(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let kv = Arc::new(RedisClient::new(&RedisConfig {
+            host: std::env::var("REDIS_HOST").unwrap_or_else(|_| "localhost".into()),
+            port: std::env::var("REDIS_PORT").map_or(Ok(6379), |p| p.parse())?,
+        })?);
+        let repo = AuthRepositoryImpl::new(ConnectionPool::new(pool), kv, 60);
+        let user_id = UserId::new();
+        let other_id = UserId::new();
+        let first = repo.create_token(CreateToken::new(user_id)).await?;
+        let second = repo.create_token(CreateToken::new(user_id)).await?;
+        let other = repo.create_token(CreateToken::new(other_id)).await?;
+
+        // 指定したユーザーのトークンだけをすべて失効させる
+        assert_eq!(repo.delete_all_tokens(user_id).await?, 2);
+        assert_eq!(repo.fetch_user_id_from_token(&first).await?, None);
+        assert_eq!(repo.fetch_user_id_from_token(&second).await?, None);
+        assert_eq!(repo.fetch_user_id_from_token(&other).await?, Some(other_id));
+        assert_eq!(repo.delete_all_tokens(user_id).await?, 0);
+
+        repo.delete_all_tokens(other_id).await?;
+        Ok(())
+    }
 }
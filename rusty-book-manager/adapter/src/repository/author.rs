@@ -0,0 +1,148 @@
+use crate::database::{
+    model::author::{AuthorBookRow, AuthorRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    author::{Author, AuthorBook, AuthorDetail},
+    id::AuthorId,
+};
+use kernel::repository::author::AuthorRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct AuthorRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl AuthorRepository for AuthorRepositoryImpl {
+    async fn find_all(&self) -> AppResult<Vec<Author>> {
+        // 蔵書の削除などでどの蔵書にも関わらなくなった著者は返さない
+        let rows = sqlx::query_as!(
+            AuthorRow,
+            r#"
+                SELECT a.author_id, a.name, COUNT(DISTINCT ba.book_id) AS "book_count!"
+                FROM authors AS a
+                INNER JOIN book_authors AS ba USING(author_id)
+                GROUP BY a.author_id, a.name
+                ORDER BY a.name
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(Author::from).collect())
+    }
+
+    async fn find_by_id(&self, author_id: AuthorId) -> AppResult<Option<AuthorDetail>> {
+        let author = sqlx::query!(
+            r#"
+                SELECT name FROM authors WHERE author_id = $1
+            "#,
+            author_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let Some(author) = author else {
+            return Ok(None);
+        };
+
+        // 新しい蔵書から順に並べる
+        let books = sqlx::query_as!(
+            AuthorBookRow,
+            r#"
+                SELECT b.book_id, b.title, ba.role, b.publication_year
+                FROM book_authors AS ba
+                INNER JOIN books AS b USING(book_id)
+                WHERE ba.author_id = $1
+                ORDER BY b.publication_year DESC NULLS LAST, b.title, ba.role
+            "#,
+            author_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(AuthorBook::try_from)
+        .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(Some(AuthorDetail {
+            id: author_id,
+            name: author.name,
+            books,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::book::BookRepositoryImpl;
+    use kernel::{
+        model::{
+            author::{AuthorName, AuthorRole},
+            book::{event::CreateBook, Bibliography},
+            id::UserId,
+        },
+        repository::book::BookRepository,
+    };
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_author_books(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let author_repo = AuthorRepositoryImpl::new(db.clone());
+        let book_repo = BookRepositoryImpl::new(db);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let book = |title: &str, year, authors: Vec<AuthorName>| CreateBook {
+            title: title.into(),
+            authors,
+            isbn: "".into(),
+            description: "".into(),
+            cover_url: None,
+            bibliography: Bibliography {
+                publication_year: Some(year),
+                ..Default::default()
+            },
+        };
+        let translator = AuthorName {
+            name: "翻訳者".into(),
+            role: AuthorRole::Translator,
+        };
+        book_repo
+            .create(
+                book(
+                    "原著",
+                    2018,
+                    AuthorName::parse_list("Steve Klabnik; Carol Nichols"),
+                ),
+                user_id,
+            )
+            .await?;
+        let mut authors = AuthorName::parse_list("Steve Klabnik");
+        authors.push(translator);
+        book_repo
+            .create(book("翻訳書", 2019, authors), user_id)
+            .await?;
+
+        let all = author_repo.find_all().await?;
+        let names = all.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Carol Nichols", "Steve Klabnik", "翻訳者"]);
+        let steve = all.iter().find(|a| a.name == "Steve Klabnik").unwrap();
+        assert_eq!(steve.book_count, 2);
+
+        let detail = author_repo.find_by_id(steve.id).await?.unwrap();
+        let titles = detail
+            .books
+            .iter()
+            .map(|b| b.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["翻訳書", "原著"]);
+        assert!(author_repo.find_by_id(AuthorId::new()).await?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::repository::blob::BlobStore;
+use shared::error::{AppError, AppResult};
+use uuid::Uuid;
+
+use super::validate_key;
+
+// ローカルのディレクトリにキーと同じパスで保存する。Content-Type は保存しない
+#[derive(new)]
+pub struct FilesystemBlobStore {
+    root: PathBuf,
+}
+
+impl FilesystemBlobStore {
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    AppError::BlobStoreError(e.to_string())
+}
+
+#[async_trait]
+impl BlobStore for FilesystemBlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>, _content_type: &str) -> AppResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        // 書き込み途中のファイルを読まれないよう、一時ファイルに書いてから置き換える
+        let tmp = path.with_file_name(format!(".{}.tmp", Uuid::new_v4().simple()));
+        if let Err(e) = tokio::fs::write(&tmp, &data).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(io_error(e));
+        }
+        tokio::fs::rename(&tmp, &path).await.map_err(io_error)
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_delete() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(format!("blob-test-{}", Uuid::new_v4()));
+        let store = FilesystemBlobStore::new(root.clone());
+
+        assert_eq!(store.get("covers/a/1.png").await?, None);
+        store
+            .put("covers/a/1.png", b"first".to_vec(), "image/png")
+            .await?;
+        store
+            .put("covers/a/1.png", b"second".to_vec(), "image/png")
+            .await?;
+        assert_eq!(store.get("covers/a/1.png").await?, Some(b"second".to_vec()));
+
+        store.delete("covers/a/1.png").await?;
+        assert_eq!(store.get("covers/a/1.png").await?, None);
+        // 存在しないキーの削除も成功する
+        store.delete("covers/a/1.png").await?;
+
+        assert!(store.get("../outside").await.is_err());
+
+        tokio::fs::remove_dir_all(root).await?;
+        Ok(())
+    }
+}
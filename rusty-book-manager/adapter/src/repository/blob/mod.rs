@@ -0,0 +1,35 @@
+use shared::error::{AppError, AppResult};
+
+pub mod filesystem;
+pub mod s3;
+
+// ストレージの外を指すキーを受け付けないよう、"/" 区切りの相対パスであることを確かめる
+fn validate_key(key: &str) -> AppResult<()> {
+    let valid = !key.is_empty()
+        && !key.contains('\\')
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::BlobStoreError(format!(
+            "invalid blob key: {key:?}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("covers/abc/1.png").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("/etc/passwd").is_err());
+        assert!(validate_key("covers/../../etc/passwd").is_err());
+        assert!(validate_key("covers//1.png").is_err());
+        assert!(validate_key("covers\\1.png").is_err());
+    }
+}
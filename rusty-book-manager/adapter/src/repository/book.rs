@@ -1,9 +1,14 @@
-use crate::database::model::book::{BookCheckoutRow, BookRow, PaginatedBookRow};
+use crate::database::model::book::{
+    BookAuthorRow, BookCheckoutRow, BookGenreRow, BookRow, BookTagRow, PaginatedBookRow,
+};
 use crate::database::ConnectionPool;
 use async_trait::async_trait;
 use derive_new::new;
 use kernel::model::{
-    id::{BookId, UserId},
+    author::{display_authors, AuthorName, BookAuthor},
+    domain_event::DomainEvent,
+    genre::Genre,
+    id::{BookId, GenreId, UserId},
     {
         book::{event::DeleteBook, Checkout},
         list::PaginatedList,
@@ -11,7 +16,7 @@ use kernel::model::{
 };
 use kernel::{
     model::book::{
-        event::{CreateBook, UpdateBook},
+        event::{BookPatch, CreateBook, UpdateBook, UpdateBookCover},
         Book, BookListOptions,
     },
     repository::book::BookRepository,
@@ -19,6 +24,8 @@ use kernel::{
 use shared::error::{AppError, AppResult};
 use std::collections::HashMap;
 
+use super::outbox::record_event;
+
 #[derive(new)]
 pub struct BookRepositoryImpl {
     db: ConnectionPool,
@@ -26,38 +33,101 @@ pub struct BookRepositoryImpl {
 
 #[async_trait]
 impl BookRepository for BookRepositoryImpl {
-    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<()> {
-        sqlx::query!(
+    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<BookId> {
+        let mut tx = self.db.begin().await?;
+        let bibliography = &event.bibliography;
+        let book_id = sqlx::query_scalar!(
             r#"
-            INSERT INTO books (title, author, isbn, description, user_id)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO books (
+                title, author, isbn, description, cover_url,
+                publisher, publication_year, language, page_count, edition, user_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING book_id AS "book_id: BookId"
             "#,
             event.title,
-            event.author,
+            display_authors(&event.authors),
             event.isbn,
             event.description,
+            event.cover_url,
+            bibliography.publisher,
+            bibliography.publication_year,
+            bibliography.language,
+            bibliography.page_count,
+            bibliography.edition,
             user_id as _
         )
-        .execute(self.db.inner_ref())
+        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
-        Ok(())
+        replace_authors(&mut tx, book_id, &event.authors).await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookCreated {
+                book_id,
+                owner_id: user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(book_id)
     }
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>> {
         // ページネーションするために、まずは件数とIDのみ取得
-        let BookListOptions { limit, offset } = options;
+        let BookListOptions {
+            limit,
+            offset,
+            filter,
+            sort,
+        } = options;
+        let (shelf_id, shelf_user_id) = filter.shelf.map(|s| (s.shelf_id, s.user_id)).unzip();
+        // 絞り込みの条件は NULL の場合は無視する。ジャンルは下位のジャンルもたどる
+        // 並び順は評価・レビュー数が同じ場合に新しい順とする
         let rows: Vec<PaginatedBookRow> = sqlx::query_as!(
             PaginatedBookRow,
             r#"
                 SELECT
                 COUNT(*) OVER() AS "total!",
-                book_id AS id
-                FROM books
-                ORDER BY created_at DESC
+                b.book_id AS id
+                FROM books AS b
+                LEFT OUTER JOIN (
+                    SELECT book_id, AVG(rating) AS average, COUNT(*) AS count
+                    FROM reviews
+                    WHERE NOT hidden
+                    GROUP BY book_id
+                ) AS r USING(book_id)
+                WHERE ($3::TEXT IS NULL OR EXISTS (
+                    SELECT 1 FROM book_tags AS bt
+                    INNER JOIN tags AS t USING(tag_id)
+                    WHERE bt.book_id = b.book_id AND t.name = $3
+                ))
+                AND ($4::UUID IS NULL OR EXISTS (
+                    WITH RECURSIVE g AS (
+                        SELECT genre_id FROM genres WHERE genre_id = $4
+                        UNION
+                        SELECT c.genre_id FROM genres AS c INNER JOIN g ON c.parent_id = g.genre_id
+                    )
+                    SELECT 1 FROM book_genres AS bg
+                    WHERE bg.book_id = b.book_id AND bg.genre_id IN (SELECT genre_id FROM g)
+                ))
+                AND ($5::UUID IS NULL OR EXISTS (
+                    SELECT 1 FROM shelf_books AS sb
+                    INNER JOIN shelves AS s USING(shelf_id)
+                    WHERE sb.book_id = b.book_id AND s.shelf_id = $5 AND s.user_id = $6
+                ))
+                ORDER BY
+                    CASE WHEN $7 = 'rating' THEN r.average END DESC NULLS LAST,
+                    CASE WHEN $7 IN ('rating', 'review_count') THEN COALESCE(r.count, 0) END DESC,
+                    b.created_at DESC
                 LIMIT $1 OFFSET $2
             "#,
             limit,
-            offset
+            offset,
+            filter.tag,
+            filter.genre_id as _,
+            shelf_id as _,
+            shelf_user_id as _,
+            sort.as_ref()
         )
         .fetch_all(self.db.inner_ref())
         .await
@@ -77,12 +147,31 @@ impl BookRepository for BookRepositoryImpl {
                     b.author AS author,
                     b.isbn AS isbn,
                     b.description AS description,
+                    b.cover_url,
+                    b.cover_image,
+                    b.publisher,
+                    b.publication_year,
+                    b.language,
+                    b.page_count,
+                    b.edition,
                     u.user_id AS owned_by,
-                    u.name AS owner_name
+                    u.name AS owner_name,
+                    -- 評価の集計には非表示のレビューを含めない
+                    (
+                        SELECT AVG(r.rating)::FLOAT8 FROM reviews AS r
+                        WHERE r.book_id = b.book_id AND NOT r.hidden
+                    ) AS rating_average,
+                    (
+                        SELECT COUNT(*) FROM reviews AS r
+                        WHERE r.book_id = b.book_id AND NOT r.hidden
+                    ) AS "review_count!",
+                    b.condition,
+                    b.version
                 FROM books AS b
                 INNER JOIN users AS u USING(user_id)
                 WHERE b.book_id IN (SELECT * FROM UNNEST($1::uuid[]))
-                ORDER BY b.created_at DESC
+                -- 1 つ目のクエリで決めた並び順を保つ
+                ORDER BY array_position($1::uuid[], b.book_id)
             "#,
             &book_ids as _
         )
@@ -92,13 +181,21 @@ impl BookRepository for BookRepositoryImpl {
 
         let book_ids = rows.iter().map(|r| r.book_id).collect::<Vec<_>>();
         let mut checkouts = self.find_checkouts(&book_ids).await?;
+        let mut authors = self.find_authors(&book_ids).await?;
+        let mut tags = self.find_tags(&book_ids).await?;
+        let mut genres = self.find_genres(&book_ids).await?;
         let items = rows
             .into_iter()
             .map(|row| {
-                let checkout = checkouts.remove(&row.book_id);
-                row.into_book(checkout)
+                let book_id = row.book_id;
+                row.into_book(
+                    checkouts.remove(&book_id),
+                    authors.remove(&book_id).unwrap_or_default(),
+                    tags.remove(&book_id).unwrap_or_default(),
+                    genres.remove(&book_id).unwrap_or_default(),
+                )
             })
-            .collect();
+            .collect::<AppResult<_>>()?;
 
         Ok(PaginatedList {
             total,
@@ -117,8 +214,26 @@ impl BookRepository for BookRepositoryImpl {
                 b.author AS author,
                 b.isbn AS isbn,
                 b.description AS description,
+                b.cover_url,
+                b.cover_image,
+                b.publisher,
+                b.publication_year,
+                b.language,
+                b.page_count,
+                b.edition,
                 u.user_id AS owned_by,
-                u.name AS owner_name
+                u.name AS owner_name,
+                -- 評価の集計には非表示のレビューを含めない
+                (
+                    SELECT AVG(r.rating)::FLOAT8 FROM reviews AS r
+                    WHERE r.book_id = b.book_id AND NOT r.hidden
+                ) AS rating_average,
+                (
+                    SELECT COUNT(*) FROM reviews AS r
+                    WHERE r.book_id = b.book_id AND NOT r.hidden
+                ) AS "review_count!",
+                b.condition,
+                b.version
             FROM books AS b
             INNER JOIN users AS u USING(user_id)
             WHERE b.book_id = $1
@@ -130,13 +245,25 @@ impl BookRepository for BookRepositoryImpl {
         .map_err(AppError::SpecificOperationError)?;
         match row {
             Some(r) => {
-                let checkout = self.find_checkouts(&[r.book_id]).await?.remove(&r.book_id);
-                Ok(Some(r.into_book(checkout)))
+                let book_id = r.book_id;
+                let checkout = self.find_checkouts(&[book_id]).await?.remove(&book_id);
+                let authors = self.find_authors(&[book_id]).await?.remove(&book_id);
+                let tags = self.find_tags(&[book_id]).await?.remove(&book_id);
+                let genres = self.find_genres(&[book_id]).await?.remove(&book_id);
+                r.into_book(
+                    checkout,
+                    authors.unwrap_or_default(),
+                    tags.unwrap_or_default(),
+                    genres.unwrap_or_default(),
+                )
+                .map(Some)
             }
             None => Ok(None),
         }
     }
     async fn update(&self, event: UpdateBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let bibliography = &event.bibliography;
         let res = sqlx::query!(
             r#"
                 UPDATE books
@@ -144,44 +271,197 @@ impl BookRepository for BookRepositoryImpl {
                     title = $1,
                     author = $2,
                     isbn = $3,
-                    description = $4
-                WHERE book_id = $5 AND user_id = $6
+                    description = $4,
+                    publisher = $5,
+                    publication_year = $6,
+                    language = $7,
+                    page_count = $8,
+                    edition = $9
+                WHERE book_id = $10 AND user_id = $11
+                AND ($12::BIGINT IS NULL OR version = $12)
             "#,
             event.title,
-            event.author,
+            display_authors(&event.authors),
             event.isbn,
             event.description,
+            bibliography.publisher,
+            bibliography.publication_year,
+            bibliography.language,
+            bibliography.page_count,
+            bibliography.edition,
             event.book_id as _,
-            event.requested_user as _
+            event.requested_user as _,
+            event.expected_version
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified book not found".into()));
+            return Err(self
+                .not_updated_reason(event.book_id, event.requested_user)
+                .await);
         }
+        replace_authors(&mut tx, event.book_id, &event.authors).await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookUpdated {
+                book_id: event.book_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
-    async fn delete(&self, event: DeleteBook) -> AppResult<()> {
+    async fn patch(&self, event: BookPatch) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let bibliography = &event.bibliography;
+        // 必須の項目は NULL なら現在の値のままにする。
+        // 書誌情報は空にもできるため、変更するかどうかを別に渡す
         let res = sqlx::query!(
             r#"
-                DELETE FROM books
-                WHERE book_id = $1 AND user_id = $2
+                UPDATE books
+                SET
+                    title = COALESCE($1, title),
+                    author = COALESCE($2, author),
+                    isbn = COALESCE($3, isbn),
+                    description = COALESCE($4, description),
+                    publisher = CASE WHEN $5 THEN $6 ELSE publisher END,
+                    publication_year = CASE WHEN $7 THEN $8 ELSE publication_year END,
+                    language = CASE WHEN $9 THEN $10 ELSE language END,
+                    page_count = CASE WHEN $11 THEN $12 ELSE page_count END,
+                    edition = CASE WHEN $13 THEN $14 ELSE edition END
+                WHERE book_id = $15 AND user_id = $16
+                AND ($17::BIGINT IS NULL OR version = $17)
             "#,
+            event.title,
+            event.authors.as_deref().map(display_authors),
+            event.isbn,
+            event.description,
+            bibliography.publisher.is_some(),
+            bibliography.publisher.clone().flatten(),
+            bibliography.publication_year.is_some(),
+            bibliography.publication_year.flatten(),
+            bibliography.language.is_some(),
+            bibliography.language.clone().flatten(),
+            bibliography.page_count.is_some(),
+            bibliography.page_count.flatten(),
+            bibliography.edition.is_some(),
+            bibliography.edition.clone().flatten(),
             event.book_id as _,
-            event.requested_user as _
+            event.requested_user as _,
+            event.expected_version
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified book not found".into()));
+            return Err(self
+                .not_updated_reason(event.book_id, event.requested_user)
+                .await);
+        }
+        if let Some(authors) = &event.authors {
+            replace_authors(&mut tx, event.book_id, authors).await?;
         }
+        record_event(
+            &mut tx,
+            DomainEvent::BookUpdated {
+                book_id: event.book_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<Option<String>> {
+        let mut tx = self.db.begin().await?;
+        // 同時に差し替えられても消すべき古いキーを取り違えないよう、行をロックして更新前の値を返す
+        let old_cover_image = sqlx::query_scalar!(
+            r#"
+                UPDATE books AS b
+                SET cover_image = $1
+                FROM (
+                    SELECT book_id, cover_image
+                    FROM books
+                    WHERE book_id = $2 AND user_id = $3
+                    FOR UPDATE
+                ) AS old
+                WHERE b.book_id = old.book_id
+                RETURNING old.cover_image
+            "#,
+            event.cover_image,
+            event.book_id as _,
+            event.requested_user as _,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookUpdated {
+                book_id: event.book_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(old_cover_image)
+    }
+    async fn delete(&self, event: DeleteBook) -> AppResult<Option<String>> {
+        let mut tx = self.db.begin().await?;
+        let Some(cover_image) = sqlx::query_scalar!(
+            r#"
+                DELETE FROM books
+                WHERE book_id = $1 AND user_id = $2
+                AND ($3::BIGINT IS NULL OR version = $3)
+                RETURNING cover_image
+            "#,
+            event.book_id as _,
+            event.requested_user as _,
+            event.expected_version
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        else {
+            return Err(self
+                .not_updated_reason(event.book_id, event.requested_user)
+                .await);
+        };
+        record_event(
+            &mut tx,
+            DomainEvent::BookDeleted {
+                book_id: event.book_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(cover_image)
+    }
 }
 
 impl BookRepositoryImpl {
+    // 更新・削除の対象が 0 件だった理由を、蔵書が存在しないのかバージョンが一致しないのかで区別する
+    async fn not_updated_reason(&self, book_id: BookId, user_id: UserId) -> AppError {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM books WHERE book_id = $1 AND user_id = $2
+                ) AS "exists!"
+            "#,
+            book_id as _,
+            user_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await;
+        match exists {
+            Ok(true) => AppError::PreconditionFailed(
+                "specified book has been modified by another request".into(),
+            ),
+            Ok(false) => AppError::EntityNotFound("specified book not found".into()),
+            Err(e) => AppError::SpecificOperationError(e),
+        }
+    }
+
     // 指定された book_id が貸出中の場合に貸出情報を返すメソッドを追加する
     async fn find_checkouts(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Checkout>> {
         let res = sqlx::query_as!(
@@ -209,31 +489,164 @@ impl BookRepositoryImpl {
 
         Ok(res)
     }
+
+    async fn find_authors(
+        &self,
+        book_ids: &[BookId],
+    ) -> AppResult<HashMap<BookId, Vec<BookAuthor>>> {
+        let rows = sqlx::query_as!(
+            BookAuthorRow,
+            r#"
+                SELECT ba.book_id, a.author_id, a.name, ba.role
+                FROM book_authors AS ba
+                INNER JOIN authors AS a USING(author_id)
+                WHERE ba.book_id = ANY($1)
+                ORDER BY ba.position
+            "#,
+            book_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let mut authors: HashMap<BookId, Vec<BookAuthor>> = HashMap::new();
+        for row in rows {
+            authors
+                .entry(row.book_id)
+                .or_default()
+                .push(BookAuthor::try_from(row)?);
+        }
+        Ok(authors)
+    }
+
+    async fn find_tags(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Vec<String>>> {
+        let rows = sqlx::query_as!(
+            BookTagRow,
+            r#"
+                SELECT bt.book_id, t.name
+                FROM book_tags AS bt
+                INNER JOIN tags AS t USING(tag_id)
+                WHERE bt.book_id = ANY($1)
+                ORDER BY t.name
+            "#,
+            book_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let mut tags: HashMap<BookId, Vec<String>> = HashMap::new();
+        for row in rows {
+            tags.entry(row.book_id).or_default().push(row.name);
+        }
+        Ok(tags)
+    }
+
+    async fn find_genres(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Vec<Genre>>> {
+        let rows = sqlx::query_as!(
+            BookGenreRow,
+            r#"
+                SELECT bg.book_id, g.genre_id, g.name, g.parent_id AS "parent_id?: GenreId"
+                FROM book_genres AS bg
+                INNER JOIN genres AS g USING(genre_id)
+                WHERE bg.book_id = ANY($1)
+                ORDER BY g.name
+            "#,
+            book_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let mut genres: HashMap<BookId, Vec<Genre>> = HashMap::new();
+        for row in rows {
+            genres
+                .entry(row.book_id)
+                .or_default()
+                .push(Genre::from(row));
+        }
+        Ok(genres)
+    }
+}
+
+// タグ・ジャンルなど別のテーブルに持つ内容を変えたときに蔵書の行を更新し、
+// トリガーでバージョンを上げる（ETag が変わるようにする）
+pub(crate) async fn touch_book(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            UPDATE books SET updated_at = CURRENT_TIMESTAMP(3) WHERE book_id = $1
+        "#,
+        book_id as _
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    Ok(())
+}
+
+// 蔵書の著者を指定された順に置き換える。著者は名前で探し、いなければ作成する
+async fn replace_authors(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+    authors: &[AuthorName],
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            DELETE FROM book_authors WHERE book_id = $1
+        "#,
+        book_id as _
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    for (position, author) in authors.iter().enumerate() {
+        // 既にいる場合も author_id を返すため、名前で更新する
+        let author_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO authors (name) VALUES ($1)
+                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING author_id
+            "#,
+            author.name
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // 同じ著者が同じ役割で重複して指定された場合は最初のものを残す
+        sqlx::query!(
+            r#"
+                INSERT INTO book_authors (book_id, author_id, role, position)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT DO NOTHING
+            "#,
+            book_id as _,
+            author_id,
+            author.role.as_ref(),
+            position as i32
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repository::{
-        book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl, user::UserRepositoryImpl,
-    };
-    use chrono::Utc;
+    use crate::repository::{book::BookRepositoryImpl, user::UserRepositoryImpl};
     use kernel::{
         model::{
-            checkout::event::{CreateCheckout, UpdateReturned},
-            id::UserId,
+            author::AuthorRole, book::event::BibliographyPatch, book::Bibliography, id::UserId,
             user::event::CreateUser,
         },
-        repository::{checkout::CheckoutRepository, user::UserRepository},
+        repository::user::UserRepository,
     };
     use std::str::FromStr;
 
     #[sqlx::test]
     async fn test_register_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        // FIXME: use fixture to prepare data
-        sqlx::query!(r#"INSERT INTO roles(name) VALUES ('Admin'), ('User');"#)
-            .execute(&pool)
-            .await?;
         let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
         let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
         let user = user_repo
@@ -245,9 +658,26 @@ mod tests {
             .await?;
         let book = CreateBook {
             title: "Test Title".into(),
-            author: "Test Author".into(),
+            authors: vec![
+                AuthorName {
+                    name: "Test Author".into(),
+                    role: AuthorRole::Author,
+                },
+                AuthorName {
+                    name: "Test Translator".into(),
+                    role: AuthorRole::Translator,
+                },
+            ],
             isbn: "Test ISBN".into(),
             description: "Test Description".into(),
+            cover_url: Some("https://covers.example.com/1.jpg".into()),
+            bibliography: Bibliography {
+                publisher: Some("Test Publisher".into()),
+                publication_year: Some(2024),
+                language: Some("ja".into()),
+                page_count: Some(320),
+                edition: Some("第2版".into()),
+            },
         };
 
         repo.create(book, user.id).await?;
@@ -255,6 +685,8 @@ mod tests {
         let options = BookListOptions {
             limit: 20,
             offset: 0,
+            filter: Default::default(),
+            sort: Default::default(),
         };
         let res = repo.find_all(options).await?;
         assert_eq!(res.items.len(), 1);
@@ -267,8 +699,11 @@ mod tests {
             id,
             title,
             author,
+            authors,
             isbn,
             description,
+            cover_url,
+            bibliography,
             owner,
             ..
         } = res.unwrap();
@@ -276,8 +711,25 @@ mod tests {
         assert_eq!(id, book_id);
         assert_eq!(title, "Test Title");
         assert_eq!(author, "Test Author");
+        let authors = authors
+            .iter()
+            .map(|a| (a.name.as_str(), a.role))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            authors,
+            vec![
+                ("Test Author", AuthorRole::Author),
+                ("Test Translator", AuthorRole::Translator)
+            ]
+        );
+        assert_eq!(bibliography.page_count, Some(320));
+        assert_eq!(bibliography.edition.as_deref(), Some("第2版"));
         assert_eq!(isbn, "Test ISBN");
         assert_eq!(description, "Test Description");
+        assert_eq!(
+            cover_url.as_deref(),
+            Some("https://covers.example.com/1.jpg")
+        );
         assert_eq!(owner.id, user.id);
 
         Ok(())
@@ -296,10 +748,12 @@ mod tests {
         let update_book = UpdateBook {
             book_id: book.id,
             title: book.title,
-            author: NEW_AUTHOR.into(), // ここが差分
+            authors: AuthorName::parse_list(NEW_AUTHOR), // ここが差分
             isbn: book.isbn,
             description: book.description,
+            bibliography: book.bibliography,
             requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap(),
+            expected_version: Some(book.version),
         };
         repo.update(update_book).await.unwrap();
 
@@ -310,6 +764,127 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_update_book_with_stale_version(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let requested_user = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book = repo.find_by_id(book_id).await?.unwrap();
+
+        let update = |expected_version| UpdateBook {
+            book_id,
+            title: book.title.clone(),
+            authors: AuthorName::parse_list("更新後の著者名"),
+            isbn: book.isbn.clone(),
+            description: book.description.clone(),
+            bibliography: book.bibliography.clone(),
+            requested_user,
+            expected_version,
+        };
+        repo.update(update(Some(book.version))).await?;
+        let updated = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(updated.version, book.version + 1);
+
+        // 取得した時点から更新されている場合は反映しない
+        let res = repo.update(update(Some(book.version))).await;
+        assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+        let res = repo
+            .delete(DeleteBook {
+                book_id,
+                requested_user,
+                expected_version: Some(book.version),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+
+        // 存在しない蔵書は従来どおり NotFound
+        let res = repo
+            .delete(DeleteBook {
+                book_id: BookId::new(),
+                requested_user,
+                expected_version: Some(book.version),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_patch_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let book = repo.find_by_id(book_id).await?.unwrap();
+
+        repo.patch(BookPatch {
+            book_id,
+            title: None,
+            authors: None,
+            isbn: None,
+            description: Some("説明だけを変更".into()),
+            bibliography: BibliographyPatch {
+                publisher: Some(Some("出版社".into())),
+                ..Default::default()
+            },
+            requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            expected_version: Some(book.version),
+        })
+        .await?;
+
+        let patched = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(patched.description, "説明だけを変更");
+        assert_eq!(patched.title, book.title);
+        assert_eq!(patched.author, book.author);
+        assert_eq!(patched.isbn, book.isbn);
+        assert_eq!(patched.bibliography.publisher.as_deref(), Some("出版社"));
+        assert_eq!(patched.version, book.version + 1);
+
+        // 著者を置き換え、出版社を空にする
+        repo.patch(BookPatch {
+            book_id,
+            title: None,
+            authors: Some(AuthorName::parse_list("著者A、著者B")),
+            isbn: None,
+            description: None,
+            bibliography: BibliographyPatch {
+                publisher: Some(None),
+                ..Default::default()
+            },
+            requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            expected_version: None,
+        })
+        .await?;
+        let patched = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(patched.author, "著者A; 著者B");
+        assert_eq!(patched.authors.len(), 2);
+        assert_eq!(patched.bibliography.publisher, None);
+        assert_eq!(patched.description, "説明だけを変更");
+
+        // 著者が多く、つなげた文字列が 255 文字を超えても保存できる
+        let authors = (0..30)
+            .map(|i| AuthorName {
+                name: format!("著者{i:02}{}", "x".repeat(20)),
+                role: AuthorRole::Author,
+            })
+            .collect::<Vec<_>>();
+        repo.patch(BookPatch {
+            book_id,
+            title: None,
+            authors: Some(authors.clone()),
+            isbn: None,
+            description: None,
+            bibliography: Default::default(),
+            requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            expected_version: None,
+        })
+        .await?;
+        let patched = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(patched.authors.len(), 30);
+        assert_eq!(AuthorName::parse_list(&patched.author), authors);
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common", "book"))]
     async fn test_delete_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
         let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
@@ -318,6 +893,7 @@ mod tests {
         repo.delete(DeleteBook {
             book_id,
             requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            expected_version: None,
         })
         .await?;
         let book = repo.find_by_id(book_id).await?;
@@ -326,4 +902,54 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_update_book_cover(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let owner = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // 所有者以外は差し替えられない
+        let res = repo
+            .update_cover(UpdateBookCover {
+                book_id,
+                cover_image: "covers/other.png".into(),
+                requested_user: UserId::new(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let old = repo
+            .update_cover(UpdateBookCover {
+                book_id,
+                cover_image: "covers/new.png".into(),
+                requested_user: owner,
+            })
+            .await?;
+        assert_eq!(old, None);
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.cover_image.as_deref(), Some("covers/new.png"));
+
+        // 差し替える前のキーを返す
+        let old = repo
+            .update_cover(UpdateBookCover {
+                book_id,
+                cover_image: "covers/newer.png".into(),
+                requested_user: owner,
+            })
+            .await?;
+        assert_eq!(old.as_deref(), Some("covers/new.png"));
+
+        // 削除した蔵書の表紙のキーを返す
+        let deleted = repo
+            .delete(DeleteBook {
+                book_id,
+                requested_user: owner,
+                expected_version: None,
+            })
+            .await?;
+        assert_eq!(deleted.as_deref(), Some("covers/newer.png"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,709 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        book::{
+            event::{BookPatch, CreateBook, DeleteBook, UpdateBook, UpdateBookCover},
+            Book, BookListOptions,
+        },
+        book_condition::{
+            event::{MarkBookFound, UpdateBookCondition, UpdateConditionCharge},
+            BookConditionRecord,
+        },
+        book_transfer::{
+            event::{
+                AcceptBookTransfer, CancelBookTransfer, DeclineBookTransfer, ForceBookTransfer,
+                OfferBookTransfer,
+            },
+            BookTransfer,
+        },
+        checkout::{
+            event::{
+                CreateCheckout, CreateCheckoutOnBehalf, ForceReturned, ReportLost, ReturnDamaged,
+                UpdateReturned,
+            },
+            Checkout, CheckoutHistoryOptions,
+        },
+        genre::{
+            event::{AddBookGenre, CreateGenre, DeleteGenre, RemoveBookGenre},
+            Genre,
+        },
+        id::{BookId, GenreId, UserId},
+        list::PaginatedList,
+        review::{
+            event::{CreateReview, DeleteReview, ModerateReview, UpdateReview},
+            Review,
+        },
+        tag::{
+            event::{AddBookTag, RemoveBookTag},
+            Tag,
+        },
+        user::{
+            event::{
+                CreateUser, DeleteUser, ResetUserPassword, UpdateUserEmail, UpdateUserName,
+                UpdateUserPassword, UpdateUserRole,
+            },
+            User,
+        },
+    },
+    repository::{
+        book::BookRepository, book_condition::BookConditionRepository,
+        book_transfer::BookTransferRepository, checkout::CheckoutRepository,
+        genre::GenreRepository, review::ReviewRepository, tag::TagRepository, user::UserRepository,
+    },
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    database::{
+        model::book::{BookCacheKey, BookListCacheKey, BookListVersionKey},
+        ConnectionPool,
+    },
+    redis::{model::JsonValue, RedisClient},
+};
+
+// 蔵書の詳細・一覧のキャッシュ。Redis の障害時はキャッシュを使わずに処理を続ける
+#[derive(new)]
+pub struct BookCache {
+    kv: Arc<RedisClient>,
+    ttl: u64,
+}
+
+impl BookCache {
+    async fn get_book(&self, book_id: BookId) -> Option<Book> {
+        let cached = self.kv.get(&BookCacheKey::from(book_id)).await;
+        decode_cached(cached)
+    }
+
+    async fn put_book(&self, book: &Book) {
+        let res = match JsonValue::encode(book) {
+            Ok(value) => {
+                self.kv
+                    .set_ex(&BookCacheKey::from(book.id), &value, self.ttl)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        log_error(res, "Failed to cache book");
+    }
+
+    // 本棚での絞り込みは利用者ごとに結果が異なるため、キャッシュしない
+    async fn list_key(&self, options: &BookListOptions) -> Option<BookListCacheKey> {
+        if options.filter.shelf.is_some() {
+            return None;
+        }
+        let version = match self.kv.get(&BookListVersionKey).await {
+            Ok(version) => version.map_or(0, |v| v.0),
+            Err(e) => {
+                tracing::warn!(error.message = %e, "Failed to read book list version");
+                return None;
+            }
+        };
+        Some(BookListCacheKey {
+            version,
+            limit: options.limit,
+            offset: options.offset,
+            tag: options.filter.tag.clone(),
+            genre_id: options.filter.genre_id,
+            sort: options.sort,
+        })
+    }
+
+    async fn get_list(&self, key: &BookListCacheKey) -> Option<PaginatedList<Book>> {
+        decode_cached(self.kv.get(key).await)
+    }
+
+    async fn put_list(&self, key: &BookListCacheKey, list: &PaginatedList<Book>) {
+        let res = match JsonValue::encode(list) {
+            Ok(value) => self.kv.set_ex(key, &value, self.ttl).await,
+            Err(e) => Err(e),
+        };
+        log_error(res, "Failed to cache book list");
+    }
+
+    // 蔵書の詳細を破棄し、一覧の世代を進める
+    async fn invalidate(&self, book_id: Option<BookId>) {
+        if let Some(book_id) = book_id {
+            log_error(
+                self.kv.delete(&BookCacheKey::from(book_id)).await,
+                "Failed to invalidate book cache",
+            );
+        }
+        log_error(
+            self.kv.incr(&BookListVersionKey).await.map(|_| ()),
+            "Failed to invalidate book list cache",
+        );
+    }
+}
+
+fn decode_cached<T: serde::de::DeserializeOwned>(
+    cached: AppResult<Option<JsonValue>>,
+) -> Option<T> {
+    match cached.and_then(|v| v.map(|v| v.decode()).transpose()) {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::warn!(error.message = %e, "Failed to read cache");
+            None
+        }
+    }
+}
+
+fn log_error(res: AppResult<()>, message: &str) {
+    if let Err(e) = res {
+        tracing::warn!(error.message = %e, "{message}");
+    }
+}
+
+// BookRepository の読み込みをキャッシュするデコレータ
+#[derive(new)]
+pub struct CachedBookRepository {
+    inner: Arc<dyn BookRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl BookRepository for CachedBookRepository {
+    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<BookId> {
+        let book_id = self.inner.create(event, user_id).await?;
+        self.cache.invalidate(None).await;
+        Ok(book_id)
+    }
+
+    async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>> {
+        let Some(key) = self.cache.list_key(&options).await else {
+            return self.inner.find_all(options).await;
+        };
+        if let Some(list) = self.cache.get_list(&key).await {
+            return Ok(list);
+        }
+        let list = self.inner.find_all(options).await?;
+        self.cache.put_list(&key, &list).await;
+        Ok(list)
+    }
+
+    async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>> {
+        if let Some(book) = self.cache.get_book(book_id).await {
+            return Ok(Some(book));
+        }
+        let book = self.inner.find_by_id(book_id).await?;
+        if let Some(book) = &book {
+            self.cache.put_book(book).await;
+        }
+        Ok(book)
+    }
+
+    async fn update(&self, event: UpdateBook) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.update(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn patch(&self, event: BookPatch) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.patch(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<Option<String>> {
+        let book_id = event.book_id;
+        let old_cover_image = self.inner.update_cover(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(old_cover_image)
+    }
+
+    async fn delete(&self, event: DeleteBook) -> AppResult<Option<String>> {
+        let book_id = event.book_id;
+        let cover_image = self.inner.delete(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(cover_image)
+    }
+}
+
+// 貸出・返却で蔵書の貸出状況が変わるので、該当する蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingCheckoutRepository {
+    inner: Arc<dyn CheckoutRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl CheckoutRepository for BookCacheInvalidatingCheckoutRepository {
+    async fn create(&self, event: CreateCheckout) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.create(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn update_returned(&self, event: UpdateReturned) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.update_returned(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn create_on_behalf(&self, event: CreateCheckoutOnBehalf) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.create_on_behalf(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn force_returned(&self, event: ForceReturned) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.force_returned(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn return_damaged(&self, event: ReturnDamaged) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.return_damaged(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn report_lost(&self, event: ReportLost) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.report_lost(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>> {
+        self.inner.find_unreturned_all().await
+    }
+
+    async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>> {
+        self.inner.find_unreturned_by_user_id(user_id).await
+    }
+
+    async fn find_history_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>> {
+        self.inner.find_history_by_book_id(book_id).await
+    }
+
+    async fn find_history_by_user_id(
+        &self,
+        user_id: UserId,
+        options: CheckoutHistoryOptions,
+    ) -> AppResult<PaginatedList<Checkout>> {
+        self.inner.find_history_by_user_id(user_id, options).await
+    }
+}
+
+// 譲渡で蔵書の所有者が変わるので、該当する蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingBookTransferRepository {
+    inner: Arc<dyn BookTransferRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl BookTransferRepository for BookCacheInvalidatingBookTransferRepository {
+    async fn offer(&self, event: OfferBookTransfer) -> AppResult<BookTransfer> {
+        self.inner.offer(event).await
+    }
+
+    async fn accept(&self, event: AcceptBookTransfer) -> AppResult<BookTransfer> {
+        let transfer = self.inner.accept(event).await?;
+        self.cache.invalidate(Some(transfer.book_id)).await;
+        Ok(transfer)
+    }
+
+    async fn decline(&self, event: DeclineBookTransfer) -> AppResult<BookTransfer> {
+        self.inner.decline(event).await
+    }
+
+    async fn cancel(&self, event: CancelBookTransfer) -> AppResult<BookTransfer> {
+        self.inner.cancel(event).await
+    }
+
+    async fn force(&self, event: ForceBookTransfer) -> AppResult<BookTransfer> {
+        let transfer = self.inner.force(event).await?;
+        self.cache.invalidate(Some(transfer.book_id)).await;
+        Ok(transfer)
+    }
+
+    async fn find_pending_by_user_id(&self, user_id: UserId) -> AppResult<Vec<BookTransfer>> {
+        self.inner.find_pending_by_user_id(user_id).await
+    }
+
+    async fn find_history(&self, book_id: BookId) -> AppResult<Vec<BookTransfer>> {
+        self.inner.find_history(book_id).await
+    }
+}
+
+// 紛失中の蔵書が見つかったり修理したりすると蔵書の状態が変わるので、該当する蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingBookConditionRepository {
+    inner: Arc<dyn BookConditionRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl BookConditionRepository for BookCacheInvalidatingBookConditionRepository {
+    async fn mark_found(&self, event: MarkBookFound) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.mark_found(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn update_condition(&self, event: UpdateBookCondition) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.update_condition(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    // 弁償の記録は蔵書の詳細に含まれない
+    async fn update_charge(&self, event: UpdateConditionCharge) -> AppResult<()> {
+        self.inner.update_charge(event).await
+    }
+
+    async fn find_history_by_book_id(
+        &self,
+        book_id: BookId,
+    ) -> AppResult<Vec<BookConditionRecord>> {
+        self.inner.find_history_by_book_id(book_id).await
+    }
+}
+
+// タグの付け外しで蔵書の内容が変わるので、該当する蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingTagRepository {
+    inner: Arc<dyn TagRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl TagRepository for BookCacheInvalidatingTagRepository {
+    async fn find_all(&self) -> AppResult<Vec<Tag>> {
+        self.inner.find_all().await
+    }
+
+    async fn add_to_book(&self, event: AddBookTag) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.add_to_book(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn remove_from_book(&self, event: RemoveBookTag) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.remove_from_book(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+}
+
+// ジャンルの付け外しで蔵書の内容が変わるので、該当する蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingGenreRepository {
+    inner: Arc<dyn GenreRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl GenreRepository for BookCacheInvalidatingGenreRepository {
+    async fn find_all(&self) -> AppResult<Vec<Genre>> {
+        self.inner.find_all().await
+    }
+
+    async fn find_by_id(&self, genre_id: GenreId) -> AppResult<Option<Genre>> {
+        self.inner.find_by_id(genre_id).await
+    }
+
+    // 追加したジャンルはまだどの蔵書にも付いていないので、キャッシュには影響しない
+    async fn create(&self, event: CreateGenre) -> AppResult<Genre> {
+        self.inner.create(event).await
+    }
+
+    // 蔵書に付いているジャンルは削除できないので、キャッシュには影響しない
+    async fn delete(&self, event: DeleteGenre) -> AppResult<()> {
+        self.inner.delete(event).await
+    }
+
+    async fn add_to_book(&self, event: AddBookGenre) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.add_to_book(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn remove_from_book(&self, event: RemoveBookGenre) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.remove_from_book(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+}
+
+// レビューの投稿・編集・削除・非表示で蔵書の評価が変わるので、該当する蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingReviewRepository {
+    inner: Arc<dyn ReviewRepository>,
+    cache: Arc<BookCache>,
+}
+
+#[async_trait]
+impl ReviewRepository for BookCacheInvalidatingReviewRepository {
+    async fn create(&self, event: CreateReview) -> AppResult<Review> {
+        let book_id = event.book_id;
+        let review = self.inner.create(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(review)
+    }
+
+    async fn find_by_book_id(
+        &self,
+        book_id: BookId,
+        include_hidden: bool,
+    ) -> AppResult<Vec<Review>> {
+        self.inner.find_by_book_id(book_id, include_hidden).await
+    }
+
+    async fn update(&self, event: UpdateReview) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.update(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteReview) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.delete(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+
+    async fn moderate(&self, event: ModerateReview) -> AppResult<()> {
+        let book_id = event.book_id;
+        self.inner.moderate(event).await?;
+        self.cache.invalidate(Some(book_id)).await;
+        Ok(())
+    }
+}
+
+// 蔵書のレスポンスには所有者・借り手の名前が含まれ、ユーザーを削除すると所有する蔵書も削除されるので、
+// そのユーザーが所有または借りている蔵書のキャッシュを破棄するデコレータ
+#[derive(new)]
+pub struct BookCacheInvalidatingUserRepository {
+    inner: Arc<dyn UserRepository>,
+    db: ConnectionPool,
+    cache: Arc<BookCache>,
+}
+
+impl BookCacheInvalidatingUserRepository {
+    async fn find_related_book_ids(&self, user_id: UserId) -> AppResult<Vec<BookId>> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT book_id AS "book_id: BookId" FROM books WHERE user_id = $1
+                UNION
+                SELECT book_id AS "book_id: BookId" FROM checkouts WHERE user_id = $1
+            "#,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map(|ids| ids.into_iter().flatten().collect())
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    async fn invalidate_books(&self, book_ids: Vec<BookId>) {
+        for book_id in book_ids {
+            log_error(
+                self.cache.kv.delete(&BookCacheKey::from(book_id)).await,
+                "Failed to invalidate book cache",
+            );
+        }
+        self.cache.invalidate(None).await;
+    }
+}
+
+#[async_trait]
+impl UserRepository for BookCacheInvalidatingUserRepository {
+    async fn find_current_user(&self, current_user_id: UserId) -> AppResult<Option<User>> {
+        self.inner.find_current_user(current_user_id).await
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<User>> {
+        self.inner.find_all().await
+    }
+
+    async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
+        self.inner.find_by_email(email).await
+    }
+
+    async fn create(&self, event: CreateUser) -> AppResult<User> {
+        self.inner.create(event).await
+    }
+
+    async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()> {
+        self.inner.update_password(event).await
+    }
+
+    async fn reset_password(&self, event: ResetUserPassword) -> AppResult<()> {
+        self.inner.reset_password(event).await
+    }
+
+    async fn update_role(&self, event: UpdateUserRole) -> AppResult<()> {
+        self.inner.update_role(event).await
+    }
+
+    async fn update_name(&self, event: UpdateUserName) -> AppResult<()> {
+        let user_id = event.user_id;
+        self.inner.update_name(event).await?;
+        let book_ids = self.find_related_book_ids(user_id).await?;
+        self.invalidate_books(book_ids).await;
+        Ok(())
+    }
+
+    async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()> {
+        let user_id = event.user_id;
+        self.inner.update_email(event).await?;
+        let book_ids = self.find_related_book_ids(user_id).await?;
+        self.invalidate_books(book_ids).await;
+        Ok(())
+    }
+
+    // 削除すると関連する行も消えるため、削除前に対象の蔵書を調べておく
+    async fn delete(&self, event: DeleteUser) -> AppResult<Vec<String>> {
+        let book_ids = self.find_related_book_ids(event.user_id).await?;
+        let cover_images = self.inner.delete(event).await?;
+        self.invalidate_books(book_ids).await;
+        Ok(cover_images)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::{
+        model::{
+            author::{AuthorRole, BookAuthor},
+            book::{Bibliography, Checkout},
+            book_condition::BookCondition,
+            id::{AuthorId, CheckoutId},
+            review::RatingSummary,
+            user::{BookOwner, CheckoutUser},
+        },
+        repository::book::MockBookRepository,
+    };
+    use shared::config::RedisConfig;
+
+    fn book() -> Book {
+        Book {
+            id: BookId::new(),
+            title: "Rust によるWebアプリケーション開発".into(),
+            author: "Yuki Toyoda".into(),
+            authors: vec![BookAuthor {
+                id: AuthorId::new(),
+                name: "Yuki Toyoda".into(),
+                role: AuthorRole::Author,
+            }],
+            isbn: "9784065369579".into(),
+            description: "".into(),
+            cover_url: None,
+            cover_image: None,
+            bibliography: Bibliography {
+                publisher: Some("講談社".into()),
+                publication_year: Some(2024),
+                ..Default::default()
+            },
+            tags: vec!["rust".into()],
+            genres: vec![Genre {
+                id: GenreId::new(),
+                name: "プログラミング".into(),
+                parent_id: None,
+            }],
+            rating: RatingSummary {
+                average: Some(4.5),
+                count: 2,
+            },
+            owner: BookOwner {
+                id: UserId::new(),
+                name: "Eleazar Fig".into(),
+            },
+            version: 1,
+            checkout: Some(Checkout {
+                checkout_id: CheckoutId::new(),
+                checked_out_by: CheckoutUser {
+                    id: UserId::new(),
+                    name: "Tom".into(),
+                },
+                checked_out_at: Utc::now(),
+            }),
+            condition: BookCondition::Damaged,
+        }
+    }
+
+    #[test]
+    fn test_cached_json_roundtrip() -> anyhow::Result<()> {
+        let book = book();
+        let decoded: Book = JsonValue::encode(&book)?.decode()?;
+        assert_eq!(format!("{decoded:?}"), format!("{book:?}"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_inner_when_redis_is_unavailable() -> anyhow::Result<()> {
+        let book = book();
+        let book_id = book.id;
+        let mut inner = MockBookRepository::new();
+        inner
+            .expect_find_by_id()
+            .times(1)
+            .return_once(move |_| Ok(Some(book)));
+        inner.expect_delete().times(1).returning(|_| Ok(None));
+
+        // 接続できない Redis を指定しても、読み書きは元のリポジトリに委ねられる
+        let kv = Arc::new(RedisClient::new(&RedisConfig {
+            host: "127.0.0.1".into(),
+            port: 1,
+        })?);
+        let repo = CachedBookRepository::new(Arc::new(inner), Arc::new(BookCache::new(kv, 60)));
+
+        let found = repo.find_by_id(book_id).await?;
+        assert_eq!(found.map(|b| b.id), Some(book_id));
+        repo.delete(DeleteBook {
+            book_id,
+            requested_user: UserId::new(),
+            expected_version: None,
+        })
+        .await?;
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_user_delete_falls_back_when_redis_is_unavailable(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        use kernel::repository::user::MockUserRepository;
+        use std::str::FromStr;
+
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let mut inner = MockUserRepository::new();
+        inner.expect_delete().times(1).returning(|_| Ok(Vec::new()));
+        let kv = Arc::new(RedisClient::new(&RedisConfig {
+            host: "127.0.0.1".into(),
+            port: 1,
+        })?);
+        let repo = BookCacheInvalidatingUserRepository::new(
+            Arc::new(inner),
+            ConnectionPool::new(pool),
+            Arc::new(BookCache::new(kv, 60)),
+        );
+
+        // 所有する蔵書を調べてから削除し、キャッシュの破棄に失敗しても削除自体は成功させる
+        let book_ids = repo.find_related_book_ids(user_id).await?;
+        assert!(book_ids.contains(&BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?));
+        repo.delete(DeleteUser { user_id }).await?;
+        Ok(())
+    }
+}
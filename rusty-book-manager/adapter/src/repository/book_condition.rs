@@ -0,0 +1,400 @@
+use crate::database::{
+    model::book_condition::{charge_columns, BookConditionRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        book_condition::{
+            event::{MarkBookFound, UpdateBookCondition, UpdateConditionCharge},
+            BookCondition, BookConditionRecord, CheckoutCharge,
+        },
+        domain_event::DomainEvent,
+        id::{BookConditionId, BookId, CheckoutId, UserId},
+    },
+    repository::book_condition::BookConditionRepository,
+};
+use shared::error::{AppError, AppResult};
+use std::str::FromStr;
+
+use super::outbox::record_event;
+
+type Transaction<'a> = sqlx::Transaction<'a, sqlx::Postgres>;
+
+#[derive(new)]
+pub struct BookConditionRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl BookConditionRepository for BookConditionRepositoryImpl {
+    async fn mark_found(&self, event: MarkBookFound) -> AppResult<()> {
+        if event.condition == BookCondition::Lost {
+            return Err(AppError::UnprocessableEntity(
+                "見つかった蔵書の状態に紛失は指定できません。".into(),
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+        if lock_condition(&mut tx, event.book_id).await? != BookCondition::Lost {
+            return Err(AppError::UnprocessableEntity(format!(
+                "書籍（{}）は紛失中ではありません。",
+                event.book_id
+            )));
+        }
+
+        record_condition(
+            &mut tx,
+            event.book_id,
+            event.condition,
+            None,
+            &event.note,
+            None,
+            event.requested_user,
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn update_condition(&self, event: UpdateBookCondition) -> AppResult<()> {
+        if event.condition == BookCondition::Lost {
+            return Err(AppError::UnprocessableEntity(
+                "紛失は貸出の届け出から記録してください。".into(),
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+        if lock_condition(&mut tx, event.book_id).await? == BookCondition::Lost {
+            return Err(AppError::UnprocessableEntity(format!(
+                "書籍（{}）は紛失中です。見つかったことを記録してください。",
+                event.book_id
+            )));
+        }
+
+        record_condition(
+            &mut tx,
+            event.book_id,
+            event.condition,
+            None,
+            &event.note,
+            None,
+            event.requested_user,
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn update_charge(&self, event: UpdateConditionCharge) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let checkout_id = sqlx::query_scalar!(
+            r#"
+                SELECT checkout_id AS "checkout_id: CheckoutId"
+                FROM book_conditions
+                WHERE book_condition_id = $1 AND book_id = $2
+                FOR UPDATE
+            "#,
+            event.book_condition_id as _,
+            event.book_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(format!(
+                "蔵書の状態の記録（{}）が見つかりませんでした。",
+                event.book_condition_id
+            ))
+        })?;
+        // 弁償は貸出に伴う破損・紛失についてのみ記録する
+        if checkout_id.is_none() {
+            return Err(AppError::UnprocessableEntity(format!(
+                "蔵書の状態の記録（{}）は貸出に伴うものではありません。",
+                event.book_condition_id
+            )));
+        }
+
+        let (charge_kind, charge_amount) = charge_columns(event.charge.as_ref());
+        sqlx::query!(
+            r#"
+                UPDATE book_conditions SET charge_kind = $2, charge_amount = $3
+                WHERE book_condition_id = $1
+            "#,
+            event.book_condition_id as _,
+            charge_kind,
+            charge_amount
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn find_history_by_book_id(
+        &self,
+        book_id: BookId,
+    ) -> AppResult<Vec<BookConditionRecord>> {
+        sqlx::query_as!(
+            BookConditionRow,
+            r#"
+                SELECT
+                book_condition_id,
+                book_id,
+                condition,
+                checkout_id AS "checkout_id: CheckoutId",
+                note,
+                charge_kind,
+                charge_amount,
+                recorded_by AS "recorded_by: UserId",
+                recorded_at
+                FROM book_conditions
+                WHERE book_id = $1
+                ORDER BY recorded_at DESC, book_condition_id
+            "#,
+            book_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(BookConditionRecord::try_from)
+        .collect()
+    }
+}
+
+// 現在の蔵書の状態を取得し、変更を終えるまで他の変更を待たせる
+async fn lock_condition(tx: &mut Transaction<'_>, book_id: BookId) -> AppResult<BookCondition> {
+    // 貸出と同じく SERIALIZABLE にして、状態の確認と変更の間に割り込まれないようにする
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+    let condition = sqlx::query_scalar!(
+        r#"
+            SELECT condition FROM books WHERE book_id = $1 FOR UPDATE
+        "#,
+        book_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| {
+        AppError::EntityNotFound(format!("書籍（{book_id}）が見つかりませんでした。"))
+    })?;
+    BookCondition::from_str(&condition).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
+
+// 蔵書の状態を変え、履歴に記録する。貸出の破損・紛失の届け出からも同じトランザクションで呼ぶ
+pub(crate) async fn record_condition(
+    tx: &mut Transaction<'_>,
+    book_id: BookId,
+    condition: BookCondition,
+    checkout_id: Option<CheckoutId>,
+    note: &str,
+    charge: Option<&CheckoutCharge>,
+    recorded_by: UserId,
+) -> AppResult<()> {
+    let res = sqlx::query!(
+        r#"
+            UPDATE books SET condition = $2 WHERE book_id = $1
+        "#,
+        book_id as _,
+        condition.as_ref()
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    if res.rows_affected() < 1 {
+        return Err(AppError::EntityNotFound(format!(
+            "書籍（{book_id}）が見つかりませんでした。"
+        )));
+    }
+
+    let (charge_kind, charge_amount) = charge_columns(charge);
+    sqlx::query!(
+        r#"
+            INSERT INTO book_conditions
+            (book_condition_id, book_id, condition, checkout_id, note,
+             charge_kind, charge_amount, recorded_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        BookConditionId::new() as _,
+        book_id as _,
+        condition.as_ref(),
+        checkout_id as _,
+        note,
+        charge_kind,
+        charge_amount,
+        recorded_by as _
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    record_event(
+        tx,
+        DomainEvent::BookConditionChanged {
+            book_id,
+            condition,
+            checkout_id,
+            user_id: recorded_by,
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::checkout::CheckoutRepositoryImpl;
+    use chrono::Utc;
+    use kernel::{
+        model::checkout::event::{CreateCheckout, ReportLost, ReturnDamaged},
+        repository::checkout::CheckoutRepository,
+    };
+
+    // 紛失した蔵書は見つかるまで貸し出せず、見つかったら元どおり貸し出せる
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_report_lost_and_mark_found(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let checkout_repo = CheckoutRepositoryImpl::new(db.clone());
+        let repo = BookConditionRepositoryImpl::new(db);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let found = |condition| MarkBookFound {
+            book_id,
+            condition,
+            note: "書庫の奥にあった".into(),
+            requested_user: user_id,
+        };
+
+        // 紛失中でない蔵書は見つかったことにできない
+        let res = repo.mark_found(found(BookCondition::Good)).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        checkout_repo
+            .create(CreateCheckout::new(book_id, user_id, Utc::now()))
+            .await?;
+        let checkout_id = checkout_repo.find_unreturned_by_user_id(user_id).await?[0].id;
+        checkout_repo
+            .report_lost(ReportLost::new(
+                checkout_id,
+                book_id,
+                user_id,
+                "電車に置き忘れた".into(),
+                Some(CheckoutCharge::Replacement),
+                Utc::now(),
+            ))
+            .await?;
+
+        let res = checkout_repo
+            .create(CreateCheckout::new(book_id, user_id, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let res = repo.mark_found(found(BookCondition::Lost)).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        repo.mark_found(found(BookCondition::Good)).await?;
+
+        let history = repo.find_history_by_book_id(book_id).await?;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].condition, BookCondition::Good);
+        assert_eq!(history[0].checkout_id, None);
+        assert_eq!(history[1].condition, BookCondition::Lost);
+        assert_eq!(history[1].checkout_id, Some(checkout_id));
+        assert_eq!(history[1].charge, Some(CheckoutCharge::Replacement));
+
+        checkout_repo
+            .create(CreateCheckout::new(book_id, user_id, Utc::now()))
+            .await?;
+        Ok(())
+    }
+
+    // 破損して返却された蔵書を修理して元に戻し、弁償の記録を後から直す
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_repair_and_update_charge(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let checkout_repo = CheckoutRepositoryImpl::new(db.clone());
+        let repo = BookConditionRepositoryImpl::new(db);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let update = |condition| UpdateBookCondition {
+            book_id,
+            condition,
+            note: "製本し直した".into(),
+            requested_user: user_id,
+        };
+
+        checkout_repo
+            .create(CreateCheckout::new(book_id, user_id, Utc::now()))
+            .await?;
+        let checkout_id = checkout_repo.find_unreturned_by_user_id(user_id).await?[0].id;
+        checkout_repo
+            .return_damaged(ReturnDamaged::new(
+                checkout_id,
+                book_id,
+                user_id,
+                "表紙が破れた".into(),
+                None,
+                Utc::now(),
+            ))
+            .await?;
+
+        // 紛失は貸出の届け出からしか記録できない
+        let res = repo.update_condition(update(BookCondition::Lost)).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        repo.update_condition(update(BookCondition::Good)).await?;
+
+        let history = repo.find_history_by_book_id(book_id).await?;
+        assert_eq!(history.len(), 2);
+        let find = |history: &[BookConditionRecord], condition| {
+            history
+                .iter()
+                .find(|h| h.condition == condition)
+                .map(|h| (h.id, h.note.clone(), h.charge.clone()))
+                .unwrap()
+        };
+        let (repaired, note, _) = find(&history, BookCondition::Good);
+        assert_eq!(note, "製本し直した");
+        let (damaged, _, charge_before) = find(&history, BookCondition::Damaged);
+        assert_eq!(charge_before, None);
+
+        // 貸出に伴わない記録には弁償を記録できない
+        let charge = |book_condition_id, charge| UpdateConditionCharge {
+            book_condition_id,
+            book_id,
+            charge,
+        };
+        let res = repo
+            .update_charge(charge(repaired, Some(CheckoutCharge::Replacement)))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = repo
+            .update_charge(charge(BookConditionId::new(), None))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.update_charge(charge(damaged, Some(CheckoutCharge::Fee { amount: 800 })))
+            .await?;
+        let history = repo.find_history_by_book_id(book_id).await?;
+        assert_eq!(
+            find(&history, BookCondition::Damaged).2,
+            Some(CheckoutCharge::Fee { amount: 800 })
+        );
+        repo.update_charge(charge(damaged, None)).await?;
+        let history = repo.find_history_by_book_id(book_id).await?;
+        assert_eq!(find(&history, BookCondition::Damaged).2, None);
+        Ok(())
+    }
+}
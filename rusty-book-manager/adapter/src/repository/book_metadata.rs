@@ -0,0 +1,250 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use kernel::{
+    model::book::metadata::{normalize_isbn, BookMetadata},
+    repository::book_metadata::BookMetadataProvider,
+};
+use serde::Deserialize;
+use shared::error::{AppError, AppResult};
+
+// Open Library の Books API（/api/books?jscmd=data）から書誌情報を取得する
+pub struct OpenLibraryMetadataProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl OpenLibraryMetadataProvider {
+    pub fn new(base_url: String, timeout: Duration) -> AppResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!("rusty-book-manager/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+        Ok(Self { client, base_url })
+    }
+}
+
+#[async_trait]
+impl BookMetadataProvider for OpenLibraryMetadataProvider {
+    async fn lookup(&self, isbn: &str) -> AppResult<Option<BookMetadata>> {
+        // ISBN の形式でなければ問い合わせるまでもなく見つからない
+        let Some(isbn) = normalize_isbn(isbn) else {
+            return Ok(None);
+        };
+        let bibkey = format!("ISBN:{isbn}");
+        let mut books: HashMap<String, OpenLibraryBook> = self
+            .client
+            .get(format!("{}/api/books", self.base_url))
+            .query(&[
+                ("bibkeys", bibkey.as_str()),
+                ("format", "json"),
+                ("jscmd", "data"),
+            ])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+        Ok(books.remove(&bibkey).map(|book| book.into_metadata(isbn)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryBook {
+    title: Option<String>,
+    subtitle: Option<String>,
+    #[serde(default)]
+    authors: Vec<OpenLibraryAuthor>,
+    notes: Option<OpenLibraryText>,
+    cover: Option<OpenLibraryCover>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryAuthor {
+    name: String,
+}
+
+// 文字列のほか {"type": "/type/text", "value": "..."} の形で返ってくることがある
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpenLibraryText {
+    Plain(String),
+    Typed { value: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryCover {
+    small: Option<String>,
+    medium: Option<String>,
+    large: Option<String>,
+}
+
+impl OpenLibraryBook {
+    fn into_metadata(self, isbn: String) -> BookMetadata {
+        let OpenLibraryBook {
+            title,
+            subtitle,
+            authors,
+            notes,
+            cover,
+        } = self;
+        let title = match (title, subtitle) {
+            (Some(title), Some(subtitle)) => Some(format!("{title}: {subtitle}")),
+            (title, _) => title,
+        };
+        let author = (!authors.is_empty()).then(|| {
+            authors
+                .into_iter()
+                .map(|a| a.name)
+                .collect::<Vec<_>>()
+                .join("; ")
+        });
+        let description = notes.map(|notes| match notes {
+            OpenLibraryText::Plain(value) | OpenLibraryText::Typed { value } => value,
+        });
+        let cover_url = cover.and_then(|c| c.large.or(c.medium).or(c.small));
+        // books テーブルの列に収まるように切り詰める
+        BookMetadata {
+            isbn,
+            title: title.filter(|v| !v.is_empty()).map(truncate_column),
+            author: author.map(truncate_column),
+            description: description.filter(|v| !v.is_empty()).map(truncate_column),
+            cover_url,
+        }
+    }
+}
+
+// title・author・description は VARCHAR(255)
+const MAX_COLUMN_CHARS: usize = 255;
+
+fn truncate_column(mut value: String) -> String {
+    if let Some((idx, _)) = value.char_indices().nth(MAX_COLUMN_CHARS) {
+        value.truncate(idx);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    // 1 回だけ決まったレスポンスを返し、受け取ったリクエスト行を返すスタブサーバー
+    async fn stub_server(status: &str, body: &str) -> anyhow::Result<(String, JoinHandle<String>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut chunk).await.expect("read");
+                if n == 0 {
+                    break;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+            }
+            stream.write_all(response.as_bytes()).await.expect("write");
+            let request = String::from_utf8_lossy(&buf);
+            request.lines().next().unwrap_or_default().to_string()
+        });
+        Ok((base_url, handle))
+    }
+
+    fn provider(base_url: String) -> anyhow::Result<OpenLibraryMetadataProvider> {
+        Ok(OpenLibraryMetadataProvider::new(
+            base_url,
+            Duration::from_secs(5),
+        )?)
+    }
+
+    #[tokio::test]
+    async fn test_lookup_book() -> anyhow::Result<()> {
+        let body = r#"{
+            "ISBN:9784065369579": {
+                "title": "RustによるWebアプリケーション開発",
+                "subtitle": "設計からリリース・運用まで",
+                "authors": [{"name": "豊田優貴"}, {"name": "松本健太郎"}],
+                "notes": {"type": "/type/text", "value": "Rust で Web アプリを作る"},
+                "cover": {
+                    "small": "https://covers.example.com/s.jpg",
+                    "medium": "https://covers.example.com/m.jpg"
+                }
+            }
+        }"#;
+        let (base_url, server) = stub_server("200 OK", body).await?;
+
+        let metadata = provider(base_url)?.lookup("978-4-06-536957-9").await?;
+
+        let request_line = server.await?;
+        assert!(request_line.starts_with("GET /api/books?"));
+        assert!(request_line.contains("bibkeys=ISBN%3A9784065369579"));
+        assert_eq!(
+            metadata,
+            Some(BookMetadata {
+                isbn: "9784065369579".into(),
+                title: Some("RustによるWebアプリケーション開発: 設計からリリース・運用まで".into()),
+                author: Some("豊田優貴; 松本健太郎".into()),
+                description: Some("Rust で Web アプリを作る".into()),
+                cover_url: Some("https://covers.example.com/m.jpg".into()),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_metadata_truncates_long_values() {
+        let book = OpenLibraryBook {
+            title: Some("長".repeat(300)),
+            subtitle: None,
+            authors: vec![OpenLibraryAuthor {
+                name: "a".repeat(300),
+            }],
+            notes: Some(OpenLibraryText::Plain("説明".repeat(200))),
+            cover: None,
+        };
+        let metadata = book.into_metadata("9784065369579".into());
+        assert_eq!(metadata.title.unwrap().chars().count(), 255);
+        assert_eq!(metadata.author.unwrap().chars().count(), 255);
+        assert_eq!(metadata.description.unwrap().chars().count(), 255);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_book_not_found() -> anyhow::Result<()> {
+        let (base_url, server) = stub_server("200 OK", "{}").await?;
+        let metadata = provider(base_url)?.lookup("9784065369579").await?;
+        server.await?;
+        assert_eq!(metadata, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lookup_book_with_server_error() -> anyhow::Result<()> {
+        let (base_url, server) = stub_server("503 Service Unavailable", "").await?;
+        let res = provider(base_url)?.lookup("9784065369579").await;
+        server.await?;
+        assert!(matches!(res, Err(AppError::ExternalServiceError(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lookup_invalid_isbn_without_request() -> anyhow::Result<()> {
+        // 接続先がなくても、ISBN の形式でなければエラーにならない
+        let metadata = provider("http://127.0.0.1:1".into())?
+            .lookup("not-an-isbn")
+            .await?;
+        assert_eq!(metadata, None);
+        Ok(())
+    }
+}
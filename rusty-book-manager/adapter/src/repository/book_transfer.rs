@@ -0,0 +1,822 @@
+use crate::database::{
+    model::book_transfer::{BookOwnershipRow, BookTransferRow, BookTransferStateRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        book_transfer::{
+            event::{
+                AcceptBookTransfer, CancelBookTransfer, DeclineBookTransfer, ForceBookTransfer,
+                OfferBookTransfer,
+            },
+            BookTransfer, BookTransferStatus,
+        },
+        domain_event::DomainEvent,
+        id::{BookId, BookTransferId, UserId},
+    },
+    repository::book_transfer::BookTransferRepository,
+};
+use shared::error::{AppError, AppResult};
+use std::str::FromStr;
+
+use super::outbox::record_event;
+
+type Transaction<'a> = sqlx::Transaction<'a, sqlx::Postgres>;
+
+#[derive(new)]
+pub struct BookTransferRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl BookTransferRepository for BookTransferRepositoryImpl {
+    async fn offer(&self, event: OfferBookTransfer) -> AppResult<BookTransfer> {
+        let mut tx = self.db.begin().await?;
+        set_transaction_serializable(&mut tx).await?;
+
+        // 所有者でない利用者には、蔵書が存在しないものとして扱う
+        let ownership = find_ownership(&mut tx, event.book_id)
+            .await?
+            .filter(|o| o.owner_id == event.requested_user)
+            .ok_or_else(|| book_not_found(event.book_id))?;
+        ensure_transferable(&ownership, event.book_id, event.to_user_id)?;
+        ensure_user_exists(&mut tx, event.to_user_id).await?;
+
+        let book_transfer_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO book_transfers
+                (book_id, from_user_id, from_user_name, to_user_id, to_user_name)
+                VALUES (
+                    $1,
+                    $2, (SELECT name FROM users WHERE user_id = $2),
+                    $3, (SELECT name FROM users WHERE user_id = $3)
+                )
+                RETURNING book_transfer_id AS "book_transfer_id: BookTransferId"
+            "#,
+            event.book_id as _,
+            event.requested_user as _,
+            event.to_user_id as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::UnprocessableEntity(format!(
+                    "蔵書（{}）には返事を待っている譲渡の申し出が既にあります。",
+                    event.book_id
+                ))
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookTransferOffered {
+                book_transfer_id,
+                book_id: event.book_id,
+                from_user_id: event.requested_user,
+                to_user_id: event.to_user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        self.find_by_id(book_transfer_id).await
+    }
+
+    async fn accept(&self, event: AcceptBookTransfer) -> AppResult<BookTransfer> {
+        let mut tx = self.db.begin().await?;
+        set_transaction_serializable(&mut tx).await?;
+
+        let transfer = find_pending_for(
+            &mut tx,
+            event.book_transfer_id,
+            event.book_id,
+            event.requested_user,
+        )
+        .await?;
+        // 申し出の後に貸し出された蔵書は、返却されるまで受け取れない
+        let ownership = find_ownership(&mut tx, transfer.book_id)
+            .await?
+            .ok_or_else(|| book_not_found(transfer.book_id))?;
+        if ownership.owner_id != transfer.from_user_id {
+            return Err(AppError::UnprocessableEntity(
+                "申し出の後に蔵書の所有者が変わっています。".into(),
+            ));
+        }
+        ensure_transferable(&ownership, transfer.book_id, transfer.to_user_id)?;
+
+        change_owner(&mut tx, transfer.book_id, transfer.to_user_id).await?;
+        resolve(
+            &mut tx,
+            event.book_transfer_id,
+            BookTransferStatus::Accepted,
+        )
+        .await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookTransferred {
+                book_transfer_id: event.book_transfer_id,
+                book_id: transfer.book_id,
+                from_user_id: transfer.from_user_id,
+                to_user_id: transfer.to_user_id,
+                forced: false,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        self.find_by_id(event.book_transfer_id).await
+    }
+
+    async fn decline(&self, event: DeclineBookTransfer) -> AppResult<BookTransfer> {
+        let mut tx = self.db.begin().await?;
+
+        let transfer = find_pending_for(
+            &mut tx,
+            event.book_transfer_id,
+            event.book_id,
+            event.requested_user,
+        )
+        .await?;
+        resolve(
+            &mut tx,
+            event.book_transfer_id,
+            BookTransferStatus::Declined,
+        )
+        .await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookTransferDeclined {
+                book_transfer_id: event.book_transfer_id,
+                book_id: transfer.book_id,
+                from_user_id: transfer.from_user_id,
+                to_user_id: transfer.to_user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        self.find_by_id(event.book_transfer_id).await
+    }
+
+    async fn cancel(&self, event: CancelBookTransfer) -> AppResult<BookTransfer> {
+        let mut tx = self.db.begin().await?;
+
+        let transfer = find_pending_offered_by(
+            &mut tx,
+            event.book_transfer_id,
+            event.book_id,
+            event.requested_user,
+        )
+        .await?;
+        resolve(
+            &mut tx,
+            event.book_transfer_id,
+            BookTransferStatus::Cancelled,
+        )
+        .await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookTransferCancelled {
+                book_transfer_id: event.book_transfer_id,
+                book_id: transfer.book_id,
+                from_user_id: transfer.from_user_id,
+                to_user_id: transfer.to_user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        self.find_by_id(event.book_transfer_id).await
+    }
+
+    async fn force(&self, event: ForceBookTransfer) -> AppResult<BookTransfer> {
+        let mut tx = self.db.begin().await?;
+        set_transaction_serializable(&mut tx).await?;
+
+        let ownership = find_ownership(&mut tx, event.book_id)
+            .await?
+            .ok_or_else(|| book_not_found(event.book_id))?;
+        ensure_transferable(&ownership, event.book_id, event.to_user_id)?;
+        ensure_user_exists(&mut tx, event.to_user_id).await?;
+
+        // 返事を待っている申し出は、元の所有者からのものなので取り消す
+        sqlx::query!(
+            r#"
+                UPDATE book_transfers
+                SET status = 'cancelled', resolved_at = CURRENT_TIMESTAMP(3)
+                WHERE book_id = $1 AND status = 'pending'
+            "#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let book_transfer_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO book_transfers (
+                    book_id, from_user_id, from_user_name, to_user_id, to_user_name,
+                    status, forced_by, resolved_at
+                )
+                VALUES (
+                    $1,
+                    $2, (SELECT name FROM users WHERE user_id = $2),
+                    $3, (SELECT name FROM users WHERE user_id = $3),
+                    'forced', $4, CURRENT_TIMESTAMP(3)
+                )
+                RETURNING book_transfer_id AS "book_transfer_id: BookTransferId"
+            "#,
+            event.book_id as _,
+            ownership.owner_id as _,
+            event.to_user_id as _,
+            event.requested_user as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        change_owner(&mut tx, event.book_id, event.to_user_id).await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookTransferred {
+                book_transfer_id,
+                book_id: event.book_id,
+                from_user_id: ownership.owner_id,
+                to_user_id: event.to_user_id,
+                forced: true,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        self.find_by_id(book_transfer_id).await
+    }
+
+    async fn find_pending_by_user_id(&self, user_id: UserId) -> AppResult<Vec<BookTransfer>> {
+        let rows = sqlx::query_as!(
+            BookTransferRow,
+            r#"
+                SELECT
+                    t.book_transfer_id,
+                    t.book_id,
+                    b.title AS book_title,
+                    t.from_user_id AS "from_user_id: UserId",
+                    COALESCE(f.name, t.from_user_name) AS "from_user_name!",
+                    t.to_user_id AS "to_user_id: UserId",
+                    COALESCE(r.name, t.to_user_name) AS "to_user_name!",
+                    t.status,
+                    t.forced_by AS "forced_by: UserId",
+                    t.created_at,
+                    t.resolved_at
+                FROM book_transfers AS t
+                INNER JOIN books AS b USING(book_id)
+                LEFT JOIN users AS f ON f.user_id = t.from_user_id
+                LEFT JOIN users AS r ON r.user_id = t.to_user_id
+                WHERE t.status = 'pending' AND (t.from_user_id = $1 OR t.to_user_id = $1)
+                ORDER BY t.created_at DESC, t.book_transfer_id
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        rows.into_iter().map(BookTransfer::try_from).collect()
+    }
+
+    async fn find_history(&self, book_id: BookId) -> AppResult<Vec<BookTransfer>> {
+        let rows = sqlx::query_as!(
+            BookTransferRow,
+            r#"
+                SELECT
+                    t.book_transfer_id,
+                    t.book_id,
+                    b.title AS book_title,
+                    t.from_user_id AS "from_user_id: UserId",
+                    COALESCE(f.name, t.from_user_name) AS "from_user_name!",
+                    t.to_user_id AS "to_user_id: UserId",
+                    COALESCE(r.name, t.to_user_name) AS "to_user_name!",
+                    t.status,
+                    t.forced_by AS "forced_by: UserId",
+                    t.created_at,
+                    t.resolved_at
+                FROM book_transfers AS t
+                INNER JOIN books AS b USING(book_id)
+                LEFT JOIN users AS f ON f.user_id = t.from_user_id
+                LEFT JOIN users AS r ON r.user_id = t.to_user_id
+                WHERE t.book_id = $1 AND t.status IN ('accepted', 'forced')
+                ORDER BY t.resolved_at, t.book_transfer_id
+            "#,
+            book_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // 履歴がない場合は、蔵書がない場合と区別する
+        if rows.is_empty() {
+            let exists = sqlx::query_scalar!(
+                r#"
+                    SELECT EXISTS (SELECT 1 FROM books WHERE book_id = $1) AS "exists!"
+                "#,
+                book_id as _
+            )
+            .fetch_one(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+            if !exists {
+                return Err(book_not_found(book_id));
+            }
+        }
+        rows.into_iter().map(BookTransfer::try_from).collect()
+    }
+}
+
+impl BookTransferRepositoryImpl {
+    async fn find_by_id(&self, book_transfer_id: BookTransferId) -> AppResult<BookTransfer> {
+        let row = sqlx::query_as!(
+            BookTransferRow,
+            r#"
+                SELECT
+                    t.book_transfer_id,
+                    t.book_id,
+                    b.title AS book_title,
+                    t.from_user_id AS "from_user_id: UserId",
+                    COALESCE(f.name, t.from_user_name) AS "from_user_name!",
+                    t.to_user_id AS "to_user_id: UserId",
+                    COALESCE(r.name, t.to_user_name) AS "to_user_name!",
+                    t.status,
+                    t.forced_by AS "forced_by: UserId",
+                    t.created_at,
+                    t.resolved_at
+                FROM book_transfers AS t
+                INNER JOIN books AS b USING(book_id)
+                LEFT JOIN users AS f ON f.user_id = t.from_user_id
+                LEFT JOIN users AS r ON r.user_id = t.to_user_id
+                WHERE t.book_transfer_id = $1
+            "#,
+            book_transfer_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        row.ok_or_else(|| transfer_not_found(book_transfer_id))?
+            .try_into()
+    }
+}
+
+fn book_not_found(book_id: BookId) -> AppError {
+    AppError::EntityNotFound(format!("蔵書（{book_id}）が見つかりませんでした。"))
+}
+
+fn transfer_not_found(book_transfer_id: BookTransferId) -> AppError {
+    AppError::EntityNotFound(format!(
+        "譲渡の申し出（{book_transfer_id}）が見つかりませんでした。"
+    ))
+}
+
+// 貸出との競合を防ぐため、貸出と同じく SERIALIZABLE で所有者を変更する
+async fn set_transaction_serializable(tx: &mut Transaction<'_>) -> AppResult<()> {
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+    Ok(())
+}
+
+async fn find_ownership(
+    tx: &mut Transaction<'_>,
+    book_id: BookId,
+) -> AppResult<Option<BookOwnershipRow>> {
+    sqlx::query_as!(
+        BookOwnershipRow,
+        r#"
+            SELECT
+                b.user_id AS owner_id,
+                EXISTS (SELECT 1 FROM checkouts AS c WHERE c.book_id = b.book_id) AS "checked_out!"
+            FROM books AS b
+            WHERE b.book_id = $1
+            FOR UPDATE
+        "#,
+        book_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+fn ensure_transferable(
+    ownership: &BookOwnershipRow,
+    book_id: BookId,
+    to_user_id: UserId,
+) -> AppResult<()> {
+    if ownership.owner_id == to_user_id {
+        return Err(AppError::UnprocessableEntity(
+            "蔵書の所有者には譲渡できません。".into(),
+        ));
+    }
+    if ownership.checked_out {
+        return Err(AppError::UnprocessableEntity(format!(
+            "貸出中の蔵書（{book_id}）は譲渡できません。"
+        )));
+    }
+    Ok(())
+}
+
+async fn ensure_user_exists(tx: &mut Transaction<'_>, user_id: UserId) -> AppResult<()> {
+    let exists = sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS (SELECT 1 FROM users WHERE user_id = $1) AS "exists!"
+        "#,
+        user_id as _
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    if !exists {
+        return Err(AppError::EntityNotFound(format!(
+            "利用者（{user_id}）が見つかりませんでした。"
+        )));
+    }
+    Ok(())
+}
+
+// 申し出を受けた本人以外には、申し出が存在しないものとして扱う
+async fn find_pending_for(
+    tx: &mut Transaction<'_>,
+    book_transfer_id: BookTransferId,
+    book_id: BookId,
+    user_id: UserId,
+) -> AppResult<BookTransferStateRow> {
+    let row = sqlx::query_as!(
+        BookTransferStateRow,
+        r#"
+            SELECT book_id, from_user_id AS "from_user_id!", to_user_id AS "to_user_id!", status
+            FROM book_transfers
+            WHERE book_transfer_id = $1 AND book_id = $2 AND to_user_id = $3
+            FOR UPDATE
+        "#,
+        book_transfer_id as _,
+        book_id as _,
+        user_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| transfer_not_found(book_transfer_id))?;
+    ensure_pending(row, book_transfer_id)
+}
+
+// 申し出た本人以外には、申し出が存在しないものとして扱う
+async fn find_pending_offered_by(
+    tx: &mut Transaction<'_>,
+    book_transfer_id: BookTransferId,
+    book_id: BookId,
+    user_id: UserId,
+) -> AppResult<BookTransferStateRow> {
+    let row = sqlx::query_as!(
+        BookTransferStateRow,
+        r#"
+            SELECT book_id, from_user_id AS "from_user_id!", to_user_id AS "to_user_id!", status
+            FROM book_transfers
+            WHERE book_transfer_id = $1 AND book_id = $2 AND from_user_id = $3
+            FOR UPDATE
+        "#,
+        book_transfer_id as _,
+        book_id as _,
+        user_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| transfer_not_found(book_transfer_id))?;
+    ensure_pending(row, book_transfer_id)
+}
+
+fn ensure_pending(
+    row: BookTransferStateRow,
+    book_transfer_id: BookTransferId,
+) -> AppResult<BookTransferStateRow> {
+    let status = BookTransferStatus::from_str(&row.status)
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+    if status != BookTransferStatus::Pending {
+        return Err(AppError::UnprocessableEntity(format!(
+            "譲渡の申し出（{book_transfer_id}）には既に返事が済んでいます。"
+        )));
+    }
+    Ok(row)
+}
+
+async fn change_owner(tx: &mut Transaction<'_>, book_id: BookId, user_id: UserId) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            UPDATE books SET user_id = $2 WHERE book_id = $1
+        "#,
+        book_id as _,
+        user_id as _
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    Ok(())
+}
+
+async fn resolve(
+    tx: &mut Transaction<'_>,
+    book_transfer_id: BookTransferId,
+    status: BookTransferStatus,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            UPDATE book_transfers
+            SET status = $2, resolved_at = CURRENT_TIMESTAMP(3)
+            WHERE book_transfer_id = $1
+        "#,
+        book_transfer_id as _,
+        status.as_ref()
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER_ID: &str = "5b4c96ac-316a-4bee-8e69-cac5eb84ff4c";
+    const BOOK_ID: &str = "9890736e-a4e4-461a-a77d-eac3517ef11b";
+
+    async fn insert_user(pool: &sqlx::PgPool, name: &str) -> anyhow::Result<UserId> {
+        let user_id = UserId::new();
+        sqlx::query(
+            r#"
+                INSERT INTO users (user_id, name, email, password_hash, role_id)
+                SELECT $1::UUID, $2, $3, 'atodehenkou', role_id FROM roles WHERE name = 'User'
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(name)
+        .bind(format!("{}@example.com", name.to_lowercase()))
+        .execute(pool)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn current_owner(pool: &sqlx::PgPool) -> anyhow::Result<UserId> {
+        let owner: uuid::Uuid =
+            sqlx::query_scalar("SELECT user_id FROM books WHERE book_id = $1::UUID")
+                .bind(BOOK_ID)
+                .fetch_one(pool)
+                .await?;
+        Ok(owner.into())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_offer_accept_and_force(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookTransferRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let owner_id = UserId::from_str(OWNER_ID)?;
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let colleague_id = insert_user(&pool, "Colleague").await?;
+        let admin_id = insert_user(&pool, "Librarian").await?;
+
+        let offered = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: colleague_id,
+                requested_user: owner_id,
+            })
+            .await?;
+        assert_eq!(offered.status, BookTransferStatus::Pending);
+        assert_eq!(offered.to_user.name, "Colleague");
+        // 返事を待っている申し出は蔵書ごとに一つまで
+        let res = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: admin_id,
+                requested_user: owner_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        assert_eq!(repo.find_pending_by_user_id(colleague_id).await?.len(), 1);
+
+        // 申し出を受けた本人以外は返事できない
+        let res = repo
+            .accept(AcceptBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: owner_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let accepted = repo
+            .accept(AcceptBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: colleague_id,
+            })
+            .await?;
+        assert_eq!(accepted.status, BookTransferStatus::Accepted);
+        assert_eq!(current_owner(&pool).await?, colleague_id);
+        let res = repo
+            .decline(DeclineBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: colleague_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 新しい所有者からの申し出は、管理者による変更で取り消される
+        let pending = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: owner_id,
+                requested_user: colleague_id,
+            })
+            .await?;
+        let forced = repo
+            .force(ForceBookTransfer {
+                book_id,
+                to_user_id: admin_id,
+                requested_user: admin_id,
+            })
+            .await?;
+        assert_eq!(forced.status, BookTransferStatus::Forced);
+        assert_eq!(forced.from_user.id, Some(colleague_id));
+        assert_eq!(forced.forced_by, Some(admin_id));
+        assert_eq!(current_owner(&pool).await?, admin_id);
+        let res = repo
+            .accept(AcceptBookTransfer {
+                book_transfer_id: pending.id,
+                book_id,
+                requested_user: owner_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 所有者の履歴には所有者が変わった譲渡だけを古い順に残す
+        let history = repo.find_history(book_id).await?;
+        assert_eq!(
+            history.iter().map(|t| t.to_user.id).collect::<Vec<_>>(),
+            vec![Some(colleague_id), Some(admin_id)]
+        );
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_cancel_offer(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookTransferRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let owner_id = UserId::from_str(OWNER_ID)?;
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let colleague_id = insert_user(&pool, "Colleague").await?;
+        assert!(repo.find_history(book_id).await?.is_empty());
+        let res = repo.find_history(BookId::new()).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let offered = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: colleague_id,
+                requested_user: owner_id,
+            })
+            .await?;
+        // 申し出を受けた利用者は取り下げられない
+        let res = repo
+            .cancel(CancelBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: colleague_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let cancelled = repo
+            .cancel(CancelBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: owner_id,
+            })
+            .await?;
+        assert_eq!(cancelled.status, BookTransferStatus::Cancelled);
+        assert!(cancelled.resolved_at.is_some());
+        assert!(repo.find_pending_by_user_id(colleague_id).await?.is_empty());
+        assert_eq!(current_owner(&pool).await?, owner_id);
+        let res = repo
+            .accept(AcceptBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: colleague_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 取り下げた後は、改めて申し出られる
+        repo.offer(OfferBookTransfer {
+            book_id,
+            to_user_id: colleague_id,
+            requested_user: owner_id,
+        })
+        .await?;
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_checked_out_book_cannot_be_transferred(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookTransferRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let owner_id = UserId::from_str(OWNER_ID)?;
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let colleague_id = insert_user(&pool, "Colleague").await?;
+
+        let offered = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: colleague_id,
+                requested_user: owner_id,
+            })
+            .await?;
+        sqlx::query("INSERT INTO checkouts (book_id, user_id) VALUES ($1::UUID, $2::UUID)")
+            .bind(BOOK_ID)
+            .bind(OWNER_ID)
+            .execute(&pool)
+            .await?;
+
+        let res = repo
+            .accept(AcceptBookTransfer {
+                book_transfer_id: offered.id,
+                book_id,
+                requested_user: colleague_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = repo
+            .force(ForceBookTransfer {
+                book_id,
+                to_user_id: colleague_id,
+                requested_user: owner_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        assert_eq!(current_owner(&pool).await?, owner_id);
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_history_survives_user_delete(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        use crate::repository::user::UserRepositoryImpl;
+        use kernel::{model::user::event::DeleteUser, repository::user::UserRepository};
+
+        let repo = BookTransferRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let owner_id = UserId::from_str(OWNER_ID)?;
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let colleague_id = insert_user(&pool, "Colleague").await?;
+        let newcomer_id = insert_user(&pool, "Newcomer").await?;
+
+        // 退職する所有者から同僚へ譲渡してから、元の所有者を削除する
+        let offered = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: colleague_id,
+                requested_user: owner_id,
+            })
+            .await?;
+        repo.accept(AcceptBookTransfer {
+            book_transfer_id: offered.id,
+            book_id,
+            requested_user: colleague_id,
+        })
+        .await?;
+        user_repo.delete(DeleteUser { user_id: owner_id }).await?;
+
+        let history = repo.find_history(book_id).await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].from_user.id, None);
+        assert_eq!(history[0].from_user.name, "Eleazar Fig");
+        assert_eq!(history[0].to_user.id, Some(colleague_id));
+
+        // 申し出の相手が削除されると、返事を待つ申し出は取り消され、新しい申し出ができる
+        let pending = repo
+            .offer(OfferBookTransfer {
+                book_id,
+                to_user_id: newcomer_id,
+                requested_user: colleague_id,
+            })
+            .await?;
+        user_repo
+            .delete(DeleteUser {
+                user_id: newcomer_id,
+            })
+            .await?;
+        assert!(repo.find_pending_by_user_id(colleague_id).await?.is_empty());
+        let cancelled = repo.find_by_id(pending.id).await?;
+        assert_eq!(cancelled.status, BookTransferStatus::Cancelled);
+        assert_eq!(cancelled.to_user.name, "Newcomer");
+        let successor_id = insert_user(&pool, "Successor").await?;
+        repo.offer(OfferBookTransfer {
+            book_id,
+            to_user_id: successor_id,
+            requested_user: colleague_id,
+        })
+        .await?;
+        Ok(())
+    }
+}
@@ -3,25 +3,57 @@ use crate::database::{
     ConnectionPool,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
 
 use derive_new::new;
+use kernel::model::book_condition::{BookCondition, CheckoutCharge};
 use kernel::model::checkout::{
-    event::{CreateCheckout, UpdateReturned},
-    Checkout,
+    event::{
+        CreateCheckout, CreateCheckoutOnBehalf, ForceReturned, ReportLost, ReturnDamaged,
+        UpdateReturned,
+    },
+    Checkout, CheckoutHistoryOptions, CheckoutOutcome,
 };
+use kernel::model::domain_event::DomainEvent;
 use kernel::model::id::{BookId, CheckoutId, UserId};
+use kernel::model::list::PaginatedList;
 use kernel::repository::checkout::CheckoutRepository;
 use shared::error::{AppError, AppResult};
 
+use super::{book_condition::record_condition, outbox::record_event};
+
 #[derive(new)]
 pub struct CheckoutRepositoryImpl {
     db: ConnectionPool,
 }
 
+// 破損した状態での返却と紛失の届け出をまとめて扱う
+struct CheckoutIncident {
+    checkout_id: CheckoutId,
+    book_id: BookId,
+    requested_user: UserId,
+    note: String,
+    charge: Option<CheckoutCharge>,
+    ended_at: DateTime<Utc>,
+    outcome: CheckoutOutcome,
+}
+
 #[async_trait]
 impl CheckoutRepository for CheckoutRepositoryImpl {
     // create checkout -> 貸出
     async fn create(&self, event: CreateCheckout) -> AppResult<()> {
+        self.create_checkout(
+            event.book_id,
+            event.checked_out_by,
+            event.checked_out_at,
+            None,
+        )
+        .await
+    }
+
+    // update returned_at -> 返却
+    async fn update_returned(&self, event: UpdateReturned) -> AppResult<()> {
         let mut tx = self.db.begin().await?;
 
         // トランザクション分離レベルをSERIALIZABLEに設定
@@ -29,175 +61,134 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
 
         // 条件：
         // - 指定の蔵書 ID をもつ蔵書が存在する
-        // - その蔵書が貸出中でない
-        {
-            let res = sqlx::query_as!(
-                CheckoutStateRow,
-                r#"
-                    SELECT
-                    b.book_id,
-                    c.checkout_id AS "checkout_id?: CheckoutId",
-                    NULL AS "user_id?: UserId"
-                    FROM books AS b
-                    LEFT OUTER JOIN checkouts AS c USING(book_id)
-                    WHERE book_id = $1;
-                "#,
-                event.book_id as _
-            )
-            .fetch_optional(&mut *tx)
-            .await
-            .map_err(AppError::SpecificOperationError)?;
-
-            match res {
-                // 条件を満たさなければエラーを返す
-                None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        " 書籍（{}）が見つかりませんでした。",
-                        event.book_id
-                    )))
-                }
-                Some(CheckoutStateRow {
-                    checkout_id: Some(_),
-                    ..
-                }) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        " 書籍（{}）に対する貸出が既に存在します。",
-                        event.book_id
-                    )))
-                }
-                // それ以外は処理続行
-                _ => {}
+        // - その蔵書が貸出中である
+        // - その蔵書を借りたユーザーが指定のユーザーと同じである
+        match self.find_checkout_state(&mut tx, event.book_id).await? {
+            // 条件を満たさなければエラーを返す
+            CheckoutStateRow {
+                checkout_id: Some(c),
+                user_id: Some(u),
+                ..
+            } if (c, u) != (event.checkout_id, event.returned_by) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    " 指定の貸出（ID（{}）, ユーザー（{}）, 書籍（{}））は返却できません。",
+                    event.checkout_id, event.returned_by, event.book_id
+                )))
             }
+            // それ以外は処理続行
+            _ => {}
         }
 
-        // create record
-        let checkout_id = CheckoutId::new();
-        let res = sqlx::query!(
-            r#"
-                INSERT INTO checkouts
-                (checkout_id, book_id, user_id, checked_out_at)
-                VALUES ($1, $2, $3, $4)
-                ;
-            "#,
-            checkout_id as _,
-            event.book_id as _,
-            event.checked_out_by as _,
-            event.checked_out_at,
+        self.move_to_returned(
+            &mut tx,
+            event.checkout_id,
+            event.returned_at,
+            CheckoutOutcome::Returned,
+            None,
+            None,
         )
-        .execute(&mut *tx)
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        .await?;
 
-        if res.rows_affected() < 1 {
-            return Err(AppError::NoRowsAffectedError(
-                "No checkout record has been created".into(),
-            ));
-        }
+        record_event(
+            &mut tx,
+            DomainEvent::CheckoutReturned {
+                checkout_id: event.checkout_id,
+                book_id: event.book_id,
+                user_id: event.returned_by,
+                returned_at: event.returned_at,
+                on_behalf_by: None,
+            },
+        )
+        .await?;
 
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
     }
 
-    // update returned_at -> 返却
-    async fn update_returned(&self, event: UpdateReturned) -> AppResult<()> {
+    async fn create_on_behalf(&self, event: CreateCheckoutOnBehalf) -> AppResult<()> {
+        self.create_checkout(
+            event.book_id,
+            event.checked_out_by,
+            event.checked_out_at,
+            Some(event.requested_user),
+        )
+        .await
+    }
+
+    async fn force_returned(&self, event: ForceReturned) -> AppResult<()> {
         let mut tx = self.db.begin().await?;
 
-        // トランザクション分離レベルをSERIALIZABLEに設定
         self.set_transaction_serializable(&mut tx).await?;
 
-        // 条件：
-        // - 指定の蔵書 ID をもつ蔵書が存在する
-        // - その蔵書が貸出中である
-        // - その蔵書を借りたユーザーが指定のユーザーと同じである
-        //
-        // ちなみにブロックはres 変数がシャドーイングで上書きされるのを防ぐため使用
-        {
-            let res = sqlx::query_as!(
-                CheckoutStateRow,
-                r#"
-                    SELECT
-                    b.book_id,
-                    c.checkout_id AS "checkout_id?: CheckoutId",
-                    c.user_id AS "user_id?: UserId"
-                    FROM books AS b
-                    LEFT OUTER JOIN checkouts AS c USING(book_id)
-                    WHERE book_id = $1;
-                "#,
-                event.book_id as _,
-            )
-            .fetch_optional(&mut *tx)
-            .await
-            .map_err(AppError::SpecificOperationError)?;
-
-            match res {
-                // 条件を満たさなければエラーを返す
-                None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        " 書籍（{}）が見つかりませんでした。",
-                        event.book_id
-                    )))
-                }
-                Some(CheckoutStateRow {
-                    checkout_id: Some(c),
-                    user_id: Some(u),
-                    ..
-                }) if (c, u) != (event.checkout_id, event.returned_by) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        " 指定の貸出（ID（{}）, ユーザー（{}）, 書籍（{}））は返却できません。",
-                        event.checkout_id, event.returned_by, event.book_id
-                    )))
-                }
-                // それ以外は処理続行
-                _ => {}
+        // 借りた利用者を問わず、指定の貸出が蔵書の現在の貸出であれば返却できる
+        let borrower = match self.find_checkout_state(&mut tx, event.book_id).await? {
+            CheckoutStateRow {
+                checkout_id: Some(c),
+                user_id: Some(u),
+                ..
+            } if c == event.checkout_id => u,
+            _ => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    " 指定の貸出（ID（{}）, 書籍（{}））は返却できません。",
+                    event.checkout_id, event.book_id
+                )))
             }
-        }
+        };
 
-        // checkoutsテーブルにある該当貸出レコードにreturned_atを追加してreturned_checkoutsテーブルにINSERT
-        let res = sqlx::query!(
-            r#"
-                INSERT INTO returned_checkouts
-                (checkout_id, book_id, user_id, checked_out_at, returned_at)
-                SELECT checkout_id, book_id, user_id, checked_out_at, $2
-                FROM checkouts
-                WHERE checkout_id = $1
-                ;
-            "#,
-            event.checkout_id as _,
+        self.move_to_returned(
+            &mut tx,
+            event.checkout_id,
             event.returned_at,
+            CheckoutOutcome::Returned,
+            Some(event.requested_user),
+            Some(&event.reason),
         )
-        .execute(&mut *tx)
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        .await?;
 
-        if res.rows_affected() < 1 {
-            return Err(AppError::NoRowsAffectedError(
-                "No returning record has been updated".into(),
-            ));
-        }
-
-        // checkoutsテーブルから該当貸出IDのレコードを削除
-        let res = sqlx::query!(
-            r#"
-                DELETE FROM checkouts WHERE checkout_id = $1;
-            "#,
-            event.checkout_id as _,
+        record_event(
+            &mut tx,
+            DomainEvent::CheckoutReturned {
+                checkout_id: event.checkout_id,
+                book_id: event.book_id,
+                user_id: borrower,
+                returned_at: event.returned_at,
+                on_behalf_by: Some(event.requested_user),
+            },
         )
-        .execute(&mut *tx)
-        .await
-        .map_err(AppError::SpecificOperationError)?;
-
-        if res.rows_affected() < 1 {
-            return Err(AppError::NoRowsAffectedError(
-                "No checkout record has been deleted".into(),
-            ));
-        }
+        .await?;
 
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
     }
 
+    async fn return_damaged(&self, event: ReturnDamaged) -> AppResult<()> {
+        self.end_with_incident(CheckoutIncident {
+            checkout_id: event.checkout_id,
+            book_id: event.book_id,
+            requested_user: event.requested_user,
+            note: event.note,
+            charge: event.charge,
+            ended_at: event.returned_at,
+            outcome: CheckoutOutcome::Damaged,
+        })
+        .await
+    }
+
+    async fn report_lost(&self, event: ReportLost) -> AppResult<()> {
+        self.end_with_incident(CheckoutIncident {
+            checkout_id: event.checkout_id,
+            book_id: event.book_id,
+            requested_user: event.requested_user,
+            note: event.note,
+            charge: event.charge,
+            ended_at: event.reported_at,
+            outcome: CheckoutOutcome::Lost,
+        })
+        .await
+    }
+
     // すべての貸出中の情報を取得
     async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>> {
         // checkoutsをbooksとINNER JOINしつつ全件抽出
@@ -209,6 +200,7 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                 c.book_id,
                 c.user_id,
                 c.checked_out_at,
+                c.checked_out_on_behalf_by AS "checked_out_on_behalf_by: UserId",
                 b.title,
                 b.author,
                 b.isbn
@@ -235,6 +227,7 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                 c.book_id,
                 c.user_id,
                 c.checked_out_at,
+                c.checked_out_on_behalf_by AS "checked_out_on_behalf_by: UserId",
                 b.title,
                 b.author,
                 b.isbn
@@ -267,6 +260,10 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                 rc.user_id,
                 rc.checked_out_at,
                 rc.returned_at,
+                rc.outcome,
+                rc.checked_out_on_behalf_by AS "checked_out_on_behalf_by: UserId",
+                rc.returned_on_behalf_by AS "returned_on_behalf_by: UserId",
+                rc.return_reason,
                 b.title,
                 b.author,
                 b.isbn
@@ -281,8 +278,8 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
         .await
         .map_err(AppError::SpecificOperationError)?
         .into_iter()
-        .map(Checkout::from)
-        .collect();
+        .map(Checkout::try_from)
+        .collect::<AppResult<_>>()?;
 
         // 貸出中の項目が存在する場合は返却済みの履歴の先頭に追加する
         if let Some(co) = checkout {
@@ -291,6 +288,79 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
 
         Ok(checkout_histories)
     }
+
+    async fn find_history_by_user_id(
+        &self,
+        user_id: UserId,
+        options: CheckoutHistoryOptions,
+    ) -> AppResult<PaginatedList<Checkout>> {
+        let CheckoutHistoryOptions {
+            limit,
+            offset,
+            returned_from,
+            returned_to,
+        } = options;
+        // 期間の指定が NULL の場合は絞り込まない
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "total!"
+                FROM returned_checkouts
+                WHERE user_id = $1
+                AND ($2::TIMESTAMPTZ IS NULL OR returned_at >= $2)
+                AND ($3::TIMESTAMPTZ IS NULL OR returned_at < $3)
+            "#,
+            user_id as _,
+            returned_from,
+            returned_to
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let items = sqlx::query_as!(
+            ReturnedCheckoutRow,
+            r#"
+                SELECT
+                rc.checkout_id,
+                rc.book_id,
+                rc.user_id,
+                rc.checked_out_at,
+                rc.returned_at,
+                rc.outcome,
+                rc.checked_out_on_behalf_by AS "checked_out_on_behalf_by: UserId",
+                rc.returned_on_behalf_by AS "returned_on_behalf_by: UserId",
+                rc.return_reason,
+                b.title,
+                b.author,
+                b.isbn
+                FROM returned_checkouts AS rc
+                INNER JOIN books AS b USING(book_id)
+                WHERE rc.user_id = $1
+                AND ($2::TIMESTAMPTZ IS NULL OR rc.returned_at >= $2)
+                AND ($3::TIMESTAMPTZ IS NULL OR rc.returned_at < $3)
+                ORDER BY rc.returned_at DESC, rc.checkout_id
+                LIMIT $4 OFFSET $5
+            "#,
+            user_id as _,
+            returned_from,
+            returned_to,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(Checkout::try_from)
+        .collect::<AppResult<_>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
 }
 
 impl CheckoutRepositoryImpl {
@@ -306,6 +376,253 @@ impl CheckoutRepositoryImpl {
         Ok(())
     }
 
+    // 貸出の作成。管理者が利用者に代わって貸し出す場合は on_behalf_by に管理者を渡す
+    async fn create_checkout(
+        &self,
+        book_id: BookId,
+        checked_out_by: UserId,
+        checked_out_at: DateTime<Utc>,
+        on_behalf_by: Option<UserId>,
+    ) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルをSERIALIZABLEに設定
+        self.set_transaction_serializable(&mut tx).await?;
+
+        // 条件：
+        // - 指定の蔵書 ID をもつ蔵書が存在する
+        // - その蔵書が貸出中でない
+        // - その蔵書が紛失中でない
+        let state = self.find_checkout_state(&mut tx, book_id).await?;
+        if state.checkout_id.is_some() {
+            return Err(AppError::UnprocessableEntity(format!(
+                " 書籍（{}）に対する貸出が既に存在します。",
+                book_id
+            )));
+        }
+        if BookCondition::from_str(&state.condition)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?
+            == BookCondition::Lost
+        {
+            return Err(AppError::UnprocessableEntity(format!(
+                " 書籍（{}）は紛失中のため貸し出せません。",
+                book_id
+            )));
+        }
+
+        // 代わりに貸し出す相手が存在しなければエラーを返す
+        if on_behalf_by.is_some() {
+            let exists = sqlx::query_scalar!(
+                r#"
+                    SELECT EXISTS (SELECT 1 FROM users WHERE user_id = $1) AS "exists!"
+                "#,
+                checked_out_by as _
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+            if !exists {
+                return Err(AppError::EntityNotFound(format!(
+                    "利用者（{checked_out_by}）が見つかりませんでした。"
+                )));
+            }
+        }
+
+        // create record
+        let checkout_id = CheckoutId::new();
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO checkouts
+                (checkout_id, book_id, user_id, checked_out_at, checked_out_on_behalf_by)
+                VALUES ($1, $2, $3, $4, $5)
+                ;
+            "#,
+            checkout_id as _,
+            book_id as _,
+            checked_out_by as _,
+            checked_out_at,
+            on_behalf_by as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No checkout record has been created".into(),
+            ));
+        }
+
+        record_event(
+            &mut tx,
+            DomainEvent::CheckoutCreated {
+                checkout_id,
+                book_id,
+                user_id: checked_out_by,
+                checked_out_at,
+                on_behalf_by,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // 破損・紛失で貸出を終え、蔵書の状態を変える。借りた利用者以外が届け出た場合は
+    // 管理者が代わりに届け出たものとして記録する
+    async fn end_with_incident(&self, incident: CheckoutIncident) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        self.set_transaction_serializable(&mut tx).await?;
+
+        let borrower = match self.find_checkout_state(&mut tx, incident.book_id).await? {
+            CheckoutStateRow {
+                checkout_id: Some(c),
+                user_id: Some(u),
+                ..
+            } if c == incident.checkout_id => u,
+            _ => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    " 指定の貸出（ID（{}）, 書籍（{}））は返却できません。",
+                    incident.checkout_id, incident.book_id
+                )))
+            }
+        };
+        let on_behalf_by = (incident.requested_user != borrower).then_some(incident.requested_user);
+
+        self.move_to_returned(
+            &mut tx,
+            incident.checkout_id,
+            incident.ended_at,
+            incident.outcome,
+            on_behalf_by,
+            None,
+        )
+        .await?;
+
+        let condition = match incident.outcome {
+            CheckoutOutcome::Lost => BookCondition::Lost,
+            CheckoutOutcome::Returned | CheckoutOutcome::Damaged => BookCondition::Damaged,
+        };
+        record_condition(
+            &mut tx,
+            incident.book_id,
+            condition,
+            Some(incident.checkout_id),
+            &incident.note,
+            incident.charge.as_ref(),
+            incident.requested_user,
+        )
+        .await?;
+
+        // 紛失した蔵書は返却されていないので、返却のイベントは出さない
+        if incident.outcome == CheckoutOutcome::Damaged {
+            record_event(
+                &mut tx,
+                DomainEvent::CheckoutReturned {
+                    checkout_id: incident.checkout_id,
+                    book_id: incident.book_id,
+                    user_id: borrower,
+                    returned_at: incident.ended_at,
+                    on_behalf_by,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // 蔵書と、その蔵書の現在の貸出を取得する。蔵書が存在しなければエラーを返す
+    async fn find_checkout_state(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        book_id: BookId,
+    ) -> AppResult<CheckoutStateRow> {
+        sqlx::query_as!(
+            CheckoutStateRow,
+            r#"
+                SELECT
+                b.book_id,
+                c.checkout_id AS "checkout_id?: CheckoutId",
+                c.user_id AS "user_id?: UserId",
+                b.condition
+                FROM books AS b
+                LEFT OUTER JOIN checkouts AS c USING(book_id)
+                WHERE book_id = $1;
+            "#,
+            book_id as _,
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(format!(" 書籍（{}）が見つかりませんでした。", book_id))
+        })
+    }
+
+    // checkoutsテーブルにある該当貸出レコードにreturned_atと終わり方を追加してreturned_checkoutsテーブルに移す。
+    // 管理者が代わりに返却する場合は returned_on_behalf_by と理由も記録する
+    async fn move_to_returned(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        checkout_id: CheckoutId,
+        returned_at: DateTime<Utc>,
+        outcome: CheckoutOutcome,
+        returned_on_behalf_by: Option<UserId>,
+        return_reason: Option<&str>,
+    ) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO returned_checkouts
+                (checkout_id, book_id, user_id, checked_out_at, returned_at, outcome,
+                 checked_out_on_behalf_by, returned_on_behalf_by, return_reason)
+                SELECT checkout_id, book_id, user_id, checked_out_at, $2, $3,
+                checked_out_on_behalf_by, $4, $5
+                FROM checkouts
+                WHERE checkout_id = $1
+                ;
+            "#,
+            checkout_id as _,
+            returned_at,
+            outcome.as_ref(),
+            returned_on_behalf_by as _,
+            return_reason,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No returning record has been updated".into(),
+            ));
+        }
+
+        // checkoutsテーブルから該当貸出IDのレコードを削除
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM checkouts WHERE checkout_id = $1;
+            "#,
+            checkout_id as _,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No checkout record has been deleted".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
     // 未返却の貸出情報を取得するために内部的に使うメソッド
     async fn find_unreturned_by_book_id(&self, book_id: BookId) -> AppResult<Option<Checkout>> {
         let res = sqlx::query_as!(
@@ -316,6 +633,7 @@ impl CheckoutRepositoryImpl {
                 c.book_id,
                 c.user_id,
                 c.checked_out_at,
+                c.checked_out_on_behalf_by AS "checked_out_on_behalf_by: UserId",
                 b.title,
                 b.author,
                 b.isbn
@@ -333,3 +651,210 @@ impl CheckoutRepositoryImpl {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_find_history_by_user_id(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_ids = [
+            "9890736e-a4e4-461a-a77d-eac3517ef11b",
+            "f397b83a-dd2a-4a01-9e77-db1eea7de5b6",
+            "17afb850-c786-49c5-a303-a3a443a2212c",
+        ];
+        // 1 月・2 月・3 月にそれぞれ 1 冊ずつ返却した履歴を作る
+        for (month, book_id) in (1..=3).zip(book_ids) {
+            let returned_at = Utc.with_ymd_and_hms(2025, month, 10, 0, 0, 0).unwrap();
+            sqlx::query(
+                "INSERT INTO returned_checkouts (checkout_id, book_id, user_id, checked_out_at, returned_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(uuid::Uuid::new_v4())
+            .bind(uuid::Uuid::from_str(book_id)?)
+            .bind(user_id.raw())
+            .bind(returned_at - chrono::Duration::days(7))
+            .bind(returned_at)
+            .execute(&pool)
+            .await?;
+        }
+
+        let all = repo
+            .find_history_by_user_id(
+                user_id,
+                CheckoutHistoryOptions {
+                    limit: 2,
+                    offset: 0,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(all.total, 3);
+        assert_eq!(all.items.len(), 2);
+        // 返却日時の新しい順
+        assert_eq!(all.items[0].book.book_id, BookId::from_str(book_ids[2])?);
+
+        let february = repo
+            .find_history_by_user_id(
+                user_id,
+                CheckoutHistoryOptions {
+                    limit: 20,
+                    offset: 0,
+                    returned_from: Some(Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap()),
+                    returned_to: Some(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()),
+                },
+            )
+            .await?;
+        assert_eq!(february.total, 1);
+        assert_eq!(
+            february.items[0].book.book_id,
+            BookId::from_str(book_ids[1])?
+        );
+
+        let others = repo
+            .find_history_by_user_id(
+                UserId::new(),
+                CheckoutHistoryOptions {
+                    limit: 20,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(others.total, 0);
+        Ok(())
+    }
+
+    // 窓口で貸し出した蔵書を、借りた利用者以外は返却できないが管理者は代わりに返却できる
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_checkout_and_return_on_behalf(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let borrower_id = UserId::new();
+        sqlx::query(
+            r#"
+                INSERT INTO users (user_id, name, email, password_hash, role_id)
+                SELECT $1::UUID, 'Leaver', 'leaver@example.com', 'atodehenkou', role_id
+                FROM roles WHERE name = 'User'
+            "#,
+        )
+        .bind(borrower_id.to_string())
+        .execute(&pool)
+        .await?;
+
+        // 存在しない利用者には貸し出せない
+        let res = repo
+            .create_on_behalf(CreateCheckoutOnBehalf::new(
+                book_id,
+                UserId::new(),
+                admin_id,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.create_on_behalf(CreateCheckoutOnBehalf::new(
+            book_id,
+            borrower_id,
+            admin_id,
+            Utc::now(),
+        ))
+        .await?;
+        let checkout = repo
+            .find_unreturned_by_book_id(book_id)
+            .await?
+            .expect("checkout should exist");
+        assert_eq!(checkout.checked_out_by, borrower_id);
+        assert_eq!(checkout.checked_out_on_behalf_by, Some(admin_id));
+
+        // 借りた利用者でなければ通常の返却はできない
+        let res = repo
+            .update_returned(UpdateReturned::new(
+                checkout.id,
+                book_id,
+                admin_id,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 現在の貸出でなければ代わりの返却もできない
+        let res = repo
+            .force_returned(ForceReturned::new(
+                CheckoutId::new(),
+                book_id,
+                admin_id,
+                "退職".into(),
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        repo.force_returned(ForceReturned::new(
+            checkout.id,
+            book_id,
+            admin_id,
+            "退職".into(),
+            Utc::now(),
+        ))
+        .await?;
+        assert!(repo.find_unreturned_by_book_id(book_id).await?.is_none());
+
+        let history = repo.find_history_by_book_id(book_id).await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].checked_out_by, borrower_id);
+        assert_eq!(history[0].checked_out_on_behalf_by, Some(admin_id));
+        assert_eq!(history[0].returned_on_behalf_by, Some(admin_id));
+        assert_eq!(history[0].return_reason.as_deref(), Some("退職"));
+        Ok(())
+    }
+
+    // 破損した状態での返却は、貸出の終わり方と蔵書の状態の履歴に弁償の方法とともに残る
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_return_damaged(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        repo.create(CreateCheckout::new(book_id, user_id, Utc::now()))
+            .await?;
+        let checkout = repo
+            .find_unreturned_by_book_id(book_id)
+            .await?
+            .expect("checkout should exist");
+        repo.return_damaged(ReturnDamaged::new(
+            checkout.id,
+            book_id,
+            user_id,
+            "表紙が破れた".into(),
+            Some(CheckoutCharge::Fee { amount: 1500 }),
+            Utc::now(),
+        ))
+        .await?;
+
+        let history = repo.find_history_by_book_id(book_id).await?;
+        assert_eq!(history[0].outcome, Some(CheckoutOutcome::Damaged));
+        // 本人が届け出たので代理の記録は残らない
+        assert_eq!(history[0].returned_on_behalf_by, None);
+
+        let (condition, charge_kind, charge_amount): (String, Option<String>, Option<i32>) =
+            sqlx::query_as(
+                "SELECT condition, charge_kind, charge_amount FROM book_conditions WHERE checkout_id = $1",
+            )
+            .bind(checkout.id.raw())
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(condition, "damaged");
+        assert_eq!(charge_kind.as_deref(), Some("fee"));
+        assert_eq!(charge_amount, Some(1500));
+
+        // 破損していても貸し出せる
+        repo.create(CreateCheckout::new(book_id, user_id, Utc::now()))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use crate::database::{model::checkout::CheckoutReminderRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        checkout::reminder::{CheckoutReminder, CheckoutReminderKind},
+        id::CheckoutId,
+    },
+    repository::checkout_reminder::CheckoutReminderRepository,
+};
+use shared::error::{AppError, AppResult};
+
+// 返却期限は貸出日時に loan_period を足した日時とし、その due_soon 前から返却を促す
+#[derive(new)]
+pub struct CheckoutReminderRepositoryImpl {
+    db: ConnectionPool,
+    loan_period: Duration,
+    due_soon: Duration,
+}
+
+#[async_trait]
+impl CheckoutReminderRepository for CheckoutReminderRepositoryImpl {
+    async fn find_unsent(&self, kind: CheckoutReminderKind) -> AppResult<Vec<CheckoutReminder>> {
+        // 期限が近い旨の催促は、期限を過ぎてからは送らない
+        let (ahead, before_due_only) = match kind {
+            CheckoutReminderKind::DueSoon => (self.due_soon, true),
+            CheckoutReminderKind::Overdue => (Duration::ZERO, false),
+        };
+        let rows = sqlx::query_as!(
+            CheckoutReminderRow,
+            r#"
+                SELECT
+                    c.checkout_id,
+                    c.book_id,
+                    b.title,
+                    c.user_id,
+                    c.checked_out_at + make_interval(secs => $1) AS "due_at!"
+                FROM checkouts AS c
+                INNER JOIN books AS b ON b.book_id = c.book_id
+                WHERE c.checked_out_at + make_interval(secs => $1)
+                        <= CURRENT_TIMESTAMP(3) + make_interval(secs => $2)
+                    AND (NOT $3 OR c.checked_out_at + make_interval(secs => $1) > CURRENT_TIMESTAMP(3))
+                    AND NOT EXISTS (
+                        SELECT 1 FROM checkout_reminders AS r
+                        WHERE r.checkout_id = c.checkout_id AND r.kind = $4
+                    )
+                ORDER BY c.checked_out_at, c.checkout_id
+            "#,
+            self.loan_period.as_secs_f64(),
+            ahead.as_secs_f64(),
+            before_due_only,
+            kind.as_ref()
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(CheckoutReminder::from).collect())
+    }
+
+    async fn record_sent(
+        &self,
+        checkout_id: CheckoutId,
+        kind: CheckoutReminderKind,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO checkout_reminders (checkout_id, kind)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            "#,
+            checkout_id as _,
+            kind.as_ref()
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::id::BookId;
+    use std::str::FromStr;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_find_unsent_reminders(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_id = "5b4c96ac-316a-4bee-8e69-cac5eb84ff4c";
+        // 20 日前・13 日前・1 日前の貸出。貸出期間 14 日なら、それぞれ期限切れ・期限間近・催促なし
+        for (book_id, days_ago) in [
+            ("9890736e-a4e4-461a-a77d-eac3517ef11b", 20),
+            ("f397b83a-dd2a-4a01-9e77-db1eea7de5b6", 13),
+            ("17afb850-c786-49c5-a303-a3a443a2212c", 1),
+        ] {
+            sqlx::query(
+                r#"
+                    INSERT INTO checkouts (book_id, user_id, checked_out_at)
+                    VALUES ($1::UUID, $2::UUID, CURRENT_TIMESTAMP(3) - make_interval(days => $3))
+                "#,
+            )
+            .bind(book_id)
+            .bind(user_id)
+            .bind(days_ago)
+            .execute(&pool)
+            .await?;
+        }
+        let repo = CheckoutReminderRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Duration::from_secs(14 * DAY),
+            Duration::from_secs(2 * DAY),
+        );
+
+        let overdue = repo.find_unsent(CheckoutReminderKind::Overdue).await?;
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(
+            overdue[0].book_id,
+            BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?
+        );
+        let due_soon = repo.find_unsent(CheckoutReminderKind::DueSoon).await?;
+        assert_eq!(due_soon.len(), 1);
+        assert_eq!(
+            due_soon[0].book_id,
+            BookId::from_str("f397b83a-dd2a-4a01-9e77-db1eea7de5b6")?
+        );
+        assert!(due_soon[0].due_at > overdue[0].due_at);
+
+        // 送った催促は二度返さない
+        repo.record_sent(overdue[0].checkout_id, CheckoutReminderKind::Overdue)
+            .await?;
+        repo.record_sent(overdue[0].checkout_id, CheckoutReminderKind::Overdue)
+            .await?;
+        assert!(repo
+            .find_unsent(CheckoutReminderKind::Overdue)
+            .await?
+            .is_empty());
+        assert_eq!(
+            repo.find_unsent(CheckoutReminderKind::DueSoon).await?.len(),
+            1
+        );
+        Ok(())
+    }
+}
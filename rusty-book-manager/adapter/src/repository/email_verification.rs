@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::user::{event::RequestEmailChange, EmailVerification, EmailVerificationToken},
+    repository::email_verification::EmailVerificationRepository,
+};
+use shared::error::AppResult;
+
+use crate::{
+    database::model::user::{EmailVerificationItem, EmailVerificationKey},
+    redis::{model::JsonValue, RedisClient},
+};
+
+#[derive(new)]
+pub struct EmailVerificationRepositoryImpl {
+    kv: Arc<RedisClient>,
+    ttl: u64,
+}
+
+#[async_trait]
+impl EmailVerificationRepository for EmailVerificationRepositoryImpl {
+    async fn create(&self, event: RequestEmailChange) -> AppResult<EmailVerificationToken> {
+        let RequestEmailChange {
+            user_id,
+            new_email,
+            token,
+        } = event;
+        let token = EmailVerificationToken(token);
+        let value = JsonValue::encode(&EmailVerificationItem { user_id, new_email })?;
+        self.kv
+            .set_ex(&EmailVerificationKey::from(&token), &value, self.ttl)
+            .await?;
+        Ok(token)
+    }
+
+    async fn consume(
+        &self,
+        token: &EmailVerificationToken,
+    ) -> AppResult<Option<EmailVerification>> {
+        // 同じトークンを二度使えないよう、取得と同時に削除する
+        self.kv
+            .get_delete(&EmailVerificationKey::from(token))
+            .await?
+            .map(|v| {
+                v.decode::<EmailVerificationItem>()
+                    .map(EmailVerification::from)
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        domain_event::{DomainEvent, OutboxEvent},
+        event_stream::{EventStreamId, StreamedEvent},
+    },
+    repository::{event_stream::EventStreamRepository, outbox::DomainEventHandler},
+};
+use serde::{Deserialize, Serialize};
+use shared::error::{AppError, AppResult};
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+
+use crate::redis::RedisClient;
+
+// 配信の履歴を残す Stream と、追加をレプリカに知らせるチャンネル
+const EVENT_STREAM_KEY: &str = "event_stream";
+const EVENT_STREAM_CHANNEL: &str = "event_stream:appended";
+// outbox が同じイベントを再び渡しても履歴に重複して追加しないよう、追加済みの印を一日残す
+const PUBLISHED_MARKER_TTL: u64 = 24 * 60 * 60;
+// Last-Event-ID で受け取り直せるイベントのおおよその数
+const HISTORY_LENGTH: usize = 1000;
+// 各接続が受け取りきれずに溜められるイベントの数。超えた接続には取りこぼしを知らせる
+const SUBSCRIBER_BUFFER: usize = 256;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredEvent {
+    event: DomainEvent,
+    occurred_at: DateTime<Utc>,
+}
+
+fn decode(id: &str, value: &str) -> AppResult<StreamedEvent> {
+    let StoredEvent { event, occurred_at } =
+        serde_json::from_str(value).map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+    Ok(StreamedEvent {
+        id: id.parse()?,
+        event,
+        occurred_at,
+    })
+}
+
+// 配信の履歴を Redis の Stream に、レプリカへの知らせを Pub/Sub に載せる
+pub struct EventStreamRepositoryImpl {
+    kv: Arc<RedisClient>,
+    sender: broadcast::Sender<StreamedEvent>,
+}
+
+impl EventStreamRepositoryImpl {
+    pub fn new(kv: Arc<RedisClient>) -> Self {
+        let (sender, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        Self { kv, sender }
+    }
+}
+
+#[async_trait]
+impl EventStreamRepository for EventStreamRepositoryImpl {
+    async fn publish(&self, event: &OutboxEvent) -> AppResult<()> {
+        let value = serde_json::to_string(&StoredEvent {
+            event: event.event.clone(),
+            occurred_at: event.occurred_at,
+        })
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        self.kv
+            .append_once(
+                &format!("{EVENT_STREAM_KEY}:published:{}", event.id),
+                PUBLISHED_MARKER_TTL,
+                EVENT_STREAM_KEY,
+                HISTORY_LENGTH,
+                EVENT_STREAM_CHANNEL,
+                &value,
+            )
+            .await
+    }
+
+    async fn find_after(&self, after: EventStreamId) -> AppResult<Option<Vec<StreamedEvent>>> {
+        // after が最も古いエントリより前なら、その間のエントリは切り詰められているかもしれない
+        let Some(first) = self.kv.first_stream_id(EVENT_STREAM_KEY).await? else {
+            return Ok(None);
+        };
+        if after < first.parse()? {
+            return Ok(None);
+        }
+        let events = self
+            .kv
+            .stream_entries_after(EVENT_STREAM_KEY, &after.to_string())
+            .await?
+            .into_iter()
+            .filter_map(|(id, value)| {
+                decode(&id, &value)
+                    .inspect_err(|e| {
+                        tracing::warn!(event.id = %id, error.message = %e, "Skipped malformed event")
+                    })
+                    .ok()
+            })
+            .collect();
+        Ok(Some(events))
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<StreamedEvent> {
+        self.sender.subscribe()
+    }
+
+    async fn listen(&self) -> AppResult<()> {
+        let messages = self.kv.subscribe(EVENT_STREAM_CHANNEL).await?;
+        let mut messages = std::pin::pin!(messages);
+        while let Some(message) = messages.next().await {
+            let Some((id, value)) = message.split_once(' ') else {
+                continue;
+            };
+            match decode(id, value) {
+                // 受け手がいなければ捨てる
+                Ok(event) => {
+                    let _ = self.sender.send(event);
+                }
+                Err(e) => {
+                    tracing::warn!(event.id = %id, error.message = %e, "Skipped malformed event")
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// outbox のイベントを接続中のクライアント向けの配信の履歴に追加する
+#[derive(new)]
+pub struct EventStreamHandler {
+    event_stream_repository: Arc<dyn EventStreamRepository>,
+}
+
+#[async_trait]
+impl DomainEventHandler for EventStreamHandler {
+    fn name(&self) -> &'static str {
+        "event_stream"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> AppResult<()> {
+        self.event_stream_repository.publish(event).await
+    }
+}
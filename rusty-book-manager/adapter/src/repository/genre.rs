@@ -0,0 +1,251 @@
+use crate::database::{model::genre::GenreRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    genre::{
+        event::{AddBookGenre, CreateGenre, DeleteGenre, RemoveBookGenre},
+        Genre,
+    },
+    id::GenreId,
+};
+use kernel::repository::genre::GenreRepository;
+use shared::error::{AppError, AppResult};
+
+use super::book::touch_book;
+
+#[derive(new)]
+pub struct GenreRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl GenreRepository for GenreRepositoryImpl {
+    async fn find_all(&self) -> AppResult<Vec<Genre>> {
+        let rows = sqlx::query_as!(
+            GenreRow,
+            r#"
+                SELECT genre_id, name, parent_id AS "parent_id?: GenreId"
+                FROM genres
+                ORDER BY name
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(Genre::from).collect())
+    }
+
+    async fn find_by_id(&self, genre_id: GenreId) -> AppResult<Option<Genre>> {
+        let row = sqlx::query_as!(
+            GenreRow,
+            r#"
+                SELECT genre_id, name, parent_id AS "parent_id?: GenreId"
+                FROM genres
+                WHERE genre_id = $1
+            "#,
+            genre_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row.map(Genre::from))
+    }
+
+    async fn create(&self, event: CreateGenre) -> AppResult<Genre> {
+        let row = sqlx::query_as!(
+            GenreRow,
+            r#"
+                INSERT INTO genres (name, parent_id) VALUES ($1, $2)
+                RETURNING genre_id, name, parent_id AS "parent_id?: GenreId"
+            "#,
+            event.name,
+            event.parent_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::UnprocessableEntity(format!(
+                    "ジャンル（{}）は既に存在します。",
+                    event.name
+                ))
+            }
+            sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                AppError::UnprocessableEntity("Specified parent genre not found".into())
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+        Ok(row.into())
+    }
+
+    async fn delete(&self, event: DeleteGenre) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM genres WHERE genre_id = $1
+            "#,
+            event.genre_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(|e| match e {
+            // 蔵書や下位のジャンルから参照されている
+            sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                AppError::UnprocessableEntity(format!(
+                    "ジャンル（{}）は使用中のため削除できません。",
+                    event.genre_id
+                ))
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("Specified genre not found".into()));
+        }
+        Ok(())
+    }
+
+    async fn add_to_book(&self, event: AddBookGenre) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 既に付いている場合は何もしない
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO book_genres (book_id, genre_id) VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            "#,
+            event.book_id as _,
+            event.genre_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                AppError::EntityNotFound("Specified book or genre not found".into())
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+        if res.rows_affected() > 0 {
+            touch_book(&mut tx, event.book_id).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn remove_from_book(&self, event: RemoveBookGenre) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM book_genres WHERE book_id = $1 AND genre_id = $2
+            "#,
+            event.book_id as _,
+            event.genre_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                "Specified genre is not attached to the book".into(),
+            ));
+        }
+        touch_book(&mut tx, event.book_id).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::book::BookRepositoryImpl;
+    use kernel::{
+        model::{
+            book::{BookListFilter, BookListOptions},
+            id::BookId,
+        },
+        repository::book::BookRepository,
+    };
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_genre_hierarchy(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let genre_repo = GenreRepositoryImpl::new(db.clone());
+        let book_repo = BookRepositoryImpl::new(db);
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        let parent = genre_repo
+            .create(CreateGenre {
+                name: "コンピュータ".into(),
+                parent_id: None,
+            })
+            .await?;
+        let child = genre_repo
+            .create(CreateGenre {
+                name: "プログラミング".into(),
+                parent_id: Some(parent.id),
+            })
+            .await?;
+        assert_eq!(child.parent_id, Some(parent.id));
+        let res = genre_repo
+            .create(CreateGenre {
+                name: "コンピュータ".into(),
+                parent_id: None,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let version = book_repo.find_by_id(book_id).await?.unwrap().version;
+        genre_repo
+            .add_to_book(AddBookGenre {
+                book_id,
+                genre_id: child.id,
+            })
+            .await?;
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.genres, vec![child.clone()]);
+        assert_eq!(book.version, version + 1);
+
+        // 上位のジャンルで絞り込むと、下位のジャンルが付いた蔵書も含まれる
+        let list = book_repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    genre_id: Some(parent.id),
+                    ..Default::default()
+                },
+                sort: Default::default(),
+            })
+            .await?;
+        assert_eq!(list.total, 1);
+        assert_eq!(list.items[0].id, book_id);
+
+        // 使用中のジャンルは削除できない
+        for genre_id in [parent.id, child.id] {
+            let res = genre_repo.delete(DeleteGenre { genre_id }).await;
+            assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        }
+
+        genre_repo
+            .remove_from_book(RemoveBookGenre {
+                book_id,
+                genre_id: child.id,
+            })
+            .await?;
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.version, version + 2);
+        genre_repo
+            .delete(DeleteGenre { genre_id: child.id })
+            .await?;
+        genre_repo
+            .delete(DeleteGenre {
+                genre_id: parent.id,
+            })
+            .await?;
+        assert!(genre_repo.find_all().await?.is_empty());
+        Ok(())
+    }
+}
@@ -1,12 +1,23 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
 use derive_new::new;
+use kernel::model::health::{DependencyHealth, HealthStatus};
 use kernel::repository::health::HealthCheckRepository;
 
-use crate::database::ConnectionPool;
+use crate::database::{ConnectionPool, MIGRATOR};
+use crate::redis::RedisClient;
+
+// 依存先が応答しない場合に Readiness の応答自体が詰まらないようにするための上限
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(new)]
 pub struct HealthCheckRepositoryImpl {
     db: ConnectionPool,
+    kv: Arc<RedisClient>,
 }
 
 #[async_trait]
@@ -17,4 +28,88 @@ impl HealthCheckRepository for HealthCheckRepositoryImpl {
             .await
             .is_ok()
     }
+
+    async fn check_database(&self) -> DependencyHealth {
+        measure("database", true, async {
+            sqlx::query("SELECT 1")
+                .fetch_one(self.db.inner_ref())
+                .await
+                .map(|_| (HealthStatus::Up, None))
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    async fn check_redis(&self) -> DependencyHealth {
+        measure("redis", true, async {
+            self.kv
+                .try_connect()
+                .await
+                .map(|_| (HealthStatus::Up, None))
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    async fn check_migrations(&self) -> DependencyHealth {
+        measure("migrations", true, async {
+            // sqlx-cli と起動時マイグレーションのどちらで適用しても同じテーブルに記録される
+            let applied: HashSet<i64> =
+                sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = TRUE")
+                    .fetch_all(self.db.inner_ref())
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .collect();
+            let pending = MIGRATOR
+                .iter()
+                .filter(|m| !m.migration_type.is_down_migration())
+                .filter(|m| !applied.contains(&m.version))
+                .map(|m| m.version.to_string())
+                .collect::<Vec<_>>();
+            if pending.is_empty() {
+                Ok((HealthStatus::Up, None))
+            } else {
+                Ok((
+                    HealthStatus::Down,
+                    Some(format!("pending migrations: {}", pending.join(", "))),
+                ))
+            }
+        })
+        .await
+    }
+
+    async fn check_pool(&self) -> DependencyHealth {
+        measure("pool", false, async {
+            let pool = self.db.inner_ref();
+            let max = pool.options().get_max_connections();
+            let in_use = pool.size().saturating_sub(pool.num_idle() as u32);
+            let status = if in_use >= max {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Up
+            };
+            Ok((status, Some(format!("{in_use}/{max} connections in use"))))
+        })
+        .await
+    }
+}
+
+async fn measure<F>(name: &'static str, required: bool, check: F) -> DependencyHealth
+where
+    F: Future<Output = Result<(HealthStatus, Option<String>), String>>,
+{
+    let started = Instant::now();
+    let (status, detail) = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(res)) => res,
+        Ok(Err(e)) => (HealthStatus::Down, Some(e)),
+        Err(_) => (HealthStatus::Down, Some("timed out".into())),
+    };
+    DependencyHealth {
+        name,
+        status,
+        required,
+        latency: started.elapsed(),
+        detail,
+    }
 }
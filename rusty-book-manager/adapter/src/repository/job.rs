@@ -0,0 +1,341 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    database::{model::job::JobRunRow, ConnectionPool},
+    redis::RedisClient,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        id::JobRunId,
+        job::{JobName, JobOutcome, JobRun, JobRunListOptions},
+        list::PaginatedList,
+    },
+    repository::job::{Job, JobLockRepository, JobRunRepository},
+};
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct JobRunRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl JobRunRepository for JobRunRepositoryImpl {
+    async fn start(&self, job_name: JobName, scheduled_at: DateTime<Utc>) -> AppResult<JobRunId> {
+        let run_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO job_runs (job_name, scheduled_at)
+                VALUES ($1, $2)
+                RETURNING job_run_id AS "job_run_id: JobRunId"
+            "#,
+            job_name.as_ref(),
+            scheduled_at
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(run_id)
+    }
+
+    async fn record_success(&self, run_id: JobRunId, outcome: JobOutcome) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                UPDATE job_runs
+                SET
+                    status = 'succeeded',
+                    processed = $2,
+                    failed = $3,
+                    finished_at = CURRENT_TIMESTAMP(3)
+                WHERE job_run_id = $1
+            "#,
+            run_id as _,
+            outcome.processed as i64,
+            outcome.failed as i64
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn record_failure(&self, run_id: JobRunId, error: String) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                UPDATE job_runs
+                SET status = 'failed', error = $2, finished_at = CURRENT_TIMESTAMP(3)
+                WHERE job_run_id = $1
+            "#,
+            run_id as _,
+            error
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn fail_stale(&self, job_name: JobName, scheduled_at: DateTime<Utc>) -> AppResult<u64> {
+        // scheduled_at はミリ秒精度で保存されるため、引数も同じ精度に丸めて比較する
+        let res = sqlx::query!(
+            r#"
+                UPDATE job_runs
+                SET
+                    status = 'failed',
+                    error = 'did not finish before a later run started',
+                    finished_at = CURRENT_TIMESTAMP(3)
+                WHERE job_name = $1 AND status = 'running' AND scheduled_at < $2::TIMESTAMP(3) WITH TIME ZONE
+            "#,
+            job_name.as_ref(),
+            scheduled_at
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected())
+    }
+
+    async fn find_all(&self, options: JobRunListOptions) -> AppResult<PaginatedList<JobRun>> {
+        let JobRunListOptions {
+            limit,
+            offset,
+            job_name,
+            status,
+        } = options;
+        let job_name = job_name.map(|n| n.as_ref().to_string());
+        let status = status.map(|s| s.as_ref().to_string());
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "total!"
+                FROM job_runs
+                WHERE ($1::TEXT IS NULL OR job_name = $1)
+                    AND ($2::TEXT IS NULL OR status = $2)
+            "#,
+            job_name,
+            status
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let rows = sqlx::query_as!(
+            JobRunRow,
+            r#"
+                SELECT
+                    job_run_id,
+                    job_name,
+                    status,
+                    scheduled_at,
+                    started_at,
+                    finished_at,
+                    processed,
+                    failed,
+                    error
+                FROM job_runs
+                WHERE ($1::TEXT IS NULL OR job_name = $1)
+                    AND ($2::TEXT IS NULL OR status = $2)
+                ORDER BY started_at DESC, job_run_id
+                LIMIT $3
+                OFFSET $4
+            "#,
+            job_name,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let items = rows
+            .into_iter()
+            .map(JobRun::try_from)
+            .collect::<AppResult<_>>()?;
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn purge_finished(&self, before: DateTime<Utc>) -> AppResult<u64> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM job_runs
+                WHERE finished_at < $1
+            "#,
+            before
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected())
+    }
+}
+
+// 予定の時刻ごとにキーを分けたロック。先に取ったレプリカが実行し、
+// 解放せずに期限切れを待つので、時計が少しずれたレプリカが同じ予定を実行し直すこともない
+#[derive(new)]
+pub struct JobLockRepositoryImpl {
+    kv: Arc<RedisClient>,
+}
+
+#[async_trait]
+impl JobLockRepository for JobLockRepositoryImpl {
+    async fn try_lock(
+        &self,
+        job_name: JobName,
+        scheduled_at: DateTime<Utc>,
+        ttl: Duration,
+    ) -> AppResult<bool> {
+        let key = format!(
+            "job-lock:{}:{}",
+            job_name.as_ref(),
+            scheduled_at.timestamp()
+        );
+        self.kv.try_lock(&key, ttl).await
+    }
+}
+
+// ジョブを予定の時刻に実行し、結果を実行の履歴に残す
+#[derive(new)]
+pub struct JobRunner {
+    job: Arc<dyn Job>,
+    lock: Arc<dyn JobLockRepository>,
+    runs: Arc<dyn JobRunRepository>,
+    lock_ttl: Duration,
+}
+
+impl JobRunner {
+    pub fn name(&self) -> JobName {
+        self.job.name()
+    }
+
+    // ロックを取れた場合だけ実行し、実行の履歴の ID を返す。
+    // ジョブ自体の失敗は履歴に残すだけで、エラーとしては返さない。
+    // 以前の予定の実行が途中でプロセスごと止まった場合は、実行中のまま残った履歴を失敗にする
+    pub async fn run(&self, scheduled_at: DateTime<Utc>) -> AppResult<Option<JobRunId>> {
+        let job_name = self.job.name();
+        if !self
+            .lock
+            .try_lock(job_name, scheduled_at, self.lock_ttl)
+            .await?
+        {
+            return Ok(None);
+        }
+        let stale = self.runs.fail_stale(job_name, scheduled_at).await?;
+        if stale > 0 {
+            tracing::warn!(
+                job = job_name.as_ref(),
+                stale,
+                "Marked unfinished job runs as failed"
+            );
+        }
+        let run_id = self.runs.start(job_name, scheduled_at).await?;
+        match self.job.run().await {
+            Ok(outcome) => self.runs.record_success(run_id, outcome).await?,
+            Err(e) => self.runs.record_failure(run_id, e.to_string()).await?,
+        }
+        Ok(Some(run_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::{
+        model::job::JobRunStatus,
+        repository::job::{MockJob, MockJobLockRepository},
+    };
+
+    #[sqlx::test]
+    async fn test_run_only_when_locked(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let runs = Arc::new(JobRunRepositoryImpl::new(ConnectionPool::new(pool)));
+        // 2 回目はほかのレプリカがロックを取ったものとする
+        let mut lock = MockJobLockRepository::new();
+        let mut locked = false;
+        lock.expect_try_lock().returning(move |_, _, _| {
+            let acquired = !locked;
+            locked = true;
+            Ok(acquired)
+        });
+        let mut job = MockJob::new();
+        job.expect_name().return_const(JobName::TokenCleanup);
+        job.expect_run()
+            .times(1)
+            .returning(|| Err(AppError::ExternalServiceError("unavailable".into())));
+        let runner = JobRunner::new(
+            Arc::new(job),
+            Arc::new(lock),
+            runs.clone(),
+            Duration::from_secs(60),
+        );
+
+        let scheduled_at = Utc::now();
+        let run_id = runner.run(scheduled_at).await?;
+        assert!(run_id.is_some());
+        assert_eq!(runner.run(scheduled_at).await?, None);
+
+        let list = runs
+            .find_all(JobRunListOptions {
+                limit: 10,
+                offset: 0,
+                job_name: Some(JobName::TokenCleanup),
+                status: None,
+            })
+            .await?;
+        assert_eq!(list.total, 1);
+        let run = &list.items[0];
+        assert_eq!(Some(run.id), run_id);
+        assert_eq!(run.status, JobRunStatus::Failed);
+        assert!(run.finished_at.is_some());
+        assert!(run.error.as_deref().unwrap().contains("unavailable"));
+
+        // 保存期間を過ぎた履歴は削除できる
+        let now = Utc::now();
+        assert_eq!(
+            runs.purge_finished(now - chrono::TimeDelta::hours(1))
+                .await?,
+            0
+        );
+        assert_eq!(
+            runs.purge_finished(now + chrono::TimeDelta::seconds(1))
+                .await?,
+            1
+        );
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_fail_stale_runs(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let runs = JobRunRepositoryImpl::new(ConnectionPool::new(pool));
+        let scheduled_at = Utc::now();
+        let an_hour_ago = scheduled_at - chrono::TimeDelta::hours(1);
+        let stale_id = runs.start(JobName::TokenCleanup, an_hour_ago).await?;
+        let current_id = runs.start(JobName::TokenCleanup, scheduled_at).await?;
+        let other_id = runs.start(JobName::AuditPurge, an_hour_ago).await?;
+
+        // 同じジョブの以前の予定で、実行中のまま残ったものだけを失敗にする
+        assert_eq!(
+            runs.fail_stale(JobName::TokenCleanup, scheduled_at).await?,
+            1
+        );
+        let all = runs
+            .find_all(JobRunListOptions {
+                limit: 10,
+                offset: 0,
+                job_name: None,
+                status: None,
+            })
+            .await?;
+        let find = |id: JobRunId| all.items.iter().find(|r| r.id == id).unwrap();
+        assert_eq!(find(stale_id).status, JobRunStatus::Failed);
+        assert!(find(stale_id).finished_at.is_some());
+        assert_eq!(find(current_id).status, JobRunStatus::Running);
+        assert_eq!(find(other_id).status, JobRunStatus::Running);
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use kernel::{model::mail::Mail, repository::mail::MailRepository};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use secrecy::ExposeSecret;
+use shared::{
+    config::{SmtpConfig, SmtpTls},
+    error::{AppError, AppResult},
+};
+
+// メールの送信手段を持たないため、宛先と件名をログに出力するだけの実装
+#[derive(Default)]
+pub struct LoggingMailRepository;
+
+#[async_trait]
+impl MailRepository for LoggingMailRepository {
+    fn is_deliverable(&self) -> bool {
+        false
+    }
+
+    async fn send(&self, mail: Mail) -> AppResult<()> {
+        // 本文には確認用のトークンが含まれるため、ログには出さない
+        tracing::info!(
+            mail.to = %mail.to,
+            mail.subject = %mail.subject,
+            "Mail is not delivered because no mail transport is configured"
+        );
+        Ok(())
+    }
+}
+
+// SMTP サーバーを経由してメールを送信する実装
+pub struct SmtpMailRepository {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailRepository {
+    pub fn new(config: &SmtpConfig) -> AppResult<Self> {
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::ExternalServiceError(format!("invalid sender address: {e}")))?;
+        let builder = match config.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| AppError::ExternalServiceError(e.to_string()))?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| AppError::ExternalServiceError(e.to_string()))?,
+        };
+        let mut builder = builder.port(config.port);
+        if let Some(credentials) = &config.credentials {
+            builder = builder.credentials(Credentials::new(
+                credentials.username.clone(),
+                credentials.password.expose_secret().clone(),
+            ));
+        }
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl MailRepository for SmtpMailRepository {
+    fn is_deliverable(&self) -> bool {
+        true
+    }
+
+    async fn send(&self, mail: Mail) -> AppResult<()> {
+        let to = mail
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::UnprocessableEntity(format!("invalid recipient: {e}")))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(mail.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body)
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::ExternalServiceError(format!("failed to send mail: {e}")))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(from: &str) -> SmtpConfig {
+        SmtpConfig {
+            host: "localhost".into(),
+            port: 2525,
+            tls: SmtpTls::None,
+            credentials: None,
+            from: from.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_smtp_send_fails_without_server() -> anyhow::Result<()> {
+        assert!(SmtpMailRepository::new(&config("not an address")).is_err());
+
+        // 接続先の SMTP サーバーがない場合は、送信に失敗したことを返す
+        let mut config = config("Book Manager <no-reply@example.com>");
+        config.port = 1;
+        let repo = SmtpMailRepository::new(&config)?;
+        assert!(repo.is_deliverable());
+        let res = repo
+            .send(Mail {
+                to: "user@example.com".into(),
+                subject: "件名".into(),
+                body: "本文".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ExternalServiceError(_))));
+        Ok(())
+    }
+}
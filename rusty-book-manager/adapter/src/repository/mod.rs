@@ -1,5 +1,30 @@
 pub mod auth;
+pub mod author;
+pub mod blob;
 pub mod book;
+pub mod book_cache;
+pub mod book_condition;
+pub mod book_metadata;
+pub mod book_transfer;
 pub mod checkout;
+pub mod checkout_reminder;
+pub mod email_verification;
+pub mod event_stream;
+pub mod genre;
 pub mod health;
+pub mod job;
+pub mod mail;
+pub mod notification;
+pub mod notification_event;
+pub mod outbox;
+pub mod rate_limit;
+pub mod recommendation;
+pub mod report;
+pub mod review;
+pub mod scheduled_job;
+pub mod shelf;
+pub mod tag;
 pub mod user;
+pub mod webhook;
+pub mod webhook_event;
+pub mod webhook_sender;
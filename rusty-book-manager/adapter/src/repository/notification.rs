@@ -0,0 +1,433 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use crate::database::{
+    model::notification::{NotificationPreferenceRow, NotificationRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{BookId, UserId},
+        list::PaginatedList,
+        mail::Mail,
+        notification::{
+            event::{
+                CreateNotification, MarkAllNotificationsRead, MarkNotificationRead,
+                UpdateNotificationPreferences,
+            },
+            Notification, NotificationKind, NotificationListOptions, NotificationPreference,
+        },
+    },
+    repository::{
+        mail::MailRepository,
+        notification::{NotificationRepository, Notifier},
+        user::UserRepository,
+    },
+};
+use shared::error::{AppError, AppResult};
+use strum::IntoEnumIterator;
+
+#[derive(new)]
+pub struct NotificationRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl NotificationRepository for NotificationRepositoryImpl {
+    async fn create(&self, event: CreateNotification) -> AppResult<Option<Notification>> {
+        let row = sqlx::query_as!(
+            NotificationRow,
+            r#"
+                INSERT INTO notifications (user_id, kind, source_id, title, body, book_id)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (user_id, kind, source_id) DO NOTHING
+                RETURNING
+                    notification_id,
+                    user_id,
+                    kind,
+                    title,
+                    body,
+                    book_id AS "book_id: BookId",
+                    read_at,
+                    created_at
+            "#,
+            event.user_id as _,
+            event.kind.as_ref(),
+            event.source_id,
+            event.title,
+            event.body,
+            event.book_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        row.map(Notification::try_from).transpose()
+    }
+
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+        options: NotificationListOptions,
+    ) -> AppResult<PaginatedList<Notification>> {
+        let NotificationListOptions {
+            limit,
+            offset,
+            unread_only,
+        } = options;
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "total!"
+                FROM notifications
+                WHERE user_id = $1
+                    AND (NOT $2 OR read_at IS NULL)
+            "#,
+            user_id as _,
+            unread_only
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let rows = sqlx::query_as!(
+            NotificationRow,
+            r#"
+                SELECT
+                    notification_id,
+                    user_id,
+                    kind,
+                    title,
+                    body,
+                    book_id AS "book_id: BookId",
+                    read_at,
+                    created_at
+                FROM notifications
+                WHERE user_id = $1
+                    AND (NOT $2 OR read_at IS NULL)
+                ORDER BY created_at DESC, notification_id
+                LIMIT $3
+                OFFSET $4
+            "#,
+            user_id as _,
+            unread_only,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let items = rows
+            .into_iter()
+            .map(Notification::try_from)
+            .collect::<AppResult<_>>()?;
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn count_unread(&self, user_id: UserId) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM notifications
+                WHERE user_id = $1 AND read_at IS NULL
+            "#,
+            user_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    async fn mark_read(&self, event: MarkNotificationRead) -> AppResult<()> {
+        // 既読の通知を既読にしても、既読にした日時は変えない
+        let res = sqlx::query!(
+            r#"
+                UPDATE notifications
+                SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP(3))
+                WHERE notification_id = $1 AND user_id = $2
+            "#,
+            event.notification_id as _,
+            event.user_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                "指定された通知が見つかりませんでした。".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn mark_all_read(&self, event: MarkAllNotificationsRead) -> AppResult<u64> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE notifications
+                SET read_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1 AND read_at IS NULL
+            "#,
+            event.user_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected())
+    }
+
+    async fn find_preferences(&self, user_id: UserId) -> AppResult<Vec<NotificationPreference>> {
+        let rows = sqlx::query_as!(
+            NotificationPreferenceRow,
+            r#"
+                SELECT kind, email
+                FROM notification_preferences
+                WHERE user_id = $1
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(NotificationKind::iter()
+            .map(|kind| NotificationPreference {
+                kind,
+                email: rows
+                    .iter()
+                    .find(|row| NotificationKind::from_str(&row.kind).ok() == Some(kind))
+                    .map_or(kind.email_by_default(), |row| row.email),
+            })
+            .collect())
+    }
+
+    async fn update_preferences(&self, event: UpdateNotificationPreferences) -> AppResult<()> {
+        // 同じ種類が複数指定された場合は後のものを使う
+        let (kinds, emails): (Vec<String>, Vec<bool>) = event
+            .preferences
+            .iter()
+            .map(|p| (p.kind, p.email))
+            .collect::<HashMap<_, _>>()
+            .into_iter()
+            .map(|(kind, email)| (kind.as_ref().to_string(), email))
+            .unzip();
+        sqlx::query!(
+            r#"
+                INSERT INTO notification_preferences (user_id, kind, email)
+                SELECT $1, kind, email
+                FROM UNNEST($2::TEXT[], $3::BOOLEAN[]) AS p(kind, email)
+                ON CONFLICT (user_id, kind) DO UPDATE SET email = EXCLUDED.email
+            "#,
+            event.user_id as _,
+            &kinds,
+            &emails
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+}
+
+#[derive(new)]
+pub struct NotifierImpl {
+    notification_repository: Arc<dyn NotificationRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    mail_repository: Arc<dyn MailRepository>,
+}
+
+#[async_trait]
+impl Notifier for NotifierImpl {
+    async fn notify(&self, event: CreateNotification) -> AppResult<()> {
+        let Some(notification) = self.notification_repository.create(event).await? else {
+            return Ok(());
+        };
+        let email = self
+            .notification_repository
+            .find_preferences(notification.user_id)
+            .await?
+            .into_iter()
+            .any(|p| p.kind == notification.kind && p.email);
+        if !email {
+            return Ok(());
+        }
+        let Some(user) = self
+            .user_repository
+            .find_current_user(notification.user_id)
+            .await?
+        else {
+            return Ok(());
+        };
+        // 受信箱の通知はすでに追加したので、メールの送信に失敗しても記録するだけで送り直さない
+        if let Err(e) = self
+            .mail_repository
+            .send(Mail {
+                to: user.email,
+                subject: notification.title,
+                body: format!("{} 様\n\n{}", user.name, notification.body),
+            })
+            .await
+        {
+            tracing::warn!(
+                notification.id = %notification.id,
+                error.message = %e,
+                "Failed to send notification mail"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::{
+        model::{id::NotificationId, role::Role, user::User},
+        repository::{
+            mail::MockMailRepository, notification::MockNotificationRepository,
+            user::MockUserRepository,
+        },
+    };
+    use uuid::Uuid;
+
+    fn create_notification(user_id: UserId, kind: NotificationKind) -> CreateNotification {
+        CreateNotification {
+            user_id,
+            kind,
+            source_id: Uuid::new_v4(),
+            title: "返却期限を過ぎています".into(),
+            body: "貸出中の「実践Rustプログラミング入門」は返却期限を過ぎています。".into(),
+            book_id: None,
+        }
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_notifications(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = NotificationRepositoryImpl::new(ConnectionPool::new(pool));
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let mut event = create_notification(user_id, NotificationKind::CheckoutOverdue);
+        event.book_id = Some(BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?);
+        let first = repo.create(event.clone()).await?.expect("created");
+        assert_eq!(first.book_id, event.book_id);
+        // 同じものから同じ種類の通知は二度作らない
+        assert!(repo.create(event.clone()).await?.is_none());
+        event.kind = NotificationKind::CheckoutDueSoon;
+        assert!(repo.create(event).await?.is_some());
+        assert_eq!(repo.count_unread(user_id).await?, 2);
+
+        repo.mark_read(MarkNotificationRead {
+            notification_id: first.id,
+            user_id,
+        })
+        .await?;
+        let unread = repo
+            .find_by_user_id(
+                user_id,
+                NotificationListOptions {
+                    limit: 20,
+                    offset: 0,
+                    unread_only: true,
+                },
+            )
+            .await?;
+        assert_eq!(unread.total, 1);
+        assert_eq!(unread.items[0].kind, NotificationKind::CheckoutDueSoon);
+
+        // ほかの利用者の通知や存在しない通知は既読にできない
+        let res = repo
+            .mark_read(MarkNotificationRead {
+                notification_id: NotificationId::new(),
+                user_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        assert_eq!(
+            repo.mark_all_read(MarkAllNotificationsRead { user_id })
+                .await?,
+            1
+        );
+        assert_eq!(repo.count_unread(user_id).await?, 0);
+
+        // 設定していない種類は既定の設定を返す
+        repo.update_preferences(UpdateNotificationPreferences {
+            user_id,
+            preferences: vec![NotificationPreference {
+                kind: NotificationKind::CheckoutOverdue,
+                email: false,
+            }],
+        })
+        .await?;
+        let preferences = repo.find_preferences(user_id).await?;
+        assert_eq!(preferences.len(), NotificationKind::iter().count());
+        for p in preferences {
+            let expected = match p.kind {
+                NotificationKind::CheckoutOverdue => false,
+                kind => kind.email_by_default(),
+            };
+            assert_eq!(p.email, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_notifier_sends_mail_by_preference() -> anyhow::Result<()> {
+        let user_id = UserId::new();
+        let mut notifications = MockNotificationRepository::new();
+        notifications.expect_create().returning(|event| {
+            Ok(Some(Notification {
+                id: NotificationId::new(),
+                user_id: event.user_id,
+                kind: event.kind,
+                title: event.title,
+                body: event.body,
+                book_id: event.book_id,
+                read_at: None,
+                created_at: chrono::Utc::now(),
+            }))
+        });
+        notifications.expect_find_preferences().returning(|_| {
+            Ok(NotificationKind::iter()
+                .map(|kind| NotificationPreference {
+                    kind,
+                    email: kind == NotificationKind::CheckoutOverdue,
+                })
+                .collect())
+        });
+        let mut users = MockUserRepository::new();
+        users.expect_find_current_user().returning(|user_id| {
+            Ok(Some(User {
+                id: user_id,
+                name: "Eleazar Fig".into(),
+                email: "eleazar.fig@example.com".into(),
+                role: Role::User,
+            }))
+        });
+        let mut mail = MockMailRepository::new();
+        mail.expect_send()
+            .withf(|mail| {
+                mail.to == "eleazar.fig@example.com"
+                    && mail.subject == "返却期限を過ぎています"
+                    && mail.body.starts_with("Eleazar Fig 様")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let notifier = NotifierImpl::new(Arc::new(notifications), Arc::new(users), Arc::new(mail));
+        notifier
+            .notify(create_notification(
+                user_id,
+                NotificationKind::CheckoutOverdue,
+            ))
+            .await?;
+        // メールで受け取らない種類は受信箱にだけ届ける
+        notifier
+            .notify(create_notification(user_id, NotificationKind::BookReturned))
+            .await?;
+        Ok(())
+    }
+}
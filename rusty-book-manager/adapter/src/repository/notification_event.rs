@@ -0,0 +1,275 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        book::Book,
+        book_condition::BookCondition,
+        domain_event::{DomainEvent, OutboxEvent},
+        id::{BookId, UserId},
+        notification::{event::CreateNotification, NotificationKind},
+        user::User,
+    },
+    repository::{
+        book::BookRepository, notification::Notifier, outbox::DomainEventHandler,
+        user::UserRepository,
+    },
+};
+use shared::error::AppResult;
+
+// outbox のイベントのうち、利用者に知らせるものを通知にする
+#[derive(new)]
+pub struct NotificationEventHandler {
+    book_repository: Arc<dyn BookRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl NotificationEventHandler {
+    // 貸出・返却を知らせる相手である蔵書の所有者と、借りた利用者を引く。自分の蔵書を自分で借りた場合や、
+    // 蔵書・借りた利用者がすでに削除されていれば知らせない
+    async fn find_owner_and_borrower(
+        &self,
+        book_id: BookId,
+        borrower_id: UserId,
+    ) -> AppResult<Option<(Book, User)>> {
+        let Some(book) = self.book_repository.find_by_id(book_id).await? else {
+            return Ok(None);
+        };
+        if book.owner.id == borrower_id {
+            return Ok(None);
+        }
+        let borrower = self.user_repository.find_current_user(borrower_id).await?;
+        Ok(borrower.map(|borrower| (book, borrower)))
+    }
+
+    // 譲渡を知らせるのに使う蔵書の題名と、相手の利用者を引く。すでに削除されていれば知らせない
+    async fn find_title_and_user(
+        &self,
+        book_id: BookId,
+        user_id: UserId,
+    ) -> AppResult<Option<(String, User)>> {
+        let Some(book) = self.book_repository.find_by_id(book_id).await? else {
+            return Ok(None);
+        };
+        let user = self.user_repository.find_current_user(user_id).await?;
+        Ok(user.map(|user| (book.title, user)))
+    }
+
+    async fn to_notifications(&self, event: &OutboxEvent) -> AppResult<Vec<CreateNotification>> {
+        let source_id = event.id.raw();
+        let notifications = match event.event {
+            DomainEvent::CheckoutCreated {
+                book_id,
+                user_id,
+                on_behalf_by,
+                ..
+            } => self
+                .find_owner_and_borrower(book_id, user_id)
+                .await?
+                .map(|(book, borrower)| CreateNotification {
+                    user_id: book.owner.id,
+                    kind: NotificationKind::BookCheckedOut,
+                    source_id,
+                    title: "蔵書が貸し出されました".into(),
+                    body: if on_behalf_by.is_some() {
+                        format!(
+                            "管理者により「{}」が {} さんに貸し出されました。",
+                            book.title, borrower.name
+                        )
+                    } else {
+                        format!(
+                            "「{}」が {} さんに貸し出されました。",
+                            book.title, borrower.name
+                        )
+                    },
+                    book_id: Some(book_id),
+                })
+                .into_iter()
+                .collect(),
+            DomainEvent::CheckoutReturned {
+                book_id,
+                user_id,
+                on_behalf_by,
+                ..
+            } => self
+                .find_owner_and_borrower(book_id, user_id)
+                .await?
+                .map(|(book, borrower)| CreateNotification {
+                    user_id: book.owner.id,
+                    kind: NotificationKind::BookReturned,
+                    source_id,
+                    title: "蔵書が返却されました".into(),
+                    body: if on_behalf_by.is_some() {
+                        format!(
+                            "管理者が {} さんに代わって「{}」を返却しました。",
+                            borrower.name, book.title
+                        )
+                    } else {
+                        format!("{} さんが「{}」を返却しました。", borrower.name, book.title)
+                    },
+                    book_id: Some(book_id),
+                })
+                .into_iter()
+                .collect(),
+            DomainEvent::BookTransferOffered {
+                book_id,
+                from_user_id,
+                to_user_id,
+                ..
+            } => self
+                .find_title_and_user(book_id, from_user_id)
+                .await?
+                .map(|(title, from_user)| CreateNotification {
+                    user_id: to_user_id,
+                    kind: NotificationKind::BookTransferOffered,
+                    source_id,
+                    title: "蔵書の譲渡を申し出られました".into(),
+                    body: format!(
+                        "{} さんから「{title}」を譲りたいとの申し出がありました。\
+                         受け取るかどうかをお返事ください。",
+                        from_user.name
+                    ),
+                    book_id: Some(book_id),
+                })
+                .into_iter()
+                .collect(),
+            DomainEvent::BookTransferDeclined {
+                book_id,
+                from_user_id,
+                to_user_id,
+                ..
+            } => self
+                .find_title_and_user(book_id, to_user_id)
+                .await?
+                .map(|(title, to_user)| CreateNotification {
+                    user_id: from_user_id,
+                    kind: NotificationKind::BookTransferDeclined,
+                    source_id,
+                    title: "蔵書の譲渡が断られました".into(),
+                    body: format!("{} さんが「{title}」の譲渡を断りました。", to_user.name),
+                    book_id: Some(book_id),
+                })
+                .into_iter()
+                .collect(),
+            // 申し出が受け入れられた場合は申し出た元の所有者に、管理者が変更した場合は新旧の所有者に知らせる
+            DomainEvent::BookTransferred {
+                book_id,
+                from_user_id,
+                to_user_id,
+                forced,
+                ..
+            } => {
+                let Some(book) = self.book_repository.find_by_id(book_id).await? else {
+                    return Ok(vec![]);
+                };
+                let mut notifications = vec![];
+                if let Some(to_user) = self.user_repository.find_current_user(to_user_id).await? {
+                    notifications.push(CreateNotification {
+                        user_id: from_user_id,
+                        kind: NotificationKind::BookTransferred,
+                        source_id,
+                        title: "蔵書の所有者が変わりました".into(),
+                        body: if forced {
+                            format!(
+                                "管理者により「{}」の所有者が {} さんに変更されました。",
+                                book.title, to_user.name
+                            )
+                        } else {
+                            format!(
+                                "{} さんが「{}」を受け取りました。",
+                                to_user.name, book.title
+                            )
+                        },
+                        book_id: Some(book_id),
+                    });
+                }
+                if forced {
+                    notifications.push(CreateNotification {
+                        user_id: to_user_id,
+                        kind: NotificationKind::BookTransferred,
+                        source_id,
+                        title: "蔵書の所有者が変わりました".into(),
+                        body: format!(
+                            "管理者により「{}」の所有者があなたに変更されました。",
+                            book.title
+                        ),
+                        book_id: Some(book_id),
+                    });
+                }
+                notifications
+            }
+            // 自分で届け出た場合を除き、蔵書の所有者に知らせる
+            DomainEvent::BookConditionChanged {
+                book_id,
+                condition,
+                checkout_id,
+                user_id,
+            } => {
+                let Some(book) = self.book_repository.find_by_id(book_id).await? else {
+                    return Ok(vec![]);
+                };
+                if book.owner.id == user_id {
+                    return Ok(vec![]);
+                }
+                let body = match (condition, checkout_id) {
+                    (BookCondition::Lost, _) => {
+                        format!("「{}」の紛失が届け出られました。", book.title)
+                    }
+                    (BookCondition::Damaged, Some(_)) => {
+                        format!("「{}」が破損した状態で返却されました。", book.title)
+                    }
+                    (BookCondition::Damaged, None) => format!(
+                        "紛失していた「{}」が見つかりました。破損があります。",
+                        book.title
+                    ),
+                    (BookCondition::Good, _) => {
+                        format!("紛失していた「{}」が見つかりました。", book.title)
+                    }
+                };
+                vec![CreateNotification {
+                    user_id: book.owner.id,
+                    kind: NotificationKind::BookConditionChanged,
+                    source_id,
+                    title: "蔵書の状態が変わりました".into(),
+                    body,
+                    book_id: Some(book_id),
+                }]
+            }
+            // 身に覚えのない変更に気づけるよう、変更された本人に知らせる
+            DomainEvent::UserUpdated { user_id } => vec![CreateNotification {
+                user_id,
+                kind: NotificationKind::AccountUpdated,
+                source_id,
+                title: "アカウント情報が変更されました".into(),
+                body: "お名前・メールアドレス・ロールのいずれかが変更されました。\
+                       お心当たりがない場合は管理者にご連絡ください。"
+                    .into(),
+                book_id: None,
+            }],
+            DomainEvent::BookCreated { .. }
+            | DomainEvent::BookUpdated { .. }
+            | DomainEvent::BookDeleted { .. }
+            | DomainEvent::BookTransferCancelled { .. }
+            | DomainEvent::UserCreated { .. }
+            | DomainEvent::UserDeleted { .. } => vec![],
+        };
+        Ok(notifications)
+    }
+}
+
+#[async_trait]
+impl DomainEventHandler for NotificationEventHandler {
+    fn name(&self) -> &'static str {
+        "notification"
+    }
+
+    // 通知は同じイベントから二度作られないので、途中で失敗しても最初から渡し直してよい
+    async fn handle(&self, event: &OutboxEvent) -> AppResult<()> {
+        for notification in self.to_notifications(event).await? {
+            self.notifier.notify(notification).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::database::{model::outbox::OutboxEventRow, ConnectionPool};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::model::{
+    domain_event::{DomainEvent, OutboxEvent},
+    id::DomainEventId,
+};
+use kernel::repository::outbox::{DomainEventHandler, OutboxRepository};
+use shared::error::{AppError, AppResult};
+
+// 変更と同じトランザクションで outbox にイベントを書き込む。
+// コミットされた変更のイベントだけが、ロールバックされた変更のイベントは残らない
+pub(crate) async fn record_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: DomainEvent,
+) -> AppResult<()> {
+    let payload = serde_json::to_string(&event)
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+    sqlx::query!(
+        r#"
+            INSERT INTO outbox (event_type, payload)
+            VALUES ($1, $2::TEXT::JSONB)
+        "#,
+        event.event_type(),
+        payload
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    Ok(())
+}
+
+#[derive(new)]
+pub struct OutboxRepositoryImpl {
+    db: ConnectionPool,
+    // 1 回目の再送までの間隔。以降は失敗するたびに倍にし、retry_max_delay で頭打ちにする
+    retry_base_delay: Duration,
+    retry_max_delay: Duration,
+}
+
+#[async_trait]
+impl OutboxRepository for OutboxRepositoryImpl {
+    async fn claim_due(&self, limit: i64, lease: Duration) -> AppResult<Vec<OutboxEvent>> {
+        let rows = sqlx::query_as!(
+            OutboxEventRow,
+            r#"
+                WITH due AS (
+                    SELECT event_id
+                    FROM outbox
+                    WHERE published_at IS NULL AND next_attempt_at <= CURRENT_TIMESTAMP(3)
+                    ORDER BY occurred_at, event_id
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                UPDATE outbox AS o
+                SET next_attempt_at = CURRENT_TIMESTAMP(3) + make_interval(secs => $2)
+                FROM due
+                WHERE o.event_id = due.event_id
+                RETURNING
+                    o.event_id,
+                    o.payload::TEXT AS "payload!",
+                    o.occurred_at
+            "#,
+            limit,
+            lease.as_secs_f64()
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // RETURNING の順序は保証されないので、発生順に並べ直す
+        let mut events = rows
+            .into_iter()
+            .map(OutboxEvent::try_from)
+            .collect::<AppResult<Vec<_>>>()?;
+        events.sort_by_key(|e| e.occurred_at);
+        Ok(events)
+    }
+
+    async fn find_handled(&self, event_id: DomainEventId) -> AppResult<Vec<String>> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT handler FROM outbox_handled_events WHERE event_id = $1
+            "#,
+            event_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    async fn record_handled(&self, event_id: DomainEventId, handler: &str) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO outbox_handled_events (event_id, handler)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            "#,
+            event_id as _,
+            handler
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn record_published(&self, event_id: DomainEventId) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                UPDATE outbox
+                SET
+                    published_at = CURRENT_TIMESTAMP(3),
+                    attempts = attempts + 1,
+                    last_error = NULL
+                WHERE event_id = $1
+            "#,
+            event_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn record_failure(&self, event_id: DomainEventId, error: String) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                UPDATE outbox
+                SET
+                    attempts = attempts + 1,
+                    last_error = $2,
+                    next_attempt_at = CURRENT_TIMESTAMP(3)
+                        + make_interval(secs => LEAST($3 * power(2, attempts), $4))
+                WHERE event_id = $1
+            "#,
+            event_id as _,
+            error,
+            self.retry_base_delay.as_secs_f64(),
+            self.retry_max_delay.as_secs_f64()
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn purge_published(&self, before: DateTime<Utc>) -> AppResult<u64> {
+        // ハンドラごとの処理済みの記録も外部キーで一緒に消える
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM outbox
+                WHERE published_at < $1
+            "#,
+            before
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected())
+    }
+}
+
+// outbox のイベントを登録されたハンドラに渡す。
+// ハンドラごとに処理済みを記録するので、一部のハンドラが失敗した場合は失敗したものだけに再び渡す
+#[derive(new)]
+pub struct OutboxDispatcher {
+    outbox: Arc<dyn OutboxRepository>,
+    handlers: Vec<Arc<dyn DomainEventHandler>>,
+}
+
+impl OutboxDispatcher {
+    // 配信時刻を過ぎたイベントを最大 limit 件処理し、処理したイベントの数を返す
+    pub async fn dispatch_due(&self, limit: i64, lease: Duration) -> AppResult<usize> {
+        let events = self.outbox.claim_due(limit, lease).await?;
+        for event in &events {
+            self.dispatch(event).await;
+        }
+        Ok(events.len())
+    }
+
+    async fn dispatch(&self, event: &OutboxEvent) {
+        let recorded = match self.handle(event).await {
+            Ok(()) => self.outbox.record_published(event.id).await,
+            Err(error) => {
+                tracing::warn!(
+                    error.message = %error,
+                    event_id = %event.id,
+                    event_type = event.event.event_type(),
+                    "Failed to handle domain event"
+                );
+                self.outbox
+                    .record_failure(event.id, error.to_string())
+                    .await
+            }
+        };
+        if let Err(e) = recorded {
+            tracing::warn!(error.message = %e, event_id = %event.id, "Failed to record domain event");
+        }
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> AppResult<()> {
+        let handled = self.outbox.find_handled(event.id).await?;
+        let mut failures = Vec::new();
+        for handler in &self.handlers {
+            let name = handler.name();
+            if handled.iter().any(|h| h == name) {
+                continue;
+            }
+            match handler.handle(event).await {
+                Ok(()) => self.outbox.record_handled(event.id, name).await?,
+                Err(e) => failures.push(format!("{name}: {e}")),
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ExternalServiceError(failures.join("; ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::id::{BookId, UserId};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // 指定した回数だけ失敗してから成功するハンドラ
+    struct FlakyHandler {
+        name: &'static str,
+        failures: usize,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DomainEventHandler for FlakyHandler {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn handle(&self, _event: &OutboxEvent) -> AppResult<()> {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst);
+            if calls < self.failures {
+                return Err(AppError::ExternalServiceError("unavailable".into()));
+            }
+            Ok(())
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_dispatch_retries_only_failed_handlers(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let event = DomainEvent::BookCreated {
+            book_id: BookId::new(),
+            owner_id: UserId::new(),
+        };
+        // ロールバックした変更のイベントは残らない
+        let mut tx = db.begin().await?;
+        record_event(&mut tx, event.clone()).await?;
+        tx.rollback().await?;
+        let mut tx = db.begin().await?;
+        record_event(&mut tx, event.clone()).await?;
+        tx.commit().await?;
+
+        // 再送の間隔を 0 にして、失敗したイベントをすぐに取り出せるようにする
+        let outbox = Arc::new(OutboxRepositoryImpl::new(
+            db.clone(),
+            Duration::ZERO,
+            Duration::ZERO,
+        ));
+        let stable = Arc::new(FlakyHandler {
+            name: "stable",
+            failures: 0,
+            calls: AtomicUsize::new(0),
+        });
+        let flaky = Arc::new(FlakyHandler {
+            name: "flaky",
+            failures: 1,
+            calls: AtomicUsize::new(0),
+        });
+        let dispatcher = OutboxDispatcher::new(outbox.clone(), vec![stable.clone(), flaky.clone()]);
+
+        // lease を 0 にして、取り出したイベントを続けて取り出せるようにする
+        assert_eq!(dispatcher.dispatch_due(10, Duration::ZERO).await?, 1);
+        assert_eq!(stable.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 1);
+
+        // 2 回目は失敗したハンドラにだけ渡し、すべて成功したら配信済みになる
+        let claimed = outbox.claim_due(10, Duration::ZERO).await?;
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].event, event);
+        assert_eq!(dispatcher.dispatch_due(10, Duration::ZERO).await?, 1);
+        assert_eq!(stable.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(dispatcher.dispatch_due(10, Duration::ZERO).await?, 0);
+
+        let (attempts, last_error) = sqlx::query_as::<_, (i32, Option<String>)>(
+            "SELECT attempts, last_error FROM outbox WHERE published_at IS NOT NULL",
+        )
+        .fetch_one(db.inner_ref())
+        .await?;
+        assert_eq!(attempts, 2);
+        assert_eq!(last_error, None);
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_checkout_records_event(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        use crate::repository::checkout::CheckoutRepositoryImpl;
+        use kernel::{
+            model::checkout::event::CreateCheckout, repository::checkout::CheckoutRepository,
+        };
+        use std::str::FromStr;
+
+        let db = ConnectionPool::new(pool);
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let checked_out_at = chrono::Utc::now();
+        let checkouts = CheckoutRepositoryImpl::new(db.clone());
+        checkouts
+            .create(CreateCheckout::new(book_id, user_id, checked_out_at))
+            .await?;
+        // 貸出中の蔵書は貸し出せず、失敗した変更のイベントは書き込まれない
+        assert!(checkouts
+            .create(CreateCheckout::new(book_id, user_id, checked_out_at))
+            .await
+            .is_err());
+
+        let outbox = OutboxRepositoryImpl::new(db, Duration::ZERO, Duration::ZERO);
+        let events = outbox.claim_due(10, Duration::from_secs(60)).await?;
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            events[0].event,
+            DomainEvent::CheckoutCreated { book_id: b, user_id: u, .. } if b == book_id && u == user_id
+        ));
+        Ok(())
+    }
+}
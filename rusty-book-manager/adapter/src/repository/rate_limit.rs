@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::rate_limit::{RateLimitDecision, RateLimitQuota};
+use kernel::repository::rate_limit::RateLimitRepository;
+use shared::error::AppResult;
+
+use crate::redis::RedisClient;
+
+#[derive(new)]
+pub struct RateLimitRepositoryImpl {
+    kv: Arc<RedisClient>,
+}
+
+#[async_trait]
+impl RateLimitRepository for RateLimitRepositoryImpl {
+    async fn hit(&self, key: &str, quota: RateLimitQuota) -> AppResult<RateLimitDecision> {
+        let (allowed, count, reset_after) = self
+            .kv
+            .hit_sliding_window(&format!("rate-limit:{key}"), quota.limit, quota.window)
+            .await?;
+        Ok(RateLimitDecision {
+            allowed,
+            limit: quota.limit,
+            remaining: quota.limit.saturating_sub(count),
+            reset_after,
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use crate::database::{model::recommendation::RecommendationRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{id::UserId, recommendation::Recommendation};
+use kernel::repository::recommendation::RecommendationRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct RecommendationRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl RecommendationRepository for RecommendationRepositoryImpl {
+    async fn find_by_user_id(&self, user_id: UserId, limit: i64) -> AppResult<Vec<Recommendation>> {
+        // 読んだ蔵書は返却済みと貸出中のもの。
+        // 共に借りられた回数は集計済みのビューから引き、著者・タグの共通数はその場で数える。
+        // 著者が共通するほうが好みに合いやすいので、著者の共通数は重く扱う
+        let rows = sqlx::query_as!(
+            RecommendationRow,
+            r#"
+                WITH read_books AS (
+                    SELECT book_id FROM returned_checkouts WHERE user_id = $1
+                    UNION
+                    SELECT book_id FROM checkouts WHERE user_id = $1
+                ),
+                co_checkouts AS (
+                    SELECT c.related_book_id AS book_id, SUM(c.borrower_count)::BIGINT AS count
+                    FROM book_co_checkouts AS c
+                    WHERE c.book_id IN (SELECT book_id FROM read_books)
+                    GROUP BY c.related_book_id
+                ),
+                shared_authors AS (
+                    SELECT ba.book_id, COUNT(DISTINCT ra.book_id) AS count
+                    FROM book_authors AS ra
+                    INNER JOIN book_authors AS ba ON ba.author_id = ra.author_id
+                    WHERE ra.book_id IN (SELECT book_id FROM read_books)
+                    GROUP BY ba.book_id
+                ),
+                shared_tags AS (
+                    SELECT bt.book_id, COUNT(DISTINCT rt.book_id) AS count
+                    FROM book_tags AS rt
+                    INNER JOIN book_tags AS bt ON bt.tag_id = rt.tag_id
+                    WHERE rt.book_id IN (SELECT book_id FROM read_books)
+                    GROUP BY bt.book_id
+                ),
+                scored AS (
+                    SELECT
+                        b.book_id,
+                        b.title,
+                        b.author,
+                        b.isbn,
+                        b.created_at,
+                        COALESCE(c.count, 0) AS co_checkout_count,
+                        COALESCE(a.count, 0) AS shared_author_count,
+                        COALESCE(t.count, 0) AS shared_tag_count
+                    FROM books AS b
+                    LEFT OUTER JOIN co_checkouts AS c ON c.book_id = b.book_id
+                    LEFT OUTER JOIN shared_authors AS a ON a.book_id = b.book_id
+                    LEFT OUTER JOIN shared_tags AS t ON t.book_id = b.book_id
+                    WHERE b.user_id <> $1
+                    AND b.book_id NOT IN (SELECT book_id FROM read_books)
+                    AND (c.book_id IS NOT NULL OR a.book_id IS NOT NULL OR t.book_id IS NOT NULL)
+                )
+                SELECT
+                    book_id,
+                    title,
+                    author,
+                    isbn,
+                    co_checkout_count + 3 * shared_author_count + shared_tag_count AS "score!",
+                    co_checkout_count AS "co_checkout_count!",
+                    shared_author_count AS "shared_author_count!",
+                    shared_tag_count AS "shared_tag_count!"
+                FROM scored
+                ORDER BY 5 DESC, co_checkout_count DESC, created_at DESC
+                LIMIT $2
+            "#,
+            user_id as _,
+            limit
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(Recommendation::from).collect())
+    }
+
+    async fn refresh(&self) -> AppResult<()> {
+        // CONCURRENTLY で更新し、集計中も推薦の読み込みを妨げない
+        sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY book_co_checkouts")
+            .execute(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::id::BookId;
+    use std::str::FromStr;
+
+    async fn insert_user(pool: &sqlx::PgPool, name: &str) -> anyhow::Result<UserId> {
+        let user_id = UserId::new();
+        sqlx::query(
+            "INSERT INTO users (user_id, name, email, password_hash, role_id)
+             SELECT $1, $2, $3, 'dummy', role_id FROM roles WHERE name = 'User'",
+        )
+        .bind(user_id.raw())
+        .bind(name)
+        .bind(format!("{name}@example.com"))
+        .execute(pool)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn insert_returned(
+        pool: &sqlx::PgPool,
+        user_id: UserId,
+        book_id: BookId,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO returned_checkouts (checkout_id, book_id, user_id) VALUES ($1, $2, $3)",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(book_id.raw())
+        .bind(user_id.raw())
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_recommendations(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = RecommendationRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let book2 = BookId::from_str("f397b83a-dd2a-4a01-9e77-db1eea7de5b6")?;
+        let book3 = BookId::from_str("17afb850-c786-49c5-a303-a3a443a2212c")?;
+        let alice = insert_user(&pool, "alice").await?;
+        let bob = insert_user(&pool, "bob").await?;
+
+        insert_returned(&pool, alice, book1).await?;
+        insert_returned(&pool, alice, book2).await?;
+        insert_returned(&pool, bob, book1).await?;
+
+        // 集計を更新するまでは共に借りられた履歴は反映されない
+        assert!(repo.find_by_user_id(bob, 10).await?.is_empty());
+
+        repo.refresh().await?;
+        let items = repo.find_by_user_id(bob, 10).await?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].book_id, book2);
+        assert_eq!(items[0].co_checkout_count, 1);
+        assert_eq!(items[0].score, 1);
+
+        // 読んだ蔵書とタグが共通する蔵書も推薦する
+        sqlx::query(
+            "WITH t AS (INSERT INTO tags (name) VALUES ('rust') RETURNING tag_id)
+             INSERT INTO book_tags (book_id, tag_id) SELECT b, tag_id FROM t, UNNEST($1::uuid[]) AS b",
+        )
+        .bind(vec![book1.raw(), book3.raw()])
+        .execute(&pool)
+        .await?;
+        let items = repo.find_by_user_id(bob, 10).await?;
+        assert_eq!(
+            items.iter().map(|r| r.book_id).collect::<Vec<_>>(),
+            vec![book2, book3]
+        );
+        assert_eq!(items[1].shared_tag_count, 1);
+
+        // タグが共通していても自分の蔵書は推薦しない
+        insert_returned(&pool, owner_id, book1).await?;
+        assert!(repo.find_by_user_id(owner_id, 10).await?.is_empty());
+        Ok(())
+    }
+}
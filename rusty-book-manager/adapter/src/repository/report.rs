@@ -0,0 +1,399 @@
+use crate::database::{
+    model::report::{
+        AuthorCheckoutRankingRow, BookCheckoutRankingRow, BorrowerRankingRow, CheckoutCountRow,
+        IdleBookRow, LoanDurationRow,
+    },
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::report::{
+    AuthorCheckoutRanking, BookCheckoutRanking, BorrowerRanking, CheckoutCount, Granularity,
+    IdleBook, LoanDuration, ReportPeriod,
+};
+use kernel::repository::report::ReportRepository;
+use shared::error::{AppError, AppResult};
+
+// 以下のクエリでは、貸出中（checkouts）と返却済み（returned_checkouts）の貸出をまとめて数える。
+// 期間の指定が NULL の場合はその側を制限しない
+#[derive(new)]
+pub struct ReportRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ReportRepository for ReportRepositoryImpl {
+    async fn checkout_counts(
+        &self,
+        period: ReportPeriod,
+        granularity: Granularity,
+    ) -> AppResult<Vec<CheckoutCount>> {
+        // 集計単位は UTC で区切る。期間の指定がない側は最初・最後の貸出までとし、
+        // 貸出のない単位も 0 件として並べる。単位が多すぎる場合は集計する前に断る
+        let bounds = sqlx::query!(
+            r#"
+                WITH all_checkouts AS (
+                    SELECT checked_out_at FROM checkouts
+                    UNION ALL
+                    SELECT checked_out_at FROM returned_checkouts
+                )
+                SELECT MIN(checked_out_at) AS first, MAX(checked_out_at) AS last
+                FROM all_checkouts
+                WHERE ($1::TIMESTAMPTZ IS NULL OR checked_out_at >= $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR checked_out_at < $2)
+            "#,
+            period.from,
+            period.to
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let first = period.from.or(bounds.first);
+        let last = period
+            .to
+            .map(|to| to - chrono::Duration::microseconds(1))
+            .or(bounds.last);
+        if let (Some(first), Some(last)) = (first, last) {
+            let buckets = granularity.bucket_count(first, last);
+            if buckets > granularity.max_buckets() {
+                return Err(AppError::BadRequest(format!(
+                    "集計単位の数が多すぎます（{buckets} > {}）。期間を絞るか、より大きな集計単位を指定してください。",
+                    granularity.max_buckets()
+                )));
+            }
+        }
+
+        let rows = sqlx::query_as!(
+            CheckoutCountRow,
+            r#"
+                WITH all_checkouts AS (
+                    SELECT checked_out_at FROM checkouts
+                    UNION ALL
+                    SELECT checked_out_at FROM returned_checkouts
+                ),
+                filtered AS (
+                    SELECT date_trunc($3, checked_out_at AT TIME ZONE 'UTC') AS period_start
+                    FROM all_checkouts
+                    WHERE ($1::TIMESTAMPTZ IS NULL OR checked_out_at >= $1)
+                    AND ($2::TIMESTAMPTZ IS NULL OR checked_out_at < $2)
+                ),
+                bounds AS (
+                    SELECT
+                        COALESCE(
+                            date_trunc($3, $1::TIMESTAMPTZ AT TIME ZONE 'UTC'),
+                            MIN(period_start)
+                        ) AS first,
+                        COALESCE(
+                            date_trunc($3, ($2::TIMESTAMPTZ - INTERVAL '1 microsecond') AT TIME ZONE 'UTC'),
+                            MAX(period_start)
+                        ) AS last
+                    FROM filtered
+                ),
+                periods AS (
+                    SELECT generate_series(first, last, ('1 ' || $3)::INTERVAL) AS period_start
+                    FROM bounds
+                )
+                SELECT
+                    p.period_start AT TIME ZONE 'UTC' AS "period_start!",
+                    COUNT(f.period_start) AS "count!"
+                FROM periods AS p
+                LEFT OUTER JOIN filtered AS f USING(period_start)
+                GROUP BY p.period_start
+                ORDER BY p.period_start
+            "#,
+            period.from,
+            period.to,
+            granularity.as_ref()
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(CheckoutCount::from).collect())
+    }
+
+    async fn top_books(
+        &self,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> AppResult<Vec<BookCheckoutRanking>> {
+        let rows = sqlx::query_as!(
+            BookCheckoutRankingRow,
+            r#"
+                WITH all_checkouts AS (
+                    SELECT book_id, checked_out_at FROM checkouts
+                    UNION ALL
+                    SELECT book_id, checked_out_at FROM returned_checkouts
+                )
+                SELECT
+                    b.book_id,
+                    b.title,
+                    b.author,
+                    COUNT(*) AS "checkout_count!"
+                FROM all_checkouts AS c
+                INNER JOIN books AS b USING(book_id)
+                WHERE ($1::TIMESTAMPTZ IS NULL OR c.checked_out_at >= $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR c.checked_out_at < $2)
+                GROUP BY b.book_id, b.title, b.author
+                ORDER BY 4 DESC, b.title
+                LIMIT $3
+            "#,
+            period.from,
+            period.to,
+            limit
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(BookCheckoutRanking::from).collect())
+    }
+
+    async fn top_authors(
+        &self,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> AppResult<Vec<AuthorCheckoutRanking>> {
+        let rows = sqlx::query_as!(
+            AuthorCheckoutRankingRow,
+            r#"
+                WITH all_checkouts AS (
+                    SELECT book_id, checked_out_at FROM checkouts
+                    UNION ALL
+                    SELECT book_id, checked_out_at FROM returned_checkouts
+                )
+                SELECT
+                    a.author_id,
+                    a.name,
+                    COUNT(*) AS "checkout_count!"
+                FROM all_checkouts AS c
+                INNER JOIN book_authors AS ba ON ba.book_id = c.book_id AND ba.role = 'author'
+                INNER JOIN authors AS a USING(author_id)
+                WHERE ($1::TIMESTAMPTZ IS NULL OR c.checked_out_at >= $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR c.checked_out_at < $2)
+                GROUP BY a.author_id, a.name
+                ORDER BY 3 DESC, a.name
+                LIMIT $3
+            "#,
+            period.from,
+            period.to,
+            limit
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(AuthorCheckoutRanking::from).collect())
+    }
+
+    async fn top_borrowers(
+        &self,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> AppResult<Vec<BorrowerRanking>> {
+        let rows = sqlx::query_as!(
+            BorrowerRankingRow,
+            r#"
+                WITH all_checkouts AS (
+                    SELECT user_id, checked_out_at FROM checkouts
+                    UNION ALL
+                    SELECT user_id, checked_out_at FROM returned_checkouts
+                )
+                SELECT
+                    u.user_id,
+                    u.name,
+                    COUNT(*) AS "checkout_count!"
+                FROM all_checkouts AS c
+                INNER JOIN users AS u USING(user_id)
+                WHERE ($1::TIMESTAMPTZ IS NULL OR c.checked_out_at >= $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR c.checked_out_at < $2)
+                GROUP BY u.user_id, u.name
+                ORDER BY 3 DESC, u.name
+                LIMIT $3
+            "#,
+            period.from,
+            period.to,
+            limit
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(BorrowerRanking::from).collect())
+    }
+
+    async fn loan_duration(&self, period: ReportPeriod) -> AppResult<LoanDuration> {
+        // 貸出日数は返却日時から貸出日時を引いたもの（端数あり）
+        let row = sqlx::query_as!(
+            LoanDurationRow,
+            r#"
+                SELECT
+                    COUNT(*) AS "returned_count!",
+                    AVG(EXTRACT(EPOCH FROM returned_at - checked_out_at) / 86400)::FLOAT8
+                        AS average_days
+                FROM returned_checkouts
+                WHERE ($1::TIMESTAMPTZ IS NULL OR returned_at >= $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR returned_at < $2)
+            "#,
+            period.from,
+            period.to
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row.into())
+    }
+
+    async fn idle_books(&self, period: ReportPeriod, limit: i64) -> AppResult<Vec<IdleBook>> {
+        let rows = sqlx::query_as!(
+            IdleBookRow,
+            r#"
+                WITH all_checkouts AS (
+                    SELECT book_id, checked_out_at FROM checkouts
+                    UNION ALL
+                    SELECT book_id, checked_out_at FROM returned_checkouts
+                )
+                SELECT
+                    b.book_id,
+                    b.title,
+                    b.author,
+                    b.created_at AS registered_at
+                FROM books AS b
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM all_checkouts AS c
+                    WHERE c.book_id = b.book_id
+                    AND ($1::TIMESTAMPTZ IS NULL OR c.checked_out_at >= $1)
+                    AND ($2::TIMESTAMPTZ IS NULL OR c.checked_out_at < $2)
+                )
+                ORDER BY b.created_at, b.title
+                LIMIT $3
+            "#,
+            period.from,
+            period.to,
+            limit
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(IdleBook::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use kernel::model::id::{BookId, UserId};
+    use std::str::FromStr;
+
+    const BOOK1: &str = "9890736e-a4e4-461a-a77d-eac3517ef11b";
+    const BOOK2: &str = "f397b83a-dd2a-4a01-9e77-db1eea7de5b6";
+    const BOOK3: &str = "17afb850-c786-49c5-a303-a3a443a2212c";
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, d, 12, 0, 0).unwrap()
+    }
+
+    async fn insert_returned(
+        pool: &sqlx::PgPool,
+        book_id: &str,
+        checked_out_at: DateTime<Utc>,
+        days: i64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO returned_checkouts (checkout_id, book_id, user_id, checked_out_at, returned_at)
+             VALUES ($1, $2, '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c', $3, $4)",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(uuid::Uuid::from_str(book_id)?)
+        .bind(checked_out_at)
+        .bind(checked_out_at + Duration::days(days))
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_reports(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ReportRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        insert_returned(&pool, BOOK1, day(1), 7).await?;
+        insert_returned(&pool, BOOK1, day(3), 3).await?;
+        insert_returned(&pool, BOOK2, day(3), 2).await?;
+        sqlx::query(
+            "WITH a AS (INSERT INTO authors (name) VALUES ('初田直也') RETURNING author_id)
+             INSERT INTO book_authors (book_id, author_id, role, position)
+             SELECT $1, author_id, 'author', 0 FROM a",
+        )
+        .bind(uuid::Uuid::from_str(BOOK1)?)
+        .execute(&pool)
+        .await?;
+        let all = ReportPeriod::default();
+
+        // 貸出のない 2 日も 0 件として含める
+        let counts = repo.checkout_counts(all, Granularity::Day).await?;
+        assert_eq!(
+            counts.iter().map(|c| c.count).collect::<Vec<_>>(),
+            vec![1, 0, 2]
+        );
+        assert_eq!(
+            counts[0].period_start,
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()
+        );
+        let period = ReportPeriod {
+            from: Some(Utc.with_ymd_and_hms(2025, 2, 27, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap()),
+        };
+        let counts = repo.checkout_counts(period, Granularity::Day).await?;
+        assert_eq!(
+            counts.iter().map(|c| c.count).collect::<Vec<_>>(),
+            vec![0, 0, 1]
+        );
+        let counts = repo.checkout_counts(all, Granularity::Month).await?;
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].count, 3);
+        // 期間の片側だけを指定しても、単位が多すぎる場合は断る
+        let period = ReportPeriod {
+            from: Some(Utc.with_ymd_and_hms(2015, 1, 1, 0, 0, 0).unwrap()),
+            to: None,
+        };
+        let res = repo.checkout_counts(period, Granularity::Day).await;
+        assert!(matches!(res, Err(AppError::BadRequest(_))));
+        let counts = repo.checkout_counts(period, Granularity::Month).await?;
+        assert_eq!(counts.iter().map(|c| c.count).sum::<i64>(), 3);
+
+        let books = repo.top_books(all, 10).await?;
+        assert_eq!(books[0].book_id, BookId::from_str(BOOK1)?);
+        assert_eq!(books[0].checkout_count, 2);
+        assert_eq!(books.len(), 2);
+
+        let authors = repo.top_authors(all, 10).await?;
+        assert_eq!(authors.len(), 1);
+        assert_eq!(authors[0].name, "初田直也");
+        assert_eq!(authors[0].checkout_count, 2);
+
+        let borrowers = repo.top_borrowers(all, 10).await?;
+        assert_eq!(
+            borrowers[0].user_id,
+            UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?
+        );
+        assert_eq!(borrowers[0].checkout_count, 3);
+
+        let duration = repo.loan_duration(all).await?;
+        assert_eq!(duration.returned_count, 3);
+        assert_eq!(duration.average_days, Some(4.0));
+
+        let idle = repo.idle_books(all, 10).await?;
+        assert_eq!(
+            idle.iter().map(|b| b.book_id).collect::<Vec<_>>(),
+            vec![BookId::from_str(BOOK3)?]
+        );
+        // 4 日以降は貸出がないので、すべての蔵書が対象になる
+        let idle = repo
+            .idle_books(
+                ReportPeriod {
+                    from: Some(Utc.with_ymd_and_hms(2025, 3, 4, 0, 0, 0).unwrap()),
+                    to: None,
+                },
+                10,
+            )
+            .await?;
+        assert_eq!(idle.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,364 @@
+use crate::database::{model::review::ReviewRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    id::{BookId, ReviewId},
+    review::{
+        event::{CreateReview, DeleteReview, ModerateReview, UpdateReview},
+        Review,
+    },
+};
+use kernel::repository::review::ReviewRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct ReviewRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ReviewRepository for ReviewRepositoryImpl {
+    async fn create(&self, event: CreateReview) -> AppResult<Review> {
+        let mut tx = self.db.begin().await?;
+
+        let book = sqlx::query_scalar!(
+            r#"
+                SELECT book_id FROM books WHERE book_id = $1
+            "#,
+            event.book_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if book.is_none() {
+            return Err(AppError::EntityNotFound(format!(
+                "書籍（{}）が見つかりませんでした。",
+                event.book_id
+            )));
+        }
+
+        // 実際に借りて返却した蔵書でなければレビューできない。
+        // 紛失した貸出は読み終えたとはいえないので、破損して返却した場合だけを含める
+        let returned = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM returned_checkouts
+                    WHERE book_id = $1 AND user_id = $2 AND outcome <> 'lost'
+                ) AS "returned!"
+            "#,
+            event.book_id as _,
+            event.reviewed_by as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !returned {
+            return Err(AppError::ForbiddenOperation);
+        }
+
+        let review_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO reviews (book_id, user_id, rating, body)
+                VALUES ($1, $2, $3, $4)
+                RETURNING review_id AS "review_id: ReviewId"
+            "#,
+            event.book_id as _,
+            event.reviewed_by as _,
+            event.rating,
+            event.body
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::UnprocessableEntity(format!(
+                    "書籍（{}）は既にレビュー済みです。",
+                    event.book_id
+                ))
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+
+        let row = sqlx::query_as!(
+            ReviewRow,
+            r#"
+                SELECT
+                    r.review_id,
+                    r.book_id,
+                    r.user_id,
+                    u.name AS user_name,
+                    r.rating,
+                    r.body,
+                    r.hidden,
+                    r.created_at,
+                    r.updated_at
+                FROM reviews AS r
+                INNER JOIN users AS u USING(user_id)
+                WHERE r.review_id = $1
+            "#,
+            review_id as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(row.into())
+    }
+
+    async fn find_by_book_id(
+        &self,
+        book_id: BookId,
+        include_hidden: bool,
+    ) -> AppResult<Vec<Review>> {
+        let rows = sqlx::query_as!(
+            ReviewRow,
+            r#"
+                SELECT
+                    r.review_id,
+                    r.book_id,
+                    r.user_id,
+                    u.name AS user_name,
+                    r.rating,
+                    r.body,
+                    r.hidden,
+                    r.created_at,
+                    r.updated_at
+                FROM reviews AS r
+                INNER JOIN users AS u USING(user_id)
+                WHERE r.book_id = $1 AND ($2 OR NOT r.hidden)
+                ORDER BY r.created_at DESC
+            "#,
+            book_id as _,
+            include_hidden
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(Review::from).collect())
+    }
+
+    async fn update(&self, event: UpdateReview) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE reviews SET rating = $1, body = $2
+                WHERE review_id = $3 AND book_id = $4 AND user_id = $5
+            "#,
+            event.rating,
+            event.body,
+            event.review_id as _,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(self
+                .not_modified_reason(event.review_id, event.book_id)
+                .await);
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteReview) -> AppResult<()> {
+        // requested_user が None の場合（管理者）は投稿者を問わない
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM reviews
+                WHERE review_id = $1 AND book_id = $2
+                AND ($3::UUID IS NULL OR user_id = $3)
+            "#,
+            event.review_id as _,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(self
+                .not_modified_reason(event.review_id, event.book_id)
+                .await);
+        }
+        Ok(())
+    }
+
+    async fn moderate(&self, event: ModerateReview) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE reviews SET hidden = $1
+                WHERE review_id = $2 AND book_id = $3
+            "#,
+            event.hidden,
+            event.review_id as _,
+            event.book_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                "Specified review not found".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ReviewRepositoryImpl {
+    // 更新・削除できなかった理由を調べる。レビューが存在すれば投稿者以外による操作である
+    async fn not_modified_reason(&self, review_id: ReviewId, book_id: BookId) -> AppError {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM reviews WHERE review_id = $1 AND book_id = $2
+                ) AS "exists!"
+            "#,
+            review_id as _,
+            book_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await;
+        match exists {
+            Ok(true) => AppError::ForbiddenOperation,
+            Ok(false) => AppError::EntityNotFound("Specified review not found".into()),
+            Err(e) => AppError::SpecificOperationError(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::book::BookRepositoryImpl;
+    use kernel::{
+        model::{
+            book::{BookListOptions, BookSort},
+            id::UserId,
+        },
+        repository::book::BookRepository,
+    };
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_review(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let repo = ReviewRepositoryImpl::new(db.clone());
+        let book_repo = BookRepositoryImpl::new(db);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("f397b83a-dd2a-4a01-9e77-db1eea7de5b6")?;
+        let event = || CreateReview {
+            book_id,
+            reviewed_by: user_id,
+            rating: 4,
+            body: "わかりやすい".into(),
+        };
+
+        // 返却済みの貸出がなければレビューできない
+        let res = repo.create(event()).await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+
+        // 紛失した貸出ではレビューできない
+        sqlx::query(
+            "INSERT INTO returned_checkouts (checkout_id, book_id, user_id, outcome) VALUES ($1, $2, $3, 'lost')",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(book_id.raw())
+        .bind(user_id.raw())
+        .execute(&pool)
+        .await?;
+        let res = repo.create(event()).await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+
+        sqlx::query(
+            "INSERT INTO returned_checkouts (checkout_id, book_id, user_id) VALUES ($1, $2, $3)",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(book_id.raw())
+        .bind(user_id.raw())
+        .execute(&pool)
+        .await?;
+        let review = repo.create(event()).await?;
+        assert_eq!(review.rating, 4);
+        assert_eq!(review.reviewed_by.id, user_id);
+
+        // 同じ蔵書へのレビューは 1 件まで
+        let res = repo.create(event()).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 投稿者以外は編集・削除できない
+        let res = repo
+            .update(UpdateReview {
+                review_id: review.id,
+                book_id,
+                requested_user: UserId::new(),
+                rating: 1,
+                body: String::new(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+        repo.update(UpdateReview {
+            review_id: review.id,
+            book_id,
+            requested_user: user_id,
+            rating: 5,
+            body: "とてもわかりやすい".into(),
+        })
+        .await?;
+
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.rating.count, 1);
+        assert_eq!(book.rating.average, Some(5.0));
+
+        // 評価順ではレビューのある蔵書が先頭になる
+        let list = book_repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: Default::default(),
+                sort: BookSort::Rating,
+            })
+            .await?;
+        assert_eq!(list.items[0].id, book_id);
+        assert_eq!(list.items[0].rating.count, 1);
+
+        // 非表示のレビューは集計や一覧に含めない
+        repo.moderate(ModerateReview {
+            review_id: review.id,
+            book_id,
+            hidden: true,
+        })
+        .await?;
+        assert!(repo.find_by_book_id(book_id, false).await?.is_empty());
+        assert_eq!(repo.find_by_book_id(book_id, true).await?.len(), 1);
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.rating.count, 0);
+        assert_eq!(book.rating.average, None);
+
+        let res = repo
+            .delete(DeleteReview {
+                review_id: review.id,
+                book_id,
+                requested_user: Some(UserId::new()),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+        // 管理者は投稿者を問わず削除できる
+        repo.delete(DeleteReview {
+            review_id: review.id,
+            book_id,
+            requested_user: None,
+        })
+        .await?;
+        let res = repo
+            .delete(DeleteReview {
+                review_id: review.id,
+                book_id,
+                requested_user: None,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        checkout::reminder::{CheckoutReminder, CheckoutReminderKind},
+        job::{JobName, JobOutcome},
+        notification::{event::CreateNotification, NotificationKind},
+    },
+    repository::{
+        auth::AuthRepository,
+        checkout_reminder::CheckoutReminderRepository,
+        job::{Job, JobRunRepository},
+        notification::Notifier,
+        outbox::OutboxRepository,
+        webhook::WebhookRepository,
+    },
+};
+use shared::error::{AppError, AppResult};
+
+// 返却期限を過ぎた貸出と期限が近い貸出の利用者に、それぞれ一度ずつ通知する
+#[derive(new)]
+pub struct CheckoutReminderJob {
+    reminder_repository: Arc<dyn CheckoutReminderRepository>,
+    notifier: Arc<dyn Notifier>,
+}
+
+fn reminder_notification(
+    kind: CheckoutReminderKind,
+    reminder: &CheckoutReminder,
+) -> CreateNotification {
+    let due_at = reminder.due_at.format("%Y-%m-%d %H:%M UTC");
+    let (notification_kind, title, body) = match kind {
+        CheckoutReminderKind::DueSoon => (
+            NotificationKind::CheckoutDueSoon,
+            "返却期限が近づいています",
+            format!(
+                "貸出中の「{}」の返却期限は {due_at} です。期限までにご返却ください。",
+                reminder.title
+            ),
+        ),
+        CheckoutReminderKind::Overdue => (
+            NotificationKind::CheckoutOverdue,
+            "返却期限を過ぎています",
+            format!(
+                "貸出中の「{}」は返却期限の {due_at} を過ぎています。速やかにご返却ください。",
+                reminder.title
+            ),
+        ),
+    };
+    CreateNotification {
+        user_id: reminder.user_id,
+        kind: notification_kind,
+        source_id: reminder.checkout_id.raw(),
+        title: title.into(),
+        body,
+        book_id: Some(reminder.book_id),
+    }
+}
+
+impl CheckoutReminderJob {
+    // 通知してから記録する
+    async fn send(&self, kind: CheckoutReminderKind, reminder: &CheckoutReminder) -> AppResult<()> {
+        self.notifier
+            .notify(reminder_notification(kind, reminder))
+            .await?;
+        self.reminder_repository
+            .record_sent(reminder.checkout_id, kind)
+            .await
+    }
+}
+
+#[async_trait]
+impl Job for CheckoutReminderJob {
+    fn name(&self) -> JobName {
+        JobName::CheckoutReminders
+    }
+
+    async fn run(&self) -> AppResult<JobOutcome> {
+        let mut outcome = JobOutcome::default();
+        for kind in [CheckoutReminderKind::Overdue, CheckoutReminderKind::DueSoon] {
+            for reminder in self.reminder_repository.find_unsent(kind).await? {
+                // 1 件の失敗でほかの利用者への通知が止まらないよう、失敗した分は数えて次に進む。
+                // 記録できなかった分は次の実行で再び渡すが、通知は貸出ごとに一度しか作られない
+                match self.send(kind, &reminder).await {
+                    Ok(()) => outcome.processed += 1,
+                    Err(e) => {
+                        tracing::warn!(
+                            error.message = %e,
+                            checkout_id = %reminder.checkout_id,
+                            kind = ?kind,
+                            "Failed to send checkout reminder"
+                        );
+                        outcome.failed += 1;
+                    }
+                }
+            }
+        }
+        Ok(outcome)
+    }
+}
+
+#[derive(new)]
+pub struct TokenCleanupJob {
+    auth_repository: Arc<dyn AuthRepository>,
+}
+
+#[async_trait]
+impl Job for TokenCleanupJob {
+    fn name(&self) -> JobName {
+        JobName::TokenCleanup
+    }
+
+    async fn run(&self) -> AppResult<JobOutcome> {
+        self.auth_repository
+            .delete_expired_tokens()
+            .await
+            .map(JobOutcome::processed)
+    }
+}
+
+// 配信済みの outbox のイベント、送信を終えた Webhook の配信、ジョブの実行履歴のうち、
+// retention より古いものを削除する
+#[derive(new)]
+pub struct AuditPurgeJob {
+    outbox_repository: Arc<dyn OutboxRepository>,
+    webhook_repository: Arc<dyn WebhookRepository>,
+    job_run_repository: Arc<dyn JobRunRepository>,
+    retention: Duration,
+}
+
+#[async_trait]
+impl Job for AuditPurgeJob {
+    fn name(&self) -> JobName {
+        JobName::AuditPurge
+    }
+
+    async fn run(&self) -> AppResult<JobOutcome> {
+        let retention = TimeDelta::from_std(self.retention)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        let before = Utc::now() - retention;
+        let outbox = self.outbox_repository.purge_published(before).await?;
+        let webhook = self.webhook_repository.purge_finished(before).await?;
+        let job_runs = self.job_run_repository.purge_finished(before).await?;
+        Ok(JobOutcome::processed(outbox + webhook + job_runs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::{
+        model::id::{BookId, CheckoutId, UserId},
+        repository::{
+            checkout_reminder::MockCheckoutReminderRepository, notification::MockNotifier,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_checkout_reminders_are_sent_once_per_kind() -> anyhow::Result<()> {
+        let checkout_id = CheckoutId::new();
+        let user_id = UserId::new();
+        let mut reminders = MockCheckoutReminderRepository::new();
+        reminders.expect_find_unsent().returning(move |kind| {
+            Ok(match kind {
+                CheckoutReminderKind::Overdue => vec![CheckoutReminder {
+                    checkout_id,
+                    book_id: BookId::new(),
+                    title: "実践Rustプログラミング入門".into(),
+                    user_id,
+                    due_at: Utc::now(),
+                }],
+                CheckoutReminderKind::DueSoon => vec![],
+            })
+        });
+        reminders
+            .expect_record_sent()
+            .withf(move |id, kind| *id == checkout_id && *kind == CheckoutReminderKind::Overdue)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(move |event| {
+                event.user_id == user_id
+                    && event.kind == NotificationKind::CheckoutOverdue
+                    && event.source_id == checkout_id.raw()
+                    && event.title == "返却期限を過ぎています"
+                    && event.body.contains("実践Rustプログラミング入門")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let job = CheckoutReminderJob::new(Arc::new(reminders), Arc::new(notifier));
+        assert_eq!(job.run().await?, JobOutcome::processed(1));
+        Ok(())
+    }
+
+    // 通知に失敗した貸出は記録せずに数え、残りの貸出への通知は続ける
+    #[tokio::test]
+    async fn test_checkout_reminders_continue_after_failure() -> anyhow::Result<()> {
+        let failing_user = UserId::new();
+        let reminder = |user_id| CheckoutReminder {
+            checkout_id: CheckoutId::new(),
+            book_id: BookId::new(),
+            title: "実践Rustプログラミング入門".into(),
+            user_id,
+            due_at: Utc::now(),
+        };
+        let mut reminders = MockCheckoutReminderRepository::new();
+        reminders.expect_find_unsent().returning(move |kind| {
+            Ok(match kind {
+                CheckoutReminderKind::Overdue => {
+                    vec![reminder(failing_user), reminder(UserId::new())]
+                }
+                CheckoutReminderKind::DueSoon => vec![reminder(UserId::new())],
+            })
+        });
+        reminders
+            .expect_record_sent()
+            .times(2)
+            .returning(|_, _| Ok(()));
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(3).returning(move |event| {
+            if event.user_id == failing_user {
+                Err(AppError::ExternalServiceError("unavailable".into()))
+            } else {
+                Ok(())
+            }
+        });
+
+        let job = CheckoutReminderJob::new(Arc::new(reminders), Arc::new(notifier));
+        assert_eq!(
+            job.run().await?,
+            JobOutcome {
+                processed: 2,
+                failed: 1
+            }
+        );
+        Ok(())
+    }
+}
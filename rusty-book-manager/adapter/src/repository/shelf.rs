@@ -0,0 +1,238 @@
+use crate::database::{model::shelf::ShelfRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    id::UserId,
+    shelf::{
+        event::{AddShelfBook, CreateShelf, DeleteShelf, RemoveShelfBook},
+        Shelf,
+    },
+};
+use kernel::repository::shelf::ShelfRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct ShelfRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ShelfRepository for ShelfRepositoryImpl {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Shelf>> {
+        let rows = sqlx::query_as!(
+            ShelfRow,
+            r#"
+                SELECT s.shelf_id, s.name, COUNT(sb.book_id) AS "book_count!"
+                FROM shelves AS s
+                LEFT OUTER JOIN shelf_books AS sb USING(shelf_id)
+                WHERE s.user_id = $1
+                GROUP BY s.shelf_id, s.name
+                ORDER BY s.created_at
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(Shelf::from).collect())
+    }
+
+    async fn create(&self, event: CreateShelf) -> AppResult<Shelf> {
+        let row = sqlx::query_as!(
+            ShelfRow,
+            r#"
+                INSERT INTO shelves (user_id, name) VALUES ($1, $2)
+                RETURNING shelf_id, name, 0::BIGINT AS "book_count!"
+            "#,
+            event.user_id as _,
+            event.name
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::UnprocessableEntity(format!("本棚（{}）は既に存在します。", event.name))
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+        Ok(row.into())
+    }
+
+    async fn delete(&self, event: DeleteShelf) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM shelves WHERE shelf_id = $1 AND user_id = $2
+            "#,
+            event.shelf_id as _,
+            event.user_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("Specified shelf not found".into()));
+        }
+        Ok(())
+    }
+
+    async fn add_book(&self, event: AddShelfBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 他のユーザーの本棚は存在しないものとして扱う
+        let shelf = sqlx::query_scalar!(
+            r#"
+                SELECT shelf_id FROM shelves WHERE shelf_id = $1 AND user_id = $2
+                FOR UPDATE
+            "#,
+            event.shelf_id as _,
+            event.user_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if shelf.is_none() {
+            return Err(AppError::EntityNotFound("Specified shelf not found".into()));
+        }
+
+        // 既に入っている場合は何もしない
+        sqlx::query!(
+            r#"
+                INSERT INTO shelf_books (shelf_id, book_id) VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            "#,
+            event.shelf_id as _,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                AppError::EntityNotFound(format!(
+                    "書籍（{}）が見つかりませんでした。",
+                    event.book_id
+                ))
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn remove_book(&self, event: RemoveShelfBook) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM shelf_books AS sb
+                USING shelves AS s
+                WHERE sb.shelf_id = s.shelf_id
+                AND sb.shelf_id = $1 AND s.user_id = $2 AND sb.book_id = $3
+            "#,
+            event.shelf_id as _,
+            event.user_id as _,
+            event.book_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                "Specified book is not on the shelf".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::book::BookRepositoryImpl;
+    use kernel::{
+        model::{
+            book::{BookListFilter, BookListOptions, ShelfFilter},
+            id::BookId,
+        },
+        repository::book::BookRepository,
+    };
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_shelf_books(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let shelf_repo = ShelfRepositoryImpl::new(db.clone());
+        let book_repo = BookRepositoryImpl::new(db);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        let shelf = shelf_repo
+            .create(CreateShelf {
+                user_id,
+                name: "読みたい".into(),
+            })
+            .await?;
+        let res = shelf_repo
+            .create(CreateShelf {
+                user_id,
+                name: "読みたい".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        shelf_repo
+            .add_book(AddShelfBook {
+                shelf_id: shelf.id,
+                user_id,
+                book_id,
+            })
+            .await?;
+        // 他のユーザーの本棚には追加できない
+        let res = shelf_repo
+            .add_book(AddShelfBook {
+                shelf_id: shelf.id,
+                user_id: UserId::new(),
+                book_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let shelves = shelf_repo.find_by_user_id(user_id).await?;
+        assert_eq!(shelves.len(), 1);
+        assert_eq!(shelves[0].book_count, 1);
+
+        let options = BookListOptions {
+            limit: 20,
+            offset: 0,
+            filter: BookListFilter {
+                shelf: Some(ShelfFilter {
+                    shelf_id: shelf.id,
+                    user_id,
+                }),
+                ..Default::default()
+            },
+            sort: Default::default(),
+        };
+        let list = book_repo.find_all(options.clone()).await?;
+        assert_eq!(
+            list.items.iter().map(|b| b.id).collect::<Vec<_>>(),
+            vec![book_id]
+        );
+
+        shelf_repo
+            .remove_book(RemoveShelfBook {
+                shelf_id: shelf.id,
+                user_id,
+                book_id,
+            })
+            .await?;
+        assert_eq!(book_repo.find_all(options).await?.total, 0);
+
+        shelf_repo
+            .delete(DeleteShelf {
+                shelf_id: shelf.id,
+                user_id,
+            })
+            .await?;
+        assert!(shelf_repo.find_by_user_id(user_id).await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::database::{model::tag::TagRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::tag::{
+    event::{AddBookTag, RemoveBookTag},
+    Tag,
+};
+use kernel::repository::tag::TagRepository;
+use shared::error::{AppError, AppResult};
+
+use super::book::touch_book;
+
+#[derive(new)]
+pub struct TagRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl TagRepository for TagRepositoryImpl {
+    async fn find_all(&self) -> AppResult<Vec<Tag>> {
+        // どの蔵書にも付いていないタグは返さない
+        let rows = sqlx::query_as!(
+            TagRow,
+            r#"
+                SELECT t.name, COUNT(*) AS "book_count!"
+                FROM tags AS t
+                INNER JOIN book_tags AS bt USING(tag_id)
+                GROUP BY t.tag_id, t.name
+                ORDER BY COUNT(*) DESC, t.name
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows.into_iter().map(Tag::from).collect())
+    }
+
+    async fn add_to_book(&self, event: AddBookTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // タグがなければ作成する。既にある場合も tag_id を返すため、名前で更新する
+        let tag_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO tags (name) VALUES ($1)
+                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING tag_id
+            "#,
+            event.name
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 既に付いている場合は何もしない
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO book_tags (book_id, tag_id) VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            "#,
+            event.book_id as _,
+            tag_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                AppError::EntityNotFound(format!(
+                    "書籍（{}）が見つかりませんでした。",
+                    event.book_id
+                ))
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
+        if res.rows_affected() > 0 {
+            touch_book(&mut tx, event.book_id).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn remove_from_book(&self, event: RemoveBookTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM book_tags
+                WHERE book_id = $1
+                AND tag_id = (SELECT tag_id FROM tags WHERE name = $2)
+            "#,
+            event.book_id as _,
+            event.name
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                "Specified tag is not attached to the book".into(),
+            ));
+        }
+        touch_book(&mut tx, event.book_id).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::book::BookRepositoryImpl;
+    use kernel::{model::id::BookId, repository::book::BookRepository};
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_add_and_remove_tag(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let tag_repo = TagRepositoryImpl::new(db.clone());
+        let book_repo = BookRepositoryImpl::new(db);
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        let version = book_repo.find_by_id(book_id).await?.unwrap().version;
+
+        // 同じタグを二度付けても一つになる
+        for name in ["web", "rust", "rust"] {
+            tag_repo
+                .add_to_book(AddBookTag {
+                    book_id,
+                    name: name.into(),
+                })
+                .await?;
+        }
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.tags, vec!["rust", "web"]);
+        // ETag の元になるバージョンは、実際にタグが付いた 2 回だけ上がる
+        assert_eq!(book.version, version + 2);
+        let tags = tag_repo.find_all().await?;
+        assert_eq!(tags.len(), 2);
+        assert!(tags.iter().all(|t| t.book_count == 1));
+
+        tag_repo
+            .remove_from_book(RemoveBookTag {
+                book_id,
+                name: "web".into(),
+            })
+            .await?;
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.tags, vec!["rust"]);
+        assert_eq!(book.version, version + 3);
+        assert_eq!(tag_repo.find_all().await?.len(), 1);
+
+        // 付いていないタグは外せない
+        let res = tag_repo
+            .remove_from_book(RemoveBookTag {
+                book_id,
+                name: "web".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        // 存在しない蔵書にはタグを付けられない
+        let res = tag_repo
+            .add_to_book(AddBookTag {
+                book_id: BookId::new(),
+                name: "rust".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        Ok(())
+    }
+}
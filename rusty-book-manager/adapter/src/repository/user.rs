@@ -1,15 +1,21 @@
 use crate::database::{model::user::UserRow, ConnectionPool};
 use async_trait::async_trait;
 use derive_new::new;
+use kernel::model::domain_event::DomainEvent;
 use kernel::model::id::UserId;
 use kernel::model::role::Role;
 use kernel::model::user::{
-    event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+    event::{
+        CreateUser, DeleteUser, ResetUserPassword, UpdateUserEmail, UpdateUserName,
+        UpdateUserPassword, UpdateUserRole,
+    },
     User,
 };
 use kernel::repository::user::UserRepository;
 use shared::error::{AppError, AppResult};
 
+use super::outbox::record_event;
+
 #[derive(new)]
 pub struct UserRepositoryImpl {
     db: ConnectionPool,
@@ -68,10 +74,34 @@ impl UserRepository for UserRepositoryImpl {
         Ok(users)
     }
 
+    async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
+        let row = sqlx::query_as!(
+            UserRow,
+            r#"
+                SELECT
+                u.user_id,
+                u.name,
+                u.email,
+                r.name as role_name,
+                u.created_at,
+                u.updated_at
+                FROM users AS u
+                INNER JOIN roles AS r USING(role_id)
+                WHERE u.email = $1
+            "#,
+            email
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        row.map(User::try_from).transpose()
+    }
+
     async fn create(&self, event: CreateUser) -> AppResult<User> {
         let user_id = UserId::new();
         let hashed_password = hash_password(&event.password)?;
         let role = Role::User;
+        let mut tx = self.db.begin().await?;
         let res = sqlx::query!(
             r#"
                 INSERT INTO users(user_id, name, email, password_hash, role_id)
@@ -83,7 +113,7 @@ impl UserRepository for UserRepositoryImpl {
             hashed_password,
             role.as_ref()
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
@@ -91,6 +121,8 @@ impl UserRepository for UserRepositoryImpl {
                 "No user has been created".into(),
             ));
         }
+        record_event(&mut tx, DomainEvent::UserCreated { user_id }).await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(User {
             id: user_id,
             name: event.name,
@@ -127,7 +159,26 @@ impl UserRepository for UserRepositoryImpl {
         Ok(())
     }
 
+    async fn reset_password(&self, event: ResetUserPassword) -> AppResult<()> {
+        let new_password_hash = hash_password(&event.new_password)?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE users SET password_hash = $2 WHERE user_id = $1;
+            "#,
+            event.user_id as _,
+            new_password_hash,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("Specified user not found".into()));
+        }
+        Ok(())
+    }
+
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
         let res = sqlx::query!(
             r#"
                 UPDATE users
@@ -139,31 +190,143 @@ impl UserRepository for UserRepositoryImpl {
             event.user_id as _,
             event.role.as_ref()
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
             return Err(AppError::EntityNotFound("Specified user not found".into()));
         }
+        record_event(
+            &mut tx,
+            DomainEvent::UserUpdated {
+                user_id: event.user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 
-    async fn delete(&self, event: DeleteUser) -> AppResult<()> {
+    async fn update_name(&self, event: UpdateUserName) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
         let res = sqlx::query!(
             r#"
-                DELETE FROM users
+                UPDATE users SET name = $2 WHERE user_id = $1;
+            "#,
+            event.user_id as _,
+            event.name,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("Specified user not found".into()));
+        }
+        // 所有者・借り手の名前は蔵書のレスポンスに含まれるため、該当する蔵書のバージョンを上げて ETag を変える
+        sqlx::query!(
+            r#"
+                UPDATE books SET updated_at = CURRENT_TIMESTAMP(3)
                 WHERE user_id = $1
+                    OR book_id IN (SELECT book_id FROM checkouts WHERE user_id = $1)
             "#,
             event.user_id as _,
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
+        record_event(
+            &mut tx,
+            DomainEvent::UserUpdated {
+                user_id: event.user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE users SET email = $2 WHERE user_id = $1;
+            "#,
+            event.user_id as _,
+            event.email,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            // 確認待ちの間に他のユーザーが同じアドレスを使い始めた場合
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                AppError::UnprocessableEntity("Email address is already in use".into())
+            }
+            e => AppError::SpecificOperationError(e),
+        })?;
         if res.rows_affected() < 1 {
             return Err(AppError::EntityNotFound("Specified user not found".into()));
         }
+        record_event(
+            &mut tx,
+            DomainEvent::UserUpdated {
+                user_id: event.user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
+
+    async fn delete(&self, event: DeleteUser) -> AppResult<Vec<String>> {
+        let mut tx = self.db.begin().await?;
+        // 所有する蔵書は外部キーの ON DELETE CASCADE で消えるので、表紙のキーを先に取り出しておく
+        let cover_images = sqlx::query_scalar!(
+            r#"
+                SELECT cover_image AS "cover_image!"
+                FROM books
+                WHERE user_id = $1 AND cover_image IS NOT NULL
+                FOR UPDATE
+            "#,
+            event.user_id as _,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // 譲渡の記録は所有者の履歴として残すが、返事を待つ申し出は相手がいなくなるので取り消す
+        sqlx::query!(
+            r#"
+                UPDATE book_transfers
+                SET status = 'cancelled', resolved_at = CURRENT_TIMESTAMP(3)
+                WHERE status = 'pending' AND (from_user_id = $1 OR to_user_id = $1)
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM users
+                WHERE user_id = $1
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("Specified user not found".into()));
+        }
+        record_event(
+            &mut tx,
+            DomainEvent::UserDeleted {
+                user_id: event.user_id,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(cover_images)
+    }
 }
 
 fn hash_password(password: &str) -> AppResult<String> {
@@ -177,3 +340,81 @@ fn verify_password(password: &str, hash: &str) -> AppResult<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_reset_password(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // 現在のパスワードを知らなくても再設定できる
+        repo.reset_password(ResetUserPassword {
+            user_id,
+            new_password: "new-password".into(),
+        })
+        .await?;
+        let password_hash: String =
+            sqlx::query_scalar("SELECT password_hash FROM users WHERE user_id = $1::UUID")
+                .bind(user_id.to_string())
+                .fetch_one(&pool)
+                .await?;
+        assert!(bcrypt::verify("new-password", &password_hash)?);
+
+        let res = repo
+            .reset_password(ResetUserPassword {
+                user_id: UserId::new(),
+                new_password: "new-password".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_update_name_bumps_book_version(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        use crate::repository::book::BookRepositoryImpl;
+        use kernel::{model::id::BookId, repository::book::BookRepository};
+
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let book_repo = BookRepositoryImpl::new(ConnectionPool::new(pool));
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let version = book_repo.find_by_id(book_id).await?.unwrap().version;
+
+        // 所有者の名前が変わると蔵書のバージョンも上がる
+        repo.update_name(UpdateUserName {
+            user_id,
+            name: "Renamed Owner".into(),
+        })
+        .await?;
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.owner.name, "Renamed Owner");
+        assert_eq!(book.version, version + 1);
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_delete_returns_cover_images(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        sqlx::query(
+            "UPDATE books SET cover_image = 'covers/1/a.png' WHERE book_id = '9890736e-a4e4-461a-a77d-eac3517ef11b'",
+        )
+        .execute(&pool)
+        .await?;
+
+        // 一緒に削除される蔵書の表紙のキーを返す
+        let cover_images = repo.delete(DeleteUser { user_id }).await?;
+        assert_eq!(cover_images, vec!["covers/1/a.png".to_string()]);
+        let books: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM books WHERE user_id = $1::UUID")
+            .bind(user_id.to_string())
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(books, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,474 @@
+use std::time::Duration;
+
+use crate::database::{
+    model::webhook::{PendingWebhookDeliveryRow, WebhookDeliveryRow, WebhookSubscriptionRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::model::{
+    id::{DomainEventId, WebhookDeliveryId},
+    list::PaginatedList,
+    webhook::{
+        event::{CreateWebhookSubscription, DeleteWebhookSubscription, RecordWebhookFailure},
+        PendingWebhookDelivery, WebhookDelivery, WebhookDeliveryListOptions, WebhookEvent,
+        WebhookSubscription,
+    },
+};
+use kernel::repository::webhook::WebhookRepository;
+use serde::Serialize;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct WebhookRepositoryImpl {
+    db: ConnectionPool,
+    // この回数だけ失敗した配信は再送をやめる
+    max_attempts: i32,
+    // 1 回目の再送までの間隔。以降は失敗するたびに倍にする
+    retry_base_delay: Duration,
+}
+
+// 購読先に送る JSON。id は元のドメインイベントの ID で、受け取る側はこれで重複を除ける
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookPayload<'a> {
+    id: DomainEventId,
+    occurred_at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a WebhookEvent,
+}
+
+#[async_trait]
+impl WebhookRepository for WebhookRepositoryImpl {
+    async fn create_subscription(
+        &self,
+        event: CreateWebhookSubscription,
+    ) -> AppResult<WebhookSubscription> {
+        let event_types: Vec<String> = event
+            .event_types
+            .iter()
+            .map(|t| t.as_ref().to_string())
+            .collect();
+        let row = sqlx::query_as!(
+            WebhookSubscriptionRow,
+            r#"
+                INSERT INTO webhook_subscriptions (url, secret, event_types)
+                VALUES ($1, $2, $3)
+                RETURNING
+                    subscription_id,
+                    url,
+                    event_types,
+                    created_at
+            "#,
+            event.url,
+            event.secret,
+            &event_types
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        row.try_into()
+    }
+
+    async fn find_subscriptions(&self) -> AppResult<Vec<WebhookSubscription>> {
+        sqlx::query_as!(
+            WebhookSubscriptionRow,
+            r#"
+                SELECT
+                    subscription_id,
+                    url,
+                    event_types,
+                    created_at
+                FROM webhook_subscriptions
+                ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(WebhookSubscription::try_from)
+        .collect()
+    }
+
+    // 購読を削除すると、その購読の配信もまとめて削除される
+    async fn delete_subscription(&self, event: DeleteWebhookSubscription) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM webhook_subscriptions WHERE subscription_id = $1
+            "#,
+            event.subscription_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(format!(
+                "Webhook の購読（{}）が見つかりませんでした。",
+                event.subscription_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn enqueue(
+        &self,
+        event_id: DomainEventId,
+        occurred_at: DateTime<Utc>,
+        event: WebhookEvent,
+    ) -> AppResult<()> {
+        let payload = serde_json::to_string(&WebhookPayload {
+            id: event_id,
+            occurred_at,
+            event: &event,
+        })
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        let event_type = event.event_type();
+        sqlx::query!(
+            r#"
+                INSERT INTO webhook_deliveries (subscription_id, event_id, event_type, payload)
+                SELECT subscription_id, $1, $2, $3::TEXT::JSONB
+                FROM webhook_subscriptions
+                WHERE $2 = ANY(event_types)
+                ON CONFLICT (subscription_id, event_id) DO NOTHING
+            "#,
+            event_id as _,
+            event_type.as_ref(),
+            payload
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn find_deliveries(
+        &self,
+        options: WebhookDeliveryListOptions,
+    ) -> AppResult<PaginatedList<WebhookDelivery>> {
+        let WebhookDeliveryListOptions {
+            limit,
+            offset,
+            status,
+        } = options;
+        let status = status.map(|s| s.as_ref().to_string());
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "total!"
+                FROM webhook_deliveries
+                WHERE $1::TEXT IS NULL OR status = $1
+            "#,
+            status
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let rows = sqlx::query_as!(
+            WebhookDeliveryRow,
+            r#"
+                SELECT
+                    delivery_id,
+                    subscription_id,
+                    event_type,
+                    payload::TEXT AS "payload!",
+                    status,
+                    attempts,
+                    next_attempt_at,
+                    last_error,
+                    delivered_at,
+                    created_at
+                FROM webhook_deliveries
+                WHERE $1::TEXT IS NULL OR status = $1
+                ORDER BY created_at DESC, delivery_id
+                LIMIT $2
+                OFFSET $3
+            "#,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let items = rows
+            .into_iter()
+            .map(WebhookDelivery::try_from)
+            .collect::<AppResult<_>>()?;
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn redeliver(&self, delivery_id: WebhookDeliveryId) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE webhook_deliveries
+                SET
+                    status = 'pending',
+                    attempts = 0,
+                    next_attempt_at = CURRENT_TIMESTAMP(3),
+                    delivered_at = NULL
+                WHERE delivery_id = $1 AND status = 'dead'
+            "#,
+            delivery_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() > 0 {
+            return Ok(());
+        }
+        // 送信待ち・送信済みの配信を送り直すと、同じイベントが重複して届いてしまう
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM webhook_deliveries WHERE delivery_id = $1) AS "exists!""#,
+            delivery_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if exists {
+            Err(AppError::UnprocessableEntity(format!(
+                "Webhook の配信（{}）は送信を諦めたものではないため、再送できません。",
+                delivery_id
+            )))
+        } else {
+            Err(AppError::EntityNotFound(format!(
+                "Webhook の配信（{}）が見つかりませんでした。",
+                delivery_id
+            )))
+        }
+    }
+
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease: Duration,
+    ) -> AppResult<Vec<PendingWebhookDelivery>> {
+        // 取り出した配信は次の送信時刻を lease だけ先に延ばしておき、
+        // 送信中にプロセスが止まっても lease の経過後に再送されるようにする
+        let rows = sqlx::query_as!(
+            PendingWebhookDeliveryRow,
+            r#"
+                WITH due AS (
+                    SELECT delivery_id
+                    FROM webhook_deliveries
+                    WHERE status = 'pending' AND next_attempt_at <= CURRENT_TIMESTAMP(3)
+                    ORDER BY next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                UPDATE webhook_deliveries AS d
+                SET next_attempt_at = CURRENT_TIMESTAMP(3) + make_interval(secs => $2)
+                FROM due, webhook_subscriptions AS s
+                WHERE d.delivery_id = due.delivery_id
+                AND s.subscription_id = d.subscription_id
+                RETURNING
+                    d.delivery_id,
+                    s.url,
+                    s.secret,
+                    d.event_type,
+                    d.payload::TEXT AS "payload!",
+                    d.attempts
+            "#,
+            limit,
+            lease.as_secs_f64()
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        rows.into_iter()
+            .map(PendingWebhookDelivery::try_from)
+            .collect()
+    }
+
+    async fn record_success(&self, delivery_id: WebhookDeliveryId) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                UPDATE webhook_deliveries
+                SET
+                    status = 'succeeded',
+                    attempts = attempts + 1,
+                    last_error = NULL,
+                    delivered_at = CURRENT_TIMESTAMP(3)
+                WHERE delivery_id = $1 AND status = 'pending'
+            "#,
+            delivery_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn record_failure(&self, event: RecordWebhookFailure) -> AppResult<()> {
+        // 再送までの間隔は retry_base_delay * 2^(これまでの失敗回数)
+        sqlx::query!(
+            r#"
+                UPDATE webhook_deliveries
+                SET
+                    attempts = attempts + 1,
+                    last_error = $2,
+                    status = CASE WHEN attempts + 1 >= $3 THEN 'dead' ELSE 'pending' END,
+                    next_attempt_at = CURRENT_TIMESTAMP(3)
+                        + make_interval(secs => $4 * power(2, attempts))
+                WHERE delivery_id = $1 AND status = 'pending'
+            "#,
+            event.delivery_id as _,
+            event.error,
+            self.max_attempts,
+            self.retry_base_delay.as_secs_f64()
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn purge_finished(&self, before: DateTime<Utc>) -> AppResult<u64> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM webhook_deliveries
+                WHERE status IN ('succeeded', 'dead') AND updated_at < $1
+            "#,
+            before
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::{
+        id::{BookId, UserId},
+        webhook::{WebhookBook, WebhookDeliveryStatus, WebhookEventType, WebhookUser},
+    };
+
+    fn book_created() -> WebhookEvent {
+        WebhookEvent::BookCreated(WebhookBook {
+            book_id: BookId::new(),
+            title: "Rust の本".into(),
+            author: "著者".into(),
+            isbn: "978-4-00-000000-0".into(),
+            owner_id: UserId::new(),
+        })
+    }
+
+    #[sqlx::test]
+    async fn test_delivery_retry_and_redeliver(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo =
+            WebhookRepositoryImpl::new(ConnectionPool::new(pool), 2, Duration::from_secs(60));
+        let subscription = repo
+            .create_subscription(CreateWebhookSubscription {
+                url: "https://example.com/hook".into(),
+                secret: "0123456789abcdef".into(),
+                event_types: vec![WebhookEventType::BookCreated],
+            })
+            .await?;
+        assert_eq!(
+            subscription.event_types,
+            vec![WebhookEventType::BookCreated]
+        );
+
+        // 購読していないイベントは積まれず、同じイベントを積み直しても配信は増えない
+        let event_id = DomainEventId::new();
+        repo.enqueue(event_id, Utc::now(), book_created()).await?;
+        repo.enqueue(event_id, Utc::now(), book_created()).await?;
+        repo.enqueue(
+            DomainEventId::new(),
+            Utc::now(),
+            WebhookEvent::UserCreated(WebhookUser {
+                user_id: UserId::new(),
+                name: "Alice".into(),
+            }),
+        )
+        .await?;
+        let lease = Duration::from_secs(60);
+        let claimed = repo.claim_due(10, lease).await?;
+        assert_eq!(claimed.len(), 1);
+        let delivery = &claimed[0];
+        assert_eq!(delivery.url, "https://example.com/hook");
+        assert_eq!(delivery.event_type, WebhookEventType::BookCreated);
+        let payload: serde_json::Value = serde_json::from_str(&delivery.payload)?;
+        assert_eq!(payload["id"], event_id.to_string());
+        assert_eq!(payload["type"], "book.created");
+        assert_eq!(payload["data"]["title"], "Rust の本");
+
+        // 取り出し中の配信は再び取り出されない
+        assert!(repo.claim_due(10, lease).await?.is_empty());
+
+        // 上限に達するまでは再送を予約し、達したら dead にする
+        let fail = |error: &str| RecordWebhookFailure {
+            delivery_id: delivery.id,
+            error: error.into(),
+        };
+        repo.record_failure(fail("connection refused")).await?;
+        let pending = repo
+            .find_deliveries(WebhookDeliveryListOptions {
+                limit: 10,
+                offset: 0,
+                status: Some(WebhookDeliveryStatus::Pending),
+            })
+            .await?;
+        assert_eq!(pending.total, 1);
+        assert_eq!(pending.items[0].attempts, 1);
+        assert!(pending.items[0].next_attempt_at > Utc::now());
+        repo.record_failure(fail("returned 500")).await?;
+        let dead = repo
+            .find_deliveries(WebhookDeliveryListOptions {
+                limit: 10,
+                offset: 0,
+                status: Some(WebhookDeliveryStatus::Dead),
+            })
+            .await?;
+        assert_eq!(dead.total, 1);
+        assert_eq!(dead.items[0].last_error.as_deref(), Some("returned 500"));
+
+        // 再送すると失敗回数を数え直してすぐに取り出せる。取り出し中の配信は再送できない
+        repo.redeliver(delivery.id).await?;
+        let claimed = repo.claim_due(10, lease).await?;
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].attempts, 0);
+        let res = repo.redeliver(delivery.id).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        repo.record_success(delivery.id).await?;
+        let succeeded = repo
+            .find_deliveries(WebhookDeliveryListOptions {
+                limit: 10,
+                offset: 0,
+                status: Some(WebhookDeliveryStatus::Succeeded),
+            })
+            .await?;
+        assert_eq!(succeeded.total, 1);
+        assert!(succeeded.items[0].delivered_at.is_some());
+        let res = repo.redeliver(delivery.id).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let res = repo.redeliver(WebhookDeliveryId::new()).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 購読を削除すると配信も消える
+        repo.delete_subscription(DeleteWebhookSubscription {
+            subscription_id: subscription.id,
+        })
+        .await?;
+        let all = repo
+            .find_deliveries(WebhookDeliveryListOptions {
+                limit: 10,
+                offset: 0,
+                status: None,
+            })
+            .await?;
+        assert_eq!(all.total, 0);
+        Ok(())
+    }
+}
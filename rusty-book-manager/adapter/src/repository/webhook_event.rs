@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        domain_event::{DomainEvent, OutboxEvent},
+        webhook::{WebhookCheckout, WebhookEvent, WebhookReturn, WebhookUser},
+    },
+    repository::{
+        book::BookRepository, outbox::DomainEventHandler, user::UserRepository,
+        webhook::WebhookRepository,
+    },
+};
+use shared::error::AppResult;
+
+// outbox のイベントのうち、Webhook で購読できるものを配信のキューに積む
+#[derive(new)]
+pub struct WebhookEventHandler {
+    book_repository: Arc<dyn BookRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    webhook_repository: Arc<dyn WebhookRepository>,
+}
+
+impl WebhookEventHandler {
+    // 通知には処理する時点の蔵書・利用者の内容を載せる。すでに削除されていれば通知しない
+    async fn to_webhook_event(&self, event: &DomainEvent) -> AppResult<Option<WebhookEvent>> {
+        let webhook_event = match *event {
+            DomainEvent::BookCreated { book_id, .. } => self
+                .book_repository
+                .find_by_id(book_id)
+                .await?
+                .map(|book| WebhookEvent::BookCreated(book.into())),
+            // 所有者や状態の変更は蔵書の更新として通知する
+            DomainEvent::BookUpdated { book_id }
+            | DomainEvent::BookTransferred { book_id, .. }
+            | DomainEvent::BookConditionChanged { book_id, .. } => self
+                .book_repository
+                .find_by_id(book_id)
+                .await?
+                .map(|book| WebhookEvent::BookUpdated(book.into())),
+            DomainEvent::CheckoutCreated {
+                checkout_id,
+                book_id,
+                user_id,
+                checked_out_at,
+                ..
+            } => Some(WebhookEvent::CheckoutCreated(WebhookCheckout {
+                checkout_id,
+                book_id,
+                checked_out_by: user_id,
+                checked_out_at,
+            })),
+            DomainEvent::CheckoutReturned {
+                checkout_id,
+                book_id,
+                user_id,
+                returned_at,
+                ..
+            } => Some(WebhookEvent::CheckoutReturned(WebhookReturn {
+                checkout_id,
+                book_id,
+                returned_by: user_id,
+                returned_at,
+            })),
+            // メールアドレスなどの個人情報は外部に送らない
+            DomainEvent::UserCreated { user_id } => self
+                .user_repository
+                .find_current_user(user_id)
+                .await?
+                .map(|user| {
+                    WebhookEvent::UserCreated(WebhookUser {
+                        user_id: user.id,
+                        name: user.name,
+                    })
+                }),
+            DomainEvent::BookDeleted { .. }
+            | DomainEvent::BookTransferOffered { .. }
+            | DomainEvent::BookTransferDeclined { .. }
+            | DomainEvent::BookTransferCancelled { .. }
+            | DomainEvent::UserUpdated { .. }
+            | DomainEvent::UserDeleted { .. } => None,
+        };
+        Ok(webhook_event)
+    }
+}
+
+#[async_trait]
+impl DomainEventHandler for WebhookEventHandler {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> AppResult<()> {
+        let Some(webhook_event) = self.to_webhook_event(&event.event).await? else {
+            return Ok(());
+        };
+        self.webhook_repository
+            .enqueue(event.id, event.occurred_at, webhook_event)
+            .await
+    }
+}
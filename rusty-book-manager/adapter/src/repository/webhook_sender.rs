@@ -0,0 +1,156 @@
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use kernel::{
+    model::webhook::{is_public_address, PendingWebhookDelivery},
+    repository::webhook::WebhookSender,
+};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    header, redirect, Url,
+};
+use sha2::Sha256;
+use shared::error::{AppError, AppResult};
+use url::Host;
+
+// 購読先に JSON を POST する。受け取る側は X-Webhook-Signature を
+// 共有鍵で検証することで、送り主と本文が改ざんされていないことを確かめられる
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new(timeout: Duration) -> AppResult<Self> {
+        // リダイレクト先に署名付きの本文を送らないよう、リダイレクトは失敗として扱う。
+        // プロキシを経由すると宛先のアドレスを確かめられないため、直接接続する
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicResolver))
+            .user_agent(concat!("rusty-book-manager/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, delivery: &PendingWebhookDelivery) -> AppResult<()> {
+        check_literal_host(&delivery.url)?;
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature = sign(&delivery.secret, &timestamp, &delivery.payload);
+        let res = self
+            .client
+            .post(&delivery.url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("X-Webhook-Id", delivery.id.to_string())
+            .header("X-Webhook-Event", delivery.event_type.as_ref())
+            .header("X-Webhook-Timestamp", &timestamp)
+            .header("X-Webhook-Signature", format!("sha256={signature}"))
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+        if !res.status().is_success() {
+            return Err(AppError::ExternalServiceError(format!(
+                "webhook endpoint returned {}",
+                res.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+// ホスト名を解決し、公開されたアドレスだけに接続させる。登録後に DNS の向き先が
+// 内部のアドレスへ変えられても、送信のたびに確かめるので届かない
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} does not resolve to a public address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// IP アドレスを直接指定した URL は名前解決を通らないため、送信前に確かめる
+fn check_literal_host(url: &str) -> AppResult<()> {
+    let url = Url::parse(url).map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+    let ip = match url.host() {
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        _ => return Ok(()),
+    };
+    if !is_public_address(ip) {
+        return Err(AppError::ExternalServiceError(format!(
+            "webhook endpoint {ip} is not a public address"
+        )));
+    }
+    Ok(())
+}
+
+// 「タイムスタンプ.本文」の HMAC-SHA256 を 16 進数で表す。
+// タイムスタンプを含めることで、受け取る側は古い配信の再送（リプレイ）を拒否できる
+fn sign(secret: &str, timestamp: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::{id::WebhookDeliveryId, webhook::WebhookEventType};
+
+    #[test]
+    fn test_sign() {
+        // echo -n '1700000000.{"type":"book.created"}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign("secret", "1700000000", r#"{"type":"book.created"}"#),
+            "cea64377d77cfb865366a43e64857c5c8031ccf5c74f41adc49a5ba1877c09fb"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_rejects_private_address() -> anyhow::Result<()> {
+        let sender = HttpWebhookSender::new(Duration::from_secs(1))?;
+        for url in ["http://127.0.0.1:9/hook", "http://[::1]:9/hook"] {
+            let res = sender
+                .send(&PendingWebhookDelivery {
+                    id: WebhookDeliveryId::new(),
+                    url: url.into(),
+                    secret: "secret".into(),
+                    event_type: WebhookEventType::BookCreated,
+                    payload: "{}".into(),
+                    attempts: 0,
+                })
+                .await;
+            assert!(
+                matches!(res, Err(AppError::ExternalServiceError(ref m)) if m.contains("public address")),
+                "{url}"
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolver_rejects_private_address() {
+        let res = PublicResolver.resolve("localhost".parse().unwrap()).await;
+        assert!(res.is_err());
+    }
+}
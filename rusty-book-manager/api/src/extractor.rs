@@ -1,5 +1,6 @@
-use axum::extract::FromRequestParts;
-use axum::http::request::Parts;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use axum::http::{header, request::Parts};
 use axum::{async_trait, RequestPartsExt};
 use axum_extra::headers::authorization::Bearer;
 use axum_extra::headers::Authorization;
@@ -9,6 +10,7 @@ use kernel::model::id::UserId;
 use kernel::model::role::Role;
 use kernel::model::user::User;
 use registry::AppRegistry;
+use serde::de::DeserializeOwned;
 use shared::error::AppError;
 
 pub struct AuthorizedUser {
@@ -54,3 +56,39 @@ impl FromRequestParts<AppRegistry> for AuthorizedUser {
         Ok(Self { access_token, user })
     }
 }
+
+// JSON Merge Patch (RFC 7396) のリクエスト本体。
+// Content-Type は application/merge-patch+json のほか、application/json も受け付ける
+pub struct MergePatch<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MergePatch<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase());
+        if !matches!(
+            content_type.as_deref(),
+            Some("application/merge-patch+json" | "application/json")
+        ) {
+            return Err(AppError::UnsupportedMediaType(
+                "expected application/merge-patch+json".into(),
+            ));
+        }
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| AppError::UnprocessableEntity(e.body_text()))?;
+        serde_json::from_slice(&bytes)
+            .map(Self)
+            .map_err(|e| AppError::UnprocessableEntity(e.to_string()))
+    }
+}
@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use kernel::model::id::AuthorId;
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::author::{AuthorDetailResponse, AuthorResponse, AuthorsResponse},
+};
+
+pub async fn list_authors(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<AuthorsResponse>> {
+    let items = registry
+        .author_repository()
+        .find_all()
+        .await?
+        .into_iter()
+        .map(AuthorResponse::from)
+        .collect();
+    Ok(Json(AuthorsResponse { items }))
+}
+
+// 著者と、その著者が関わった蔵書の一覧を返す
+pub async fn show_author(
+    _user: AuthorizedUser,
+    Path(author_id): Path<AuthorId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<AuthorDetailResponse>> {
+    registry
+        .author_repository()
+        .find_by_id(author_id)
+        .await?
+        .map(AuthorDetailResponse::from)
+        .map(Json)
+        .ok_or_else(|| AppError::EntityNotFound("not found".into()))
+}
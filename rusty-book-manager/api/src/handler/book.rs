@@ -1,25 +1,45 @@
 use crate::{
-    extractor::AuthorizedUser,
+    extractor::{AuthorizedUser, MergePatch},
     model::book::{
-        BookListQuery, BookResponse, CreateBookRequest, PaginatedBookResponse, UpdateBookRequest,
-        UpdateBookRequestWithIds,
+        book_etag, BookListQuery, BookListQueryWithUserId, BookLookupQuery, BookMetadataResponse,
+        BookResponse, CreateBookRequest, PaginatedBookResponse, PatchBookRequest,
+        PatchBookRequestWithIds, RegisterBookQuery, UpdateBookRequest, UpdateBookRequestWithIds,
     },
 };
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use axum_extra::{
+    headers::{HeaderMapExt, IfMatch, IfNoneMatch},
+    TypedHeader,
+};
 use garde::Validate;
-use kernel::model::{book::event::DeleteBook, id::BookId};
+use kernel::model::{
+    book::event::{BookPatch, DeleteBook},
+    id::BookId,
+};
 use registry::AppRegistry;
 use shared::error::{AppError, AppResult};
 
+use super::cover::delete_cover_images;
+
 pub async fn register_book(
     user: AuthorizedUser,
+    Query(query): Query<RegisterBookQuery>,
     State(registry): State<AppRegistry>,
-    Json(req): Json<CreateBookRequest>,
+    Json(mut req): Json<CreateBookRequest>,
 ) -> Result<StatusCode, AppError> {
+    if query.autofill {
+        // 取得できなくても登録は続け、足りない項目はバリデーションで報告する
+        match registry.book_metadata_provider().lookup(&req.isbn).await {
+            Ok(Some(metadata)) => req.fill_missing(metadata),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(error.message = %e, "Failed to look up book metadata"),
+        }
+    }
     req.validate(&())?;
     registry
         .book_repository()
@@ -29,46 +49,70 @@ pub async fn register_book(
 }
 
 pub async fn show_book_list(
-    _user: AuthorizedUser,
+    user: AuthorizedUser,
     Query(query): Query<BookListQuery>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<PaginatedBookResponse>> {
     query.validate(&())?;
     registry
         .book_repository()
-        .find_all(query.into())
+        .find_all(BookListQueryWithUserId::new(user.id(), query).into())
         .await
         .map(PaginatedBookResponse::from)
         .map(Json)
     // .map(Json) は .map(|v| Json(v)) と同じ
 }
 
+// ISBN から書誌情報を取得し、登録フォームの入力候補として返す
+pub async fn lookup_book(
+    _user: AuthorizedUser,
+    Query(query): Query<BookLookupQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookMetadataResponse>> {
+    query.validate(&())?;
+    registry
+        .book_metadata_provider()
+        .lookup(&query.isbn)
+        .await?
+        .map(BookMetadataResponse::from)
+        .map(Json)
+        .ok_or_else(|| AppError::EntityNotFound("no metadata found for the ISBN".into()))
+}
+
 #[tracing::instrument(skip(_user, registry), fields(user_id = %_user.id().to_string()))]
 pub async fn show_book(
     _user: AuthorizedUser,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<BookResponse>> {
+    headers: HeaderMap,
+) -> AppResult<Response> {
     tracing::info!("ここにログを追加したよ〜");
-    registry
+    let book = registry
         .book_repository()
         .find_by_id(book_id)
-        .await
-        .and_then(|bc| match bc {
-            Some(bc) => Ok(Json(bc.into())),
-            None => Err(AppError::EntityNotFound("not found".into())),
-        })
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("not found".into()))?;
+    let etag = book_etag(&book);
+    // クライアントが持っているものと同じなら本文を返さない
+    if let Some(if_none_match) = headers.typed_get::<IfNoneMatch>() {
+        if !if_none_match.precondition_passes(&etag) {
+            return Ok((StatusCode::NOT_MODIFIED, TypedHeader(etag)).into_response());
+        }
+    }
+    Ok((TypedHeader(etag), Json(BookResponse::from(book))).into_response())
 }
 
 pub async fn update_book(
     user: AuthorizedUser,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
+    headers: HeaderMap,
     Json(req): Json<UpdateBookRequest>,
 ) -> AppResult<StatusCode> {
     req.validate(&())?;
 
-    let update_book = UpdateBookRequestWithIds::new(book_id, user.id(), req);
+    let expected_version = check_if_match(&registry, book_id, &headers).await?;
+    let update_book = UpdateBookRequestWithIds::new(book_id, user.id(), expected_version, req);
     registry
         .book_repository()
         .update(update_book.into())
@@ -76,18 +120,77 @@ pub async fn update_book(
         .map(|_| StatusCode::OK)
 }
 
+// 指定された項目だけを更新する。null を指定した description は空になる
+pub async fn patch_book(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    headers: HeaderMap,
+    MergePatch(req): MergePatch<PatchBookRequest>,
+) -> AppResult<StatusCode> {
+    let expected_version = check_if_match(&registry, book_id, &headers).await?;
+    let patch = BookPatch::try_from(PatchBookRequestWithIds::new(
+        book_id,
+        user.id(),
+        expected_version,
+        req,
+    ))?;
+    patch.validate(&())?;
+    if patch.is_empty() {
+        return Ok(StatusCode::OK);
+    }
+    registry
+        .book_repository()
+        .patch(patch)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
 pub async fn delete_book(
     user: AuthorizedUser,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
+    headers: HeaderMap,
 ) -> AppResult<StatusCode> {
+    let expected_version = check_if_match(&registry, book_id, &headers).await?;
     let delete_book = DeleteBook {
         book_id,
         requested_user: user.id(),
+        expected_version,
     };
-    registry
+    let cover_image = registry.book_repository().delete(delete_book).await?;
+    // 削除をコミットした後で表紙の画像を消す
+    if let Some(cover_image) = cover_image {
+        delete_cover_images(&registry, cover_image).await;
+    }
+    Ok(StatusCode::OK)
+}
+
+// 更新・削除の前に If-Match ヘッダーを検証し、リポジトリで照合させるバージョンを返す。
+// `If-Match: *` の場合はバージョンを問わない
+async fn check_if_match(
+    registry: &AppRegistry,
+    book_id: BookId,
+    headers: &HeaderMap,
+) -> AppResult<Option<i64>> {
+    // ヘッダーがない場合と、値が不正な場合はどちらも 428 とする
+    let if_match = headers
+        .contains_key(header::IF_MATCH)
+        .then(|| headers.typed_get::<IfMatch>())
+        .flatten()
+        .ok_or(AppError::PreconditionRequired)?;
+    if if_match.is_any() {
+        return Ok(None);
+    }
+    let book = registry
         .book_repository()
-        .delete(delete_book)
-        .await
-        .map(|_| StatusCode::OK)
+        .find_by_id(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("not found".into()))?;
+    if !if_match.precondition_passes(&book_etag(&book)) {
+        return Err(AppError::PreconditionFailed(
+            "specified book has been modified".into(),
+        ));
+    }
+    Ok(Some(book.version))
 }
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::id::{BookConditionId, BookId, CheckoutId};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use super::genre::check_book_owner;
+use crate::{
+    extractor::AuthorizedUser,
+    model::book_condition::{
+        BookConditionsResponse, MarkBookFoundRequest, UpdateBookConditionRequest,
+        UpdateConditionChargeRequest,
+    },
+};
+
+// 紛失中の蔵書が見つかったら、所有者か管理者が貸し出せる状態に戻す
+pub async fn mark_book_found(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<MarkBookFoundRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    check_book_owner(&user, &registry, book_id).await?;
+    registry
+        .book_condition_repository()
+        .mark_found(req.into_event(book_id, user.id()))
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 破損した蔵書を修理した場合などに、所有者か管理者が蔵書の状態を変える
+pub async fn update_book_condition(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBookConditionRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    check_book_owner(&user, &registry, book_id).await?;
+    registry
+        .book_condition_repository()
+        .update_condition(req.into_event(book_id, user.id()))
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 返却・紛失の届け出の後で決まった弁償の方法を、管理者が記録し直す
+pub async fn update_condition_charge(
+    user: AuthorizedUser,
+    Path((book_id, book_condition_id)): Path<(BookId, BookConditionId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateConditionChargeRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+    registry
+        .book_condition_repository()
+        .update_charge(req.into_event(book_id, book_condition_id))
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 弁償の記録を含むので、所有者と管理者には全件を見せ、
+// それ以外の利用者には自身の貸出に紐づく記録だけを見せる
+pub async fn show_condition_history(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookConditionsResponse>> {
+    let is_owner = match check_book_owner(&user, &registry, book_id).await {
+        Ok(()) => true,
+        Err(AppError::ForbiddenOperation) => false,
+        Err(e) => return Err(e),
+    };
+    let mut records = registry
+        .book_condition_repository()
+        .find_history_by_book_id(book_id)
+        .await?;
+    if !is_owner {
+        let own_checkouts: HashSet<CheckoutId> = registry
+            .checkout_repository()
+            .find_history_by_book_id(book_id)
+            .await?
+            .into_iter()
+            .filter(|co| co.checked_out_by == user.id())
+            .map(|co| co.id)
+            .collect();
+        if own_checkouts.is_empty() {
+            return Err(AppError::ForbiddenOperation);
+        }
+        records.retain(|r| {
+            r.checkout_id
+                .is_some_and(|checkout_id| own_checkouts.contains(&checkout_id))
+        });
+    }
+    Ok(Json(BookConditionsResponse::from(records)))
+}
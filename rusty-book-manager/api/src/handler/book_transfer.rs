@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use kernel::model::{
+    book_transfer::event::{
+        AcceptBookTransfer, CancelBookTransfer, DeclineBookTransfer, ForceBookTransfer,
+        OfferBookTransfer,
+    },
+    id::{BookId, BookTransferId},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::book_transfer::{BookTransferRequest, BookTransferResponse, BookTransfersResponse},
+};
+
+// 所有者が蔵書を別の利用者に譲ることを申し出る。相手が受け入れるまで所有者は変わらない
+pub async fn offer_book_transfer(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<BookTransferRequest>,
+) -> AppResult<(StatusCode, Json<BookTransferResponse>)> {
+    registry
+        .book_transfer_repository()
+        .offer(OfferBookTransfer {
+            book_id,
+            to_user_id: req.to_user_id,
+            requested_user: user.id(),
+        })
+        .await
+        .map(|transfer| (StatusCode::CREATED, Json(transfer.into())))
+}
+
+pub async fn accept_book_transfer(
+    user: AuthorizedUser,
+    Path((book_id, book_transfer_id)): Path<(BookId, BookTransferId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookTransferResponse>> {
+    registry
+        .book_transfer_repository()
+        .accept(AcceptBookTransfer {
+            book_transfer_id,
+            book_id,
+            requested_user: user.id(),
+        })
+        .await
+        .map(BookTransferResponse::from)
+        .map(Json)
+}
+
+pub async fn decline_book_transfer(
+    user: AuthorizedUser,
+    Path((book_id, book_transfer_id)): Path<(BookId, BookTransferId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookTransferResponse>> {
+    registry
+        .book_transfer_repository()
+        .decline(DeclineBookTransfer {
+            book_transfer_id,
+            book_id,
+            requested_user: user.id(),
+        })
+        .await
+        .map(BookTransferResponse::from)
+        .map(Json)
+}
+
+// 返事を待っている申し出を、申し出た所有者が取り下げる
+pub async fn cancel_book_transfer(
+    user: AuthorizedUser,
+    Path((book_id, book_transfer_id)): Path<(BookId, BookTransferId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookTransferResponse>> {
+    registry
+        .book_transfer_repository()
+        .cancel(CancelBookTransfer {
+            book_transfer_id,
+            book_id,
+            requested_user: user.id(),
+        })
+        .await
+        .map(BookTransferResponse::from)
+        .map(Json)
+}
+
+// 管理者は申し出を経ずに所有者を変更できる
+pub async fn force_book_transfer(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<BookTransferRequest>,
+) -> AppResult<Json<BookTransferResponse>> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    registry
+        .book_transfer_repository()
+        .force(ForceBookTransfer {
+            book_id,
+            to_user_id: req.to_user_id,
+            requested_user: user.id(),
+        })
+        .await
+        .map(BookTransferResponse::from)
+        .map(Json)
+}
+
+// 蔵書の所有者が変わった譲渡を古い順に返す
+pub async fn show_owner_history(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookTransfersResponse>> {
+    registry
+        .book_transfer_repository()
+        .find_history(book_id)
+        .await
+        .map(BookTransfersResponse::from)
+        .map(Json)
+}
+
+// 自分が申し出た、または申し出を受けた返事待ちの譲渡
+pub async fn list_book_transfers(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookTransfersResponse>> {
+    registry
+        .book_transfer_repository()
+        .find_pending_by_user_id(user.id())
+        .await
+        .map(BookTransfersResponse::from)
+        .map(Json)
+}
@@ -1,15 +1,24 @@
-use crate::{extractor::AuthorizedUser, model::checkout::CheckoutsResponse};
+use crate::{
+    extractor::AuthorizedUser,
+    model::checkout::{
+        CheckoutIncidentRequest, CheckoutOnBehalfRequest, CheckoutsResponse, ForceReturnRequest,
+    },
+};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     Json,
 };
+use garde::Validate;
 use kernel::model::{
-    checkout::event::{CreateCheckout, UpdateReturned},
+    checkout::event::{
+        CreateCheckout, CreateCheckoutOnBehalf, ForceReturned, ReportLost, ReturnDamaged,
+        UpdateReturned,
+    },
     id::{BookId, CheckoutId},
 };
 use registry::AppRegistry;
-use shared::error::AppResult;
+use shared::error::{AppError, AppResult};
 
 pub async fn checkout_book(
     user: AuthorizedUser,
@@ -37,20 +46,139 @@ pub async fn return_book(
         .map(|_| StatusCode::OK)
 }
 
+// 窓口で管理者が利用者に代わって貸し出す
+pub async fn checkout_book_on_behalf(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CheckoutOnBehalfRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    let create_checkout =
+        CreateCheckoutOnBehalf::new(book_id, req.user_id, user.id(), chrono::Utc::now());
+    registry
+        .checkout_repository()
+        .create_on_behalf(create_checkout)
+        .await
+        .map(|_| StatusCode::CREATED)
+}
+
+// 退職などで借りた利用者が返却できない貸出を、管理者が代わりに返却する
+pub async fn force_return_book(
+    user: AuthorizedUser,
+    Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<ForceReturnRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+    let force_returned = ForceReturned::new(
+        checkout_id,
+        book_id,
+        user.id(),
+        req.reason,
+        chrono::Utc::now(),
+    );
+    registry
+        .checkout_repository()
+        .force_returned(force_returned)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 破損した状態で返却する。借りた利用者のほか、管理者も代わりに届け出られる
+pub async fn return_damaged_book(
+    user: AuthorizedUser,
+    Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CheckoutIncidentRequest>,
+) -> AppResult<StatusCode> {
+    check_incident_request(&user, &registry, checkout_id, &req).await?;
+    let return_damaged = ReturnDamaged::new(
+        checkout_id,
+        book_id,
+        user.id(),
+        req.note,
+        req.charge.map(Into::into),
+        chrono::Utc::now(),
+    );
+    registry
+        .checkout_repository()
+        .return_damaged(return_damaged)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 紛失を届け出て貸出を終える。蔵書は見つかるまで貸し出せなくなる
+pub async fn report_lost_book(
+    user: AuthorizedUser,
+    Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CheckoutIncidentRequest>,
+) -> AppResult<StatusCode> {
+    check_incident_request(&user, &registry, checkout_id, &req).await?;
+    let report_lost = ReportLost::new(
+        checkout_id,
+        book_id,
+        user.id(),
+        req.note,
+        req.charge.map(Into::into),
+        chrono::Utc::now(),
+    );
+    registry
+        .checkout_repository()
+        .report_lost(report_lost)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 弁償の方法は管理者のみ指定できる。管理者以外は自分が借りている貸出についてのみ届け出られる
+async fn check_incident_request(
+    user: &AuthorizedUser,
+    registry: &AppRegistry,
+    checkout_id: CheckoutId,
+    req: &CheckoutIncidentRequest,
+) -> AppResult<()> {
+    req.validate(&())?;
+    if user.is_admin() {
+        return Ok(());
+    }
+    if req.charge.is_some() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    let borrowing = registry
+        .checkout_repository()
+        .find_unreturned_by_user_id(user.id())
+        .await?
+        .iter()
+        .any(|c| c.id == checkout_id);
+    if !borrowing {
+        return Err(AppError::UnprocessableEntity(format!(
+            " 指定の貸出（ID（{}））は返却できません。",
+            checkout_id
+        )));
+    }
+    Ok(())
+}
+
 pub async fn show_checked_out_list(
-    _user: AuthorizedUser,
+    user: AuthorizedUser,
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<CheckoutsResponse>> {
     registry
         .checkout_repository()
         .find_unreturned_all()
         .await
-        .map(CheckoutsResponse::from)
+        .map(|c| CheckoutsResponse::visible_to(c, user.id(), user.is_admin()))
         .map(Json)
 }
 
 pub async fn checkout_history(
-    _user: AuthorizedUser,
+    user: AuthorizedUser,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<CheckoutsResponse>> {
@@ -58,6 +186,6 @@ pub async fn checkout_history(
         .checkout_repository()
         .find_history_by_book_id(book_id)
         .await
-        .map(CheckoutsResponse::from)
+        .map(|c| CheckoutsResponse::visible_to(c, user.id(), user.is_admin()))
         .map(Json)
 }
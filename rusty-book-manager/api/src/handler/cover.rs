@@ -0,0 +1,160 @@
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use kernel::model::{
+    book::{event::UpdateBookCover, Book},
+    id::BookId,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        book::BookResponse,
+        cover::{
+            content_type_of, cover_key, thumbnail_key, CoverFormat, CoverImage, MAX_COVER_SIZE,
+        },
+    },
+};
+
+// multipart/form-data の cover フィールドで受け取った画像を表紙にする。蔵書の所有者のみ実行できる
+pub async fn upload_cover(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    mut multipart: Multipart,
+) -> AppResult<Json<BookResponse>> {
+    let book = find_book(&registry, book_id).await?;
+    if book.owner.id != user.id() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    let (content_type, data) = read_cover_field(&mut multipart).await?;
+    let cover = tokio::task::spawn_blocking(move || CoverImage::process(&content_type, data))
+        .await
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))??;
+
+    let key = cover_key(book_id, cover.format);
+    let store = registry.blob_store();
+    store
+        .put(&key, cover.data, cover.format.content_type())
+        .await?;
+    store
+        .put(
+            &thumbnail_key(&key),
+            cover.thumbnail,
+            CoverFormat::Jpeg.content_type(),
+        )
+        .await?;
+    let old = registry
+        .book_repository()
+        .update_cover(UpdateBookCover {
+            book_id,
+            cover_image: key,
+            requested_user: user.id(),
+        })
+        .await?;
+
+    // 差し替え前の画像は参照されなくなるので消す。失敗しても表紙の更新は成功とする
+    if let Some(old) = old {
+        delete_cover_images(&registry, old).await;
+    }
+
+    find_book(&registry, book_id)
+        .await
+        .map(BookResponse::from)
+        .map(Json)
+}
+
+pub async fn show_cover(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    show_cover_image(&registry, book_id, |key| key.to_string()).await
+}
+
+pub async fn show_cover_thumbnail(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    show_cover_image(&registry, book_id, thumbnail_key).await
+}
+
+async fn show_cover_image(
+    registry: &AppRegistry,
+    book_id: BookId,
+    key_of: impl Fn(&str) -> String,
+) -> AppResult<impl IntoResponse> {
+    let not_found = || AppError::EntityNotFound("cover image not found".into());
+    let cover_image = find_book(registry, book_id)
+        .await?
+        .cover_image
+        .ok_or_else(not_found)?;
+    let key = key_of(&cover_image);
+    let data = registry
+        .blob_store()
+        .get(&key)
+        .await?
+        .ok_or_else(not_found)?;
+    Ok(([(header::CONTENT_TYPE, content_type_of(&key))], data))
+}
+
+// 表紙とサムネイルを消す。蔵書の更新はコミット済みのため、失敗してもログに残すだけにする
+pub(crate) async fn delete_cover_images(registry: &AppRegistry, cover_image: String) {
+    let store = registry.blob_store();
+    for key in [thumbnail_key(&cover_image), cover_image] {
+        if let Err(e) = store.delete(&key).await {
+            tracing::warn!(error.message = %e, key, "Failed to delete cover image");
+        }
+    }
+}
+
+async fn find_book(registry: &AppRegistry, book_id: BookId) -> AppResult<Book> {
+    registry
+        .book_repository()
+        .find_by_id(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("not found".into()))
+}
+
+// cover フィールドの Content-Type と中身を読む。上限を超えた時点で読むのをやめる
+async fn read_cover_field(multipart: &mut Multipart) -> AppResult<(String, Vec<u8>)> {
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some("cover") {
+            continue;
+        }
+        let content_type = field
+            .content_type()
+            .map(|v| v.to_ascii_lowercase())
+            .ok_or_else(|| AppError::UnsupportedMediaType("cover has no content type".into()))?;
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if data.len() + chunk.len() > MAX_COVER_SIZE {
+                return Err(too_large());
+            }
+            data.extend_from_slice(&chunk);
+        }
+        return Ok((content_type, data));
+    }
+    Err(AppError::UnprocessableEntity(
+        "cover field is required".into(),
+    ))
+}
+
+fn multipart_error(e: MultipartError) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        too_large()
+    } else {
+        AppError::UnprocessableEntity(e.body_text())
+    }
+}
+
+fn too_large() -> AppError {
+    AppError::PayloadTooLarge(format!("cover must be at most {MAX_COVER_SIZE} bytes"))
+}
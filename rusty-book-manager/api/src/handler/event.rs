@@ -0,0 +1,153 @@
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use kernel::model::{
+    auth::AccessToken,
+    event_stream::{EventStreamId, StreamedEvent},
+    id::UserId,
+    role::Role,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use tokio::time::{interval_at, Instant};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, IntervalStream},
+    Stream, StreamExt,
+};
+
+use crate::{extractor::AuthorizedUser, model::event::EventResponse};
+
+// 中継するプロキシに接続を切られないよう、イベントがない間もこの間隔でコメント行を送る
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+// 接続中にログアウト・トークンの失効・権限の変更・ユーザーの削除があった場合に
+// ストリームを閉じるため、この間隔でトークンとユーザーを確認し直す
+const REAUTHORIZE_INTERVAL: Duration = Duration::from_secs(15);
+
+enum StreamItem {
+    Event(Event),
+    Reauthorize,
+    Closed,
+}
+
+// 接続したときと同じユーザー・権限のままであれば true を返す
+async fn is_still_authorized(
+    registry: &AppRegistry,
+    access_token: &AccessToken,
+    viewer_id: UserId,
+    is_admin: bool,
+) -> bool {
+    let user = match registry
+        .auth_repository()
+        .fetch_user_id_from_token(access_token)
+        .await
+    {
+        Ok(Some(user_id)) if user_id == viewer_id => {
+            registry.user_repository().find_current_user(user_id).await
+        }
+        Ok(_) => return false,
+        Err(e) => Err(e),
+    };
+    match user {
+        Ok(Some(user)) => (user.role == Role::Admin) == is_admin,
+        Ok(None) => false,
+        Err(e) => {
+            tracing::warn!(error.message = %e, "Failed to reauthorize event stream");
+            false
+        }
+    }
+}
+
+// 取りこぼしたイベントがあることを知らせる。受け取ったクライアントは状態を取得し直す
+fn reset_event() -> Event {
+    Event::default().event("reset").data("{}")
+}
+
+fn to_sse_event(event: StreamedEvent, viewer_id: UserId, is_admin: bool) -> Option<Event> {
+    EventResponse::visible_to(event, viewer_id, is_admin)?
+        .try_into()
+        .ok()
+}
+
+// 蔵書・貸出・返却などの変更を Server-Sent Events で流す。
+// Last-Event-ID を指定すると、そのイベントより後のものから受け取り直せる。
+// 認証は他の API と同じく Authorization: Bearer ヘッダーのみで受け付ける。
+// ブラウザ標準の EventSource はヘッダーを付けられないため、fetch で読み込むクライアントを使う。
+// トークンが使えなくなった場合や権限が変わった場合は接続を閉じるので、クライアントは認証し直して再接続する
+pub async fn stream_events(
+    user: AuthorizedUser,
+    headers: HeaderMap,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| AppError::UnprocessableEntity("イベントの ID が不正です".into()))?
+                .parse::<EventStreamId>()
+        })
+        .transpose()?;
+    let (viewer_id, is_admin) = (user.id(), user.is_admin());
+    let access_token = Arc::new(user.access_token);
+
+    let repository = registry.event_stream_repository();
+    // 履歴を読んでいる間に追加されたイベントを取りこぼさないよう、先に受信を始める
+    let receiver = repository.subscribe();
+    let (replayed, sent_up_to) = match last_event_id {
+        None => (Vec::new(), None),
+        Some(after) => match repository.find_after(after).await? {
+            Some(events) => {
+                let last = events.last().map_or(after, |event| event.id);
+                let replayed = events
+                    .into_iter()
+                    .filter_map(|event| to_sse_event(event, viewer_id, is_admin))
+                    .collect();
+                (replayed, Some(last))
+            }
+            None => (vec![reset_event()], None),
+        },
+    };
+
+    let live = BroadcastStream::new(receiver).filter_map(move |received| match received {
+        // 履歴から送り済みのイベント
+        Ok(event) if sent_up_to.is_some_and(|id| event.id <= id) => None,
+        Ok(event) => to_sse_event(event, viewer_id, is_admin),
+        Err(BroadcastStreamRecvError::Lagged(_)) => Some(reset_event()),
+    });
+    let events = tokio_stream::iter(replayed)
+        .chain(live)
+        .map(StreamItem::Event)
+        // 受信が終わったら確認を待たずにストリームを閉じる
+        .chain(tokio_stream::once(StreamItem::Closed));
+    let reauthorize = IntervalStream::new(interval_at(
+        Instant::now() + REAUTHORIZE_INTERVAL,
+        REAUTHORIZE_INTERVAL,
+    ))
+    .map(|_| StreamItem::Reauthorize);
+
+    let stream = events
+        .merge(reauthorize)
+        .then(move |item| {
+            let (registry, access_token) = (registry.clone(), access_token.clone());
+            async move {
+                match item {
+                    StreamItem::Reauthorize
+                        if is_still_authorized(&registry, &access_token, viewer_id, is_admin)
+                            .await =>
+                    {
+                        Some(None)
+                    }
+                    StreamItem::Event(event) => Some(Some(event)),
+                    StreamItem::Reauthorize | StreamItem::Closed => None,
+                }
+            }
+        })
+        .take_while(Option::is_some)
+        .filter_map(|item| item.flatten().map(Ok));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL)))
+}
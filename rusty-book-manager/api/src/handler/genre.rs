@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    genre::event::{AddBookGenre, DeleteGenre, RemoveBookGenre},
+    id::{BookId, GenreId},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::genre::{CreateGenreRequest, GenreResponse, GenresResponse},
+};
+
+pub async fn list_genres(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<GenresResponse>> {
+    let items = registry
+        .genre_repository()
+        .find_all()
+        .await?
+        .into_iter()
+        .map(GenreResponse::from)
+        .collect();
+    Ok(Json(GenresResponse { items }))
+}
+
+// ジャンルの分類は管理者のみ変更できる
+pub async fn create_genre(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateGenreRequest>,
+) -> AppResult<(StatusCode, Json<GenreResponse>)> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+    let genre = registry.genre_repository().create(req.into()).await?;
+    Ok((StatusCode::CREATED, Json(genre.into())))
+}
+
+pub async fn delete_genre(
+    user: AuthorizedUser,
+    Path(genre_id): Path<GenreId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    registry
+        .genre_repository()
+        .delete(DeleteGenre { genre_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 蔵書のジャンルは所有者か管理者のみ変更できる
+pub async fn add_book_genre(
+    user: AuthorizedUser,
+    Path((book_id, genre_id)): Path<(BookId, GenreId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    check_book_owner(&user, &registry, book_id).await?;
+    registry
+        .genre_repository()
+        .add_to_book(AddBookGenre { book_id, genre_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn remove_book_genre(
+    user: AuthorizedUser,
+    Path((book_id, genre_id)): Path<(BookId, GenreId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    check_book_owner(&user, &registry, book_id).await?;
+    registry
+        .genre_repository()
+        .remove_from_book(RemoveBookGenre { book_id, genre_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub(crate) async fn check_book_owner(
+    user: &AuthorizedUser,
+    registry: &AppRegistry,
+    book_id: BookId,
+) -> AppResult<()> {
+    if user.is_admin() {
+        return Ok(());
+    }
+    let book = registry
+        .book_repository()
+        .find_by_id(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("not found".into()))?;
+    if book.owner.id != user.id() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use axum::{extract::State, http::StatusCode};
+use crate::model::health::{HealthStatusName, LivenessResponse, ReadinessResponse};
+use axum::{extract::State, http::StatusCode, Json};
+use kernel::model::health::HealthReport;
 use registry::AppRegistry;
 
 pub async fn health_check() -> StatusCode {
@@ -12,3 +14,32 @@ pub async fn health_check_db(State(registry): State<AppRegistry>) -> StatusCode
         StatusCode::INTERNAL_SERVER_ERROR
     }
 }
+
+// プロセスが応答できるかだけを返す（依存先の状態は見ない）
+pub async fn health_check_live() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: HealthStatusName::Up,
+    })
+}
+
+// 必須の依存先がひとつでも Down なら 503 を返す
+pub async fn health_check_ready(
+    State(registry): State<AppRegistry>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let repo = registry.health_check_repository();
+    let (database, redis, migrations, pool) = tokio::join!(
+        repo.check_database(),
+        repo.check_redis(),
+        repo.check_migrations(),
+        repo.check_pool(),
+    );
+    let report = HealthReport {
+        checks: vec![database, redis, migrations, pool],
+    };
+    let status_code = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status_code, Json(report.into()))
+}
@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use garde::Validate;
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::job::{JobRunListQuery, PaginatedJobRunResponse},
+};
+
+// スケジューラが実行したジョブの履歴を新しい順に返す
+pub async fn list_job_runs(
+    user: AuthorizedUser,
+    Query(query): Query<JobRunListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedJobRunResponse>> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    registry
+        .job_run_repository()
+        .find_all(query.into())
+        .await
+        .map(PaginatedJobRunResponse::from)
+        .map(Json)
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    id::NotificationId,
+    notification::event::{MarkAllNotificationsRead, MarkNotificationRead},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::notification::{
+        NotificationListQuery, NotificationPreferencesResponse, PaginatedNotificationResponse,
+        UpdateNotificationPreferencesRequest, UpdateNotificationPreferencesRequestWithUserId,
+    },
+};
+
+// 自分宛ての通知を新しい順に返す
+pub async fn list_notifications(
+    user: AuthorizedUser,
+    Query(query): Query<NotificationListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedNotificationResponse>> {
+    query.validate(&())?;
+    let repository = registry.notification_repository();
+    let notifications = repository.find_by_user_id(user.id(), query.into()).await?;
+    let unread_count = repository.count_unread(user.id()).await?;
+    Ok(Json(PaginatedNotificationResponse::new(
+        notifications,
+        unread_count,
+    )))
+}
+
+pub async fn mark_notification_read(
+    user: AuthorizedUser,
+    Path(notification_id): Path<NotificationId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .notification_repository()
+        .mark_read(MarkNotificationRead {
+            notification_id,
+            user_id: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+pub async fn mark_all_notifications_read(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .notification_repository()
+        .mark_all_read(MarkAllNotificationsRead { user_id: user.id() })
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+pub async fn get_notification_preferences(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<NotificationPreferencesResponse>> {
+    registry
+        .notification_repository()
+        .find_preferences(user.id())
+        .await
+        .map(NotificationPreferencesResponse::from)
+        .map(Json)
+}
+
+// 更新後のすべての種類の設定を返す
+pub async fn update_notification_preferences(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateNotificationPreferencesRequest>,
+) -> AppResult<Json<NotificationPreferencesResponse>> {
+    let repository = registry.notification_repository();
+    repository
+        .update_preferences(
+            UpdateNotificationPreferencesRequestWithUserId::new(user.id(), req).into(),
+        )
+        .await?;
+    repository
+        .find_preferences(user.id())
+        .await
+        .map(NotificationPreferencesResponse::from)
+        .map(Json)
+}
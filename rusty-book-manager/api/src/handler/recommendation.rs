@@ -0,0 +1,29 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use garde::Validate;
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::recommendation::{RecommendationQuery, RecommendationResponse, RecommendationsResponse},
+};
+
+// 貸出履歴から、共に借りられた蔵書や著者・タグが共通する蔵書を推薦する
+pub async fn get_recommendations(
+    user: AuthorizedUser,
+    Query(query): Query<RecommendationQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<RecommendationsResponse>> {
+    query.validate(&())?;
+    let items = registry
+        .recommendation_repository()
+        .find_by_user_id(user.id(), query.limit)
+        .await?
+        .into_iter()
+        .map(RecommendationResponse::from)
+        .collect();
+    Ok(Json(RecommendationsResponse { items }))
+}
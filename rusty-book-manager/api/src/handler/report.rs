@@ -0,0 +1,129 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use garde::Validate;
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        csv::CsvResponse,
+        report::{
+            report_items, report_period, AuthorRankingResponse, BookRankingResponse,
+            BorrowerRankingResponse, CheckoutCountQuery, CheckoutCountResponse, IdleBookResponse,
+            LoanDurationResponse, ReportFormat, ReportPeriodQuery, ReportRankingQuery,
+        },
+    },
+};
+
+// レポートはいずれも管理者のみ参照できる
+
+pub async fn checkout_counts(
+    user: AuthorizedUser,
+    Query(query): Query<CheckoutCountQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    let items = registry
+        .report_repository()
+        .checkout_counts(
+            report_period(query.from, query.to),
+            query.granularity.into(),
+        )
+        .await?;
+    report_items::<_, CheckoutCountResponse>(query.format, "checkouts.csv", items)
+}
+
+pub async fn popular_books(
+    user: AuthorizedUser,
+    Query(query): Query<ReportRankingQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    let items = registry
+        .report_repository()
+        .top_books(report_period(query.from, query.to), query.limit)
+        .await?;
+    report_items::<_, BookRankingResponse>(query.format, "popular-books.csv", items)
+}
+
+pub async fn popular_authors(
+    user: AuthorizedUser,
+    Query(query): Query<ReportRankingQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    let items = registry
+        .report_repository()
+        .top_authors(report_period(query.from, query.to), query.limit)
+        .await?;
+    report_items::<_, AuthorRankingResponse>(query.format, "popular-authors.csv", items)
+}
+
+pub async fn top_borrowers(
+    user: AuthorizedUser,
+    Query(query): Query<ReportRankingQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    let items = registry
+        .report_repository()
+        .top_borrowers(report_period(query.from, query.to), query.limit)
+        .await?;
+    report_items::<_, BorrowerRankingResponse>(query.format, "top-borrowers.csv", items)
+}
+
+pub async fn idle_books(
+    user: AuthorizedUser,
+    Query(query): Query<ReportRankingQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    let items = registry
+        .report_repository()
+        .idle_books(report_period(query.from, query.to), query.limit)
+        .await?;
+    report_items::<_, IdleBookResponse>(query.format, "idle-books.csv", items)
+}
+
+// 期間は返却日で絞り込む
+pub async fn loan_duration(
+    user: AuthorizedUser,
+    Query(query): Query<ReportPeriodQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    let duration = LoanDurationResponse::from(
+        registry
+            .report_repository()
+            .loan_duration(report_period(query.from, query.to))
+            .await?,
+    );
+    Ok(match query.format {
+        ReportFormat::Json => Json(duration).into_response(),
+        ReportFormat::Csv => {
+            CsvResponse::from_records("loan-duration.csv", [duration])?.into_response()
+        }
+    })
+}
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    id::{BookId, ReviewId},
+    review::event::{DeleteReview, ModerateReview},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::review::{
+        CreateReviewRequestWithIds, ReviewRequest, ReviewResponse, ReviewVisibilityRequest,
+        ReviewsResponse, UpdateReviewRequestWithIds,
+    },
+};
+
+// 管理者には非表示にしたレビューも返す
+pub async fn list_reviews(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ReviewsResponse>> {
+    let items = registry
+        .review_repository()
+        .find_by_book_id(book_id, user.is_admin())
+        .await?
+        .into_iter()
+        .map(ReviewResponse::from)
+        .collect();
+    Ok(Json(ReviewsResponse { items }))
+}
+
+pub async fn create_review(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<ReviewRequest>,
+) -> AppResult<(StatusCode, Json<ReviewResponse>)> {
+    req.validate(&())?;
+    let review = registry
+        .review_repository()
+        .create(CreateReviewRequestWithIds::new(book_id, user.id(), req).into())
+        .await?;
+    Ok((StatusCode::CREATED, Json(review.into())))
+}
+
+pub async fn update_review(
+    user: AuthorizedUser,
+    Path((book_id, review_id)): Path<(BookId, ReviewId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<ReviewRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    registry
+        .review_repository()
+        .update(UpdateReviewRequestWithIds::new(review_id, book_id, user.id(), req).into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 投稿者に加え、管理者も削除できる
+pub async fn delete_review(
+    user: AuthorizedUser,
+    Path((book_id, review_id)): Path<(BookId, ReviewId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .review_repository()
+        .delete(DeleteReview {
+            review_id,
+            book_id,
+            requested_user: (!user.is_admin()).then(|| user.id()),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// レビューの表示・非表示は管理者のみ切り替えられる
+pub async fn update_review_visibility(
+    user: AuthorizedUser,
+    Path((book_id, review_id)): Path<(BookId, ReviewId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<ReviewVisibilityRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    registry
+        .review_repository()
+        .moderate(ModerateReview {
+            review_id,
+            book_id,
+            hidden: req.hidden,
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    id::{BookId, ShelfId},
+    shelf::event::{AddShelfBook, DeleteShelf, RemoveShelfBook},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::shelf::{
+        CreateShelfRequest, CreateShelfRequestWithUserId, ShelfResponse, ShelvesResponse,
+    },
+};
+
+pub async fn list_shelves(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ShelvesResponse>> {
+    let items = registry
+        .shelf_repository()
+        .find_by_user_id(user.id())
+        .await?
+        .into_iter()
+        .map(ShelfResponse::from)
+        .collect();
+    Ok(Json(ShelvesResponse { items }))
+}
+
+pub async fn create_shelf(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateShelfRequest>,
+) -> AppResult<(StatusCode, Json<ShelfResponse>)> {
+    req.validate(&())?;
+    let shelf = registry
+        .shelf_repository()
+        .create(CreateShelfRequestWithUserId::new(user.id(), req).into())
+        .await?;
+    Ok((StatusCode::CREATED, Json(shelf.into())))
+}
+
+pub async fn delete_shelf(
+    user: AuthorizedUser,
+    Path(shelf_id): Path<ShelfId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .shelf_repository()
+        .delete(DeleteShelf {
+            shelf_id,
+            user_id: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn add_shelf_book(
+    user: AuthorizedUser,
+    Path((shelf_id, book_id)): Path<(ShelfId, BookId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .shelf_repository()
+        .add_book(AddShelfBook {
+            shelf_id,
+            user_id: user.id(),
+            book_id,
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn remove_shelf_book(
+    user: AuthorizedUser,
+    Path((shelf_id, book_id)): Path<(ShelfId, BookId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .shelf_repository()
+        .remove_book(RemoveShelfBook {
+            shelf_id,
+            user_id: user.id(),
+            book_id,
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use super::genre::check_book_owner;
+use crate::{
+    extractor::AuthorizedUser,
+    model::tag::{BookTagPath, TagResponse, TagsResponse},
+};
+
+pub async fn list_tags(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TagsResponse>> {
+    let items = registry
+        .tag_repository()
+        .find_all()
+        .await?
+        .into_iter()
+        .map(TagResponse::from)
+        .collect();
+    Ok(Json(TagsResponse { items }))
+}
+
+// ジャンルと同じく、タグは蔵書の所有者と管理者のみ付け外しできる
+pub async fn add_book_tag(
+    user: AuthorizedUser,
+    Path(path): Path<BookTagPath>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    path.validate(&())?;
+    check_book_owner(&user, &registry, path.book_id).await?;
+    registry
+        .tag_repository()
+        .add_to_book(path.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn remove_book_tag(
+    user: AuthorizedUser,
+    Path(path): Path<BookTagPath>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    path.validate(&())?;
+    check_book_owner(&user, &registry, path.book_id).await?;
+    registry
+        .tag_repository()
+        .remove_from_book(path.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
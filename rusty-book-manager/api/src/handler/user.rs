@@ -1,21 +1,35 @@
 use crate::{
-    extractor::AuthorizedUser,
-    model::checkout::CheckoutsResponse,
+    extractor::{AuthorizedUser, MergePatch},
+    model::checkout::{
+        checkout_history_csv, CheckoutHistoryExportQuery, CheckoutHistoryQuery, CheckoutsResponse,
+        PaginatedCheckoutResponse,
+    },
+    model::csv::CsvResponse,
     model::user::{
-        CreateUserRequest, UpdateUserPasswordRequest, UpdateUserPasswordRequestWithUserId,
-        UpdateUserRoleRequest, UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
+        CreateUserRequest, CurrentUserPatch, UpdateCurrentUserRequest, UpdateUserPasswordRequest,
+        UpdateUserPasswordRequestWithUserId, UpdateUserRoleRequest,
+        UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse, VerifyEmailRequest,
     },
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use garde::Validate;
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::model::{
+    id::UserId,
+    mail::Mail,
+    user::{
+        event::{DeleteUser, RequestEmailChange, UpdateUserEmail, UpdateUserName},
+        EmailVerificationToken,
+    },
+};
 use registry::AppRegistry;
 use shared::error::{AppError, AppResult};
 
+use super::cover::delete_cover_images;
+
 pub async fn register_user(
     user: AuthorizedUser,
     State(registry): State<AppRegistry>,
@@ -55,10 +69,14 @@ pub async fn delete_user(
         return Err(AppError::ForbiddenOperation);
     }
 
-    registry
+    let cover_images = registry
         .user_repository()
         .delete(DeleteUser { user_id })
         .await?;
+    // 削除をコミットした後で、一緒に削除された蔵書の表紙の画像を消す
+    for cover_image in cover_images {
+        delete_cover_images(&registry, cover_image).await;
+    }
 
     Ok(StatusCode::OK)
 }
@@ -85,6 +103,110 @@ pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
     Json(UserResponse::from(user.user))
 }
 
+// 名前はすぐに変更する。メールアドレスを変更する場合は新しいアドレスに確認用のトークンを送り、
+// 確認が済むまでは 202 Accepted を返して現在のアドレスのままにする（メールの送信手段がなければ 503）。
+// 一部だけが反映されないよう、変更を始める前にすべての項目を検証する
+pub async fn update_current_user(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    MergePatch(req): MergePatch<UpdateCurrentUserRequest>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let patch = CurrentUserPatch::try_from(req)?;
+    patch.validate(&())?;
+    let CurrentUserPatch { name, email } = patch;
+    let name = name.filter(|name| *name != user.user.name);
+    let email = email.filter(|email| *email != user.user.email);
+
+    if let Some(email) = &email {
+        // 確認用のトークンを届けられないと変更を完了できないため、受け付けない
+        if !registry.mail_repository().is_deliverable() {
+            return Err(AppError::ServiceUnavailable(
+                "email change is unavailable because no mail transport is configured".into(),
+            ));
+        }
+        if registry
+            .user_repository()
+            .find_by_email(email)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::UnprocessableEntity(
+                "specified email is already in use".into(),
+            ));
+        }
+    }
+
+    if let Some(name) = name {
+        registry
+            .user_repository()
+            .update_name(UpdateUserName {
+                user_id: user.id(),
+                name,
+            })
+            .await?;
+    }
+
+    let mut status = StatusCode::OK;
+    if let Some(email) = email {
+        let token = registry
+            .email_verification_repository()
+            .create(RequestEmailChange::new(user.id(), email.clone()))
+            .await?;
+        registry
+            .mail_repository()
+            .send(Mail {
+                to: email,
+                subject: "メールアドレスの確認".into(),
+                body: format!(
+                    "メールアドレスの変更を完了するには、次のトークンを POST /api/v1/users/me/email/verify に送信してください。\n\n{}",
+                    token.0
+                ),
+            })
+            .await?;
+        status = StatusCode::ACCEPTED;
+    }
+
+    let current = registry
+        .user_repository()
+        .find_current_user(user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("user not found".into()))?;
+    Ok((status, Json(current.into())))
+}
+
+// 確認用のトークンを使ってメールアドレスの変更を反映する
+pub async fn verify_email(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<VerifyEmailRequest>,
+) -> AppResult<Json<UserResponse>> {
+    req.validate(&())?;
+
+    let verification = registry
+        .email_verification_repository()
+        .consume(&EmailVerificationToken(req.token))
+        .await?
+        .ok_or_else(|| AppError::UnprocessableEntity("invalid or expired token".into()))?;
+    if verification.user_id != user.id() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .user_repository()
+        .update_email(UpdateUserEmail {
+            user_id: user.id(),
+            email: verification.new_email,
+        })
+        .await?;
+
+    let current = registry
+        .user_repository()
+        .find_current_user(user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("user not found".into()))?;
+    Ok(Json(current.into()))
+}
+
 pub async fn change_password(
     user: AuthorizedUser,
     State(registry): State<AppRegistry>,
@@ -108,6 +230,94 @@ pub async fn get_checkouts(
         .checkout_repository()
         .find_unreturned_by_user_id(user.id())
         .await
-        .map(CheckoutsResponse::from)
+        .map(|c| CheckoutsResponse::visible_to(c, user.id(), user.is_admin()))
         .map(Json)
 }
+
+pub async fn get_checkout_history(
+    user: AuthorizedUser,
+    Query(query): Query<CheckoutHistoryQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedCheckoutResponse>> {
+    find_checkout_history(&registry, &user, user.id(), query).await
+}
+
+// 他の利用者の返却履歴は管理者のみ参照できる
+pub async fn get_user_checkout_history(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    Query(query): Query<CheckoutHistoryQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedCheckoutResponse>> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    find_checkout_history(&registry, &user, user_id, query).await
+}
+
+pub async fn export_checkout_history(
+    user: AuthorizedUser,
+    Query(query): Query<CheckoutHistoryExportQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<CsvResponse> {
+    export_history(&registry, user.id(), query).await
+}
+
+pub async fn export_user_checkout_history(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    Query(query): Query<CheckoutHistoryExportQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<CsvResponse> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    export_history(&registry, user_id, query).await
+}
+
+async fn find_checkout_history(
+    registry: &AppRegistry,
+    viewer: &AuthorizedUser,
+    user_id: UserId,
+    query: CheckoutHistoryQuery,
+) -> AppResult<Json<PaginatedCheckoutResponse>> {
+    query.validate(&())?;
+    registry
+        .checkout_repository()
+        .find_history_by_user_id(user_id, query.into())
+        .await
+        .map(|c| PaginatedCheckoutResponse::visible_to(c, viewer.id(), viewer.is_admin()))
+        .map(Json)
+}
+
+// 一度に読み込む件数を抑えるため、ページを分けて取得してから CSV にまとめる
+const EXPORT_PAGE_SIZE: i64 = 500;
+// 応答をメモリ上で組み立てるため、一度に出力できる件数に上限を設ける
+const EXPORT_MAX_ROWS: i64 = 10_000;
+
+async fn export_history(
+    registry: &AppRegistry,
+    user_id: UserId,
+    query: CheckoutHistoryExportQuery,
+) -> AppResult<CsvResponse> {
+    query.validate(&())?;
+    let mut items = Vec::new();
+    loop {
+        let page = registry
+            .checkout_repository()
+            .find_history_by_user_id(user_id, query.options(EXPORT_PAGE_SIZE, items.len() as i64))
+            .await?;
+        if page.total > EXPORT_MAX_ROWS {
+            return Err(AppError::UnprocessableEntity(format!(
+                "too many checkouts to export ({} > {EXPORT_MAX_ROWS}); narrow the period with from and to",
+                page.total
+            )));
+        }
+        let fetched = page.items.len() as i64;
+        items.extend(page.items);
+        if fetched < EXPORT_PAGE_SIZE || items.len() as i64 >= page.total {
+            break;
+        }
+    }
+    checkout_history_csv(items)
+}
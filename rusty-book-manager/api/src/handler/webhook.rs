@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    id::{WebhookDeliveryId, WebhookSubscriptionId},
+    webhook::event::DeleteWebhookSubscription,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::webhook::{
+        CreateWebhookSubscriptionRequest, PaginatedWebhookDeliveryResponse,
+        WebhookDeliveryListQuery, WebhookSubscriptionResponse, WebhookSubscriptionsResponse,
+    },
+};
+
+// Webhook の購読と配信はいずれも管理者のみ操作できる
+
+pub async fn list_webhook_subscriptions(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<WebhookSubscriptionsResponse>> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    let items = registry
+        .webhook_repository()
+        .find_subscriptions()
+        .await?
+        .into_iter()
+        .map(WebhookSubscriptionResponse::from)
+        .collect();
+    Ok(Json(WebhookSubscriptionsResponse { items }))
+}
+
+pub async fn create_webhook_subscription(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateWebhookSubscriptionRequest>,
+) -> AppResult<(StatusCode, Json<WebhookSubscriptionResponse>)> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+    let subscription = registry
+        .webhook_repository()
+        .create_subscription(req.into())
+        .await?;
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+pub async fn delete_webhook_subscription(
+    user: AuthorizedUser,
+    Path(subscription_id): Path<WebhookSubscriptionId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    registry
+        .webhook_repository()
+        .delete_subscription(DeleteWebhookSubscription { subscription_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn list_webhook_deliveries(
+    user: AuthorizedUser,
+    Query(query): Query<WebhookDeliveryListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedWebhookDeliveryResponse>> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+    registry
+        .webhook_repository()
+        .find_deliveries(query.into())
+        .await
+        .map(PaginatedWebhookDeliveryResponse::from)
+        .map(Json)
+}
+
+// 送信はバックグラウンドで行うので、受け付けた時点で 202 を返す
+pub async fn redeliver_webhook(
+    user: AuthorizedUser,
+    Path(delivery_id): Path<WebhookDeliveryId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    registry
+        .webhook_repository()
+        .redeliver(delivery_id)
+        .await
+        .map(|_| StatusCode::ACCEPTED)
+}
@@ -1,4 +1,5 @@
 pub mod extractor;
 pub mod handler;
+pub mod middleware;
 pub mod model;
 pub mod route;
@@ -0,0 +1,227 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use kernel::model::{
+    auth::AccessToken,
+    rate_limit::{RateLimitDecision, RateLimitQuota},
+};
+use registry::AppRegistry;
+use shared::config::{RateLimitConfig, RateLimitKey, RateLimitPolicy};
+use tower::{Layer, Service};
+
+// IETF の RateLimit ヘッダーフィールドのドラフトに合わせたヘッダー
+static RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+static RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+static RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+static RATELIMIT_POLICY: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+// 設定されたポリシーに従い、ユーザーまたはクライアントの IP アドレスごとにリクエスト数を制限する。
+// カウンタは Redis に置くため、複数のレプリカで動かしても制限は共有される
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    state: Arc<RateLimitState>,
+}
+
+impl RateLimitLayer {
+    pub fn new(registry: AppRegistry, config: RateLimitConfig) -> Self {
+        Self {
+            state: Arc::new(RateLimitState { registry, config }),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    state: Arc<RateLimitState>,
+}
+
+impl<S> Service<Request> for RateLimit<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        // poll_ready 済みのサービスを使うため、クローンと入れ替える
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let state = self.state.clone();
+        Box::pin(async move {
+            let Some(policy) = state.policy_for(&req) else {
+                return inner.call(req).await;
+            };
+            // リクエスト本体は Sync ではないため、await の前に必要な値を取り出しておく
+            let token = bearer_token(req.headers()).map(str::to_string);
+            let ip = state.client_ip(&req);
+            let key = format!(
+                "{}:{}",
+                policy.name,
+                state.client_key(policy, token, ip).await
+            );
+            let quota = RateLimitQuota {
+                limit: policy.limit,
+                window: policy.window,
+            };
+            let decision = match state
+                .registry
+                .rate_limit_repository()
+                .hit(&key, quota)
+                .await
+            {
+                Ok(decision) => decision,
+                Err(e) => {
+                    // Redis の障害で API 全体を止めないよう、数えられない場合は通す
+                    tracing::warn!(
+                        error.message = %e,
+                        policy = policy.name,
+                        "Failed to apply rate limit"
+                    );
+                    return inner.call(req).await;
+                }
+            };
+            if !decision.allowed {
+                let mut res = StatusCode::TOO_MANY_REQUESTS.into_response();
+                insert_headers(res.headers_mut(), policy, &decision);
+                res.headers_mut()
+                    .insert(header::RETRY_AFTER, reset_secs(&decision).into());
+                return Ok(res);
+            }
+            let mut res = inner.call(req).await?;
+            insert_headers(res.headers_mut(), policy, &decision);
+            Ok(res)
+        })
+    }
+}
+
+struct RateLimitState {
+    registry: AppRegistry,
+    config: RateLimitConfig,
+}
+
+impl RateLimitState {
+    // 制限の対象外（無効化されている、一致するポリシーがない、無制限）なら None を返す
+    fn policy_for(&self, req: &Request) -> Option<&RateLimitPolicy> {
+        if !self.config.enabled {
+            return None;
+        }
+        let path = req.uri().path();
+        self.config
+            .policies
+            .iter()
+            .find(|p| {
+                p.method
+                    .as_deref()
+                    .is_none_or(|m| m == req.method().as_str())
+                    && path_matches(&p.path, path)
+            })
+            .filter(|p| p.limit > 0)
+    }
+
+    async fn client_key(
+        &self,
+        policy: &RateLimitPolicy,
+        token: Option<String>,
+        ip: Option<IpAddr>,
+    ) -> String {
+        if policy.key == RateLimitKey::User {
+            if let Some(token) = token {
+                // 無効なトークンは IP アドレスで数える（トークンを変えて制限を逃れられないように）
+                if let Ok(Some(user_id)) = self
+                    .registry
+                    .auth_repository()
+                    .fetch_user_id_from_token(&AccessToken(token))
+                    .await
+                {
+                    return format!("user:{user_id}");
+                }
+            }
+        }
+        match ip {
+            Some(ip) => format!("ip:{ip}"),
+            // Unix ドメインソケット経由などで接続元が分からない場合はひとまとめに数える
+            None => "ip:unknown".into(),
+        }
+    }
+
+    // X-Forwarded-For の先頭はクライアントが自由に書けるため、信頼するリバースプロキシが
+    // 末尾に追加した値（プロキシから見た接続元）を使う
+    fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        if self.config.trust_forwarded_for {
+            let forwarded = req
+                .headers()
+                .get_all("x-forwarded-for")
+                .iter()
+                .next_back()
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit(',').next())
+                .and_then(|v| v.trim().parse().ok());
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+        req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    }
+}
+
+// "/api/v1/books" は "/api/v1/books" と "/api/v1/books/..." に一致し、"/api/v1/bookshelf" には一致しない
+fn path_matches(prefix: &str, path: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+fn reset_secs(decision: &RateLimitDecision) -> u64 {
+    // 0 秒と返すとすぐに再送されるので切り上げる
+    decision.reset_after.as_millis().div_ceil(1000) as u64
+}
+
+fn insert_headers(headers: &mut HeaderMap, policy: &RateLimitPolicy, decision: &RateLimitDecision) {
+    headers.insert(RATELIMIT_LIMIT.clone(), decision.limit.into());
+    headers.insert(RATELIMIT_REMAINING.clone(), decision.remaining.into());
+    headers.insert(RATELIMIT_RESET.clone(), reset_secs(decision).into());
+    if let Ok(v) = HeaderValue::from_str(&format!("{};w={}", policy.limit, policy.window.as_secs()))
+    {
+        headers.insert(RATELIMIT_POLICY.clone(), v);
+    }
+}
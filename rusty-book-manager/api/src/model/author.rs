@@ -0,0 +1,167 @@
+use garde::Validate;
+use kernel::model::{
+    author::{Author, AuthorBook, AuthorDetail, AuthorName, AuthorRole, BookAuthor},
+    id::{AuthorId, BookId},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorRoleName {
+    #[default]
+    Author,
+    Translator,
+    Editor,
+}
+
+impl From<AuthorRole> for AuthorRoleName {
+    fn from(value: AuthorRole) -> Self {
+        match value {
+            AuthorRole::Author => Self::Author,
+            AuthorRole::Translator => Self::Translator,
+            AuthorRole::Editor => Self::Editor,
+        }
+    }
+}
+
+impl From<AuthorRoleName> for AuthorRole {
+    fn from(value: AuthorRoleName) -> Self {
+        match value {
+            AuthorRoleName::Author => Self::Author,
+            AuthorRoleName::Translator => Self::Translator,
+            AuthorRoleName::Editor => Self::Editor,
+        }
+    }
+}
+
+// 登録・更新時に指定する著者。role を省略した場合は author になる
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorRequest {
+    // 空白だけの名前を通さないよう、前後の空白を除いてから検証する
+    #[garde(length(min = 1, max = 255))]
+    #[serde(deserialize_with = "trimmed")]
+    pub name: String,
+    #[garde(skip)]
+    #[serde(default)]
+    pub role: AuthorRoleName,
+}
+
+impl From<AuthorRequest> for AuthorName {
+    fn from(value: AuthorRequest) -> Self {
+        let AuthorRequest { name, role } = value;
+        Self {
+            name,
+            role: role.into(),
+        }
+    }
+}
+
+fn trimmed<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer).map(|s| s.trim().to_string())
+}
+
+// authors を指定した場合はそちらを使い、なければ author の文字列を分割する
+pub fn author_names(author: &str, authors: Vec<AuthorRequest>) -> Vec<AuthorName> {
+    if authors.is_empty() {
+        AuthorName::parse_list(author)
+    } else {
+        authors.into_iter().map(AuthorName::from).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookAuthorResponse {
+    pub id: AuthorId,
+    pub name: String,
+    pub role: AuthorRoleName,
+}
+
+impl From<BookAuthor> for BookAuthorResponse {
+    fn from(value: BookAuthor) -> Self {
+        let BookAuthor { id, name, role } = value;
+        Self {
+            id,
+            name,
+            role: role.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorsResponse {
+    pub items: Vec<AuthorResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorResponse {
+    pub id: AuthorId,
+    pub name: String,
+    pub book_count: i64,
+}
+
+impl From<Author> for AuthorResponse {
+    fn from(value: Author) -> Self {
+        let Author {
+            id,
+            name,
+            book_count,
+        } = value;
+        Self {
+            id,
+            name,
+            book_count,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorDetailResponse {
+    pub id: AuthorId,
+    pub name: String,
+    pub books: Vec<AuthorBookResponse>,
+}
+
+impl From<AuthorDetail> for AuthorDetailResponse {
+    fn from(value: AuthorDetail) -> Self {
+        let AuthorDetail { id, name, books } = value;
+        Self {
+            id,
+            name,
+            books: books.into_iter().map(AuthorBookResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorBookResponse {
+    pub id: BookId,
+    pub title: String,
+    pub role: AuthorRoleName,
+    pub publication_year: Option<i32>,
+}
+
+impl From<AuthorBook> for AuthorBookResponse {
+    fn from(value: AuthorBook) -> Self {
+        let AuthorBook {
+            book_id,
+            title,
+            role,
+            publication_year,
+        } = value;
+        Self {
+            id: book_id,
+            title,
+            role: role.into(),
+            publication_year,
+        }
+    }
+}
@@ -1,47 +1,193 @@
-use super::user::BookOwner;
+use super::{
+    author::{author_names, AuthorRequest, BookAuthorResponse},
+    book_condition::BookConditionName,
+    genre::GenreResponse,
+    tag::is_tag,
+    user::BookOwner,
+    webhook::is_http_url,
+};
+use axum_extra::headers::ETag;
 use derive_new::new;
 use garde::Validate;
 use kernel::model::{
+    author::AuthorName,
     book::{
-        event::{CreateBook, UpdateBook},
-        Book, BookListOptions,
+        event::{BibliographyPatch, BookPatch, CreateBook, UpdateBook},
+        metadata::{normalize_isbn, BookMetadata},
+        Bibliography, Book, BookListFilter, BookListOptions, BookSort, ShelfFilter,
     },
-    id::{BookId, UserId},
+    id::{BookId, GenreId, ShelfId, UserId},
     list::PaginatedList,
+    tag::normalize_tag,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use shared::error::AppError;
 
 use super::user::CheckoutUser;
 use chrono::{DateTime, Utc};
 use kernel::model::book::Checkout;
 use kernel::model::id::CheckoutId;
 
-// descriptionのみskip可能とする
+// descriptionのみskip可能とする。
+// 自動補完する場合は title・author も省略でき、ISBN から取得した値で埋める
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBookRequest {
-    #[garde(length(min = 1))]
+    #[garde(length(min = 1, max = 255))]
+    #[serde(default)]
     pub title: String,
-    #[garde(length(min = 1))]
+    // "A; B" のように複数の著者を区切って書ける
+    #[garde(custom(has_author(&self.authors)))]
+    #[serde(default)]
     pub author: String,
+    // 翻訳者・編集者など役割を指定する場合に使う。指定した場合は author より優先する
+    #[garde(dive)]
+    #[serde(default)]
+    pub authors: Vec<AuthorRequest>,
     #[garde(length(min = 1))]
     pub isbn: String,
-    #[garde(skip)]
+    #[garde(length(max = 255))]
+    #[serde(default)]
     pub description: String,
+    #[garde(custom(is_cover_url))]
+    pub cover_url: Option<String>,
+    #[garde(dive)]
+    #[serde(flatten)]
+    pub bibliography: BibliographyRequest,
+}
+
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct BibliographyRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub publisher: Option<String>,
+    #[garde(range(min = 1, max = 9999))]
+    pub publication_year: Option<i32>,
+    // BCP 47 の言語タグ（ja、en-US など）
+    #[garde(length(min = 2, max = 35))]
+    pub language: Option<String>,
+    #[garde(range(min = 1))]
+    pub page_count: Option<i32>,
+    #[garde(length(min = 1, max = 64))]
+    pub edition: Option<String>,
+}
+impl From<BibliographyRequest> for Bibliography {
+    fn from(value: BibliographyRequest) -> Self {
+        let BibliographyRequest {
+            publisher,
+            publication_year,
+            language,
+            page_count,
+            edition,
+        } = value;
+        Self {
+            publisher,
+            publication_year,
+            language,
+            page_count,
+            edition,
+        }
+    }
+}
+
+fn has_author(authors: &[AuthorRequest]) -> impl FnOnce(&String, &()) -> garde::Result + '_ {
+    move |author, _| {
+        if authors.is_empty() && AuthorName::parse_list(author).is_empty() {
+            return Err(garde::Error::new("author or authors is required"));
+        }
+        Ok(())
+    }
+}
+fn is_cover_url(value: &Option<String>, ctx: &()) -> garde::Result {
+    value.as_deref().map_or(Ok(()), |url| is_http_url(url, ctx))
+}
+impl CreateBookRequest {
+    // 入力されていない項目だけを書誌情報で埋める
+    pub fn fill_missing(&mut self, metadata: BookMetadata) {
+        let BookMetadata {
+            title,
+            author,
+            description,
+            cover_url,
+            ..
+        } = metadata;
+        fill(&mut self.title, title);
+        fill(&mut self.author, author);
+        fill(&mut self.description, description);
+        if self.cover_url.is_none() {
+            self.cover_url = cover_url;
+        }
+    }
+}
+fn fill(field: &mut String, value: Option<String>) {
+    if let (true, Some(value)) = (field.is_empty(), value) {
+        *field = value;
+    }
 }
 impl From<CreateBookRequest> for CreateBook {
     fn from(req: CreateBookRequest) -> Self {
         let CreateBookRequest {
             title,
             author,
+            authors,
             isbn,
             description,
+            cover_url,
+            bibliography,
         } = req;
         Self {
+            title,
+            authors: author_names(&author, authors),
+            isbn,
+            description,
+            cover_url,
+            bibliography: bibliography.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterBookQuery {
+    // true の場合、ISBN から書誌情報を取得して未入力の項目を埋める
+    #[serde(default)]
+    pub autofill: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BookLookupQuery {
+    #[garde(custom(is_isbn))]
+    pub isbn: String,
+}
+fn is_isbn(value: &str, _: &()) -> garde::Result {
+    normalize_isbn(value)
+        .map(|_| ())
+        .ok_or_else(|| garde::Error::new("not a valid ISBN-10 or ISBN-13"))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookMetadataResponse {
+    pub isbn: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub cover_url: Option<String>,
+}
+impl From<BookMetadata> for BookMetadataResponse {
+    fn from(value: BookMetadata) -> Self {
+        let BookMetadata {
+            isbn,
             title,
             author,
+            description,
+            cover_url,
+        } = value;
+        Self {
             isbn,
+            title,
+            author,
             description,
+            cover_url,
         }
     }
 }
@@ -51,38 +197,141 @@ impl From<CreateBookRequest> for CreateBook {
 pub struct UpdateBookRequest {
     #[garde(length(min = 1))]
     pub title: String,
-    #[garde(length(min = 1))]
+    #[garde(custom(has_author(&self.authors)))]
+    #[serde(default)]
     pub author: String,
+    #[garde(dive)]
+    #[serde(default)]
+    pub authors: Vec<AuthorRequest>,
     #[garde(length(min = 1))]
     pub isbn: String,
     #[garde(skip)]
     pub description: String,
+    // 省略した項目は空になる
+    #[garde(dive)]
+    #[serde(flatten)]
+    pub bibliography: BibliographyRequest,
 }
 #[derive(new)]
-pub struct UpdateBookRequestWithIds(BookId, UserId, UpdateBookRequest);
+pub struct UpdateBookRequestWithIds(BookId, UserId, Option<i64>, UpdateBookRequest);
 impl From<UpdateBookRequestWithIds> for UpdateBook {
     fn from(value: UpdateBookRequestWithIds) -> Self {
         let UpdateBookRequestWithIds(
             book_id,
             user_id,
+            expected_version,
             UpdateBookRequest {
                 title,
                 author,
+                authors,
                 isbn,
                 description,
+                bibliography,
             },
         ) = value;
         Self {
             book_id,
             title,
-            author,
+            authors: author_names(&author, authors),
             isbn,
             description,
+            bibliography: bibliography.into(),
             requested_user: user_id,
+            expected_version,
         }
     }
 }
 
+// JSON Merge Patch の本体。キーがない場合は None、null の場合は Some(None) になる
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchBookRequest {
+    #[serde(default, deserialize_with = "nullable")]
+    pub title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub author: Option<Option<String>>,
+    // author と両方指定した場合はこちらを優先する
+    #[serde(default, deserialize_with = "nullable")]
+    pub authors: Option<Option<Vec<AuthorRequest>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub isbn: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub description: Option<Option<String>>,
+    // 書誌情報は null で空にできる
+    #[serde(default, deserialize_with = "nullable")]
+    pub publisher: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub publication_year: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub language: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub page_count: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub edition: Option<Option<String>>,
+}
+
+pub(crate) fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(new)]
+pub struct PatchBookRequestWithIds(BookId, UserId, Option<i64>, PatchBookRequest);
+impl TryFrom<PatchBookRequestWithIds> for BookPatch {
+    type Error = AppError;
+
+    fn try_from(value: PatchBookRequestWithIds) -> Result<Self, Self::Error> {
+        let PatchBookRequestWithIds(
+            book_id,
+            user_id,
+            expected_version,
+            PatchBookRequest {
+                title,
+                author,
+                authors,
+                isbn,
+                description,
+                publisher,
+                publication_year,
+                language,
+                page_count,
+                edition,
+            },
+        ) = value;
+        let authors = match required(authors, "authors")? {
+            Some(authors) => Some(authors.into_iter().map(AuthorName::from).collect()),
+            None => required(author, "author")?.map(|a| AuthorName::parse_list(&a)),
+        };
+        Ok(Self {
+            book_id,
+            title: required(title, "title")?,
+            authors,
+            isbn: required(isbn, "isbn")?,
+            // description は null で空にできる
+            description: description.map(Option::unwrap_or_default),
+            bibliography: BibliographyPatch {
+                publisher,
+                publication_year,
+                language,
+                page_count,
+                edition,
+            },
+            requested_user: user_id,
+            expected_version,
+        })
+    }
+}
+
+// 必須の項目は null で削除できない
+pub(crate) fn required<T>(value: Option<Option<T>>, name: &str) -> Result<Option<T>, AppError> {
+    value
+        .map(|v| v.ok_or_else(|| AppError::UnprocessableEntity(format!("{name} cannot be null"))))
+        .transpose()
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct BookListQuery {
     #[garde(range(min = 0))]
@@ -91,15 +340,70 @@ pub struct BookListQuery {
     #[garde(range(min = 0))]
     #[serde(default)] // defaultは0
     pub offset: i64,
+    // 以下は指定した条件をすべて満たす蔵書に絞り込む
+    #[garde(custom(is_tag_filter))]
+    pub tag: Option<String>,
+    #[garde(skip)]
+    pub genre: Option<GenreId>,
+    // 自分の本棚のみ指定できる
+    #[garde(skip)]
+    pub shelf: Option<ShelfId>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub sort: BookSortName,
 }
 const DEFAULT_LIMIT: i64 = 20;
 const fn default_limit() -> i64 {
     DEFAULT_LIMIT
 }
-impl From<BookListQuery> for BookListOptions {
-    fn from(value: BookListQuery) -> Self {
-        let BookListQuery { limit, offset } = value;
-        Self { limit, offset }
+fn is_tag_filter(value: &Option<String>, ctx: &()) -> garde::Result {
+    value.as_deref().map_or(Ok(()), |v| is_tag(v, ctx))
+}
+
+// 一覧の並び順。newest（既定）・rating（平均評価の高い順）・review_count（レビューの多い順）
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookSortName {
+    #[default]
+    Newest,
+    Rating,
+    ReviewCount,
+}
+impl From<BookSortName> for BookSort {
+    fn from(value: BookSortName) -> Self {
+        match value {
+            BookSortName::Newest => Self::Newest,
+            BookSortName::Rating => Self::Rating,
+            BookSortName::ReviewCount => Self::ReviewCount,
+        }
+    }
+}
+
+#[derive(new)]
+pub struct BookListQueryWithUserId(UserId, BookListQuery);
+impl From<BookListQueryWithUserId> for BookListOptions {
+    fn from(value: BookListQueryWithUserId) -> Self {
+        let BookListQueryWithUserId(
+            user_id,
+            BookListQuery {
+                limit,
+                offset,
+                tag,
+                genre,
+                shelf,
+                sort,
+            },
+        ) = value;
+        Self {
+            limit,
+            offset,
+            filter: BookListFilter {
+                tag: tag.as_deref().and_then(normalize_tag),
+                genre_id: genre,
+                shelf: shelf.map(|shelf_id| ShelfFilter { shelf_id, user_id }),
+            },
+            sort: sort.into(),
+        }
     }
 }
 
@@ -109,10 +413,23 @@ pub struct BookResponse {
     pub id: BookId,
     pub title: String,
     pub author: String,
+    pub authors: Vec<BookAuthorResponse>,
     pub isbn: String,
     pub description: String,
+    #[serde(flatten)]
+    pub bibliography: BibliographyResponse,
+    // アップロードされた表紙画像があればその URL、なければ ISBN から取得した書影の URL
+    pub cover_url: Option<String>,
+    pub cover_thumbnail_url: Option<String>,
+    pub tags: Vec<String>,
+    pub genres: Vec<GenreResponse>,
+    // 非表示のレビューを除いた平均評価。レビューがない場合は null
+    pub rating_average: Option<f64>,
+    pub review_count: i64,
     pub owner: BookOwner,
     pub checkout: Option<BookCheckoutResponse>,
+    // 紛失中（lost）の蔵書は見つかるまで貸し出せない
+    pub condition: BookConditionName,
 }
 impl From<Book> for BookResponse {
     fn from(book: Book) -> Self {
@@ -120,23 +437,88 @@ impl From<Book> for BookResponse {
             id,
             title,
             author,
+            authors,
             isbn,
             description,
+            cover_url,
+            cover_image,
+            bibliography,
+            tags,
+            genres,
+            rating,
             owner,
             checkout,
+            condition,
+            ..
         } = book;
+        let uploaded = cover_image.is_some();
         Self {
             id,
             title,
             author,
+            authors: authors.into_iter().map(BookAuthorResponse::from).collect(),
             isbn,
             description,
+            bibliography: bibliography.into(),
+            cover_url: if uploaded {
+                Some(format!("/api/v1/books/{id}/cover"))
+            } else {
+                cover_url
+            },
+            cover_thumbnail_url: uploaded.then(|| format!("/api/v1/books/{id}/cover/thumbnail")),
+            tags,
+            genres: genres.into_iter().map(GenreResponse::from).collect(),
+            rating_average: rating.average,
+            review_count: rating.count,
             owner: owner.into(),
             checkout: checkout.map(BookCheckoutResponse::from),
+            condition: condition.into(),
         }
     }
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BibliographyResponse {
+    pub publisher: Option<String>,
+    pub publication_year: Option<i32>,
+    pub language: Option<String>,
+    pub page_count: Option<i32>,
+    pub edition: Option<String>,
+}
+impl From<Bibliography> for BibliographyResponse {
+    fn from(value: Bibliography) -> Self {
+        let Bibliography {
+            publisher,
+            publication_year,
+            language,
+            page_count,
+            edition,
+        } = value;
+        Self {
+            publisher,
+            publication_year,
+            language,
+            page_count,
+            edition,
+        }
+    }
+}
+
+// 蔵書のバージョンに加え、レスポンスに含まれる貸出状況と評価も反映した ETag を返す
+pub fn book_etag(book: &Book) -> ETag {
+    let mut tag = book.version.to_string();
+    if let Some(c) = &book.checkout {
+        tag.push_str(&format!("-{}", c.checkout_id));
+    }
+    if let Some(average) = book.rating.average {
+        tag.push_str(&format!("-r{}x{average:.3}", book.rating.count));
+    }
+    format!("\"{tag}\"")
+        .parse()
+        .expect("ETag consists of visible ASCII characters")
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginatedBookResponse {
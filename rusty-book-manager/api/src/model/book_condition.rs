@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    book_condition::{
+        event::{MarkBookFound, UpdateBookCondition, UpdateConditionCharge},
+        BookCondition, BookConditionRecord, CheckoutCharge,
+    },
+    id::{BookConditionId, BookId, CheckoutId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookConditionName {
+    Good,
+    Damaged,
+    Lost,
+}
+
+impl From<BookCondition> for BookConditionName {
+    fn from(value: BookCondition) -> Self {
+        match value {
+            BookCondition::Good => Self::Good,
+            BookCondition::Damaged => Self::Damaged,
+            BookCondition::Lost => Self::Lost,
+        }
+    }
+}
+
+impl From<BookConditionName> for BookCondition {
+    fn from(value: BookConditionName) -> Self {
+        match value {
+            BookConditionName::Good => Self::Good,
+            BookConditionName::Damaged => Self::Damaged,
+            BookConditionName::Lost => Self::Lost,
+        }
+    }
+}
+
+// 弁償の方法。fee は金額（円）を、replacement は同じ本を用意しての弁償を表す
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Validate)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CheckoutChargeBody {
+    Fee {
+        #[garde(range(min = 0))]
+        amount: i32,
+    },
+    Replacement,
+}
+
+impl From<CheckoutChargeBody> for CheckoutCharge {
+    fn from(value: CheckoutChargeBody) -> Self {
+        match value {
+            CheckoutChargeBody::Fee { amount } => Self::Fee { amount },
+            CheckoutChargeBody::Replacement => Self::Replacement,
+        }
+    }
+}
+
+impl From<CheckoutCharge> for CheckoutChargeBody {
+    fn from(value: CheckoutCharge) -> Self {
+        match value {
+            CheckoutCharge::Fee { amount } => Self::Fee { amount },
+            CheckoutCharge::Replacement => Self::Replacement,
+        }
+    }
+}
+
+// 見つかった蔵書に破損があれば damaged を true にする
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkBookFoundRequest {
+    #[garde(length(max = 1000))]
+    #[serde(default)]
+    pub note: String,
+    #[garde(skip)]
+    #[serde(default)]
+    pub damaged: bool,
+}
+
+impl MarkBookFoundRequest {
+    pub fn into_event(self, book_id: BookId, requested_user: UserId) -> MarkBookFound {
+        let MarkBookFoundRequest { note, damaged } = self;
+        MarkBookFound {
+            book_id,
+            condition: if damaged {
+                BookCondition::Damaged
+            } else {
+                BookCondition::Good
+            },
+            note,
+            requested_user,
+        }
+    }
+}
+
+// 修理などで蔵書の状態を変える。紛失（lost）は指定できない
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBookConditionRequest {
+    #[garde(skip)]
+    pub condition: BookConditionName,
+    #[garde(length(max = 1000))]
+    #[serde(default)]
+    pub note: String,
+}
+
+impl UpdateBookConditionRequest {
+    pub fn into_event(self, book_id: BookId, requested_user: UserId) -> UpdateBookCondition {
+        let UpdateBookConditionRequest { condition, note } = self;
+        UpdateBookCondition {
+            book_id,
+            condition: condition.into(),
+            note,
+            requested_user,
+        }
+    }
+}
+
+// 破損・紛失の記録の弁償の方法。null にすると弁償なしにする
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateConditionChargeRequest {
+    #[garde(dive)]
+    pub charge: Option<CheckoutChargeBody>,
+}
+
+impl UpdateConditionChargeRequest {
+    pub fn into_event(
+        self,
+        book_id: BookId,
+        book_condition_id: BookConditionId,
+    ) -> UpdateConditionCharge {
+        UpdateConditionCharge {
+            book_condition_id,
+            book_id,
+            charge: self.charge.map(CheckoutCharge::from),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookConditionsResponse {
+    pub items: Vec<BookConditionResponse>,
+}
+
+impl From<Vec<BookConditionRecord>> for BookConditionsResponse {
+    fn from(value: Vec<BookConditionRecord>) -> Self {
+        Self {
+            items: value.into_iter().map(BookConditionResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookConditionResponse {
+    pub id: BookConditionId,
+    pub condition: BookConditionName,
+    pub checkout_id: Option<CheckoutId>,
+    pub note: String,
+    pub charge: Option<CheckoutChargeBody>,
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<BookConditionRecord> for BookConditionResponse {
+    fn from(value: BookConditionRecord) -> Self {
+        let BookConditionRecord {
+            id,
+            book_id: _,
+            condition,
+            checkout_id,
+            note,
+            charge,
+            recorded_by,
+            recorded_at,
+        } = value;
+        Self {
+            id,
+            condition: condition.into(),
+            checkout_id,
+            note,
+            charge: charge.map(CheckoutChargeBody::from),
+            recorded_by,
+            recorded_at,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    book_transfer::{BookTransfer, BookTransferStatus},
+    id::{BookId, BookTransferId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+use super::user::TransferUser;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookTransferStatusName {
+    Pending,
+    Accepted,
+    Declined,
+    Cancelled,
+    Forced,
+}
+
+impl From<BookTransferStatus> for BookTransferStatusName {
+    fn from(value: BookTransferStatus) -> Self {
+        match value {
+            BookTransferStatus::Pending => Self::Pending,
+            BookTransferStatus::Accepted => Self::Accepted,
+            BookTransferStatus::Declined => Self::Declined,
+            BookTransferStatus::Cancelled => Self::Cancelled,
+            BookTransferStatus::Forced => Self::Forced,
+        }
+    }
+}
+
+// 譲渡の申し出と、管理者による所有者の変更に使う
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTransferRequest {
+    pub to_user_id: UserId,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTransfersResponse {
+    pub items: Vec<BookTransferResponse>,
+}
+
+impl From<Vec<BookTransfer>> for BookTransfersResponse {
+    fn from(value: Vec<BookTransfer>) -> Self {
+        Self {
+            items: value.into_iter().map(BookTransferResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTransferResponse {
+    pub id: BookTransferId,
+    pub book_id: BookId,
+    pub book_title: String,
+    pub from_user: TransferUser,
+    pub to_user: TransferUser,
+    pub status: BookTransferStatusName,
+    pub forced_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl From<BookTransfer> for BookTransferResponse {
+    fn from(value: BookTransfer) -> Self {
+        let BookTransfer {
+            id,
+            book_id,
+            book_title,
+            from_user,
+            to_user,
+            status,
+            forced_by,
+            created_at,
+            resolved_at,
+        } = value;
+        Self {
+            id,
+            book_id,
+            book_title,
+            from_user: from_user.into(),
+            to_user: to_user.into(),
+            status: status.into(),
+            forced_by,
+            created_at,
+            resolved_at,
+        }
+    }
+}
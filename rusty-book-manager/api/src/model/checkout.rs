@@ -1,9 +1,18 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use garde::Validate;
 use kernel::model::{
-    checkout::{Checkout, CheckoutBook},
+    checkout::{Checkout, CheckoutBook, CheckoutHistoryOptions, CheckoutOutcome},
     id::{BookId, CheckoutId, UserId},
+    list::PaginatedList,
+};
+use serde::{Deserialize, Serialize};
+use shared::error::AppResult;
+
+use super::{
+    book_condition::CheckoutChargeBody,
+    csv::CsvResponse,
+    date_range::{is_not_before, utc_range},
 };
-use serde::Serialize;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,13 +28,48 @@ impl From<Vec<Checkout>> for CheckoutsResponse {
     }
 }
 
-#[derive(Serialize)]
+impl CheckoutsResponse {
+    pub fn visible_to(value: Vec<Checkout>, viewer_id: UserId, is_admin: bool) -> Self {
+        Self {
+            items: value
+                .into_iter()
+                .map(|c| CheckoutResponse::visible_to(c, viewer_id, is_admin))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckoutOutcomeName {
+    Returned,
+    Damaged,
+    Lost,
+}
+
+impl From<CheckoutOutcome> for CheckoutOutcomeName {
+    fn from(value: CheckoutOutcome) -> Self {
+        match value {
+            CheckoutOutcome::Returned => Self::Returned,
+            CheckoutOutcome::Damaged => Self::Damaged,
+            CheckoutOutcome::Lost => Self::Lost,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CheckoutResponse {
     pub id: CheckoutId,
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
     pub returned_at: Option<DateTime<Utc>>,
+    // 貸出の終わり方。未返却の場合は null
+    pub outcome: Option<CheckoutOutcomeName>,
+    // 管理者が利用者に代わって貸出・返却した場合の管理者と、返却した理由
+    pub checked_out_on_behalf_by: Option<UserId>,
+    pub returned_on_behalf_by: Option<UserId>,
+    pub return_reason: Option<String>,
     pub book: CheckoutBookResponse,
 }
 
@@ -36,6 +80,10 @@ impl From<Checkout> for CheckoutResponse {
             checked_out_by,
             checked_out_at,
             returned_at,
+            outcome,
+            checked_out_on_behalf_by,
+            returned_on_behalf_by,
+            return_reason,
             book,
         } = value;
         Self {
@@ -43,12 +91,29 @@ impl From<Checkout> for CheckoutResponse {
             checked_out_by,
             checked_out_at,
             returned_at,
+            outcome: outcome.map(CheckoutOutcomeName::from),
+            checked_out_on_behalf_by,
+            returned_on_behalf_by,
+            return_reason,
             book: book.into(),
         }
     }
 }
 
-#[derive(Serialize)]
+impl CheckoutResponse {
+    // 代理の貸出・返却の詳細は、管理者と借りた本人にだけ見せる
+    pub fn visible_to(value: Checkout, viewer_id: UserId, is_admin: bool) -> Self {
+        let mut res = Self::from(value);
+        if !is_admin && res.checked_out_by != viewer_id {
+            res.checked_out_on_behalf_by = None;
+            res.returned_on_behalf_by = None;
+            res.return_reason = None;
+        }
+        res
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CheckoutBookResponse {
     pub id: BookId,
@@ -73,3 +138,152 @@ impl From<CheckoutBook> for CheckoutBookResponse {
         }
     }
 }
+
+// 窓口で管理者が利用者に代わって貸し出す際の、借りる利用者
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutOnBehalfRequest {
+    pub user_id: UserId,
+}
+
+// 管理者が借りた利用者に代わって返却する理由
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceReturnRequest {
+    #[garde(length(min = 1, max = 1000))]
+    pub reason: String,
+}
+
+// 破損した状態での返却や紛失の届け出の内容。弁償の方法は管理者のみ指定できる
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutIncidentRequest {
+    #[garde(length(max = 1000))]
+    #[serde(default)]
+    pub note: String,
+    #[garde(dive)]
+    pub charge: Option<CheckoutChargeBody>,
+}
+
+// 返却履歴の一覧。from・to は返却日（UTC）で、いずれも指定した日を含む
+#[derive(Debug, Deserialize, Validate)]
+pub struct CheckoutHistoryQuery {
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+    #[garde(skip)]
+    pub from: Option<NaiveDate>,
+    #[garde(custom(is_not_before(&self.from)))]
+    pub to: Option<NaiveDate>,
+}
+const fn default_limit() -> i64 {
+    20
+}
+
+impl From<CheckoutHistoryQuery> for CheckoutHistoryOptions {
+    fn from(value: CheckoutHistoryQuery) -> Self {
+        let CheckoutHistoryQuery {
+            limit,
+            offset,
+            from,
+            to,
+        } = value;
+        let (returned_from, returned_to) = utc_range(from, to);
+        Self {
+            limit,
+            offset,
+            returned_from,
+            returned_to,
+        }
+    }
+}
+
+// 返却履歴の CSV のエクスポート。期間の指定は一覧と同じ
+#[derive(Debug, Deserialize, Validate)]
+pub struct CheckoutHistoryExportQuery {
+    #[garde(skip)]
+    pub from: Option<NaiveDate>,
+    #[garde(custom(is_not_before(&self.from)))]
+    pub to: Option<NaiveDate>,
+}
+
+impl CheckoutHistoryExportQuery {
+    // エクスポートはページを分けずにすべて返すので、ここでは期間のみ決める
+    pub fn options(&self, limit: i64, offset: i64) -> CheckoutHistoryOptions {
+        let (returned_from, returned_to) = utc_range(self.from, self.to);
+        CheckoutHistoryOptions {
+            limit,
+            offset,
+            returned_from,
+            returned_to,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedCheckoutResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<CheckoutResponse>,
+}
+
+impl PaginatedCheckoutResponse {
+    pub fn visible_to(value: PaginatedList<Checkout>, viewer_id: UserId, is_admin: bool) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items
+                .into_iter()
+                .map(|c| CheckoutResponse::visible_to(c, viewer_id, is_admin))
+                .collect(),
+        }
+    }
+}
+
+// 読書履歴の CSV
+pub fn checkout_history_csv(items: Vec<Checkout>) -> AppResult<CsvResponse> {
+    let records = items.into_iter().map(
+        |Checkout {
+             id,
+             checked_out_at,
+             returned_at,
+             book,
+             ..
+         }| {
+            vec![
+                id.to_string(),
+                book.book_id.to_string(),
+                book.title,
+                book.author,
+                book.isbn,
+                checked_out_at.to_rfc3339(),
+                returned_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            ]
+        },
+    );
+    CsvResponse::new(
+        "checkout-history.csv",
+        &[
+            "checkout_id",
+            "book_id",
+            "title",
+            "author",
+            "isbn",
+            "checked_out_at",
+            "returned_at",
+        ],
+        records,
+    )
+}
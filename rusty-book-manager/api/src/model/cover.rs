@@ -0,0 +1,129 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, ImageReader, Limits};
+use kernel::model::id::BookId;
+use shared::error::{AppError, AppResult};
+use uuid::Uuid;
+
+// アップロードできる表紙画像のファイルサイズの上限
+pub const MAX_COVER_SIZE: usize = 5 * 1024 * 1024;
+// サムネイルはこの大きさに収まるよう縦横比を保って縮小する
+const THUMBNAIL_SIZE: u32 = 240;
+// 展開後のサイズが極端に大きい画像でメモリを使い切らないよう、縦横の画素数を制限する
+const MAX_COVER_DIMENSION: u32 = 8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl CoverFormat {
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type {
+            "image/jpeg" => Some(Self::Jpeg),
+            "image/png" => Some(Self::Png),
+            "image/webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Webp => "webp",
+        }
+    }
+
+    fn image_format(self) -> ImageFormat {
+        match self {
+            Self::Jpeg => ImageFormat::Jpeg,
+            Self::Png => ImageFormat::Png,
+            Self::Webp => ImageFormat::WebP,
+        }
+    }
+}
+
+// 検証済みの表紙画像とそのサムネイル
+pub struct CoverImage {
+    pub format: CoverFormat,
+    pub data: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+impl CoverImage {
+    // 宣言された Content-Type と中身が一致する画像であることを確かめ、サムネイルを作る。
+    // 画像の展開は重いので、非同期のタスクからは spawn_blocking で呼び出す
+    pub fn process(content_type: &str, data: Vec<u8>) -> AppResult<Self> {
+        let format = CoverFormat::from_content_type(content_type).ok_or_else(|| {
+            AppError::UnsupportedMediaType(
+                "cover must be image/jpeg, image/png or image/webp".into(),
+            )
+        })?;
+        if image::guess_format(&data).ok() != Some(format.image_format()) {
+            return Err(AppError::UnsupportedMediaType(format!(
+                "content is not {content_type}"
+            )));
+        }
+
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_COVER_DIMENSION);
+        limits.max_image_height = Some(MAX_COVER_DIMENSION);
+        let mut reader = ImageReader::with_format(Cursor::new(&data), format.image_format());
+        reader.limits(limits);
+        let image = reader
+            .decode()
+            .map_err(|e| AppError::UnprocessableEntity(format!("invalid image: {e}")))?;
+
+        // JPEG は透過を扱えないので RGB に変換してから書き出す
+        let mut thumbnail = Cursor::new(Vec::new());
+        image
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+            .to_rgb8()
+            .write_to(&mut thumbnail, ImageFormat::Jpeg)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+
+        Ok(Self {
+            format,
+            data,
+            thumbnail: thumbnail.into_inner(),
+        })
+    }
+}
+
+// アップロードのたびに別のキーにし、差し替え中も古い画像を読めるようにする
+pub fn cover_key(book_id: BookId, format: CoverFormat) -> String {
+    format!(
+        "covers/{book_id}/{}.{}",
+        Uuid::new_v4().simple(),
+        format.extension()
+    )
+}
+
+// サムネイルは常に JPEG で保存する
+pub fn thumbnail_key(cover_key: &str) -> String {
+    let stem = cover_key
+        .rsplit_once('.')
+        .map_or(cover_key, |(stem, _)| stem);
+    format!("{stem}.thumb.jpg")
+}
+
+// 保存したキーの拡張子から Content-Type を決める
+pub fn content_type_of(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("jpg") => CoverFormat::Jpeg.content_type(),
+        Some("png") => CoverFormat::Png.content_type(),
+        Some("webp") => CoverFormat::Webp.content_type(),
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,77 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+use shared::error::{AppError, AppResult};
+
+// ダウンロード用の CSV。Excel で開いても文字化けしないよう UTF-8 の BOM を付ける
+pub struct CsvResponse {
+    filename: &'static str,
+    body: Vec<u8>,
+}
+
+impl CsvResponse {
+    pub fn new<R>(
+        filename: &'static str,
+        header: &[&str],
+        records: impl IntoIterator<Item = R>,
+    ) -> AppResult<Self>
+    where
+        R: IntoIterator<Item = String>,
+    {
+        let csv_error = |e: csv::Error| AppError::ConversionEntityError(e.to_string());
+        let mut writer = csv::Writer::from_writer(b"\xEF\xBB\xBF".to_vec());
+        writer.write_record(header).map_err(csv_error)?;
+        for record in records {
+            writer
+                .write_record(record.into_iter().map(escape_formula).collect::<Vec<_>>())
+                .map_err(csv_error)?;
+        }
+        let body = writer
+            .into_inner()
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        Ok(Self { filename, body })
+    }
+}
+
+// 表計算ソフトで開いたときに数式として実行されないよう、数式の先頭になり得る文字で
+// 始まる値には ' を付ける（CSV インジェクション対策）
+fn escape_formula(value: String) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value
+    }
+}
+
+impl IntoResponse for CsvResponse {
+    fn into_response(self) -> Response {
+        (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", self.filename),
+                ),
+            ],
+            self.body,
+        )
+            .into_response()
+    }
+}
+
+// CSV の 1 行として出力できる項目
+pub trait CsvRecord {
+    const HEADER: &'static [&'static str];
+
+    fn into_record(self) -> Vec<String>;
+}
+
+impl CsvResponse {
+    pub fn from_records<T: CsvRecord>(
+        filename: &'static str,
+        items: impl IntoIterator<Item = T>,
+    ) -> AppResult<Self> {
+        Self::new(filename, T::HEADER, items.into_iter().map(T::into_record))
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+// from・to を日付（UTC）で受け取るクエリで、to が from より前でないことを確かめる
+pub fn is_not_before(
+    from: &Option<NaiveDate>,
+) -> impl FnOnce(&Option<NaiveDate>, &()) -> garde::Result + '_ {
+    move |to, _| match (from, to) {
+        (Some(from), Some(to)) if to < from => Err(garde::Error::new("to must not be before from")),
+        _ => Ok(()),
+    }
+}
+
+// いずれも指定した日を含む日付の範囲を、日時の [from, to) の範囲に直す
+pub fn utc_range(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let start_of = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|d| d.and_utc());
+    (
+        from.and_then(start_of),
+        to.and_then(|d| d.succ_opt()).and_then(start_of),
+    )
+}
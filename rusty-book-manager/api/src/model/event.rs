@@ -0,0 +1,145 @@
+use axum::response::sse::Event;
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    domain_event::DomainEvent,
+    event_stream::{EventStreamId, StreamedEvent},
+    id::{BookId, CheckoutId, UserId},
+};
+use serde::Serialize;
+
+// 受け取ったクライアントは蔵書の一覧・詳細を取得し直す
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookEventResponse {
+    pub book_id: BookId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+// 借りた利用者は、管理者と本人にだけ見せる
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutEventResponse {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEventResponse {
+    pub user_id: UserId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum EventData {
+    Book(BookEventResponse),
+    Checkout(CheckoutEventResponse),
+    User(UserEventResponse),
+}
+
+// SSE の 1 件のメッセージ。id は Last-Event-ID での再開に、event_type は event フィールドに使う
+#[derive(Debug)]
+pub struct EventResponse {
+    pub id: EventStreamId,
+    pub event_type: &'static str,
+    pub data: EventData,
+}
+
+impl EventResponse {
+    // 閲覧者に見せてよい内容に絞る。利用者の変更は管理者にしか見せない
+    pub fn visible_to(event: StreamedEvent, viewer_id: UserId, is_admin: bool) -> Option<Self> {
+        let StreamedEvent {
+            id,
+            event,
+            occurred_at,
+        } = event;
+        let event_type = event.event_type();
+        let data = match event {
+            DomainEvent::BookCreated { book_id, .. }
+            | DomainEvent::BookUpdated { book_id }
+            | DomainEvent::BookDeleted { book_id }
+            | DomainEvent::BookTransferred { book_id, .. }
+            | DomainEvent::BookConditionChanged { book_id, .. } => {
+                EventData::Book(BookEventResponse {
+                    book_id,
+                    occurred_at,
+                })
+            }
+            // 返事待ちの譲渡は、当事者と管理者にだけ見せる
+            DomainEvent::BookTransferOffered {
+                book_id,
+                from_user_id,
+                to_user_id,
+                ..
+            }
+            | DomainEvent::BookTransferDeclined {
+                book_id,
+                from_user_id,
+                to_user_id,
+                ..
+            }
+            | DomainEvent::BookTransferCancelled {
+                book_id,
+                from_user_id,
+                to_user_id,
+                ..
+            } => {
+                if !(is_admin || viewer_id == from_user_id || viewer_id == to_user_id) {
+                    return None;
+                }
+                EventData::Book(BookEventResponse {
+                    book_id,
+                    occurred_at,
+                })
+            }
+            DomainEvent::CheckoutCreated {
+                checkout_id,
+                book_id,
+                user_id,
+                ..
+            }
+            | DomainEvent::CheckoutReturned {
+                checkout_id,
+                book_id,
+                user_id,
+                ..
+            } => EventData::Checkout(CheckoutEventResponse {
+                checkout_id,
+                book_id,
+                user_id: (is_admin || user_id == viewer_id).then_some(user_id),
+                occurred_at,
+            }),
+            DomainEvent::UserCreated { user_id }
+            | DomainEvent::UserUpdated { user_id }
+            | DomainEvent::UserDeleted { user_id } => {
+                if !is_admin {
+                    return None;
+                }
+                EventData::User(UserEventResponse {
+                    user_id,
+                    occurred_at,
+                })
+            }
+        };
+        Some(Self {
+            id,
+            event_type,
+            data,
+        })
+    }
+}
+
+impl TryFrom<EventResponse> for Event {
+    type Error = axum::Error;
+
+    fn try_from(value: EventResponse) -> Result<Self, Self::Error> {
+        Event::default()
+            .id(value.id.to_string())
+            .event(value.event_type)
+            .json_data(value.data)
+    }
+}
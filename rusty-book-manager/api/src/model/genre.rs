@@ -0,0 +1,51 @@
+use garde::Validate;
+use kernel::model::{
+    genre::{event::CreateGenre, Genre},
+    id::GenreId,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenresResponse {
+    pub items: Vec<GenreResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenreResponse {
+    pub id: GenreId,
+    pub name: String,
+    pub parent_id: Option<GenreId>,
+}
+
+impl From<Genre> for GenreResponse {
+    fn from(value: Genre) -> Self {
+        let Genre {
+            id,
+            name,
+            parent_id,
+        } = value;
+        Self {
+            id,
+            name,
+            parent_id,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGenreRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(skip)]
+    pub parent_id: Option<GenreId>,
+}
+
+impl From<CreateGenreRequest> for CreateGenre {
+    fn from(value: CreateGenreRequest) -> Self {
+        let CreateGenreRequest { name, parent_id } = value;
+        Self { name, parent_id }
+    }
+}
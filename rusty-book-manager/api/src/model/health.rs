@@ -0,0 +1,77 @@
+use kernel::model::health::{DependencyHealth, HealthReport, HealthStatus};
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatusName {
+    Up,
+    Degraded,
+    Down,
+}
+
+impl From<HealthStatus> for HealthStatusName {
+    fn from(value: HealthStatus) -> Self {
+        match value {
+            HealthStatus::Up => Self::Up,
+            HealthStatus::Degraded => Self::Degraded,
+            HealthStatus::Down => Self::Down,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivenessResponse {
+    pub status: HealthStatusName,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessResponse {
+    pub status: HealthStatusName,
+    pub checks: Vec<DependencyHealthResponse>,
+}
+
+impl From<HealthReport> for ReadinessResponse {
+    fn from(value: HealthReport) -> Self {
+        let status = value.status().into();
+        Self {
+            status,
+            checks: value
+                .checks
+                .into_iter()
+                .map(DependencyHealthResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyHealthResponse {
+    pub name: String,
+    pub status: HealthStatusName,
+    pub required: bool,
+    pub latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl From<DependencyHealth> for DependencyHealthResponse {
+    fn from(value: DependencyHealth) -> Self {
+        let DependencyHealth {
+            name,
+            status,
+            required,
+            latency,
+            detail,
+        } = value;
+        Self {
+            name: name.into(),
+            status: status.into(),
+            required,
+            latency_ms: latency.as_secs_f64() * 1000.0,
+            detail,
+        }
+    }
+}
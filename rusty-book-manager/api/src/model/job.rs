@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    id::JobRunId,
+    job::{JobName, JobRun, JobRunListOptions, JobRunStatus},
+    list::PaginatedList,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobNameName {
+    CheckoutReminders,
+    TokenCleanup,
+    AuditPurge,
+}
+
+impl From<JobNameName> for JobName {
+    fn from(value: JobNameName) -> Self {
+        match value {
+            JobNameName::CheckoutReminders => Self::CheckoutReminders,
+            JobNameName::TokenCleanup => Self::TokenCleanup,
+            JobNameName::AuditPurge => Self::AuditPurge,
+        }
+    }
+}
+
+impl From<JobName> for JobNameName {
+    fn from(value: JobName) -> Self {
+        match value {
+            JobName::CheckoutReminders => Self::CheckoutReminders,
+            JobName::TokenCleanup => Self::TokenCleanup,
+            JobName::AuditPurge => Self::AuditPurge,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobRunStatusName {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<JobRunStatusName> for JobRunStatus {
+    fn from(value: JobRunStatusName) -> Self {
+        match value {
+            JobRunStatusName::Running => Self::Running,
+            JobRunStatusName::Succeeded => Self::Succeeded,
+            JobRunStatusName::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<JobRunStatus> for JobRunStatusName {
+    fn from(value: JobRunStatus) -> Self {
+        match value {
+            JobRunStatus::Running => Self::Running,
+            JobRunStatus::Succeeded => Self::Succeeded,
+            JobRunStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct JobRunListQuery {
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+    // 以下は指定しない場合はすべてのジョブ・状態を返す
+    #[garde(skip)]
+    pub job: Option<JobNameName>,
+    #[garde(skip)]
+    pub status: Option<JobRunStatusName>,
+}
+const fn default_limit() -> i64 {
+    20
+}
+
+impl From<JobRunListQuery> for JobRunListOptions {
+    fn from(value: JobRunListQuery) -> Self {
+        let JobRunListQuery {
+            limit,
+            offset,
+            job,
+            status,
+        } = value;
+        Self {
+            limit,
+            offset,
+            job_name: job.map(JobName::from),
+            status: status.map(JobRunStatus::from),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedJobRunResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<JobRunResponse>,
+}
+
+impl From<PaginatedList<JobRun>> for PaginatedJobRunResponse {
+    fn from(value: PaginatedList<JobRun>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(JobRunResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRunResponse {
+    pub id: JobRunId,
+    pub job: JobNameName,
+    pub status: JobRunStatusName,
+    pub scheduled_at: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    // 送ったメールや削除した行など、ジョブが処理した件数
+    pub processed: Option<i64>,
+    // 一部の処理に失敗しても続けた場合の、失敗した件数
+    pub failed: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl From<JobRun> for JobRunResponse {
+    fn from(value: JobRun) -> Self {
+        let JobRun {
+            id,
+            job_name,
+            status,
+            scheduled_at,
+            started_at,
+            finished_at,
+            processed,
+            failed,
+            error,
+        } = value;
+        Self {
+            id,
+            job: job_name.into(),
+            status: status.into(),
+            scheduled_at,
+            started_at,
+            finished_at,
+            processed,
+            failed,
+            error,
+        }
+    }
+}
@@ -1,4 +1,21 @@
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod book_condition;
+pub mod book_transfer;
 pub mod checkout;
+pub mod cover;
+pub mod csv;
+pub mod date_range;
+pub mod event;
+pub mod genre;
+pub mod health;
+pub mod job;
+pub mod notification;
+pub mod recommendation;
+pub mod report;
+pub mod review;
+pub mod shelf;
+pub mod tag;
 pub mod user;
+pub mod webhook;
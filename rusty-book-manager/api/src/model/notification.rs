@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{BookId, NotificationId, UserId},
+    list::PaginatedList,
+    notification::{
+        event::UpdateNotificationPreferences, Notification, NotificationKind,
+        NotificationListOptions, NotificationPreference,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKindName {
+    BookCheckedOut,
+    BookReturned,
+    CheckoutDueSoon,
+    CheckoutOverdue,
+    AccountUpdated,
+    BookTransferOffered,
+    BookTransferDeclined,
+    BookTransferred,
+    BookConditionChanged,
+}
+
+impl From<NotificationKindName> for NotificationKind {
+    fn from(value: NotificationKindName) -> Self {
+        match value {
+            NotificationKindName::BookCheckedOut => Self::BookCheckedOut,
+            NotificationKindName::BookReturned => Self::BookReturned,
+            NotificationKindName::CheckoutDueSoon => Self::CheckoutDueSoon,
+            NotificationKindName::CheckoutOverdue => Self::CheckoutOverdue,
+            NotificationKindName::AccountUpdated => Self::AccountUpdated,
+            NotificationKindName::BookTransferOffered => Self::BookTransferOffered,
+            NotificationKindName::BookTransferDeclined => Self::BookTransferDeclined,
+            NotificationKindName::BookTransferred => Self::BookTransferred,
+            NotificationKindName::BookConditionChanged => Self::BookConditionChanged,
+        }
+    }
+}
+
+impl From<NotificationKind> for NotificationKindName {
+    fn from(value: NotificationKind) -> Self {
+        match value {
+            NotificationKind::BookCheckedOut => Self::BookCheckedOut,
+            NotificationKind::BookReturned => Self::BookReturned,
+            NotificationKind::CheckoutDueSoon => Self::CheckoutDueSoon,
+            NotificationKind::CheckoutOverdue => Self::CheckoutOverdue,
+            NotificationKind::AccountUpdated => Self::AccountUpdated,
+            NotificationKind::BookTransferOffered => Self::BookTransferOffered,
+            NotificationKind::BookTransferDeclined => Self::BookTransferDeclined,
+            NotificationKind::BookTransferred => Self::BookTransferred,
+            NotificationKind::BookConditionChanged => Self::BookConditionChanged,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct NotificationListQuery {
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+    // true の場合は未読の通知だけを返す
+    #[garde(skip)]
+    #[serde(default)]
+    pub unread: bool,
+}
+const fn default_limit() -> i64 {
+    20
+}
+
+impl From<NotificationListQuery> for NotificationListOptions {
+    fn from(value: NotificationListQuery) -> Self {
+        let NotificationListQuery {
+            limit,
+            offset,
+            unread,
+        } = value;
+        Self {
+            limit,
+            offset,
+            unread_only: unread,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedNotificationResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    // 絞り込みにかかわらず、利用者の未読の通知の数
+    pub unread_count: i64,
+    pub items: Vec<NotificationResponse>,
+}
+
+impl PaginatedNotificationResponse {
+    pub fn new(list: PaginatedList<Notification>, unread_count: i64) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = list;
+        Self {
+            total,
+            limit,
+            offset,
+            unread_count,
+            items: items.into_iter().map(NotificationResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationResponse {
+    pub id: NotificationId,
+    pub kind: NotificationKindName,
+    pub title: String,
+    pub body: String,
+    pub book_id: Option<BookId>,
+    // 未読の場合は None
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Notification> for NotificationResponse {
+    fn from(value: Notification) -> Self {
+        let Notification {
+            id,
+            user_id: _,
+            kind,
+            title,
+            body,
+            book_id,
+            read_at,
+            created_at,
+        } = value;
+        Self {
+            id,
+            kind: kind.into(),
+            title,
+            body,
+            book_id,
+            read_at,
+            created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferenceBody {
+    pub kind: NotificationKindName,
+    // 受信箱に加えてメールでも受け取るかどうか
+    pub email: bool,
+}
+
+impl From<NotificationPreference> for NotificationPreferenceBody {
+    fn from(value: NotificationPreference) -> Self {
+        let NotificationPreference { kind, email } = value;
+        Self {
+            kind: kind.into(),
+            email,
+        }
+    }
+}
+
+impl From<NotificationPreferenceBody> for NotificationPreference {
+    fn from(value: NotificationPreferenceBody) -> Self {
+        let NotificationPreferenceBody { kind, email } = value;
+        Self {
+            kind: kind.into(),
+            email,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferencesResponse {
+    pub items: Vec<NotificationPreferenceBody>,
+}
+
+impl From<Vec<NotificationPreference>> for NotificationPreferencesResponse {
+    fn from(value: Vec<NotificationPreference>) -> Self {
+        Self {
+            items: value
+                .into_iter()
+                .map(NotificationPreferenceBody::from)
+                .collect(),
+        }
+    }
+}
+
+// 指定しなかった種類の設定は変えない
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationPreferencesRequest {
+    pub items: Vec<NotificationPreferenceBody>,
+}
+
+#[derive(new)]
+pub struct UpdateNotificationPreferencesRequestWithUserId(
+    UserId,
+    UpdateNotificationPreferencesRequest,
+);
+impl From<UpdateNotificationPreferencesRequestWithUserId> for UpdateNotificationPreferences {
+    fn from(value: UpdateNotificationPreferencesRequestWithUserId) -> Self {
+        let UpdateNotificationPreferencesRequestWithUserId(
+            user_id,
+            UpdateNotificationPreferencesRequest { items },
+        ) = value;
+        Self {
+            user_id,
+            preferences: items
+                .into_iter()
+                .map(NotificationPreference::from)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use garde::Validate;
+use kernel::model::{id::BookId, recommendation::Recommendation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecommendationQuery {
+    #[garde(range(min = 1, max = 50))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+const fn default_limit() -> i64 {
+    10
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationsResponse {
+    pub items: Vec<RecommendationResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationResponse {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub score: i64,
+    // 以下は推薦の根拠。クライアントで「○○を借りた人が借りています」などと表示できる
+    pub co_checkout_count: i64,
+    pub shared_author_count: i64,
+    pub shared_tag_count: i64,
+}
+
+impl From<Recommendation> for RecommendationResponse {
+    fn from(value: Recommendation) -> Self {
+        let Recommendation {
+            book_id,
+            title,
+            author,
+            isbn,
+            score,
+            co_checkout_count,
+            shared_author_count,
+            shared_tag_count,
+        } = value;
+        Self {
+            book_id,
+            title,
+            author,
+            isbn,
+            score,
+            co_checkout_count,
+            shared_author_count,
+            shared_tag_count,
+        }
+    }
+}
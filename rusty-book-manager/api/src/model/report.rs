@@ -0,0 +1,330 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use garde::Validate;
+use kernel::model::{
+    id::{AuthorId, BookId, UserId},
+    report::{
+        AuthorCheckoutRanking, BookCheckoutRanking, BorrowerRanking, CheckoutCount, Granularity,
+        IdleBook, LoanDuration, ReportPeriod,
+    },
+};
+use serde::{Deserialize, Serialize};
+use shared::error::AppResult;
+
+use super::{
+    csv::{CsvRecord, CsvResponse},
+    date_range::{is_not_before, utc_range},
+};
+
+// レポートの出力形式。format=csv で CSV としてダウンロードできる
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GranularityName {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+impl From<GranularityName> for Granularity {
+    fn from(value: GranularityName) -> Self {
+        match value {
+            GranularityName::Day => Self::Day,
+            GranularityName::Week => Self::Week,
+            GranularityName::Month => Self::Month,
+        }
+    }
+}
+
+// from・to は日付（UTC）で、いずれも指定した日を含む
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReportPeriodQuery {
+    #[garde(skip)]
+    pub from: Option<NaiveDate>,
+    #[garde(custom(is_not_before(&self.from)))]
+    pub to: Option<NaiveDate>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CheckoutCountQuery {
+    #[garde(skip)]
+    pub from: Option<NaiveDate>,
+    #[garde(custom(is_not_before(&self.from)))]
+    pub to: Option<NaiveDate>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub granularity: GranularityName,
+    #[garde(skip)]
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+// ランキングなど件数を絞るレポートのクエリ
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReportRankingQuery {
+    #[garde(skip)]
+    pub from: Option<NaiveDate>,
+    #[garde(custom(is_not_before(&self.from)))]
+    pub to: Option<NaiveDate>,
+    #[garde(range(min = 1, max = 100))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(skip)]
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+const fn default_limit() -> i64 {
+    10
+}
+
+pub fn report_period(from: Option<NaiveDate>, to: Option<NaiveDate>) -> ReportPeriod {
+    let (from, to) = utc_range(from, to);
+    ReportPeriod { from, to }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportItemsResponse<T> {
+    pub items: Vec<T>,
+}
+
+// 指定された形式で一覧のレポートを返す
+pub fn report_items<T, R>(
+    format: ReportFormat,
+    filename: &'static str,
+    items: Vec<T>,
+) -> AppResult<Response>
+where
+    R: Serialize + CsvRecord + From<T>,
+{
+    let items = items.into_iter().map(R::from).collect::<Vec<_>>();
+    Ok(match format {
+        ReportFormat::Json => Json(ReportItemsResponse { items }).into_response(),
+        ReportFormat::Csv => CsvResponse::from_records(filename, items)?.into_response(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutCountResponse {
+    pub period_start: DateTime<Utc>,
+    pub count: i64,
+}
+
+impl From<CheckoutCount> for CheckoutCountResponse {
+    fn from(value: CheckoutCount) -> Self {
+        let CheckoutCount {
+            period_start,
+            count,
+        } = value;
+        Self {
+            period_start,
+            count,
+        }
+    }
+}
+
+impl CsvRecord for CheckoutCountResponse {
+    const HEADER: &'static [&'static str] = &["period_start", "count"];
+
+    fn into_record(self) -> Vec<String> {
+        vec![
+            self.period_start.date_naive().to_string(),
+            self.count.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookRankingResponse {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub checkout_count: i64,
+}
+
+impl From<BookCheckoutRanking> for BookRankingResponse {
+    fn from(value: BookCheckoutRanking) -> Self {
+        let BookCheckoutRanking {
+            book_id,
+            title,
+            author,
+            checkout_count,
+        } = value;
+        Self {
+            book_id,
+            title,
+            author,
+            checkout_count,
+        }
+    }
+}
+
+impl CsvRecord for BookRankingResponse {
+    const HEADER: &'static [&'static str] = &["book_id", "title", "author", "checkout_count"];
+
+    fn into_record(self) -> Vec<String> {
+        vec![
+            self.book_id.to_string(),
+            self.title,
+            self.author,
+            self.checkout_count.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorRankingResponse {
+    pub author_id: AuthorId,
+    pub name: String,
+    pub checkout_count: i64,
+}
+
+impl From<AuthorCheckoutRanking> for AuthorRankingResponse {
+    fn from(value: AuthorCheckoutRanking) -> Self {
+        let AuthorCheckoutRanking {
+            author_id,
+            name,
+            checkout_count,
+        } = value;
+        Self {
+            author_id,
+            name,
+            checkout_count,
+        }
+    }
+}
+
+impl CsvRecord for AuthorRankingResponse {
+    const HEADER: &'static [&'static str] = &["author_id", "name", "checkout_count"];
+
+    fn into_record(self) -> Vec<String> {
+        vec![
+            self.author_id.to_string(),
+            self.name,
+            self.checkout_count.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BorrowerRankingResponse {
+    pub user_id: UserId,
+    pub name: String,
+    pub checkout_count: i64,
+}
+
+impl From<BorrowerRanking> for BorrowerRankingResponse {
+    fn from(value: BorrowerRanking) -> Self {
+        let BorrowerRanking {
+            user_id,
+            name,
+            checkout_count,
+        } = value;
+        Self {
+            user_id,
+            name,
+            checkout_count,
+        }
+    }
+}
+
+impl CsvRecord for BorrowerRankingResponse {
+    const HEADER: &'static [&'static str] = &["user_id", "name", "checkout_count"];
+
+    fn into_record(self) -> Vec<String> {
+        vec![
+            self.user_id.to_string(),
+            self.name,
+            self.checkout_count.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanDurationResponse {
+    pub returned_count: i64,
+    pub average_days: Option<f64>,
+}
+
+impl From<LoanDuration> for LoanDurationResponse {
+    fn from(value: LoanDuration) -> Self {
+        let LoanDuration {
+            returned_count,
+            average_days,
+        } = value;
+        Self {
+            returned_count,
+            average_days,
+        }
+    }
+}
+
+impl CsvRecord for LoanDurationResponse {
+    const HEADER: &'static [&'static str] = &["returned_count", "average_days"];
+
+    fn into_record(self) -> Vec<String> {
+        vec![
+            self.returned_count.to_string(),
+            self.average_days
+                .map(|d| format!("{d:.2}"))
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleBookResponse {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub registered_at: DateTime<Utc>,
+}
+
+impl From<IdleBook> for IdleBookResponse {
+    fn from(value: IdleBook) -> Self {
+        let IdleBook {
+            book_id,
+            title,
+            author,
+            registered_at,
+        } = value;
+        Self {
+            book_id,
+            title,
+            author,
+            registered_at,
+        }
+    }
+}
+
+impl CsvRecord for IdleBookResponse {
+    const HEADER: &'static [&'static str] = &["book_id", "title", "author", "registered_at"];
+
+    fn into_record(self) -> Vec<String> {
+        vec![
+            self.book_id.to_string(),
+            self.title,
+            self.author,
+            self.registered_at.to_rfc3339(),
+        ]
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{BookId, ReviewId, UserId},
+    review::{
+        event::{CreateReview, UpdateReview},
+        Review,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::user::ReviewUser;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewsResponse {
+    pub items: Vec<ReviewResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewResponse {
+    pub id: ReviewId,
+    pub book_id: BookId,
+    pub reviewed_by: ReviewUser,
+    pub rating: i16,
+    pub body: String,
+    pub hidden: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Review> for ReviewResponse {
+    fn from(value: Review) -> Self {
+        let Review {
+            id,
+            book_id,
+            reviewed_by,
+            rating,
+            body,
+            hidden,
+            created_at,
+            updated_at,
+        } = value;
+        Self {
+            id,
+            book_id,
+            reviewed_by: reviewed_by.into(),
+            rating,
+            body,
+            hidden,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+// 投稿・編集で共通のリクエスト。本文は省略でき、星の数だけの評価も受け付ける
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewRequest {
+    #[garde(range(min = 1, max = 5))]
+    pub rating: i16,
+    #[garde(length(max = 4000))]
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(new)]
+pub struct CreateReviewRequestWithIds(BookId, UserId, ReviewRequest);
+impl From<CreateReviewRequestWithIds> for CreateReview {
+    fn from(value: CreateReviewRequestWithIds) -> Self {
+        let CreateReviewRequestWithIds(book_id, reviewed_by, ReviewRequest { rating, body }) =
+            value;
+        Self {
+            book_id,
+            reviewed_by,
+            rating,
+            body,
+        }
+    }
+}
+
+#[derive(new)]
+pub struct UpdateReviewRequestWithIds(ReviewId, BookId, UserId, ReviewRequest);
+impl From<UpdateReviewRequestWithIds> for UpdateReview {
+    fn from(value: UpdateReviewRequestWithIds) -> Self {
+        let UpdateReviewRequestWithIds(
+            review_id,
+            book_id,
+            requested_user,
+            ReviewRequest { rating, body },
+        ) = value;
+        Self {
+            review_id,
+            book_id,
+            requested_user,
+            rating,
+            body,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewVisibilityRequest {
+    pub hidden: bool,
+}
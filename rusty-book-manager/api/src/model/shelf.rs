@@ -0,0 +1,52 @@
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{ShelfId, UserId},
+    shelf::{event::CreateShelf, Shelf},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShelvesResponse {
+    pub items: Vec<ShelfResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShelfResponse {
+    pub id: ShelfId,
+    pub name: String,
+    pub book_count: i64,
+}
+
+impl From<Shelf> for ShelfResponse {
+    fn from(value: Shelf) -> Self {
+        let Shelf {
+            id,
+            name,
+            book_count,
+        } = value;
+        Self {
+            id,
+            name,
+            book_count,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShelfRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct CreateShelfRequestWithUserId(UserId, CreateShelfRequest);
+impl From<CreateShelfRequestWithUserId> for CreateShelf {
+    fn from(value: CreateShelfRequestWithUserId) -> Self {
+        let CreateShelfRequestWithUserId(user_id, CreateShelfRequest { name }) = value;
+        Self { user_id, name }
+    }
+}
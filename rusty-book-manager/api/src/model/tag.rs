@@ -0,0 +1,65 @@
+use garde::Validate;
+use kernel::model::{
+    id::BookId,
+    tag::{
+        event::{AddBookTag, RemoveBookTag},
+        normalize_tag, Tag,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagsResponse {
+    pub items: Vec<TagResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagResponse {
+    pub name: String,
+    pub book_count: i64,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(value: Tag) -> Self {
+        let Tag { name, book_count } = value;
+        Self { name, book_count }
+    }
+}
+
+// /books/:book_id/tags/:tag のパスパラメータ
+#[derive(Deserialize, Validate)]
+pub struct BookTagPath {
+    #[garde(skip)]
+    pub book_id: BookId,
+    #[garde(custom(is_tag))]
+    pub tag: String,
+}
+
+pub(crate) fn is_tag(value: &str, _: &()) -> garde::Result {
+    normalize_tag(value)
+        .map(|_| ())
+        .ok_or_else(|| garde::Error::new("tag must consist of letters, digits and -_+#."))
+}
+
+// バリデーション済みであることを前提に、タグ名を正規化して渡す
+impl From<BookTagPath> for AddBookTag {
+    fn from(value: BookTagPath) -> Self {
+        let BookTagPath { book_id, tag } = value;
+        Self {
+            book_id,
+            name: normalize_tag(&tag).unwrap_or(tag),
+        }
+    }
+}
+
+impl From<BookTagPath> for RemoveBookTag {
+    fn from(value: BookTagPath) -> Self {
+        let BookTagPath { book_id, tag } = value;
+        Self {
+            book_id,
+            name: normalize_tag(&tag).unwrap_or(tag),
+        }
+    }
+}
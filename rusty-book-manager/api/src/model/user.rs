@@ -9,8 +9,11 @@ use kernel::model::{
     },
 };
 use serde::{Deserialize, Serialize};
+use shared::error::AppError;
 use strum::VariantNames;
 
+use super::book::{nullable, required};
+
 #[derive(Serialize, Deserialize, VariantNames)]
 #[strum(serialize_all = "kebab-case")]
 pub enum RoleName {
@@ -122,6 +125,43 @@ impl From<CreateUserRequest> for CreateUser {
     }
 }
 
+// JSON Merge Patch の本体。指定した項目だけを変更する。メールアドレスは確認が済むまで反映しない
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCurrentUserRequest {
+    #[serde(default, deserialize_with = "nullable")]
+    pub name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub email: Option<Option<String>>,
+}
+
+// 名前・メールアドレスは null で削除できない
+#[derive(Debug, Validate)]
+pub struct CurrentUserPatch {
+    #[garde(length(min = 1))]
+    pub name: Option<String>,
+    #[garde(email)]
+    pub email: Option<String>,
+}
+impl TryFrom<UpdateCurrentUserRequest> for CurrentUserPatch {
+    type Error = AppError;
+
+    fn try_from(value: UpdateCurrentUserRequest) -> Result<Self, Self::Error> {
+        let UpdateCurrentUserRequest { name, email } = value;
+        Ok(Self {
+            name: required(name, "name")?,
+            email: required(email, "email")?,
+        })
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailRequest {
+    #[garde(length(min = 1))]
+    pub token: String,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleRequest {
@@ -167,3 +207,31 @@ impl From<kernel::model::user::CheckoutUser> for CheckoutUser {
         Self { id, name }
     }
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferUser {
+    pub id: Option<UserId>,
+    pub name: String,
+}
+
+impl From<kernel::model::user::TransferUser> for TransferUser {
+    fn from(value: kernel::model::user::TransferUser) -> Self {
+        let kernel::model::user::TransferUser { id, name } = value;
+        Self { id, name }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewUser {
+    pub id: UserId,
+    pub name: String,
+}
+
+impl From<kernel::model::user::ReviewUser> for ReviewUser {
+    fn from(value: kernel::model::user::ReviewUser) -> Self {
+        let kernel::model::user::ReviewUser { id, name } = value;
+        Self { id, name }
+    }
+}
@@ -0,0 +1,283 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    id::{WebhookDeliveryId, WebhookSubscriptionId},
+    list::PaginatedList,
+    webhook::{
+        event::CreateWebhookSubscription, is_public_address, WebhookDelivery,
+        WebhookDeliveryListOptions, WebhookDeliveryStatus, WebhookEventType, WebhookSubscription,
+    },
+};
+use serde::{Deserialize, Serialize};
+use url::{Host, Url};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum WebhookEventTypeName {
+    #[serde(rename = "book.created")]
+    BookCreated,
+    #[serde(rename = "book.updated")]
+    BookUpdated,
+    #[serde(rename = "checkout.created")]
+    CheckoutCreated,
+    #[serde(rename = "checkout.returned")]
+    CheckoutReturned,
+    #[serde(rename = "user.created")]
+    UserCreated,
+}
+
+impl From<WebhookEventTypeName> for WebhookEventType {
+    fn from(value: WebhookEventTypeName) -> Self {
+        match value {
+            WebhookEventTypeName::BookCreated => Self::BookCreated,
+            WebhookEventTypeName::BookUpdated => Self::BookUpdated,
+            WebhookEventTypeName::CheckoutCreated => Self::CheckoutCreated,
+            WebhookEventTypeName::CheckoutReturned => Self::CheckoutReturned,
+            WebhookEventTypeName::UserCreated => Self::UserCreated,
+        }
+    }
+}
+
+impl From<WebhookEventType> for WebhookEventTypeName {
+    fn from(value: WebhookEventType) -> Self {
+        match value {
+            WebhookEventType::BookCreated => Self::BookCreated,
+            WebhookEventType::BookUpdated => Self::BookUpdated,
+            WebhookEventType::CheckoutCreated => Self::CheckoutCreated,
+            WebhookEventType::CheckoutReturned => Self::CheckoutReturned,
+            WebhookEventType::UserCreated => Self::UserCreated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatusName {
+    Pending,
+    Succeeded,
+    Dead,
+}
+
+impl From<WebhookDeliveryStatusName> for WebhookDeliveryStatus {
+    fn from(value: WebhookDeliveryStatusName) -> Self {
+        match value {
+            WebhookDeliveryStatusName::Pending => Self::Pending,
+            WebhookDeliveryStatusName::Succeeded => Self::Succeeded,
+            WebhookDeliveryStatusName::Dead => Self::Dead,
+        }
+    }
+}
+
+impl From<WebhookDeliveryStatus> for WebhookDeliveryStatusName {
+    fn from(value: WebhookDeliveryStatus) -> Self {
+        match value {
+            WebhookDeliveryStatus::Pending => Self::Pending,
+            WebhookDeliveryStatus::Succeeded => Self::Succeeded,
+            WebhookDeliveryStatus::Dead => Self::Dead,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookSubscriptionRequest {
+    #[garde(custom(is_webhook_url))]
+    pub url: String,
+    // ペイロードの署名に使う、受け取る側と共有する鍵
+    #[garde(length(min = 16, max = 256))]
+    pub secret: String,
+    #[garde(length(min = 1))]
+    pub event_types: Vec<WebhookEventTypeName>,
+}
+
+pub(crate) fn is_http_url(value: &str, _: &()) -> garde::Result {
+    let valid = (value.starts_with("http://") || value.starts_with("https://"))
+        && !value.contains(char::is_whitespace)
+        && value.len() <= 2048;
+    if !valid {
+        return Err(garde::Error::new("must be an http:// or https:// URL"));
+    }
+    Ok(())
+}
+
+// 宛先がループバックやプライベートのアドレスを直接指す URL は受け付けない。
+// ホスト名の解決結果は送信時に確かめる
+fn is_webhook_url(value: &str, ctx: &()) -> garde::Result {
+    is_http_url(value, ctx)?;
+    let url = Url::parse(value).map_err(|_| garde::Error::new("must be a valid URL"))?;
+    let public = match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_public_address(ip.into()),
+        Some(Host::Ipv6(ip)) => is_public_address(ip.into()),
+        None => false,
+    };
+    if !public {
+        return Err(garde::Error::new("must not point to a private address"));
+    }
+    Ok(())
+}
+
+impl From<CreateWebhookSubscriptionRequest> for CreateWebhookSubscription {
+    fn from(value: CreateWebhookSubscriptionRequest) -> Self {
+        let CreateWebhookSubscriptionRequest {
+            url,
+            secret,
+            mut event_types,
+        } = value;
+        // 同じイベントの重複は 1 つにまとめる
+        event_types.sort();
+        event_types.dedup();
+        Self {
+            url,
+            secret,
+            event_types: event_types
+                .into_iter()
+                .map(WebhookEventType::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptionsResponse {
+    pub items: Vec<WebhookSubscriptionResponse>,
+}
+
+// 共有鍵は返さない
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptionResponse {
+    pub id: WebhookSubscriptionId,
+    pub url: String,
+    pub event_types: Vec<WebhookEventTypeName>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookSubscription> for WebhookSubscriptionResponse {
+    fn from(value: WebhookSubscription) -> Self {
+        let WebhookSubscription {
+            id,
+            url,
+            event_types,
+            created_at,
+        } = value;
+        Self {
+            id,
+            url,
+            event_types: event_types
+                .into_iter()
+                .map(WebhookEventTypeName::from)
+                .collect(),
+            created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct WebhookDeliveryListQuery {
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+    // 指定しない場合はすべての状態を返す
+    #[garde(skip)]
+    pub status: Option<WebhookDeliveryStatusName>,
+}
+const fn default_limit() -> i64 {
+    20
+}
+
+impl From<WebhookDeliveryListQuery> for WebhookDeliveryListOptions {
+    fn from(value: WebhookDeliveryListQuery) -> Self {
+        let WebhookDeliveryListQuery {
+            limit,
+            offset,
+            status,
+        } = value;
+        Self {
+            limit,
+            offset,
+            status: status.map(WebhookDeliveryStatus::from),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedWebhookDeliveryResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<WebhookDeliveryResponse>,
+}
+
+impl From<PaginatedList<WebhookDelivery>> for PaginatedWebhookDeliveryResponse {
+    fn from(value: PaginatedList<WebhookDelivery>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items
+                .into_iter()
+                .map(WebhookDeliveryResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResponse {
+    pub id: WebhookDeliveryId,
+    pub subscription_id: WebhookSubscriptionId,
+    pub event_type: WebhookEventTypeName,
+    // 購読先に送る JSON
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatusName,
+    pub attempts: i32,
+    // 次に送る予定の日時。送信待ちの場合のみ意味を持つ
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(value: WebhookDelivery) -> Self {
+        let WebhookDelivery {
+            id,
+            subscription_id,
+            event_type,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            last_error,
+            delivered_at,
+            created_at,
+        } = value;
+        Self {
+            id,
+            subscription_id,
+            event_type: event_type.into(),
+            payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::String(payload)),
+            status: status.into(),
+            attempts,
+            next_attempt_at,
+            last_error,
+            delivered_at,
+            created_at,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::author::{list_authors, show_author};
+
+pub fn build_author_routes() -> Router<AppRegistry> {
+    let author_router = Router::new()
+        .route("/", get(list_authors))
+        .route("/:author_id", get(show_author));
+    Router::new().nest("/authors", author_router)
+}
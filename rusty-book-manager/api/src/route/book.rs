@@ -1,30 +1,134 @@
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{get, post, put},
     Router,
 };
 use registry::AppRegistry;
 
-use crate::handler::book::{delete_book, register_book, show_book, show_book_list, update_book};
+use crate::handler::book::{
+    delete_book, lookup_book, patch_book, register_book, show_book, show_book_list, update_book,
+};
+use crate::handler::book_condition::{
+    mark_book_found, show_condition_history, update_book_condition, update_condition_charge,
+};
+use crate::handler::book_transfer::{
+    accept_book_transfer, cancel_book_transfer, decline_book_transfer, force_book_transfer,
+    offer_book_transfer, show_owner_history,
+};
 use crate::handler::checkout::{
-    checkout_book, checkout_history, return_book, show_checked_out_list,
+    checkout_book, checkout_book_on_behalf, checkout_history, force_return_book, report_lost_book,
+    return_book, return_damaged_book, show_checked_out_list,
 };
+use crate::handler::cover::{show_cover, show_cover_thumbnail, upload_cover};
+use crate::handler::genre::{add_book_genre, remove_book_genre};
+use crate::handler::review::{
+    create_review, delete_review, list_reviews, update_review, update_review_visibility,
+};
+use crate::handler::tag::{add_book_tag, remove_book_tag};
+use crate::model::cover::MAX_COVER_SIZE;
 
 pub fn build_book_routes() -> Router<AppRegistry> {
     let books_routers = Router::new()
         .route("/", get(show_book_list).post(register_book))
+        .route("/lookup", get(lookup_book))
         .route(
             "/:book_id",
-            get(show_book).put(update_book).delete(delete_book),
+            get(show_book)
+                .put(update_book)
+                .patch(patch_book)
+                .delete(delete_book),
+        );
+
+    // multipart の境界やヘッダーの分だけ、画像の上限より余裕を持たせる
+    let cover_router = Router::new()
+        .route(
+            "/:book_id/cover",
+            get(show_cover)
+                .put(upload_cover)
+                .layer(DefaultBodyLimit::max(MAX_COVER_SIZE + 64 * 1024)),
+        )
+        .route("/:book_id/cover/thumbnail", get(show_cover_thumbnail));
+
+    let classification_router = Router::new()
+        .route(
+            "/:book_id/tags/:tag",
+            put(add_book_tag).delete(remove_book_tag),
+        )
+        .route(
+            "/:book_id/genres/:genre_id",
+            put(add_book_genre).delete(remove_book_genre),
         );
 
     let checkout_router = Router::new()
         .route("/checkouts", get(show_checked_out_list))
         .route("/:book_id/checkouts", post(checkout_book))
+        .route(
+            "/:book_id/checkouts/on-behalf",
+            post(checkout_book_on_behalf),
+        )
         .route(
             "/:book_id/checkouts/:checkout_id/returned",
             put(return_book),
         )
+        .route(
+            "/:book_id/checkouts/:checkout_id/force-returned",
+            put(force_return_book),
+        )
+        .route(
+            "/:book_id/checkouts/:checkout_id/damaged",
+            put(return_damaged_book),
+        )
+        .route(
+            "/:book_id/checkouts/:checkout_id/lost",
+            put(report_lost_book),
+        )
         .route("/:book_id/checkout-history", get(checkout_history));
 
-    Router::new().nest("/books", books_routers.merge(checkout_router))
+    let transfer_router = Router::new()
+        .route("/:book_id/transfers", post(offer_book_transfer))
+        .route("/:book_id/transfers/force", post(force_book_transfer))
+        .route(
+            "/:book_id/transfers/:book_transfer_id/accepted",
+            put(accept_book_transfer),
+        )
+        .route(
+            "/:book_id/transfers/:book_transfer_id/declined",
+            put(decline_book_transfer),
+        )
+        .route(
+            "/:book_id/transfers/:book_transfer_id/cancelled",
+            put(cancel_book_transfer),
+        )
+        .route("/:book_id/owner-history", get(show_owner_history));
+
+    let condition_router = Router::new()
+        .route("/:book_id/found", put(mark_book_found))
+        .route("/:book_id/condition", put(update_book_condition))
+        .route("/:book_id/condition-history", get(show_condition_history))
+        .route(
+            "/:book_id/condition-history/:book_condition_id/charge",
+            put(update_condition_charge),
+        );
+
+    let review_router = Router::new()
+        .route("/:book_id/reviews", get(list_reviews).post(create_review))
+        .route(
+            "/:book_id/reviews/:review_id",
+            put(update_review).delete(delete_review),
+        )
+        .route(
+            "/:book_id/reviews/:review_id/visibility",
+            put(update_review_visibility),
+        );
+
+    Router::new().nest(
+        "/books",
+        books_routers
+            .merge(cover_router)
+            .merge(classification_router)
+            .merge(checkout_router)
+            .merge(transfer_router)
+            .merge(condition_router)
+            .merge(review_router),
+    )
 }
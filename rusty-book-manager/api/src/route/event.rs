@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::event::stream_events;
+
+pub fn build_event_routes() -> Router<AppRegistry> {
+    Router::new().route("/events", get(stream_events))
+}
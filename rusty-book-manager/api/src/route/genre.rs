@@ -0,0 +1,14 @@
+use axum::{
+    routing::{delete, get},
+    Router,
+};
+use registry::AppRegistry;
+
+use crate::handler::genre::{create_genre, delete_genre, list_genres};
+
+pub fn build_genre_routes() -> Router<AppRegistry> {
+    let genre_router = Router::new()
+        .route("/", get(list_genres).post(create_genre))
+        .route("/:genre_id", delete(delete_genre));
+    Router::new().nest("/genres", genre_router)
+}
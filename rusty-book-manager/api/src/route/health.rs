@@ -1,11 +1,15 @@
 use axum::{routing::get, Router};
 use registry::AppRegistry;
 
-use crate::handler::health::{health_check, health_check_db};
+use crate::handler::health::{
+    health_check, health_check_db, health_check_live, health_check_ready,
+};
 
 pub fn build_health_check_routes() -> Router<AppRegistry> {
     let routers = Router::new()
         .route("/", get(health_check))
-        .route("/db", get(health_check_db));
+        .route("/db", get(health_check_db))
+        .route("/live", get(health_check_live))
+        .route("/ready", get(health_check_ready));
     Router::new().nest("/health", routers)
 }
@@ -0,0 +1,9 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::job::list_job_runs;
+
+pub fn build_job_routes() -> Router<AppRegistry> {
+    let job_router = Router::new().route("/runs", get(list_job_runs));
+    Router::new().nest("/jobs", job_router)
+}
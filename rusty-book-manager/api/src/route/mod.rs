@@ -1,5 +1,12 @@
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod event;
+pub mod genre;
 pub mod health;
+pub mod job;
+pub mod report;
+pub mod tag;
 pub mod user;
 pub mod v1;
+pub mod webhook;
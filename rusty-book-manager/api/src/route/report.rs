@@ -0,0 +1,17 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::report::{
+    checkout_counts, idle_books, loan_duration, popular_authors, popular_books, top_borrowers,
+};
+
+pub fn build_report_routes() -> Router<AppRegistry> {
+    let report_router = Router::new()
+        .route("/checkouts", get(checkout_counts))
+        .route("/popular-books", get(popular_books))
+        .route("/popular-authors", get(popular_authors))
+        .route("/top-borrowers", get(top_borrowers))
+        .route("/idle-books", get(idle_books))
+        .route("/loan-duration", get(loan_duration));
+    Router::new().nest("/reports", report_router)
+}
@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::tag::list_tags;
+
+pub fn build_tag_routes() -> Router<AppRegistry> {
+    Router::new().route("/tags", get(list_tags))
+}
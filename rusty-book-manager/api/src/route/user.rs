@@ -1,20 +1,56 @@
+use crate::handler::book_transfer::list_book_transfers;
+use crate::handler::notification::{
+    get_notification_preferences, list_notifications, mark_all_notifications_read,
+    mark_notification_read, update_notification_preferences,
+};
+use crate::handler::recommendation::get_recommendations;
+use crate::handler::shelf::{
+    add_shelf_book, create_shelf, delete_shelf, list_shelves, remove_shelf_book,
+};
 use crate::handler::user::{
-    change_password, change_role, delete_user, get_checkouts, get_current_user, list_users,
-    register_user,
+    change_password, change_role, delete_user, export_checkout_history,
+    export_user_checkout_history, get_checkout_history, get_checkouts, get_current_user,
+    get_user_checkout_history, list_users, register_user, update_current_user, verify_email,
 };
 use axum::{
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
     Router,
 };
 use registry::AppRegistry;
 
 pub fn build_user_router() -> Router<AppRegistry> {
     let user_router = Router::new()
-        .route("/me", get(get_current_user))
+        .route("/me", get(get_current_user).patch(update_current_user))
+        .route("/me/email/verify", post(verify_email))
         .route("/me/password", put(change_password))
         .route("/me/checkouts", get(get_checkouts))
+        .route("/me/checkout-history", get(get_checkout_history))
+        .route("/me/checkout-history.csv", get(export_checkout_history))
+        .route("/me/recommendations", get(get_recommendations))
+        .route("/me/book-transfers", get(list_book_transfers))
+        .route("/me/notifications", get(list_notifications))
+        .route("/me/notifications/read", put(mark_all_notifications_read))
+        .route(
+            "/me/notifications/:notification_id/read",
+            put(mark_notification_read),
+        )
+        .route(
+            "/me/notifications/preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/me/shelves", get(list_shelves).post(create_shelf))
+        .route("/me/shelves/:shelf_id", delete(delete_shelf))
+        .route(
+            "/me/shelves/:shelf_id/books/:book_id",
+            put(add_shelf_book).delete(remove_shelf_book),
+        )
         .route("/", get(list_users).post(register_user))
         .route("/:user_id", delete(delete_user))
-        .route("/:user_id/role", put(change_role));
+        .route("/:user_id/role", put(change_role))
+        .route("/:user_id/checkout-history", get(get_user_checkout_history))
+        .route(
+            "/:user_id/checkout-history.csv",
+            get(export_user_checkout_history),
+        );
     Router::new().nest("/users", user_router)
 }
@@ -1,11 +1,23 @@
-use super::{book::build_book_routes, health::build_health_check_routes, user::build_user_router};
+use super::{
+    author::build_author_routes, book::build_book_routes, event::build_event_routes,
+    genre::build_genre_routes, health::build_health_check_routes, job::build_job_routes,
+    report::build_report_routes, tag::build_tag_routes, user::build_user_router,
+    webhook::build_webhook_routes,
+};
 use axum::Router;
 use registry::AppRegistry;
 
 pub fn routes() -> Router<AppRegistry> {
     let router = Router::new()
         .merge(build_book_routes())
+        .merge(build_author_routes())
+        .merge(build_tag_routes())
+        .merge(build_genre_routes())
         .merge(build_health_check_routes())
+        .merge(build_report_routes())
+        .merge(build_webhook_routes())
+        .merge(build_event_routes())
+        .merge(build_job_routes())
         .merge(build_user_router());
     Router::new().nest("/api/v1", router)
 }
@@ -0,0 +1,25 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+use registry::AppRegistry;
+
+use crate::handler::webhook::{
+    create_webhook_subscription, delete_webhook_subscription, list_webhook_deliveries,
+    list_webhook_subscriptions, redeliver_webhook,
+};
+
+pub fn build_webhook_routes() -> Router<AppRegistry> {
+    let webhook_router = Router::new()
+        .route(
+            "/",
+            get(list_webhook_subscriptions).post(create_webhook_subscription),
+        )
+        .route("/:subscription_id", delete(delete_webhook_subscription))
+        .route("/deliveries", get(list_webhook_deliveries))
+        .route(
+            "/deliveries/:delivery_id/redeliver",
+            post(redeliver_webhook),
+        );
+    Router::new().nest("/webhooks", webhook_router)
+}
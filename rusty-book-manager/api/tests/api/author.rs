@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use api::model::author::{AuthorDetailResponse, AuthorRoleName};
+use kernel::{
+    model::{
+        author::{AuthorBook, AuthorDetail, AuthorRole},
+        id::{AuthorId, BookId},
+    },
+    repository::author::MockAuthorRepository,
+};
+
+#[rstest]
+#[case(true, StatusCode::OK)]
+#[case(false, StatusCode::NOT_FOUND)]
+#[tokio::test]
+async fn show_author_with_books(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] found: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let author_id = AuthorId::new();
+    fixture.expect_author_repository().returning(move || {
+        let mut mock = MockAuthorRepository::new();
+        mock.expect_find_by_id().returning(move |id| {
+            Ok(found.then(|| AuthorDetail {
+                id,
+                name: "尾崎亮太".into(),
+                books: vec![AuthorBook {
+                    book_id: BookId::new(),
+                    title: "プログラミングRust".into(),
+                    role: AuthorRole::Translator,
+                    publication_year: Some(2022),
+                }],
+            }))
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1(&format!("/authors/{author_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    if found {
+        let author = deserialize_json!(resp, AuthorDetailResponse);
+        assert_eq!(author.id, author_id);
+        assert_eq!(author.books.len(), 1);
+        assert_eq!(author.books[0].role, AuthorRoleName::Translator);
+        assert_eq!(author.books[0].publication_year, Some(2022));
+    }
+    Ok(())
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    http::{header, Request, StatusCode},
 };
 use rstest::rstest;
 use tower::ServiceExt;
@@ -11,16 +11,46 @@ use crate::{
     deserialize_json,
     helper::{fixture, make_router, v1, TestRequestExt},
 };
-use api::model::book::PaginatedBookResponse;
+use api::model::book::{BookMetadataResponse, BookResponse, PaginatedBookResponse};
+use api::model::book_condition::BookConditionName;
 use kernel::{
     model::{
-        book::Book,
-        id::{BookId, UserId},
+        author::AuthorRole,
+        book::{metadata::BookMetadata, Book},
+        book_condition::BookCondition,
+        id::{BookId, GenreId, ShelfId, UserId},
         list::PaginatedList,
         user::BookOwner,
     },
-    repository::book::MockBookRepository,
+    repository::{
+        blob::MockBlobStore, book::MockBookRepository, book_metadata::MockBookMetadataProvider,
+    },
 };
+use shared::error::AppError;
+
+fn make_book(book_id: BookId, version: i64) -> Book {
+    Book {
+        id: book_id,
+        title: "RustによるWebアプリケーション開発".to_string(),
+        isbn: "".to_string(),
+        author: "Yuki Toyoda".to_string(),
+        authors: vec![],
+        description: "RustによるWebアプリケーション開発".to_string(),
+        cover_url: None,
+        cover_image: None,
+        bibliography: Default::default(),
+        tags: vec![],
+        genres: vec![],
+        rating: Default::default(),
+        owner: BookOwner {
+            id: UserId::new(),
+            name: "Yuki Toyoda".to_string(),
+        },
+        checkout: None,
+        condition: BookCondition::Good,
+        version,
+    }
+}
 
 #[rstest]
 #[case("/books", StatusCode::OK, 20, 0)]
@@ -29,6 +59,7 @@ use kernel::{
 #[case("/books?offset=20", StatusCode::OK, 20, 20)]
 #[case("/books?limit=-1", StatusCode::BAD_REQUEST, 0, 0)]
 #[case("/books?offset=aaa", StatusCode::BAD_REQUEST, 0, 0)]
+#[case("/books?tag=a:b", StatusCode::BAD_REQUEST, 0, 0)]
 #[tokio::test]
 async fn show_book_list_with_query(
     // 1. fixtureとしてmockオブジェクトを渡している
@@ -44,18 +75,7 @@ async fn show_book_list_with_query(
     fixture.expect_book_repository().returning(move || {
         let mut mock = MockBookRepository::new();
         mock.expect_find_all().returning(move |opt| {
-            let items = vec![Book {
-                id: book_id,
-                title: "RustによるWebアプリケーション開発".to_string(),
-                isbn: "".to_string(),
-                author: "Yuki Toyoda".to_string(),
-                description: "RustによるWebアプリケーション開発".to_string(),
-                owner: BookOwner {
-                    id: UserId::new(),
-                    name: "Yuki Toyoda".to_string(),
-                },
-                checkout: None,
-            }];
+            let items = vec![make_book(book_id, 1)];
             Ok(PaginatedList {
                 total: 1,
                 limit: opt.limit,
@@ -70,7 +90,7 @@ async fn show_book_list_with_query(
     let app: axum::Router = make_router(fixture);
 
     // 4. リクエストを作成・送信し、レスポンスのステータスコードを検証する
-    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), status_code);
 
@@ -84,3 +104,484 @@ async fn show_book_list_with_query(
     // 6. テストが成功していることを示す
     Ok(())
 }
+
+#[rstest]
+#[tokio::test]
+async fn show_book_list_with_filter(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let genre_id = GenreId::new();
+    let shelf_id = ShelfId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all()
+            .withf(move |opt| {
+                // タグは正規化され、本棚はリクエストしたユーザーのものとして渡される
+                opt.filter.tag.as_deref() == Some("domain-driven-design")
+                    && opt.filter.genre_id == Some(genre_id)
+                    && opt.filter.shelf.as_ref().map(|s| s.shelf_id) == Some(shelf_id)
+            })
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(v1(&format!(
+        "/books?tag=Domain%20Driven%20Design&genre={genre_id}&shelf={shelf_id}"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(None, StatusCode::OK)]
+#[case(Some(r#""3""#), StatusCode::NOT_MODIFIED)]
+#[case(Some(r#""2", "3""#), StatusCode::NOT_MODIFIED)]
+#[case(Some(r#""2""#), StatusCode::OK)]
+#[tokio::test]
+async fn show_book_with_if_none_match(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] if_none_match: Option<&str>,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, 3))));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let mut req = Request::get(v1(&format!("/books/{book_id}"))).bearer();
+    if let Some(v) = if_none_match {
+        req = req.header(header::IF_NONE_MATCH, v);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+
+    assert_eq!(resp.status(), status_code);
+    assert_eq!(resp.headers()[header::ETAG], r#""3""#);
+    if status_code == StatusCode::OK {
+        let result = deserialize_json!(resp, BookResponse);
+        assert_eq!(result.id, book_id);
+        assert_eq!(result.condition, BookConditionName::Good);
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(None, StatusCode::PRECONDITION_REQUIRED, None)]
+#[case(Some(r#""2""#), StatusCode::PRECONDITION_FAILED, None)]
+#[case(Some(r#""3""#), StatusCode::OK, Some(3))]
+#[case(Some("*"), StatusCode::OK, None)]
+#[tokio::test]
+async fn update_book_with_if_match(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] if_match: Option<&str>,
+    #[case] status_code: StatusCode,
+    #[case] expected_version: Option<i64>,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, 3))));
+        // If-Match が一致した場合だけ、照合するバージョンを付けて更新される
+        mock.expect_update()
+            .withf(move |event| event.expected_version == expected_version)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let mut req = Request::put(v1(&format!("/books/{book_id}")))
+        .bearer()
+        .application_json();
+    if let Some(v) = if_match {
+        req = req.header(header::IF_MATCH, v);
+    }
+    let body = serde_json::json!({
+        "title": "RustによるWebアプリケーション開発",
+        "author": "Yuki Toyoda",
+        "isbn": "978-4065369579",
+        "description": "",
+    });
+    let resp = app.oneshot(req.body(Body::from(body.to_string()))?).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn delete_book_requires_if_match(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_book_repository()
+        .returning(|| Arc::new(MockBookRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::delete(v1(&format!("/books/{}", BookId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_REQUIRED);
+
+    Ok(())
+}
+
+// 削除をコミットした後で、蔵書の表紙とサムネイルを消す
+#[rstest]
+#[tokio::test]
+async fn delete_book_removes_cover_images(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_delete()
+            .withf(move |event| event.book_id == book_id && event.expected_version.is_none())
+            .returning(|_| Ok(Some("covers/1/a.png".into())));
+        Arc::new(mock)
+    });
+    let deletes = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let deletes_for_mock = deletes.clone();
+    let mut blob_store = MockBlobStore::new();
+    blob_store.expect_delete().returning(move |key| {
+        deletes_for_mock.lock().unwrap().push(key.to_string());
+        Ok(())
+    });
+    let blob_store = Arc::new(blob_store);
+    fixture
+        .expect_blob_store()
+        .returning(move || blob_store.clone());
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::delete(v1(&format!("/books/{book_id}")))
+        .bearer()
+        .header(header::IF_MATCH, "*")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        *deletes.lock().unwrap(),
+        vec!["covers/1/a.thumb.jpg", "covers/1/a.png"]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    Some("application/merge-patch+json"),
+    r#"{"title": "改訂版"}"#,
+    StatusCode::OK
+)]
+#[case(Some("application/json"), r#"{"title": "改訂版"}"#, StatusCode::OK)]
+#[case(
+    Some("text/plain"),
+    r#"{"title": "改訂版"}"#,
+    StatusCode::UNSUPPORTED_MEDIA_TYPE
+)]
+#[case(None, r#"{"title": "改訂版"}"#, StatusCode::UNSUPPORTED_MEDIA_TYPE)]
+#[case(
+    Some("application/merge-patch+json"),
+    r#"{"title": null}"#,
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[case(
+    Some("application/merge-patch+json"),
+    r#"{"title": ""}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    Some("application/merge-patch+json"),
+    r#"{"title": "#,
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn patch_book_with_content_type(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] content_type: Option<&str>,
+    #[case] body: &'static str,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_patch()
+            .withf(|event| {
+                event.title.as_deref() == Some("改訂版")
+                    && event.authors.is_none()
+                    && event.description.is_none()
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let mut req = Request::patch(v1(&format!("/books/{}", BookId::new())))
+        .bearer()
+        .header(header::IF_MATCH, "*");
+    if let Some(v) = content_type {
+        req = req.header(header::CONTENT_TYPE, v);
+    }
+    let resp = app.oneshot(req.body(Body::from(body))?).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn patch_book_clears_description_with_null(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, 3))));
+        mock.expect_patch()
+            .withf(|event| {
+                event.title.is_none()
+                    && event.description.as_deref() == Some("")
+                    && event.expected_version == Some(3)
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::patch(v1(&format!("/books/{}", BookId::new())))
+        .bearer()
+        .header(header::CONTENT_TYPE, "application/merge-patch+json")
+        .header(header::IF_MATCH, r#""3""#)
+        .body(Body::from(r#"{"description": null}"#))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+fn make_metadata(isbn: &str) -> BookMetadata {
+    BookMetadata {
+        isbn: isbn.to_string(),
+        title: Some("RustによるWebアプリケーション開発".to_string()),
+        author: Some("豊田優貴".to_string()),
+        description: None,
+        cover_url: Some("https://covers.example.com/l.jpg".to_string()),
+    }
+}
+
+#[rstest]
+#[case("/books/lookup?isbn=978-4-06-536957-9", StatusCode::OK)]
+#[case("/books/lookup?isbn=9780000000000", StatusCode::NOT_FOUND)]
+#[case("/books/lookup?isbn=9789999999999", StatusCode::BAD_GATEWAY)]
+#[case("/books/lookup?isbn=12345", StatusCode::BAD_REQUEST)]
+#[case("/books/lookup", StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn lookup_book(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_book_metadata_provider().returning(|| {
+        let mut mock = MockBookMetadataProvider::new();
+        mock.expect_lookup().returning(|isbn| match isbn {
+            "9780000000000" => Ok(None),
+            "9789999999999" => Err(AppError::ExternalServiceError("timed out".into())),
+            _ => Ok(Some(make_metadata("9784065369579"))),
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+    if status_code == StatusCode::OK {
+        let result = deserialize_json!(resp, BookMetadataResponse);
+        assert_eq!(result.isbn, "9784065369579");
+        assert_eq!(
+            result.cover_url.as_deref(),
+            Some("https://covers.example.com/l.jpg")
+        );
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books?autofill=true", Ok(true), StatusCode::CREATED)]
+#[case("/books?autofill=true", Ok(false), StatusCode::BAD_REQUEST)]
+#[case("/books?autofill=true", Err(()), StatusCode::BAD_REQUEST)]
+#[case("/books", Ok(true), StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn register_book_with_autofill(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] found: Result<bool, ()>,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_book_metadata_provider().returning(move || {
+        let mut mock = MockBookMetadataProvider::new();
+        mock.expect_lookup().returning(move |isbn| match found {
+            Ok(found) => Ok(found.then(|| make_metadata(isbn))),
+            Err(()) => Err(AppError::ExternalServiceError("timed out".into())),
+        });
+        Arc::new(mock)
+    });
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        // 入力された description はそのまま、未入力の項目は取得した値で埋める
+        mock.expect_create()
+            .withf(|event, _| {
+                event.title == "RustによるWebアプリケーション開発"
+                    && event.authors.len() == 1
+                    && event.authors[0].name == "豊田優貴"
+                    && event.description == "手元のメモ"
+                    && event.cover_url.as_deref() == Some("https://covers.example.com/l.jpg")
+            })
+            .returning(|_, _| Ok(BookId::new()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let body = serde_json::json!({
+        "isbn": "9784065369579",
+        "description": "手元のメモ",
+    });
+    let req = Request::post(v1(path))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    serde_json::json!({"author": "Steve Klabnik; Carol Nichols"}),
+    StatusCode::CREATED,
+    vec![("Steve Klabnik", AuthorRole::Author), ("Carol Nichols", AuthorRole::Author)]
+)]
+#[case(
+    serde_json::json!({
+        "author": "無視される",
+        "authors": [{"name": "Steve Klabnik"}, {"name": "尾崎亮太", "role": "translator"}]
+    }),
+    StatusCode::CREATED,
+    vec![("Steve Klabnik", AuthorRole::Author), ("尾崎亮太", AuthorRole::Translator)]
+)]
+#[case(serde_json::json!({}), StatusCode::BAD_REQUEST, vec![])]
+#[case(serde_json::json!({"authors": [{"name": ""}]}), StatusCode::BAD_REQUEST, vec![])]
+#[case(serde_json::json!({"authors": [{"name": "   "}]}), StatusCode::BAD_REQUEST, vec![])]
+#[case(
+    serde_json::json!({"author": "A", "publicationYear": 0}),
+    StatusCode::BAD_REQUEST,
+    vec![]
+)]
+#[tokio::test]
+async fn register_book_with_authors(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] fields: serde_json::Value,
+    #[case] status_code: StatusCode,
+    #[case] expected_authors: Vec<(&'static str, AuthorRole)>,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let expected_authors = expected_authors.clone();
+        let mut mock = MockBookRepository::new();
+        mock.expect_create()
+            .withf(move |event, _| {
+                let authors = event
+                    .authors
+                    .iter()
+                    .map(|a| (a.name.as_str(), a.role))
+                    .collect::<Vec<_>>();
+                authors == expected_authors
+                    && event.bibliography.publisher.as_deref() == Some("オライリー・ジャパン")
+                    && event.bibliography.page_count == Some(560)
+            })
+            .returning(|_, _| Ok(BookId::new()));
+        Arc::new(mock)
+    });
+
+    let mut body = serde_json::json!({
+        "title": "プログラミングRust",
+        "isbn": "9784873119786",
+        "publisher": "オライリー・ジャパン",
+        "pageCount": 560,
+    });
+    body.as_object_mut()
+        .unwrap()
+        .extend(fields.as_object().unwrap().clone());
+    let app: axum::Router = make_router(fixture);
+    let req = Request::post(v1("/books"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!({"coverUrl": "https://covers.example.com/l.jpg"}), StatusCode::CREATED)]
+#[case(serde_json::json!({"coverUrl": "javascript:alert(1)"}), StatusCode::BAD_REQUEST)]
+#[case(serde_json::json!({"coverUrl": ""}), StatusCode::BAD_REQUEST)]
+#[case(serde_json::json!({"title": "長".repeat(256)}), StatusCode::BAD_REQUEST)]
+#[case(serde_json::json!({"description": "a".repeat(256)}), StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn register_book_validates_fields(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] fields: serde_json::Value,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create().returning(|_, _| Ok(BookId::new()));
+        Arc::new(mock)
+    });
+
+    let mut body = serde_json::json!({
+        "title": "プログラミングRust",
+        "author": "Jim Blandy",
+        "isbn": "9784873119786",
+    });
+    body.as_object_mut()
+        .unwrap()
+        .extend(fields.as_object().unwrap().clone());
+    let app: axum::Router = make_router(fixture);
+    let req = Request::post(v1("/books"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, fixture_registry, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::book_condition::{BookConditionName, BookConditionsResponse, CheckoutChargeBody};
+use kernel::{
+    model::{
+        book::Book,
+        book_condition::{BookCondition, BookConditionRecord, CheckoutCharge},
+        checkout::{Checkout, CheckoutBook},
+        id::{BookConditionId, BookId, CheckoutId, UserId},
+        user::BookOwner,
+    },
+    repository::{
+        auth::MockAuthRepository, book::MockBookRepository,
+        book_condition::MockBookConditionRepository, checkout::MockCheckoutRepository,
+    },
+};
+use registry::MockAppRegistryExt;
+
+fn make_checkout(checkout_id: CheckoutId, book_id: BookId) -> Checkout {
+    Checkout {
+        id: checkout_id,
+        checked_out_by: UserId::new(),
+        checked_out_at: Utc::now(),
+        returned_at: None,
+        outcome: None,
+        checked_out_on_behalf_by: None,
+        returned_on_behalf_by: None,
+        return_reason: None,
+        book: CheckoutBook {
+            book_id,
+            title: "実践Rustプログラミング入門".into(),
+            author: "初田直也".into(),
+            isbn: "978-4798061702".into(),
+        },
+    }
+}
+
+fn make_book(book_id: BookId, condition: BookCondition) -> Book {
+    Book {
+        id: book_id,
+        title: "実践Rustプログラミング入門".into(),
+        author: "初田直也".into(),
+        authors: vec![],
+        isbn: "978-4798061702".into(),
+        description: "".into(),
+        cover_url: None,
+        cover_image: None,
+        bibliography: Default::default(),
+        tags: vec![],
+        genres: vec![],
+        rating: Default::default(),
+        // 認証済みのユーザーとは別の所有者
+        owner: BookOwner {
+            id: UserId::new(),
+            name: "owner".into(),
+        },
+        checkout: None,
+        condition,
+        version: 1,
+    }
+}
+
+// 管理者以外は自分が借りている貸出についてのみ届け出られ、弁償の方法は指定できない
+#[rstest]
+#[case(false, true, None, StatusCode::OK)]
+#[case(false, false, None, StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(false, true, Some(serde_json::json!({ "kind": "fee", "amount": 1500 })), StatusCode::FORBIDDEN)]
+#[case(true, false, Some(serde_json::json!({ "kind": "fee", "amount": 1500 })), StatusCode::OK)]
+#[case(true, false, Some(serde_json::json!({ "kind": "fee", "amount": -1 })), StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn report_lost_book(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] borrowing: bool,
+    #[case] charge: Option<serde_json::Value>,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+    registry.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_unreturned_by_user_id()
+            .returning(move |_| {
+                Ok(if borrowing {
+                    vec![make_checkout(checkout_id, book_id)]
+                } else {
+                    vec![]
+                })
+            });
+        mock.expect_report_lost()
+            .withf(move |event| {
+                event.checkout_id == checkout_id
+                    && event.book_id == book_id
+                    && event.charge == admin.then_some(CheckoutCharge::Fee { amount: 1500 })
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "note": "電車に置き忘れた", "charge": charge });
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/checkouts/{checkout_id}/lost"
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn return_damaged_book_with_replacement(
+    fixture_auth: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, true);
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+    registry.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_return_damaged()
+            .withf(move |event| {
+                event.checkout_id == checkout_id
+                    && event.note == "表紙が破れた"
+                    && event.charge == Some(CheckoutCharge::Replacement)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "note": "表紙が破れた", "charge": { "kind": "replacement" } });
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/checkouts/{checkout_id}/damaged"
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    Ok(())
+}
+
+// 紛失した蔵書を見つかったことにできるのは所有者と管理者だけ
+#[rstest]
+#[case(false, StatusCode::FORBIDDEN)]
+#[case(true, StatusCode::OK)]
+#[tokio::test]
+async fn mark_book_found(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, BookCondition::Lost))));
+        Arc::new(mock)
+    });
+    registry
+        .expect_book_condition_repository()
+        .returning(move || {
+            let mut mock = MockBookConditionRepository::new();
+            mock.expect_mark_found()
+                .withf(move |event| {
+                    event.book_id == book_id && event.condition == BookCondition::Damaged
+                })
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "note": "書庫の奥にあった", "damaged": true });
+    let req = Request::put(v1(&format!("/books/{book_id}/found")))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_condition_history(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, true);
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+    registry
+        .expect_book_condition_repository()
+        .returning(move || {
+            let mut mock = MockBookConditionRepository::new();
+            mock.expect_find_history_by_book_id()
+                .returning(move |book_id| {
+                    Ok(vec![BookConditionRecord {
+                        id: BookConditionId::new(),
+                        book_id,
+                        condition: BookCondition::Lost,
+                        checkout_id: Some(checkout_id),
+                        note: "電車に置き忘れた".into(),
+                        charge: Some(CheckoutCharge::Fee { amount: 3000 }),
+                        recorded_by: Some(UserId::new()),
+                        recorded_at: Utc::now(),
+                    }])
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::get(v1(&format!("/books/{book_id}/condition-history")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, BookConditionsResponse);
+    assert_eq!(body.items.len(), 1);
+    assert_eq!(body.items[0].condition, BookConditionName::Lost);
+    assert_eq!(body.items[0].checkout_id, Some(checkout_id));
+    assert_eq!(
+        body.items[0].charge,
+        Some(CheckoutChargeBody::Fee { amount: 3000 })
+    );
+    Ok(())
+}
+
+// 所有者・管理者以外は、自身の貸出に紐づく記録だけを閲覧できる
+#[rstest]
+#[case(true, StatusCode::OK)]
+#[case(false, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn show_condition_history_to_borrower(
+    fixture_registry: MockAppRegistryExt,
+    #[case] borrowed: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = with_role(fixture_registry, false);
+    registry.expect_auth_repository().returning(move || {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_fetch_user_id_from_token()
+            .returning(move |_| Ok(Some(user_id)));
+        Arc::new(mock)
+    });
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, BookCondition::Lost))));
+        Arc::new(mock)
+    });
+    let book_id = BookId::new();
+    let own_checkout_id = CheckoutId::new();
+    let other_checkout_id = CheckoutId::new();
+    registry.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_history_by_book_id()
+            .returning(move |book_id| {
+                let mut own = make_checkout(own_checkout_id, book_id);
+                if borrowed {
+                    own.checked_out_by = user_id;
+                }
+                Ok(vec![own, make_checkout(other_checkout_id, book_id)])
+            });
+        Arc::new(mock)
+    });
+    registry
+        .expect_book_condition_repository()
+        .returning(move || {
+            let mut mock = MockBookConditionRepository::new();
+            mock.expect_find_history_by_book_id()
+                .returning(move |book_id| {
+                    let record = |checkout_id, condition| BookConditionRecord {
+                        id: BookConditionId::new(),
+                        book_id,
+                        condition,
+                        checkout_id,
+                        note: "".into(),
+                        charge: Some(CheckoutCharge::Fee { amount: 3000 }),
+                        recorded_by: Some(UserId::new()),
+                        recorded_at: Utc::now(),
+                    };
+                    Ok(vec![
+                        record(Some(own_checkout_id), BookCondition::Lost),
+                        record(Some(other_checkout_id), BookCondition::Damaged),
+                        record(None, BookCondition::Good),
+                    ])
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::get(v1(&format!("/books/{book_id}/condition-history")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    if borrowed {
+        // 他の利用者の貸出に紐づく記録や、貸出と関係のない記録は含まない
+        let body = deserialize_json!(resp, BookConditionsResponse);
+        assert_eq!(body.items.len(), 1);
+        assert_eq!(body.items[0].checkout_id, Some(own_checkout_id));
+        assert_eq!(
+            body.items[0].charge,
+            Some(CheckoutChargeBody::Fee { amount: 3000 })
+        );
+    }
+    Ok(())
+}
+
+// 修理した蔵書を元の状態に戻せるのは所有者と管理者だけ
+#[rstest]
+#[case(false, StatusCode::FORBIDDEN)]
+#[case(true, StatusCode::OK)]
+#[tokio::test]
+async fn update_book_condition(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, BookCondition::Damaged))));
+        Arc::new(mock)
+    });
+    registry
+        .expect_book_condition_repository()
+        .returning(move || {
+            let mut mock = MockBookConditionRepository::new();
+            mock.expect_update_condition()
+                .withf(move |event| {
+                    event.book_id == book_id
+                        && event.condition == BookCondition::Good
+                        && event.note == "製本し直した"
+                })
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "condition": "good", "note": "製本し直した" });
+    let req = Request::put(v1(&format!("/books/{book_id}/condition")))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+// 弁償の方法を記録し直せるのは管理者だけ
+#[rstest]
+#[case(false, serde_json::json!({ "kind": "fee", "amount": 800 }), StatusCode::FORBIDDEN)]
+#[case(true, serde_json::json!({ "kind": "fee", "amount": 800 }), StatusCode::OK)]
+#[case(true, serde_json::json!(null), StatusCode::OK)]
+#[case(true, serde_json::json!({ "kind": "fee", "amount": -1 }), StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn update_condition_charge(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] charge: serde_json::Value,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    let book_condition_id = BookConditionId::new();
+    let expected = serde_json::from_value::<Option<CheckoutChargeBody>>(charge.clone())?
+        .map(CheckoutCharge::from);
+    registry
+        .expect_book_condition_repository()
+        .returning(move || {
+            let expected = expected.clone();
+            let mut mock = MockBookConditionRepository::new();
+            mock.expect_update_charge()
+                .withf(move |event| {
+                    event.book_id == book_id
+                        && event.book_condition_id == book_condition_id
+                        && event.charge == expected
+                })
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "charge": charge });
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/condition-history/{book_condition_id}/charge"
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::book_transfer::{BookTransferResponse, BookTransferStatusName};
+use kernel::{
+    model::{
+        book_transfer::{BookTransfer, BookTransferStatus},
+        id::{BookId, BookTransferId, UserId},
+        user::TransferUser,
+    },
+    repository::book_transfer::MockBookTransferRepository,
+};
+use registry::MockAppRegistryExt;
+use shared::error::AppError;
+
+fn book_transfer(book_id: BookId, to_user_id: UserId, status: BookTransferStatus) -> BookTransfer {
+    BookTransfer {
+        id: BookTransferId::new(),
+        book_id,
+        book_title: "実践Rustプログラミング入門".into(),
+        from_user: TransferUser {
+            id: Some(UserId::new()),
+            name: "dummy-user".into(),
+        },
+        to_user: TransferUser {
+            id: Some(to_user_id),
+            name: "colleague".into(),
+        },
+        status,
+        forced_by: None,
+        created_at: Utc::now(),
+        resolved_at: None,
+    }
+}
+
+fn transfer_request(to_user_id: UserId) -> anyhow::Result<Body> {
+    let body = serde_json::json!({ "toUserId": to_user_id });
+    Ok(Body::from(serde_json::to_string(&body)?))
+}
+
+#[rstest]
+#[tokio::test]
+async fn offer_book_transfer(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    let book_id = BookId::new();
+    let to_user_id = UserId::new();
+    registry
+        .expect_book_transfer_repository()
+        .returning(move || {
+            let mut mock = MockBookTransferRepository::new();
+            mock.expect_offer()
+                .withf(move |event| event.book_id == book_id && event.to_user_id == to_user_id)
+                .returning(|event| {
+                    Ok(book_transfer(
+                        event.book_id,
+                        event.to_user_id,
+                        BookTransferStatus::Pending,
+                    ))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::post(v1(&format!("/books/{book_id}/transfers")))
+        .bearer()
+        .application_json()
+        .body(transfer_request(to_user_id)?)?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body = deserialize_json!(resp, BookTransferResponse);
+    assert_eq!(body.book_id, book_id);
+    assert_eq!(body.to_user.id, Some(to_user_id));
+    assert_eq!(body.status, BookTransferStatusName::Pending);
+    Ok(())
+}
+
+// 貸出中の蔵書の譲渡や、返事の済んだ申し出への返事は受け付けない
+#[rstest]
+#[tokio::test]
+async fn accept_book_transfer_rejected(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    let book_id = BookId::new();
+    let book_transfer_id = BookTransferId::new();
+    registry
+        .expect_book_transfer_repository()
+        .returning(move || {
+            let mut mock = MockBookTransferRepository::new();
+            mock.expect_accept()
+                .withf(move |event| {
+                    event.book_id == book_id && event.book_transfer_id == book_transfer_id
+                })
+                .returning(|_| {
+                    Err(AppError::UnprocessableEntity(
+                        "貸出中の蔵書は譲渡できません。".into(),
+                    ))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/transfers/{book_transfer_id}/accepted"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    Ok(())
+}
+
+#[rstest]
+#[case(false, StatusCode::FORBIDDEN)]
+#[case(true, StatusCode::OK)]
+#[tokio::test]
+async fn force_book_transfer_requires_admin(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    let to_user_id = UserId::new();
+    registry
+        .expect_book_transfer_repository()
+        .returning(move || {
+            let mut mock = MockBookTransferRepository::new();
+            mock.expect_force().returning(|event| {
+                let mut transfer =
+                    book_transfer(event.book_id, event.to_user_id, BookTransferStatus::Forced);
+                transfer.forced_by = Some(event.requested_user);
+                Ok(transfer)
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::post(v1(&format!("/books/{book_id}/transfers/force")))
+        .bearer()
+        .application_json()
+        .body(transfer_request(to_user_id)?)?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    if admin {
+        let body = deserialize_json!(resp, BookTransferResponse);
+        assert_eq!(body.status, BookTransferStatusName::Forced);
+        assert!(body.forced_by.is_some());
+    }
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn cancel_book_transfer(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    let book_id = BookId::new();
+    let book_transfer_id = BookTransferId::new();
+    registry
+        .expect_book_transfer_repository()
+        .returning(move || {
+            let mut mock = MockBookTransferRepository::new();
+            mock.expect_cancel()
+                .withf(move |event| {
+                    event.book_id == book_id && event.book_transfer_id == book_transfer_id
+                })
+                .returning(|event| {
+                    Ok(book_transfer(
+                        event.book_id,
+                        UserId::new(),
+                        BookTransferStatus::Cancelled,
+                    ))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/transfers/{book_transfer_id}/cancelled"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, BookTransferResponse);
+    assert_eq!(body.status, BookTransferStatusName::Cancelled);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_owner_history_of_unknown_book(
+    fixture_auth: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    let book_id = BookId::new();
+    registry
+        .expect_book_transfer_repository()
+        .returning(move || {
+            let mut mock = MockBookTransferRepository::new();
+            mock.expect_find_history()
+                .withf(move |id| *id == book_id)
+                .returning(|id| {
+                    Err(AppError::EntityNotFound(format!(
+                        "蔵書（{id}）が見つかりませんでした。"
+                    )))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::get(v1(&format!("/books/{book_id}/owner-history")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
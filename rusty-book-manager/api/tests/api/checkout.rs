@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::helper::{fixture_auth, make_router, v1, with_role, TestRequestExt};
+use kernel::{
+    model::id::{BookId, CheckoutId, UserId},
+    repository::checkout::MockCheckoutRepository,
+};
+use registry::MockAppRegistryExt;
+
+#[rstest]
+#[case(false, StatusCode::FORBIDDEN)]
+#[case(true, StatusCode::CREATED)]
+#[tokio::test]
+async fn checkout_book_on_behalf_requires_admin(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    let user_id = UserId::new();
+    registry.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_create_on_behalf()
+            .withf(move |event| event.book_id == book_id && event.checked_out_by == user_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "userId": user_id });
+    let req = Request::post(v1(&format!("/books/{book_id}/checkouts/on-behalf")))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+#[rstest]
+#[case(false, "退職のため", StatusCode::FORBIDDEN)]
+#[case(true, "退職のため", StatusCode::OK)]
+#[case(true, "", StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn force_return_book(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] reason: &'static str,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, admin);
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+    registry.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_force_returned()
+            .withf(move |event| {
+                event.book_id == book_id
+                    && event.checkout_id == checkout_id
+                    && event.reason == reason
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({ "reason": reason });
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/checkouts/{checkout_id}/force-returned"
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_auth, fixture_registry, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::checkout::PaginatedCheckoutResponse;
+use kernel::{
+    model::{
+        checkout::{Checkout, CheckoutBook, CheckoutOutcome},
+        id::{BookId, CheckoutId, UserId},
+        list::PaginatedList,
+    },
+    repository::{auth::MockAuthRepository, checkout::MockCheckoutRepository},
+};
+use registry::MockAppRegistryExt;
+
+fn make_checkout(title: &str) -> Checkout {
+    let at = Utc.with_ymd_and_hms(2025, 2, 1, 9, 0, 0).unwrap();
+    Checkout {
+        id: CheckoutId::new(),
+        checked_out_by: UserId::new(),
+        checked_out_at: at,
+        returned_at: Some(at),
+        outcome: Some(CheckoutOutcome::Returned),
+        checked_out_on_behalf_by: None,
+        returned_on_behalf_by: None,
+        return_reason: None,
+        book: CheckoutBook {
+            book_id: BookId::new(),
+            title: title.into(),
+            author: "初田直也".into(),
+            isbn: "978-4798061702".into(),
+        },
+    }
+}
+
+#[rstest]
+#[case("", StatusCode::OK, None, None)]
+#[case("?from=2025-02-01&to=2025-02-28", StatusCode::OK, Some((2025, 2, 1)), Some((2025, 3, 1)))]
+#[case("?from=2025-02-01&to=2025-01-31", StatusCode::BAD_REQUEST, None, None)]
+#[case("?from=2025-02-30", StatusCode::BAD_REQUEST, None, None)]
+#[tokio::test]
+async fn get_checkout_history_filters_by_returned_date(
+    mut fixture: MockAppRegistryExt,
+    #[case] query: &str,
+    #[case] expected: StatusCode,
+    #[case] from: Option<(i32, u32, u32)>,
+    #[case] to: Option<(i32, u32, u32)>,
+) -> anyhow::Result<()> {
+    let at = |d: Option<(i32, u32, u32)>| {
+        d.map(|(y, m, d)| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap())
+    };
+    let (from, to) = (at(from), at(to));
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_history_by_user_id()
+            .withf(move |_, opt| opt.returned_from == from && opt.returned_to == to)
+            .returning(|_, opt| {
+                Ok(PaginatedList {
+                    total: 1,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![make_checkout("実践Rustプログラミング入門")],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1(&format!("/users/me/checkout-history{query}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == StatusCode::OK {
+        let body = deserialize_json!(resp, PaginatedCheckoutResponse);
+        assert_eq!(body.total, 1);
+        assert_eq!(body.limit, 20);
+        assert!(body.items[0].returned_at.is_some());
+    }
+    Ok(())
+}
+
+#[rstest]
+#[case(true, StatusCode::OK)]
+#[case(false, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn get_user_checkout_history_requires_admin(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    fixture_auth
+        .expect_checkout_repository()
+        .returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_find_history_by_user_id()
+                .withf(move |id, _| *id == user_id)
+                .returning(|_, opt| {
+                    Ok(PaginatedList {
+                        total: 0,
+                        limit: opt.limit,
+                        offset: opt.offset,
+                        items: vec![],
+                    })
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(with_role(fixture_auth, admin));
+    let req = Request::get(v1(&format!("/users/{user_id}/checkout-history")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    Ok(())
+}
+
+// ページを分けて取得した履歴をすべて CSV にまとめる
+#[rstest]
+#[tokio::test]
+async fn export_checkout_history_as_csv(mut fixture: MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_checkout_repository().returning(|| {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_history_by_user_id().returning(|_, opt| {
+            let total = 501;
+            let count = (total - opt.offset).min(opt.limit);
+            let items = (0..count)
+                .map(|i| make_checkout(&format!("Rust, \"実践\" {}", opt.offset + i)))
+                .collect();
+            Ok(PaginatedList {
+                total,
+                limit: opt.limit,
+                offset: opt.offset,
+                items,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1("/users/me/checkout-history.csv"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()[header::CONTENT_TYPE],
+        "text/csv; charset=utf-8"
+    );
+
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let text = String::from_utf8(bytes.to_vec())?;
+    let mut lines = text.strip_prefix('\u{feff}').unwrap().lines();
+    assert_eq!(
+        lines.next(),
+        Some("checkout_id,book_id,title,author,isbn,checked_out_at,returned_at")
+    );
+    let rows = lines.collect::<Vec<_>>();
+    assert_eq!(rows.len(), 501);
+    assert!(rows[0]
+        .contains(r#","Rust, ""実践"" 0",初田直也,978-4798061702,2025-02-01T09:00:00+00:00,"#));
+    assert!(rows[500].contains("実践\"\" 500"));
+    Ok(())
+}
+
+// 表計算ソフトで数式として扱われる値は ' を付けて出力する
+#[rstest]
+#[case("=HYPERLINK(\"https://example.com\")", "'=HYPERLINK(")]
+#[case("+1", "'+1")]
+#[case("-1", "'-1")]
+#[case("@SUM(A1)", "'@SUM(A1)")]
+#[case("\tタブ", "'\tタブ")]
+#[case("実践Rust", ",実践Rust,")]
+#[tokio::test]
+async fn export_checkout_history_escapes_formulas(
+    mut fixture: MockAppRegistryExt,
+    #[case] title: &'static str,
+    #[case] expected: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_history_by_user_id()
+            .returning(move |_, opt| {
+                Ok(PaginatedList {
+                    total: 1,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![make_checkout(title)],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1("/users/me/checkout-history.csv"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let text = String::from_utf8(bytes.to_vec())?;
+    assert!(text.lines().nth(1).unwrap().contains(expected));
+    Ok(())
+}
+
+// 件数が多すぎる場合は読み込む前に断り、期間を絞ってもらう
+#[rstest]
+#[tokio::test]
+async fn export_checkout_history_rejects_too_many_rows(
+    mut fixture: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_checkout_repository().returning(|| {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_history_by_user_id()
+            .withf(|_, opt| opt.offset == 0)
+            .times(1)
+            .returning(|_, opt| {
+                Ok(PaginatedList {
+                    total: 10_001,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: (0..opt.limit)
+                        .map(|i| make_checkout(&format!("実践Rust {i}")))
+                        .collect(),
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1("/users/me/checkout-history.csv"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    Ok(())
+}
+
+// 代理の貸出・返却の詳細は、管理者と借りた本人にだけ返す
+#[rstest]
+#[case(true, false, true)]
+#[case(false, true, true)]
+#[case(false, false, false)]
+#[tokio::test]
+async fn book_checkout_history_hides_override_details(
+    mut fixture_registry: MockAppRegistryExt,
+    #[case] is_borrower: bool,
+    #[case] admin: bool,
+    #[case] visible: bool,
+) -> anyhow::Result<()> {
+    let viewer_id = UserId::new();
+    let book_id = BookId::new();
+    let admin_id = UserId::new();
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_fetch_user_id_from_token()
+                .returning(move |_| Ok(Some(viewer_id)));
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_checkout_repository()
+        .returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_find_history_by_book_id().returning(move |_| {
+                let mut checkout = make_checkout("Rust の本");
+                if is_borrower {
+                    checkout.checked_out_by = viewer_id;
+                }
+                checkout.checked_out_on_behalf_by = Some(admin_id);
+                checkout.returned_on_behalf_by = Some(admin_id);
+                checkout.return_reason = Some("退職のため".into());
+                Ok(vec![checkout])
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(with_role(fixture_registry, admin));
+    let req = Request::get(v1(&format!("/books/{book_id}/checkout-history")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body = deserialize_json!(resp, serde_json::Value);
+    let item = &body["items"][0];
+    if visible {
+        assert_eq!(item["checkedOutOnBehalfBy"], admin_id.to_string());
+        assert_eq!(item["returnedOnBehalfBy"], admin_id.to_string());
+        assert_eq!(item["returnReason"], "退職のため");
+    } else {
+        assert!(item["checkedOutOnBehalfBy"].is_null());
+        assert!(item["returnedOnBehalfBy"].is_null());
+        assert!(item["returnReason"].is_null());
+    }
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_auth, fixture_registry, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::{genre::GenreResponse, shelf::ShelfResponse};
+use kernel::{
+    model::{
+        book::Book,
+        book_condition::BookCondition,
+        genre::Genre,
+        id::{BookId, GenreId, ShelfId, UserId},
+        shelf::Shelf,
+        user::BookOwner,
+    },
+    repository::{
+        auth::MockAuthRepository, book::MockBookRepository, genre::MockGenreRepository,
+        shelf::MockShelfRepository, tag::MockTagRepository,
+    },
+};
+use registry::MockAppRegistryExt;
+
+fn make_book(book_id: BookId, owner_id: UserId) -> Book {
+    Book {
+        id: book_id,
+        title: "実践Rustプログラミング入門".into(),
+        author: "初田直也".into(),
+        authors: vec![],
+        isbn: "978-4798061702".into(),
+        description: "".into(),
+        cover_url: None,
+        cover_image: None,
+        bibliography: Default::default(),
+        tags: vec![],
+        genres: vec![],
+        rating: Default::default(),
+        owner: BookOwner {
+            id: owner_id,
+            name: "owner".into(),
+        },
+        checkout: None,
+        condition: BookCondition::Good,
+        version: 1,
+    }
+}
+
+// fixture_auth は呼び出すたびに異なるユーザー ID を返すため、蔵書の所有者と比べる場合は固定する
+fn with_user(mut registry: MockAppRegistryExt, user_id: UserId) -> MockAppRegistryExt {
+    registry.expect_auth_repository().returning(move || {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_fetch_user_id_from_token()
+            .returning(move |_| Ok(Some(user_id)));
+        Arc::new(mock)
+    });
+    registry
+}
+
+fn with_book_owned_by(mut registry: MockAppRegistryExt, owner_id: UserId) -> MockAppRegistryExt {
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, owner_id))));
+        Arc::new(mock)
+    });
+    registry
+}
+
+#[rstest]
+#[case("Rust", StatusCode::OK, Some("rust"))]
+#[case("Domain%20Driven", StatusCode::OK, Some("domain-driven"))]
+#[case("a:b", StatusCode::BAD_REQUEST, None)]
+#[tokio::test]
+async fn add_book_tag_normalizes_name(
+    fixture_registry: MockAppRegistryExt,
+    #[case] tag: &str,
+    #[case] expected_status: StatusCode,
+    #[case] expected_name: Option<&'static str>,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = with_book_owned_by(with_user(fixture_registry, user_id), user_id);
+    registry.expect_tag_repository().returning(move || {
+        let mut mock = MockTagRepository::new();
+        mock.expect_add_to_book()
+            .withf(move |e| Some(e.name.as_str()) == expected_name)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(registry, false));
+    let req = Request::put(v1(&format!("/books/{}/tags/{tag}", BookId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+// タグの付け外しは蔵書の所有者と管理者のみ
+#[rstest]
+#[case(true, false, StatusCode::OK)]
+#[case(false, true, StatusCode::OK)]
+#[case(false, false, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn book_tags_require_owner_or_admin(
+    fixture_registry: MockAppRegistryExt,
+    #[values("put", "delete")] method: &str,
+    #[case] owner: bool,
+    #[case] admin: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let owner_id = if owner { user_id } else { UserId::new() };
+    let mut registry = with_book_owned_by(with_user(fixture_registry, user_id), owner_id);
+    registry.expect_tag_repository().returning(|| {
+        let mut mock = MockTagRepository::new();
+        mock.expect_add_to_book().returning(|_| Ok(()));
+        mock.expect_remove_from_book().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(registry, admin));
+    let req = Request::builder()
+        .method(method.to_uppercase().as_str())
+        .uri(v1(&format!("/books/{}/tags/rust", BookId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+#[rstest]
+#[case(true, StatusCode::CREATED)]
+#[case(false, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn create_genre_requires_admin(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let parent_id = GenreId::new();
+    fixture_auth.expect_genre_repository().returning(|| {
+        let mut mock = MockGenreRepository::new();
+        mock.expect_create().returning(|e| {
+            Ok(Genre {
+                id: GenreId::new(),
+                name: e.name,
+                parent_id: e.parent_id,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, admin));
+    let req = Request::post(v1("/genres"))
+        .bearer()
+        .application_json()
+        .body(Body::from(format!(
+            r#"{{"name": "プログラミング", "parentId": "{parent_id}"}}"#
+        )))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == StatusCode::CREATED {
+        let genre = deserialize_json!(resp, GenreResponse);
+        assert_eq!(genre.name, "プログラミング");
+        assert_eq!(genre.parent_id, Some(parent_id));
+    }
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_shelf_for_current_user(mut fixture: MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_shelf_repository().returning(|| {
+        let mut mock = MockShelfRepository::new();
+        mock.expect_create().returning(|e| {
+            Ok(Shelf {
+                id: ShelfId::new(),
+                name: e.name,
+                book_count: 0,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::post(v1("/users/me/shelves"))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"name": "読みたい"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let shelf = deserialize_json!(resp, ShelfResponse);
+    assert_eq!(shelf.name, "読みたい");
+    assert_eq!(shelf.book_count, 0);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn add_shelf_book_passes_current_user(
+    fixture_registry: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let shelf_id = ShelfId::new();
+    let book_id = BookId::new();
+    let user_id = UserId::new();
+    let mut registry = with_user(fixture_registry, user_id);
+    registry.expect_shelf_repository().returning(move || {
+        let mut mock = MockShelfRepository::new();
+        mock.expect_add_book()
+            .withf(move |e| e.shelf_id == shelf_id && e.book_id == book_id && e.user_id == user_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(registry, false));
+    let req = Request::put(v1(&format!("/users/me/shelves/{shelf_id}/books/{book_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    Ok(())
+}
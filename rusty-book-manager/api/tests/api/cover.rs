@@ -0,0 +1,289 @@
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use image::{ImageFormat, RgbaImage};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use api::model::book::BookResponse;
+use kernel::{
+    model::{
+        book::Book,
+        book_condition::BookCondition,
+        id::{BookId, UserId},
+        role::Role,
+        user::{BookOwner, User},
+    },
+    repository::{
+        auth::MockAuthRepository, blob::MockBlobStore, book::MockBookRepository,
+        user::MockUserRepository,
+    },
+};
+
+const BOUNDARY: &str = "cover-boundary";
+
+fn make_book(book_id: BookId, owner: UserId, cover_image: Option<&str>) -> Book {
+    Book {
+        id: book_id,
+        title: "RustによるWebアプリケーション開発".to_string(),
+        isbn: "9784065369579".to_string(),
+        author: "Yuki Toyoda".to_string(),
+        authors: vec![],
+        description: "".to_string(),
+        cover_url: None,
+        cover_image: cover_image.map(str::to_string),
+        bibliography: Default::default(),
+        tags: vec![],
+        genres: vec![],
+        rating: Default::default(),
+        owner: BookOwner {
+            id: owner,
+            name: "dummy-user".to_string(),
+        },
+        checkout: None,
+        condition: BookCondition::Good,
+        version: 1,
+    }
+}
+
+fn png(width: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Cursor::new(Vec::new());
+    RgbaImage::new(width, height).write_to(&mut buf, ImageFormat::Png)?;
+    Ok(buf.into_inner())
+}
+
+fn multipart_body(content_type: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"cover\"; filename=\"cover\"\r\nContent-Type: {content_type}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+    body
+}
+
+fn upload_request(book_id: BookId, body: Vec<u8>) -> anyhow::Result<Request<Body>> {
+    Ok(Request::put(v1(&format!("/books/{book_id}/cover")))
+        .bearer()
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))?)
+}
+
+// 認証済みユーザーを蔵書の所有者にするため、ユーザー ID を固定したレジストリを作る
+fn registry_for(owner: UserId) -> registry::MockAppRegistryExt {
+    let mut registry = registry::MockAppRegistryExt::new();
+    registry.expect_auth_repository().returning(move || {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_fetch_user_id_from_token()
+            .returning(move |_| Ok(Some(owner)));
+        Arc::new(mock)
+    });
+    registry.expect_user_repository().returning(|| {
+        let mut mock = MockUserRepository::new();
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "dummy-user".to_string(),
+                email: "dummy@example.com".to_string(),
+                role: Role::User,
+            }))
+        });
+        Arc::new(mock)
+    });
+    registry
+}
+
+#[rstest]
+#[tokio::test]
+async fn upload_cover_stores_image_and_thumbnail() -> anyhow::Result<()> {
+    let owner = UserId::new();
+    let book_id = BookId::new();
+    let mut registry = registry_for(owner);
+
+    // 一度目の取得は差し替え前、二度目は差し替え後の蔵書を返す
+    let stored = Arc::new(Mutex::new(Some("covers/old/1.png".to_string())));
+    let stored_for_find = stored.clone();
+    let stored_for_update = stored.clone();
+    let mut book_repository = MockBookRepository::new();
+    book_repository.expect_find_by_id().returning(move |id| {
+        let cover = stored_for_find.lock().unwrap().clone();
+        Ok(Some(make_book(id, owner, cover.as_deref())))
+    });
+    book_repository
+        .expect_update_cover()
+        .times(1)
+        .returning(move |event| Ok(stored_for_update.lock().unwrap().replace(event.cover_image)));
+    let book_repository = Arc::new(book_repository);
+    registry
+        .expect_book_repository()
+        .returning(move || book_repository.clone());
+
+    let puts = Arc::new(Mutex::new(Vec::new()));
+    let deletes = Arc::new(Mutex::new(Vec::new()));
+    let (puts_for_mock, deletes_for_mock) = (puts.clone(), deletes.clone());
+    let mut blob_store = MockBlobStore::new();
+    blob_store
+        .expect_put()
+        .returning(move |key, data, content_type| {
+            puts_for_mock
+                .lock()
+                .unwrap()
+                .push((key.to_string(), data, content_type.to_string()));
+            Ok(())
+        });
+    blob_store.expect_delete().returning(move |key| {
+        deletes_for_mock.lock().unwrap().push(key.to_string());
+        Ok(())
+    });
+    let blob_store = Arc::new(blob_store);
+    registry
+        .expect_blob_store()
+        .returning(move || blob_store.clone());
+
+    let app = make_router(registry);
+    let image = png(600, 900)?;
+    let resp = app
+        .oneshot(upload_request(
+            book_id,
+            multipart_body("image/png", &image),
+        )?)
+        .await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let result = deserialize_json!(resp, BookResponse);
+    assert_eq!(
+        result.cover_url.as_deref(),
+        Some(format!("/api/v1/books/{book_id}/cover").as_str())
+    );
+    assert!(result.cover_thumbnail_url.is_some());
+
+    let puts = puts.lock().unwrap();
+    assert_eq!(puts.len(), 2);
+    let (key, data, content_type) = &puts[0];
+    assert!(key.starts_with(&format!("covers/{book_id}/")) && key.ends_with(".png"));
+    assert_eq!(data, &image);
+    assert_eq!(content_type, "image/png");
+    let (thumb_key, thumb, thumb_content_type) = &puts[1];
+    assert!(thumb_key.ends_with(".thumb.jpg"));
+    assert_eq!(thumb_content_type, "image/jpeg");
+    let thumb = image::load_from_memory_with_format(thumb, ImageFormat::Jpeg)?;
+    assert_eq!((thumb.width(), thumb.height()), (160, 240));
+
+    assert_eq!(
+        *deletes.lock().unwrap(),
+        vec!["covers/old/1.thumb.jpg", "covers/old/1.png"]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case("text/plain", b"hello".to_vec(), StatusCode::UNSUPPORTED_MEDIA_TYPE)]
+#[case("image/jpeg", png(10, 10).unwrap(), StatusCode::UNSUPPORTED_MEDIA_TYPE)]
+#[case("image/png", b"\x89PNG\r\n\x1a\nbroken".to_vec(), StatusCode::UNPROCESSABLE_ENTITY)]
+#[case("image/png", vec![0u8; 5 * 1024 * 1024 + 1], StatusCode::PAYLOAD_TOO_LARGE)]
+#[tokio::test]
+async fn upload_cover_rejects_invalid_image(
+    #[case] content_type: &str,
+    #[case] data: Vec<u8>,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    let owner = UserId::new();
+    let mut registry = registry_for(owner);
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |id| Ok(Some(make_book(id, owner, None))));
+        Arc::new(mock)
+    });
+    // 検証に失敗した画像は保存しない
+    registry
+        .expect_blob_store()
+        .returning(|| Arc::new(MockBlobStore::new()));
+
+    let app = make_router(registry);
+    let resp = app
+        .oneshot(upload_request(
+            BookId::new(),
+            multipart_body(content_type, &data),
+        )?)
+        .await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn upload_cover_requires_owner(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(|id| Ok(Some(make_book(id, UserId::new(), None))));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let resp = app
+        .oneshot(upload_request(
+            BookId::new(),
+            multipart_body("image/png", &png(10, 10)?),
+        )?)
+        .await?;
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/cover", "covers/x/1.webp", "image/webp")]
+#[case("/cover/thumbnail", "covers/x/1.thumb.jpg", "image/jpeg")]
+#[tokio::test]
+async fn show_cover(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] suffix: &str,
+    #[case] expected_key: &'static str,
+    #[case] expected_content_type: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(|id| Ok(Some(make_book(id, UserId::new(), Some("covers/x/1.webp")))));
+        Arc::new(mock)
+    });
+    fixture.expect_blob_store().returning(move || {
+        let mut mock = MockBlobStore::new();
+        mock.expect_get()
+            .withf(move |key| key == expected_key)
+            .returning(|_| Ok(Some(b"image".to_vec())));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1(&format!("/books/{}{suffix}", BookId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], expected_content_type);
+
+    Ok(())
+}
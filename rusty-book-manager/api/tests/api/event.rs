@@ -0,0 +1,316 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::Body,
+    http::{Request, Response, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tokio::sync::broadcast;
+use tower::ServiceExt;
+
+use crate::helper::{fixture_auth, fixture_registry, make_router, v1, with_role, TestRequestExt};
+use kernel::{
+    model::{
+        domain_event::DomainEvent,
+        event_stream::{EventStreamId, StreamedEvent},
+        id::{BookId, CheckoutId, UserId},
+        role::Role,
+        user::User,
+    },
+    repository::{
+        auth::MockAuthRepository, event_stream::MockEventStreamRepository, user::MockUserRepository,
+    },
+};
+use registry::MockAppRegistryExt;
+
+fn streamed(id: &str, event: DomainEvent) -> StreamedEvent {
+    StreamedEvent {
+        id: id.parse().unwrap(),
+        event,
+        occurred_at: Utc::now(),
+    }
+}
+
+fn checkout_created(user_id: UserId) -> DomainEvent {
+    DomainEvent::CheckoutCreated {
+        checkout_id: CheckoutId::new(),
+        book_id: BookId::new(),
+        user_id,
+        checked_out_at: Utc::now(),
+        on_behalf_by: None,
+    }
+}
+
+// live のイベントを流し終えると接続が閉じるリポジトリを登録する
+fn with_event_stream(
+    mut registry: MockAppRegistryExt,
+    mut mock: MockEventStreamRepository,
+    live: Vec<StreamedEvent>,
+) -> MockAppRegistryExt {
+    let (sender, receiver) = broadcast::channel(16);
+    for event in live {
+        sender.send(event).unwrap();
+    }
+    mock.expect_subscribe().return_once(move || receiver);
+    registry
+        .expect_event_stream_repository()
+        .return_once(move || Arc::new(mock));
+    registry
+}
+
+// SSE の本文を (id, event, data) の組に分ける
+async fn read_events(
+    resp: Response<Body>,
+) -> anyhow::Result<Vec<(Option<String>, String, serde_json::Value)>> {
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let body = String::from_utf8(bytes.to_vec())?;
+    let mut events = Vec::new();
+    for block in body.split("\n\n").filter(|b| !b.trim().is_empty()) {
+        let (mut id, mut event, mut data) = (None, String::new(), serde_json::Value::Null);
+        for line in block.lines() {
+            match line.split_once(':') {
+                Some(("id", v)) => id = Some(v.trim().to_string()),
+                Some(("event", v)) => event = v.trim().to_string(),
+                Some(("data", v)) => data = serde_json::from_str(v.trim())?,
+                _ => {}
+            }
+        }
+        events.push((id, event, data));
+    }
+    Ok(events)
+}
+
+#[rstest]
+#[case(false, vec!["book.updated", "checkout.created"])]
+#[case(true, vec!["book.updated", "checkout.created", "user.created"])]
+#[tokio::test]
+async fn stream_events_filters_by_role(
+    fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected: Vec<&str>,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let borrower = UserId::new();
+    let registry = with_event_stream(
+        with_role(fixture_auth, admin),
+        MockEventStreamRepository::new(),
+        vec![
+            streamed("1-0", DomainEvent::BookUpdated { book_id }),
+            streamed("2-0", checkout_created(borrower)),
+            streamed("3-0", DomainEvent::UserCreated { user_id: borrower }),
+        ],
+    );
+
+    let app = make_router(registry);
+    let req = Request::get(v1("/events")).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/event-stream");
+
+    let events = read_events(resp).await?;
+    let types: Vec<&str> = events.iter().map(|(_, t, _)| t.as_str()).collect();
+    assert_eq!(types, expected);
+    assert_eq!(events[0].0.as_deref(), Some("1-0"));
+    assert_eq!(events[0].2["bookId"], book_id.to_string());
+    // 借りた利用者は管理者にしか見せない
+    assert_eq!(events[1].2.get("userId").is_some(), admin);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn stream_events_shows_own_checkout_to_borrower(
+    mut fixture_registry: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let viewer_id = UserId::new();
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_fetch_user_id_from_token()
+                .returning(move |_| Ok(Some(viewer_id)));
+            Arc::new(mock)
+        });
+    let registry = with_event_stream(
+        with_role(fixture_registry, false),
+        MockEventStreamRepository::new(),
+        vec![streamed("1-0", checkout_created(viewer_id))],
+    );
+
+    let app = make_router(registry);
+    let req = Request::get(v1("/events")).bearer().body(Body::empty())?;
+    let events = read_events(app.oneshot(req).await?).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].2["userId"], viewer_id.to_string());
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn stream_events_resumes_after_last_event_id(
+    fixture_auth: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let mut mock = MockEventStreamRepository::new();
+    mock.expect_find_after()
+        .withf(|after| *after == "10-0".parse::<EventStreamId>().unwrap())
+        .returning(move |_| {
+            Ok(Some(vec![
+                streamed("11-0", DomainEvent::BookUpdated { book_id }),
+                streamed("12-0", DomainEvent::BookDeleted { book_id }),
+            ]))
+        });
+    // 履歴を読んでいる間に届いたイベントは、履歴から送ったものと重ならない分だけ送る
+    let registry = with_event_stream(
+        with_role(fixture_auth, false),
+        mock,
+        vec![
+            streamed("12-0", DomainEvent::BookDeleted { book_id }),
+            streamed(
+                "13-0",
+                DomainEvent::BookCreated {
+                    book_id,
+                    owner_id: UserId::new(),
+                },
+            ),
+        ],
+    );
+
+    let app = make_router(registry);
+    let req = Request::get(v1("/events"))
+        .bearer()
+        .header("Last-Event-ID", "10-0")
+        .body(Body::empty())?;
+    let events = read_events(app.oneshot(req).await?).await?;
+    let ids: Vec<&str> = events
+        .iter()
+        .filter_map(|(id, _, _)| id.as_deref())
+        .collect();
+    assert_eq!(ids, vec!["11-0", "12-0", "13-0"]);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn stream_events_resets_when_history_is_gone(
+    fixture_auth: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let mut mock = MockEventStreamRepository::new();
+    mock.expect_find_after().returning(|_| Ok(None));
+    let registry = with_event_stream(
+        with_role(fixture_auth, false),
+        mock,
+        vec![streamed(
+            "20-0",
+            DomainEvent::BookUpdated {
+                book_id: BookId::new(),
+            },
+        )],
+    );
+
+    let app = make_router(registry);
+    let req = Request::get(v1("/events"))
+        .bearer()
+        .header("Last-Event-ID", "1-0")
+        .body(Body::empty())?;
+    let events = read_events(app.oneshot(req).await?).await?;
+    let types: Vec<&str> = events.iter().map(|(_, t, _)| t.as_str()).collect();
+    assert_eq!(types, vec!["reset", "book.updated"]);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn stream_events_rejects_malformed_last_event_id(
+    fixture_auth: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, false));
+    let req = Request::get(v1("/events"))
+        .bearer()
+        .header("Last-Event-ID", "not-an-id")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    Ok(())
+}
+
+// 接続時は管理者。接続後にトークン・ユーザーの状態が変わった場合を再現する
+#[rstest]
+#[case::logged_out(false, Some(true), true)]
+#[case::demoted(true, Some(false), true)]
+#[case::deleted(true, None, true)]
+#[case::unchanged(true, Some(true), false)]
+#[tokio::test(start_paused = true)]
+async fn stream_events_closes_when_no_longer_authorized(
+    mut fixture_registry: MockAppRegistryExt,
+    #[case] token_valid: bool,
+    #[case] admin_after: Option<bool>,
+    #[case] closed: bool,
+) -> anyhow::Result<()> {
+    let viewer_id = UserId::new();
+    // 1 回目は接続時の認証、2 回目以降は接続中の確認
+    let auth_calls = Arc::new(AtomicUsize::new(0));
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let auth_calls = auth_calls.clone();
+            let mut mock = MockAuthRepository::new();
+            mock.expect_fetch_user_id_from_token().returning(move |_| {
+                let first = auth_calls.fetch_add(1, Ordering::SeqCst) == 0;
+                Ok((first || token_valid).then_some(viewer_id))
+            });
+            Arc::new(mock)
+        });
+    let user_calls = Arc::new(AtomicUsize::new(0));
+    fixture_registry
+        .expect_user_repository()
+        .returning(move || {
+            let user_calls = user_calls.clone();
+            let mut mock = MockUserRepository::new();
+            mock.expect_find_current_user().returning(move |id| {
+                let first = user_calls.fetch_add(1, Ordering::SeqCst) == 0;
+                let admin = if first { Some(true) } else { admin_after };
+                Ok(admin.map(|admin| User {
+                    id,
+                    name: "dummy-user".into(),
+                    email: "dummy@example.com".into(),
+                    role: if admin { Role::Admin } else { Role::User },
+                }))
+            });
+            Arc::new(mock)
+        });
+    // 送信側を残しておき、イベントの受信では接続が閉じないようにする
+    let (sender, receiver) = broadcast::channel(16);
+    sender.send(streamed(
+        "1-0",
+        DomainEvent::BookUpdated {
+            book_id: BookId::new(),
+        },
+    ))?;
+    let mut mock = MockEventStreamRepository::new();
+    mock.expect_subscribe().return_once(move || receiver);
+    fixture_registry
+        .expect_event_stream_repository()
+        .return_once(move || Arc::new(mock));
+
+    let app = make_router(fixture_registry);
+    let req = Request::get(v1("/events")).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let res = tokio::time::timeout(Duration::from_secs(60), read_events(resp)).await;
+    assert_eq!(res.is_ok(), closed);
+    if let Ok(events) = res {
+        let types: Vec<String> = events?.into_iter().map(|(_, t, _)| t).collect();
+        assert_eq!(types, vec!["book.updated"]);
+    }
+    drop(sender);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use kernel::{
+    model::health::{DependencyHealth, HealthStatus},
+    repository::health::MockHealthCheckRepository,
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_registry, make_router, v1},
+};
+
+fn dependency(name: &'static str, status: HealthStatus, required: bool) -> DependencyHealth {
+    DependencyHealth {
+        name,
+        status,
+        required,
+        latency: Duration::from_millis(1),
+        detail: None,
+    }
+}
+
+#[rstest]
+#[case(HealthStatus::Up, HealthStatus::Up, StatusCode::OK, "up")]
+#[case(HealthStatus::Up, HealthStatus::Degraded, StatusCode::OK, "degraded")]
+#[case(
+    HealthStatus::Down,
+    HealthStatus::Up,
+    StatusCode::SERVICE_UNAVAILABLE,
+    "down"
+)]
+#[tokio::test]
+async fn health_check_ready(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] redis: HealthStatus,
+    #[case] pool: HealthStatus,
+    #[case] status_code: StatusCode,
+    #[case] expected_status: &str,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_health_check_repository()
+        .returning(move || {
+            let mut mock = MockHealthCheckRepository::new();
+            mock.expect_check_database()
+                .returning(|| dependency("database", HealthStatus::Up, true));
+            mock.expect_check_redis()
+                .returning(move || dependency("redis", redis, true));
+            mock.expect_check_migrations()
+                .returning(|| dependency("migrations", HealthStatus::Up, true));
+            mock.expect_check_pool()
+                .returning(move || dependency("pool", pool, false));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+
+    let req = Request::get(v1("/health/ready")).body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), status_code);
+
+    let result = deserialize_json!(resp, serde_json::Value);
+    assert_eq!(result["status"], expected_status);
+    assert_eq!(result["checks"].as_array().map(Vec::len), Some(4));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn health_check_live(fixture_registry: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let app: axum::Router = make_router(fixture_registry);
+
+    let req = Request::get(v1("/health/live")).body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
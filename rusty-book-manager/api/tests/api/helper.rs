@@ -62,8 +62,26 @@ pub fn fixture(mut fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
     fixture_auth
 }
 
+// 管理者かどうかを指定して、認証済みのユーザーを返すようにする
+pub fn with_role(mut registry: MockAppRegistryExt, admin: bool) -> MockAppRegistryExt {
+    registry.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+        mock.expect_find_current_user().returning(move |id| {
+            Ok(Some(User {
+                id,
+                name: "dummy-user".into(),
+                email: "dummy@example.com".into(),
+                role: if admin { Role::Admin } else { Role::User },
+            }))
+        });
+        Arc::new(mock)
+    });
+    registry
+}
+
 pub trait TestRequestExt {
     fn bearer(self) -> Builder;
+    #[allow(dead_code)]
     fn application_json(self) -> Builder;
 }
 
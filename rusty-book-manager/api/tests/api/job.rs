@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::job::{JobNameName, JobRunStatusName, PaginatedJobRunResponse};
+use kernel::{
+    model::{
+        id::JobRunId,
+        job::{JobName, JobRun, JobRunStatus},
+        list::PaginatedList,
+    },
+    repository::job::MockJobRunRepository,
+};
+use registry::MockAppRegistryExt;
+
+#[rstest]
+#[tokio::test]
+async fn list_job_runs_requires_admin(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, false));
+    let req = Request::get(v1("/jobs/runs"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    Ok(())
+}
+
+#[rstest]
+#[case("/jobs/runs", None, None)]
+#[case(
+    "/jobs/runs?job=token_cleanup&status=failed",
+    Some(JobName::TokenCleanup),
+    Some(JobRunStatus::Failed)
+)]
+#[tokio::test]
+async fn list_job_runs_with_filters(
+    fixture_auth: MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_job: Option<JobName>,
+    #[case] expected_status: Option<JobRunStatus>,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, true);
+    let run_id = JobRunId::new();
+    registry.expect_job_run_repository().returning(move || {
+        let mut mock = MockJobRunRepository::new();
+        mock.expect_find_all()
+            .withf(move |options| {
+                options.job_name == expected_job
+                    && options.status == expected_status
+                    && options.limit == 20
+            })
+            .returning(move |options| {
+                let now = Utc::now();
+                Ok(PaginatedList {
+                    total: 1,
+                    limit: options.limit,
+                    offset: options.offset,
+                    items: vec![JobRun {
+                        id: run_id,
+                        job_name: JobName::TokenCleanup,
+                        status: JobRunStatus::Failed,
+                        scheduled_at: now,
+                        started_at: now,
+                        finished_at: Some(now),
+                        processed: None,
+                        failed: None,
+                        error: Some("unavailable".into()),
+                    }],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, PaginatedJobRunResponse);
+    assert_eq!(body.total, 1);
+    assert_eq!(body.items[0].id, run_id);
+    assert_eq!(body.items[0].job, JobNameName::TokenCleanup);
+    assert_eq!(body.items[0].status, JobRunStatusName::Failed);
+    assert_eq!(body.items[0].error.as_deref(), Some("unavailable"));
+    Ok(())
+}
+
+#[rstest]
+#[case("/jobs/runs?job=unknown")]
+#[case("/jobs/runs?limit=-1")]
+#[tokio::test]
+async fn list_job_runs_rejects_invalid_query(
+    fixture_auth: MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
@@ -1,2 +1,19 @@
+mod author;
 mod book;
+mod book_condition;
+mod book_transfer;
+mod checkout;
+mod checkout_history;
+mod classification;
+mod cover;
+mod event;
+mod health;
 mod helper;
+mod job;
+mod notification;
+mod rate_limit;
+mod recommendation;
+mod report;
+mod review;
+mod user;
+mod webhook;
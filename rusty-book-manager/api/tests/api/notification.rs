@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::notification::{
+    NotificationKindName, NotificationPreferencesResponse, PaginatedNotificationResponse,
+};
+use kernel::{
+    model::{
+        id::{NotificationId, UserId},
+        list::PaginatedList,
+        notification::{Notification, NotificationKind, NotificationPreference},
+    },
+    repository::notification::MockNotificationRepository,
+};
+use registry::MockAppRegistryExt;
+use shared::error::AppError;
+use strum::IntoEnumIterator;
+
+#[rstest]
+#[case("/users/me/notifications", false)]
+#[case("/users/me/notifications?unread=true", true)]
+#[tokio::test]
+async fn list_notifications_with_unread_count(
+    fixture_auth: MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_unread_only: bool,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    let notification_id = NotificationId::new();
+    registry
+        .expect_notification_repository()
+        .returning(move || {
+            let mut mock = MockNotificationRepository::new();
+            mock.expect_find_by_user_id()
+                .withf(move |_, options| {
+                    options.unread_only == expected_unread_only && options.limit == 20
+                })
+                .returning(move |user_id, options| {
+                    Ok(PaginatedList {
+                        total: 1,
+                        limit: options.limit,
+                        offset: options.offset,
+                        items: vec![Notification {
+                            id: notification_id,
+                            user_id,
+                            kind: NotificationKind::CheckoutOverdue,
+                            title: "返却期限を過ぎています".into(),
+                            body: "速やかにご返却ください。".into(),
+                            book_id: None,
+                            read_at: None,
+                            created_at: Utc::now(),
+                        }],
+                    })
+                });
+            mock.expect_count_unread().returning(|_| Ok(3));
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, PaginatedNotificationResponse);
+    assert_eq!(body.total, 1);
+    assert_eq!(body.unread_count, 3);
+    assert_eq!(body.items[0].id, notification_id);
+    assert_eq!(body.items[0].kind, NotificationKindName::CheckoutOverdue);
+    assert!(body.items[0].read_at.is_none());
+    Ok(())
+}
+
+// ほかの利用者の通知は見つからないものとして扱う
+#[rstest]
+#[case(true, StatusCode::NO_CONTENT)]
+#[case(false, StatusCode::NOT_FOUND)]
+#[tokio::test]
+async fn mark_notification_read(
+    fixture_auth: MockAppRegistryExt,
+    #[case] found: bool,
+    #[case] expected_status: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    let notification_id = NotificationId::new();
+    registry
+        .expect_notification_repository()
+        .returning(move || {
+            let mut mock = MockNotificationRepository::new();
+            mock.expect_mark_read()
+                .withf(move |event| event.notification_id == notification_id)
+                .returning(move |_| {
+                    if found {
+                        Ok(())
+                    } else {
+                        Err(AppError::EntityNotFound("not found".into()))
+                    }
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::put(v1(&format!(
+        "/users/me/notifications/{notification_id}/read"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn mark_all_notifications_read(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    registry.expect_notification_repository().returning(|| {
+        let mut mock = MockNotificationRepository::new();
+        mock.expect_mark_all_read().times(1).returning(|_| Ok(2));
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let req = Request::put(v1("/users/me/notifications/read"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_notification_preferences(fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let mut registry = with_role(fixture_auth, false);
+    registry.expect_notification_repository().returning(|| {
+        let mut mock = MockNotificationRepository::new();
+        mock.expect_update_preferences()
+            .withf(|event| {
+                event.preferences
+                    == vec![NotificationPreference {
+                        kind: NotificationKind::BookReturned,
+                        email: true,
+                    }]
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        mock.expect_find_preferences().returning(|_: UserId| {
+            Ok(NotificationKind::iter()
+                .map(|kind| NotificationPreference {
+                    kind,
+                    email: kind == NotificationKind::BookReturned || kind.email_by_default(),
+                })
+                .collect())
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(registry);
+    let body = serde_json::json!({
+        "items": [{ "kind": "book_returned", "email": true }]
+    });
+    let req = Request::put(v1("/users/me/notifications/preferences"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, NotificationPreferencesResponse);
+    assert_eq!(body.items.len(), NotificationKind::iter().count());
+    assert!(body
+        .items
+        .iter()
+        .any(|p| p.kind == NotificationKindName::BookReturned && p.email));
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_notification_preferences_rejects_unknown_kind(
+    fixture_auth: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, false));
+    let body = serde_json::json!({
+        "items": [{ "kind": "hold_available", "email": true }]
+    });
+    let req = Request::put(v1("/users/me/notifications/preferences"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    Ok(())
+}
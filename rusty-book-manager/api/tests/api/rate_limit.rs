@@ -0,0 +1,169 @@
+use std::{sync::Arc, time::Duration};
+
+use api::{middleware::rate_limit::RateLimitLayer, route::v1};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use kernel::{
+    model::rate_limit::RateLimitDecision, repository::rate_limit::MockRateLimitRepository,
+};
+use registry::{AppRegistry, MockAppRegistryExt};
+use rstest::rstest;
+use shared::{
+    config::{RateLimitConfig, RateLimitKey, RateLimitPolicy},
+    error::AppError,
+};
+use tower::ServiceExt;
+
+use crate::helper::{fixture_auth, fixture_registry, v1, TestRequestExt};
+
+fn make_router(registry: MockAppRegistryExt, limit: u64) -> Router {
+    make_router_with(registry, limit, false)
+}
+
+fn make_router_with(registry: MockAppRegistryExt, limit: u64, trust_forwarded_for: bool) -> Router {
+    let registry: AppRegistry = Arc::new(registry);
+    let config = RateLimitConfig {
+        enabled: true,
+        trust_forwarded_for,
+        policies: vec![RateLimitPolicy {
+            name: "health".into(),
+            method: Some("GET".into()),
+            path: "/api/v1/health".into(),
+            limit,
+            window: Duration::from_secs(60),
+            key: RateLimitKey::User,
+        }],
+    };
+    Router::new()
+        .merge(v1::routes())
+        .layer(RateLimitLayer::new(registry.clone(), config))
+        .with_state(registry)
+}
+
+fn decision(allowed: bool, remaining: u64) -> RateLimitDecision {
+    RateLimitDecision {
+        allowed,
+        limit: 5,
+        remaining,
+        reset_after: Duration::from_millis(1500),
+    }
+}
+
+#[rstest]
+#[case(true, StatusCode::OK)]
+#[case(false, StatusCode::TOO_MANY_REQUESTS)]
+#[tokio::test]
+async fn rate_limit_by_user(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] allowed: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    fixture_auth
+        .expect_rate_limit_repository()
+        .returning(move || {
+            let mut mock = MockRateLimitRepository::new();
+            mock.expect_hit()
+                .withf(|key, quota| key.starts_with("health:user:") && quota.limit == 5)
+                .returning(move |_, _| Ok(decision(allowed, if allowed { 4 } else { 0 })));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_auth, 5);
+    let req = Request::get(v1("/health")).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), expected);
+    let headers = resp.headers();
+    assert_eq!(headers["RateLimit-Limit"], "5");
+    assert_eq!(headers["RateLimit-Reset"], "2");
+    assert_eq!(headers["RateLimit-Policy"], "5;w=60");
+    if allowed {
+        assert_eq!(headers["RateLimit-Remaining"], "4");
+        assert!(!headers.contains_key("Retry-After"));
+    } else {
+        assert_eq!(headers["RateLimit-Remaining"], "0");
+        assert_eq!(headers["Retry-After"], "2");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn rate_limit_falls_back_to_ip_and_fails_open(
+    mut fixture_registry: MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_rate_limit_repository()
+        .returning(|| {
+            let mut mock = MockRateLimitRepository::new();
+            // 接続元が分からないテスト環境ではまとめて数えられる
+            mock.expect_hit()
+                .withf(|key, _| key == "health:ip:unknown")
+                .returning(|_, _| Err(AppError::UnprocessableEntity("redis is down".into())));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry, 5);
+    let req = Request::get(v1("/health")).body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(!resp.headers().contains_key("RateLimit-Limit"));
+
+    Ok(())
+}
+
+#[rstest]
+#[case("203.0.113.7")]
+#[case("198.51.100.1, 203.0.113.7")]
+#[case("10.0.0.1, 192.0.2.99, 203.0.113.7")]
+#[tokio::test]
+async fn rate_limit_uses_ip_appended_by_proxy(
+    mut fixture_registry: MockAppRegistryExt,
+    #[case] forwarded_for: &str,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_rate_limit_repository()
+        .returning(|| {
+            let mut mock = MockRateLimitRepository::new();
+            // クライアントが先頭に付け足した値ではなく、プロキシが追加した末尾の値で数える
+            mock.expect_hit()
+                .withf(|key, _| key == "health:ip:203.0.113.7")
+                .returning(|_, _| Ok(decision(true, 4)));
+            Arc::new(mock)
+        });
+
+    let app = make_router_with(fixture_registry, 5, true);
+    let req = Request::get(v1("/health"))
+        .header("X-Forwarded-For", forwarded_for)
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/health", 0)]
+#[case("/healthz", 5)]
+#[tokio::test]
+async fn rate_limit_is_skipped_without_matching_policy(
+    fixture_registry: MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] limit: u64,
+) -> anyhow::Result<()> {
+    // rate_limit_repository が呼ばれるとモックが panic する
+    let app = make_router(fixture_registry, limit);
+    let req = Request::get(v1(path)).body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_ne!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(!resp.headers().contains_key("RateLimit-Limit"));
+
+    Ok(())
+}
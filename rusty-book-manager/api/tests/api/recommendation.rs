@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use api::model::recommendation::RecommendationsResponse;
+use kernel::{
+    model::{id::BookId, recommendation::Recommendation},
+    repository::recommendation::MockRecommendationRepository,
+};
+use registry::MockAppRegistryExt;
+
+#[rstest]
+#[case("/users/me/recommendations", StatusCode::OK, 10)]
+#[case("/users/me/recommendations?limit=50", StatusCode::OK, 50)]
+#[case("/users/me/recommendations?limit=0", StatusCode::BAD_REQUEST, 0)]
+#[case("/users/me/recommendations?limit=51", StatusCode::BAD_REQUEST, 0)]
+#[tokio::test]
+async fn get_recommendations_with_limit(
+    mut fixture: MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected: StatusCode,
+    #[case] expected_limit: i64,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture
+        .expect_recommendation_repository()
+        .returning(move || {
+            let mut mock = MockRecommendationRepository::new();
+            mock.expect_find_by_user_id()
+                .withf(move |_, limit| *limit == expected_limit)
+                .returning(move |_, _| {
+                    Ok(vec![Recommendation {
+                        book_id,
+                        title: "RustによるWebアプリケーション開発".into(),
+                        author: "豊田優貴".into(),
+                        isbn: "978-4065369579".into(),
+                        score: 4,
+                        co_checkout_count: 1,
+                        shared_author_count: 1,
+                        shared_tag_count: 0,
+                    }])
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == StatusCode::OK {
+        let body = deserialize_json!(resp, RecommendationsResponse);
+        assert_eq!(body.items.len(), 1);
+        assert_eq!(body.items[0].book_id, book_id);
+        assert_eq!(body.items[0].score, 4);
+    }
+    Ok(())
+}
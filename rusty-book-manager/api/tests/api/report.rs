@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::report::{CheckoutCountResponse, LoanDurationResponse, ReportItemsResponse};
+use kernel::{
+    model::{
+        id::BookId,
+        report::{BookCheckoutRanking, CheckoutCount, Granularity, LoanDuration, ReportPeriod},
+    },
+    repository::report::MockReportRepository,
+};
+use registry::MockAppRegistryExt;
+
+#[rstest]
+#[case("/reports/checkouts")]
+#[case("/reports/popular-books")]
+#[case("/reports/popular-authors")]
+#[case("/reports/top-borrowers")]
+#[case("/reports/idle-books")]
+#[case("/reports/loan-duration")]
+#[tokio::test]
+async fn reports_require_admin(
+    fixture_auth: MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, false));
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_counts_by_week(mut fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let period = ReportPeriod {
+        from: Some(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()),
+        to: Some(Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap()),
+    };
+    fixture_auth.expect_report_repository().returning(move || {
+        let mut mock = MockReportRepository::new();
+        mock.expect_checkout_counts()
+            .withf(move |p, g| *p == period && *g == Granularity::Week)
+            .returning(|_, _| {
+                Ok(vec![CheckoutCount {
+                    period_start: Utc.with_ymd_and_hms(2025, 2, 24, 0, 0, 0).unwrap(),
+                    count: 3,
+                }])
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::get(v1(
+        "/reports/checkouts?from=2025-03-01&to=2025-03-31&granularity=week",
+    ))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, ReportItemsResponse<CheckoutCountResponse>);
+    assert_eq!(body.items.len(), 1);
+    assert_eq!(body.items[0].count, 3);
+    Ok(())
+}
+
+#[rstest]
+#[case("?limit=0")]
+#[case("?limit=101")]
+#[case("?from=2025-03-02&to=2025-03-01")]
+#[case("?format=xml")]
+#[tokio::test]
+async fn popular_books_rejects_invalid_query(
+    fixture_auth: MockAppRegistryExt,
+    #[case] query: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::get(v1(&format!("/reports/popular-books{query}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn popular_books_as_csv(mut fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture_auth.expect_report_repository().returning(move || {
+        let mut mock = MockReportRepository::new();
+        mock.expect_top_books()
+            .withf(|_, limit| *limit == 5)
+            .returning(move |_, _| {
+                Ok(vec![BookCheckoutRanking {
+                    book_id,
+                    title: "実践Rustプログラミング入門".into(),
+                    author: "初田直也, 山口聖弘".into(),
+                    checkout_count: 12,
+                }])
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::get(v1("/reports/popular-books?limit=5&format=csv"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()[header::CONTENT_DISPOSITION],
+        "attachment; filename=\"popular-books.csv\""
+    );
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let text = String::from_utf8(bytes.to_vec())?;
+    assert_eq!(
+        text.strip_prefix('\u{feff}').unwrap(),
+        format!(
+            "book_id,title,author,checkout_count\n{book_id},実践Rustプログラミング入門,\"初田直也, 山口聖弘\",12\n"
+        )
+    );
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn loan_duration_without_returns(mut fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_report_repository().returning(|| {
+        let mut mock = MockReportRepository::new();
+        mock.expect_loan_duration().returning(|_| {
+            Ok(LoanDuration {
+                returned_count: 0,
+                average_days: None,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::get(v1("/reports/loan-duration"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, LoanDurationResponse);
+    assert_eq!(body.returned_count, 0);
+    assert_eq!(body.average_days, None);
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_auth, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::review::ReviewResponse;
+use kernel::{
+    model::{
+        book::BookSort,
+        id::{BookId, ReviewId},
+        list::PaginatedList,
+        review::Review,
+        user::ReviewUser,
+    },
+    repository::{book::MockBookRepository, review::MockReviewRepository},
+};
+use registry::MockAppRegistryExt;
+
+#[rstest]
+#[case(r#"{"rating": 5, "body": "良い本"}"#, StatusCode::CREATED)]
+#[case(r#"{"rating": 1}"#, StatusCode::CREATED)]
+#[case(r#"{"rating": 0}"#, StatusCode::BAD_REQUEST)]
+#[case(r#"{"rating": 6}"#, StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn create_review_validates_rating(
+    mut fixture: MockAppRegistryExt,
+    #[case] body: &'static str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_review_repository().returning(move || {
+        let mut mock = MockReviewRepository::new();
+        mock.expect_create()
+            .withf(move |e| e.book_id == book_id)
+            .returning(|e| {
+                Ok(Review {
+                    id: ReviewId::new(),
+                    book_id: e.book_id,
+                    reviewed_by: ReviewUser {
+                        id: e.reviewed_by,
+                        name: "dummy-user".into(),
+                    },
+                    rating: e.rating,
+                    body: e.body,
+                    hidden: false,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::post(v1(&format!("/books/{book_id}/reviews")))
+        .bearer()
+        .application_json()
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == StatusCode::CREATED {
+        let review = deserialize_json!(resp, ReviewResponse);
+        assert_eq!(review.book_id, book_id);
+        assert!(!review.hidden);
+    }
+    Ok(())
+}
+
+// 管理者は投稿者を問わず削除できる
+#[rstest]
+#[case(true)]
+#[case(false)]
+#[tokio::test]
+async fn delete_review_by_admin_ignores_author(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_review_repository().returning(move || {
+        let mut mock = MockReviewRepository::new();
+        mock.expect_delete()
+            .withf(move |e| e.requested_user.is_none() == admin)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, admin));
+    let req = Request::delete(v1(&format!(
+        "/books/{}/reviews/{}",
+        BookId::new(),
+        ReviewId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    Ok(())
+}
+
+#[rstest]
+#[case(true, StatusCode::OK)]
+#[case(false, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn update_review_visibility_requires_admin(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_review_repository().returning(|| {
+        let mut mock = MockReviewRepository::new();
+        mock.expect_moderate()
+            .withf(|e| e.hidden)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, admin));
+    let req = Request::put(v1(&format!(
+        "/books/{}/reviews/{}/visibility",
+        BookId::new(),
+        ReviewId::new()
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(r#"{"hidden": true}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    Ok(())
+}
+
+#[rstest]
+#[case("/books", StatusCode::OK, BookSort::Newest)]
+#[case("/books?sort=rating", StatusCode::OK, BookSort::Rating)]
+#[case("/books?sort=review_count", StatusCode::OK, BookSort::ReviewCount)]
+#[case("/books?sort=popular", StatusCode::BAD_REQUEST, BookSort::Newest)]
+#[tokio::test]
+async fn show_book_list_with_sort(
+    mut fixture: MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected: StatusCode,
+    #[case] sort: BookSort,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all()
+            .withf(move |opt| opt.sort == sort)
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, TestRequestExt},
+};
+use api::model::user::UserResponse;
+use kernel::{
+    model::{
+        id::UserId,
+        role::Role,
+        user::{EmailVerification, EmailVerificationToken, User},
+    },
+    repository::{
+        blob::MockBlobStore, email_verification::MockEmailVerificationRepository,
+        mail::MockMailRepository, user::MockUserRepository,
+    },
+};
+
+fn make_user(id: UserId) -> User {
+    User {
+        id,
+        name: "dummy-user".to_string(),
+        email: "dummy@example.com".to_string(),
+        role: Role::User,
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_current_user_name(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    // 呼び出し回数を検証するため、同じモックを返す
+    let mut mock = MockUserRepository::new();
+    mock.expect_find_current_user()
+        .returning(|id| Ok(Some(make_user(id))));
+    mock.expect_update_name()
+        .withf(|event| event.name == "new-name")
+        .times(1)
+        .returning(|_| Ok(()));
+    let mock = Arc::new(mock);
+    fixture_auth
+        .expect_user_repository()
+        .returning(move || mock.clone());
+
+    let app = make_router(fixture_auth);
+    let req = Request::patch(v1("/users/me"))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"name": "new-name"}"#))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(r#"{"name": null}"#, StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(
+    r#"{"name": "new-name", "email": null}"#,
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[case(r#"{"name": "new-name", "email": "invalid"}"#, StatusCode::BAD_REQUEST)]
+#[case(r#"{"name": "", "email": "new@example.com"}"#, StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn update_current_user_rejects_invalid_patch(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] body: &'static str,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_user_repository().returning(|| {
+        let mut mock = MockUserRepository::new();
+        mock.expect_find_current_user()
+            .returning(|id| Ok(Some(make_user(id))));
+        // どれか一つでも不正なら何も変更しない
+        mock.expect_update_name().never();
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_auth);
+    let req = Request::patch(v1("/users/me"))
+        .bearer()
+        .header(header::CONTENT_TYPE, "application/merge-patch+json")
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(None, true, StatusCode::ACCEPTED)]
+#[case(Some(UserId::new()), true, StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(None, false, StatusCode::SERVICE_UNAVAILABLE)]
+#[tokio::test]
+async fn update_current_user_email_requires_verification(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] existing_user: Option<UserId>,
+    #[case] deliverable: bool,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    let accepted = status_code == StatusCode::ACCEPTED;
+    // 呼び出し回数を検証するため、同じモックを返す
+    let mut mock = MockUserRepository::new();
+    mock.expect_find_current_user()
+        .returning(|id| Ok(Some(make_user(id))));
+    mock.expect_find_by_email()
+        .returning(move |_| Ok(existing_user.map(make_user)));
+    // メールアドレスが使えない場合は名前も変更しない
+    mock.expect_update_name()
+        .times(usize::from(accepted))
+        .returning(|_| Ok(()));
+    // 確認が済むまでメールアドレスは変更しない
+    mock.expect_update_email().never();
+    let mock = Arc::new(mock);
+    fixture_auth
+        .expect_user_repository()
+        .returning(move || mock.clone());
+    fixture_auth
+        .expect_email_verification_repository()
+        .returning(move || {
+            let mut mock = MockEmailVerificationRepository::new();
+            mock.expect_create()
+                .withf(|event| event.new_email == "new@example.com")
+                .times(usize::from(accepted))
+                .returning(|event| Ok(EmailVerificationToken(event.token)));
+            Arc::new(mock)
+        });
+    // メールを届けられない場合は確認用のトークンを発行しない
+    let mut mail = MockMailRepository::new();
+    mail.expect_is_deliverable().return_const(deliverable);
+    mail.expect_send()
+        .withf(|mail| mail.to == "new@example.com")
+        .times(usize::from(accepted))
+        .returning(|_| Ok(()));
+    let mail = Arc::new(mail);
+    fixture_auth
+        .expect_mail_repository()
+        .returning(move || mail.clone());
+
+    let app = make_router(fixture_auth);
+    let req = Request::patch(v1("/users/me"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            r#"{"name": "new-name", "email": "new@example.com"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+    if accepted {
+        let result = deserialize_json!(resp, UserResponse);
+        assert_eq!(result.email, "dummy@example.com");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(true, true, StatusCode::OK)]
+#[case(true, false, StatusCode::FORBIDDEN)]
+#[case(false, true, StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn verify_email(
+    #[case] found: bool,
+    #[case] same_user: bool,
+    #[case] status_code: StatusCode,
+) -> anyhow::Result<()> {
+    // トークンとユーザーを照合するため、認証済みユーザーの ID を固定する
+    let user_id = UserId::new();
+    let mut registry = registry::MockAppRegistryExt::new();
+    registry.expect_auth_repository().returning(move || {
+        let mut mock = kernel::repository::auth::MockAuthRepository::new();
+        mock.expect_fetch_user_id_from_token()
+            .returning(move |_| Ok(Some(user_id)));
+        Arc::new(mock)
+    });
+    let mut mock = MockUserRepository::new();
+    mock.expect_find_current_user()
+        .returning(|id| Ok(Some(make_user(id))));
+    mock.expect_update_email()
+        .withf(move |event| event.user_id == user_id && event.email == "new@example.com")
+        .times(usize::from(status_code == StatusCode::OK))
+        .returning(|_| Ok(()));
+    let mock = Arc::new(mock);
+    registry
+        .expect_user_repository()
+        .returning(move || mock.clone());
+    registry
+        .expect_email_verification_repository()
+        .returning(move || {
+            let mut mock = MockEmailVerificationRepository::new();
+            mock.expect_consume()
+                .withf(|token| token.0 == "token")
+                .returning(move |_| {
+                    Ok(found.then(|| EmailVerification {
+                        user_id: if same_user { user_id } else { UserId::new() },
+                        new_email: "new@example.com".into(),
+                    }))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(registry);
+    let req = Request::post(v1("/users/me/email/verify"))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"token": "token"}"#))?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), status_code);
+
+    Ok(())
+}
+
+// 削除をコミットした後で、一緒に削除された蔵書の表紙とサムネイルを消す
+#[rstest]
+#[tokio::test]
+async fn delete_user_removes_cover_images(
+    fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = fixture_auth;
+    registry.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                role: Role::Admin,
+                ..make_user(id)
+            }))
+        });
+        mock.expect_delete()
+            .withf(move |event| event.user_id == user_id)
+            .returning(|_| Ok(vec!["covers/1/a.png".into(), "covers/2/b.jpg".into()]));
+        Arc::new(mock)
+    });
+    let deletes = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let deletes_for_mock = deletes.clone();
+    let mut blob_store = MockBlobStore::new();
+    blob_store.expect_delete().returning(move |key| {
+        deletes_for_mock.lock().unwrap().push(key.to_string());
+        Ok(())
+    });
+    let blob_store = Arc::new(blob_store);
+    registry
+        .expect_blob_store()
+        .returning(move || blob_store.clone());
+
+    let app = make_router(registry);
+    let req = Request::delete(v1(&format!("/users/{user_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        *deletes.lock().unwrap(),
+        vec![
+            "covers/1/a.thumb.jpg",
+            "covers/1/a.png",
+            "covers/2/b.thumb.jpg",
+            "covers/2/b.jpg"
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request, http::StatusCode};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, with_role, TestRequestExt},
+};
+use api::model::webhook::{
+    PaginatedWebhookDeliveryResponse, WebhookDeliveryStatusName, WebhookEventTypeName,
+    WebhookSubscriptionResponse,
+};
+use kernel::{
+    model::{
+        id::{WebhookDeliveryId, WebhookSubscriptionId},
+        list::PaginatedList,
+        webhook::{WebhookDelivery, WebhookDeliveryStatus, WebhookEventType, WebhookSubscription},
+    },
+    repository::webhook::MockWebhookRepository,
+};
+use registry::MockAppRegistryExt;
+use shared::error::AppError;
+
+#[rstest]
+#[case(Request::get(v1("/webhooks")))]
+#[case(Request::get(v1("/webhooks/deliveries")))]
+#[case(Request::delete(v1(&format!("/webhooks/{}", WebhookSubscriptionId::new()))))]
+#[case(Request::post(v1(&format!("/webhooks/deliveries/{}/redeliver", WebhookDeliveryId::new()))))]
+#[tokio::test]
+async fn webhooks_require_admin(
+    fixture_auth: MockAppRegistryExt,
+    #[case] req: axum::http::request::Builder,
+) -> anyhow::Result<()> {
+    let app = make_router(with_role(fixture_auth, false));
+    let resp = app.oneshot(req.bearer().body(Body::empty())?).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    r#"{"url": "https://chat.example.com/hook", "secret": "0123456789abcdef", "eventTypes": ["book.created", "checkout.returned", "book.created"]}"#,
+    StatusCode::CREATED
+)]
+#[case(
+    r#"{"url": "ftp://chat.example.com/hook", "secret": "0123456789abcdef", "eventTypes": ["book.created"]}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "http://127.0.0.1:8080/hook", "secret": "0123456789abcdef", "eventTypes": ["book.created"]}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "http://169.254.169.254/latest/meta-data", "secret": "0123456789abcdef", "eventTypes": ["book.created"]}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "http://[::ffff:10.0.0.1]/hook", "secret": "0123456789abcdef", "eventTypes": ["book.created"]}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "http://LOCALHOST./hook", "secret": "0123456789abcdef", "eventTypes": ["book.created"]}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "https://chat.example.com/hook", "secret": "short", "eventTypes": ["book.created"]}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "https://chat.example.com/hook", "secret": "0123456789abcdef", "eventTypes": []}"#,
+    StatusCode::BAD_REQUEST
+)]
+#[case(
+    r#"{"url": "https://chat.example.com/hook", "secret": "0123456789abcdef", "eventTypes": ["book.deleted"]}"#,
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn create_webhook_subscription(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] body: &'static str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_webhook_repository().returning(|| {
+        let mut mock = MockWebhookRepository::new();
+        // 重複したイベントはまとめて登録する
+        mock.expect_create_subscription()
+            .withf(|e| {
+                e.secret == "0123456789abcdef"
+                    && e.event_types
+                        == vec![
+                            WebhookEventType::BookCreated,
+                            WebhookEventType::CheckoutReturned,
+                        ]
+            })
+            .returning(|e| {
+                Ok(WebhookSubscription {
+                    id: WebhookSubscriptionId::new(),
+                    url: e.url,
+                    event_types: e.event_types,
+                    created_at: Utc::now(),
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::post(v1("/webhooks"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == StatusCode::CREATED {
+        let subscription = deserialize_json!(resp, WebhookSubscriptionResponse);
+        assert_eq!(subscription.url, "https://chat.example.com/hook");
+        assert_eq!(
+            subscription.event_types,
+            vec![
+                WebhookEventTypeName::BookCreated,
+                WebhookEventTypeName::CheckoutReturned
+            ]
+        );
+    }
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_dead_webhook_deliveries(mut fixture_auth: MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_webhook_repository().returning(|| {
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_find_deliveries()
+            .withf(|o| o.status == Some(WebhookDeliveryStatus::Dead) && o.limit == 20)
+            .returning(|o| {
+                Ok(PaginatedList {
+                    total: 1,
+                    limit: o.limit,
+                    offset: o.offset,
+                    items: vec![WebhookDelivery {
+                        id: WebhookDeliveryId::new(),
+                        subscription_id: WebhookSubscriptionId::new(),
+                        event_type: WebhookEventType::BookCreated,
+                        payload: r#"{"type": "book.created", "data": {"title": "Rust"}}"#.into(),
+                        status: WebhookDeliveryStatus::Dead,
+                        attempts: 8,
+                        next_attempt_at: Utc::now(),
+                        last_error: Some("webhook endpoint returned 500".into()),
+                        delivered_at: None,
+                        created_at: Utc::now(),
+                    }],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::get(v1("/webhooks/deliveries?status=dead"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = deserialize_json!(resp, PaginatedWebhookDeliveryResponse);
+    assert_eq!(body.total, 1);
+    let delivery = &body.items[0];
+    assert_eq!(delivery.status, WebhookDeliveryStatusName::Dead);
+    assert_eq!(delivery.payload["data"]["title"], "Rust");
+    Ok(())
+}
+
+#[rstest]
+#[case(true, StatusCode::ACCEPTED)]
+#[case(false, StatusCode::NOT_FOUND)]
+#[tokio::test]
+async fn redeliver_webhook(
+    mut fixture_auth: MockAppRegistryExt,
+    #[case] exists: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let delivery_id = WebhookDeliveryId::new();
+    fixture_auth.expect_webhook_repository().returning(move || {
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_redeliver()
+            .withf(move |id| *id == delivery_id)
+            .returning(move |id| {
+                if exists {
+                    Ok(())
+                } else {
+                    Err(AppError::EntityNotFound(format!("{id}")))
+                }
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(with_role(fixture_auth, true));
+    let req = Request::post(v1(&format!("/webhooks/deliveries/{delivery_id}/redeliver")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::model::id::{AuthorId, BookId};
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+
+// 蔵書に対する関わり方
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, EnumString, AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AuthorRole {
+    #[default]
+    Author,
+    Translator,
+    Editor,
+}
+
+// 蔵書に関わった著者。登録・更新時に指定した順に並ぶ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookAuthor {
+    pub id: AuthorId,
+    pub name: String,
+    pub role: AuthorRole,
+}
+
+// 登録・更新時に指定する著者。同じ名前の著者は同一人物として扱う
+#[derive(Debug, Clone, PartialEq, Eq, Validate)]
+pub struct AuthorName {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(skip)]
+    pub role: AuthorRole,
+}
+
+impl AuthorName {
+    // "A; B" や "A、B" のように一つの文字列で書かれた著者を分割する。
+    // "Klabnik, Steve" のように名前に含まれることがあるため、カンマでは区切らない。
+    // 既存のデータを移行するマイグレーションと同じ区切り文字を使う
+    pub fn parse_list(author: &str) -> Vec<Self> {
+        author
+            .split(AUTHOR_SEPARATORS)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|name| Self {
+                name: name.into(),
+                role: AuthorRole::Author,
+            })
+            .collect()
+    }
+}
+
+// 著者の名前に含まれない区切り文字
+const AUTHOR_SEPARATORS: [char; 3] = [';', '；', '、'];
+
+// books.author に保存する表示用の文字列。翻訳者・編集者しかいない場合はその名前を使う。
+// parse_list で元の著者に戻せるように区切る
+pub fn display_authors(authors: &[AuthorName]) -> String {
+    let names = |role: Option<AuthorRole>| {
+        authors
+            .iter()
+            .filter(|a| role.is_none_or(|r| a.role == r))
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+    };
+    let primary = names(Some(AuthorRole::Author));
+    if primary.is_empty() {
+        names(None).join("; ")
+    } else {
+        primary.join("; ")
+    }
+}
+
+// 著者と、関わった蔵書の数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub id: AuthorId,
+    pub name: String,
+    pub book_count: i64,
+}
+
+// 著者のページに表示する、著者と関わった蔵書の一覧
+#[derive(Debug)]
+pub struct AuthorDetail {
+    pub id: AuthorId,
+    pub name: String,
+    pub books: Vec<AuthorBook>,
+}
+
+#[derive(Debug)]
+pub struct AuthorBook {
+    pub book_id: BookId,
+    pub title: String,
+    pub role: AuthorRole,
+    pub publication_year: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(authors: &[AuthorName]) -> Vec<&str> {
+        authors.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            names(&AuthorName::parse_list("Steve Klabnik; Carol Nichols")),
+            vec!["Steve Klabnik", "Carol Nichols"]
+        );
+        assert_eq!(
+            names(&AuthorName::parse_list("初田直也、山口聖弘；吉川哲史")),
+            vec!["初田直也", "山口聖弘", "吉川哲史"]
+        );
+        // カンマ・アンパサンドなどは名前の一部として扱う
+        assert_eq!(
+            names(&AuthorName::parse_list("Klabnik, Steve")),
+            vec!["Klabnik, Steve"]
+        );
+        assert_eq!(
+            names(&AuthorName::parse_list("Simon & Garfunkel")),
+            vec!["Simon & Garfunkel"]
+        );
+        assert!(AuthorName::parse_list(" ; ").is_empty());
+    }
+
+    #[test]
+    fn test_display_authors() {
+        let mut authors = AuthorName::parse_list("A; B");
+        authors.push(AuthorName {
+            name: "C".into(),
+            role: AuthorRole::Translator,
+        });
+        assert_eq!(display_authors(&authors), "A; B");
+        assert_eq!(
+            AuthorName::parse_list(&display_authors(&authors)),
+            authors[..2]
+        );
+        assert_eq!(display_authors(&authors[2..]), "C");
+    }
+}
@@ -1,19 +1,96 @@
-use crate::model::id::{BookId, UserId};
+use crate::model::{
+    author::AuthorName,
+    book::Bibliography,
+    id::{BookId, UserId},
+};
+use garde::Validate;
 
 pub struct CreateBook {
     pub title: String,
-    pub author: String,
+    // 1 人以上。books.author には表示用に連ねた名前を保存する
+    pub authors: Vec<AuthorName>,
     pub isbn: String,
     pub description: String,
+    pub cover_url: Option<String>,
+    pub bibliography: Bibliography,
 }
 
 #[derive(Debug)]
 pub struct UpdateBook {
     pub book_id: BookId,
     pub title: String,
-    pub author: String,
+    pub authors: Vec<AuthorName>,
     pub isbn: String,
     pub description: String,
+    pub bibliography: Bibliography,
+    pub requested_user: UserId,
+    // 指定した場合、現在のバージョンと一致するときだけ更新する
+    pub expected_version: Option<i64>,
+}
+
+// 指定されたフィールドだけを更新する（None のフィールドは現在の値のまま）
+#[derive(Debug, Validate)]
+pub struct BookPatch {
+    #[garde(skip)]
+    pub book_id: BookId,
+    #[garde(length(min = 1))]
+    pub title: Option<String>,
+    // 指定した場合は著者をすべて置き換える
+    #[garde(length(min = 1), dive)]
+    pub authors: Option<Vec<AuthorName>>,
+    #[garde(length(min = 1))]
+    pub isbn: Option<String>,
+    #[garde(skip)]
+    pub description: Option<String>,
+    #[garde(dive)]
+    pub bibliography: BibliographyPatch,
+    #[garde(skip)]
+    pub requested_user: UserId,
+    // 指定した場合、現在のバージョンと一致するときだけ更新する
+    #[garde(skip)]
+    pub expected_version: Option<i64>,
+}
+
+impl BookPatch {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.authors.is_none()
+            && self.isbn.is_none()
+            && self.description.is_none()
+            && self.bibliography.is_empty()
+    }
+}
+
+// 書誌情報の差分。Some(None) の項目は空にする
+#[derive(Debug, Default, Validate)]
+pub struct BibliographyPatch {
+    #[garde(length(min = 1, max = 255))]
+    pub publisher: Option<Option<String>>,
+    #[garde(range(min = 1, max = 9999))]
+    pub publication_year: Option<Option<i32>>,
+    #[garde(length(min = 2, max = 35))]
+    pub language: Option<Option<String>>,
+    #[garde(range(min = 1))]
+    pub page_count: Option<Option<i32>>,
+    #[garde(length(min = 1, max = 64))]
+    pub edition: Option<Option<String>>,
+}
+
+impl BibliographyPatch {
+    pub fn is_empty(&self) -> bool {
+        self.publisher.is_none()
+            && self.publication_year.is_none()
+            && self.language.is_none()
+            && self.page_count.is_none()
+            && self.edition.is_none()
+    }
+}
+
+// アップロードされた表紙画像に差し替える
+#[derive(Debug)]
+pub struct UpdateBookCover {
+    pub book_id: BookId,
+    pub cover_image: String,
     pub requested_user: UserId,
 }
 
@@ -21,4 +98,6 @@ pub struct UpdateBook {
 pub struct DeleteBook {
     pub book_id: BookId,
     pub requested_user: UserId,
+    // 指定した場合、現在のバージョンと一致するときだけ削除する
+    pub expected_version: Option<i64>,
 }
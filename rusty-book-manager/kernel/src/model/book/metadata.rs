@@ -0,0 +1,47 @@
+// ISBN から外部のサービスで取得した書誌情報。取得できなかった項目は None になる
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookMetadata {
+    pub isbn: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub cover_url: Option<String>,
+}
+
+// ハイフンと空白を取り除き、ISBN-10 / ISBN-13 の形式であれば返す
+pub fn normalize_isbn(isbn: &str) -> Option<String> {
+    let isbn: String = isbn
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let valid = match isbn.len() {
+        10 => {
+            isbn[..9].chars().all(|c| c.is_ascii_digit())
+                && isbn[9..].chars().all(|c| c.is_ascii_digit() || c == 'X')
+        }
+        13 => isbn.chars().all(|c| c.is_ascii_digit()),
+        _ => false,
+    };
+    valid.then_some(isbn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_isbn() {
+        assert_eq!(
+            normalize_isbn("978-4-06-536957-9").as_deref(),
+            Some("9784065369579")
+        );
+        assert_eq!(
+            normalize_isbn("4-06-536957-x").as_deref(),
+            Some("406536957X")
+        );
+        assert_eq!(normalize_isbn("97840653695X9"), None);
+        assert_eq!(normalize_isbn("12345"), None);
+        assert_eq!(normalize_isbn(""), None);
+    }
+}
@@ -1,29 +1,96 @@
 use crate::model::{
-    id::{BookId, CheckoutId},
+    author::BookAuthor,
+    book_condition::BookCondition,
+    genre::Genre,
+    id::{BookId, CheckoutId, GenreId, ShelfId, UserId},
+    review::RatingSummary,
     user::{BookOwner, CheckoutUser},
 };
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
 
 pub mod event;
+pub mod metadata;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Book {
     pub id: BookId,
     pub title: String,
+    // 著者名を連ねた表示用の文字列
     pub author: String,
+    #[serde(default)]
+    pub authors: Vec<BookAuthor>,
     pub isbn: String,
     pub description: String,
+    pub cover_url: Option<String>,
+    // アップロードされた表紙画像のキー
+    pub cover_image: Option<String>,
+    #[serde(default)]
+    pub bibliography: Bibliography,
+    // 名前順に並べる
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub genres: Vec<Genre>,
+    #[serde(default)]
+    pub rating: RatingSummary,
     pub owner: BookOwner,
     pub checkout: Option<Checkout>,
+    #[serde(default)]
+    pub condition: BookCondition,
+    // 更新のたびに増えるバージョン（楽観的排他制御に使う）
+    pub version: i64,
 }
 
-#[derive(Debug)]
+// 書誌情報。分からない項目は None
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bibliography {
+    pub publisher: Option<String>,
+    pub publication_year: Option<i32>,
+    // BCP 47 の言語タグ（ja、en-US など）
+    pub language: Option<String>,
+    pub page_count: Option<i32>,
+    pub edition: Option<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct BookListOptions {
     pub limit: i64,
     pub offset: i64,
+    pub filter: BookListFilter,
+    pub sort: BookSort,
+}
+
+// 一覧の並び順。評価・レビュー数が同じ場合は新しい順に並べる
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum BookSort {
+    #[default]
+    Newest,
+    // 平均評価の高い順。レビューのない蔵書は最後になる
+    Rating,
+    ReviewCount,
+}
+
+// 指定した条件をすべて満たす蔵書に絞り込む
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookListFilter {
+    // 正規化済みのタグ名
+    pub tag: Option<String>,
+    // 下位のジャンルの蔵書も含める
+    pub genre_id: Option<GenreId>,
+    pub shelf: Option<ShelfFilter>,
+}
+
+// 本棚は持ち主のものしか参照できない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShelfFilter {
+    pub shelf_id: ShelfId,
+    pub user_id: UserId,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Checkout {
     pub checkout_id: CheckoutId,
     pub checked_out_by: CheckoutUser,
@@ -0,0 +1,29 @@
+use crate::model::{
+    book_condition::{BookCondition, CheckoutCharge},
+    id::{BookConditionId, BookId, UserId},
+};
+
+// 紛失中の蔵書が見つかったことを記録し、貸し出せる状態に戻す。
+// condition は見つかった蔵書の状態で、Good か Damaged のいずれか
+pub struct MarkBookFound {
+    pub book_id: BookId,
+    pub condition: BookCondition,
+    pub note: String,
+    pub requested_user: UserId,
+}
+
+// 修理などで蔵書の状態を変える。condition は Good か Damaged のいずれかで、
+// 紛失中の蔵書には使えない（MarkBookFound で戻す）
+pub struct UpdateBookCondition {
+    pub book_id: BookId,
+    pub condition: BookCondition,
+    pub note: String,
+    pub requested_user: UserId,
+}
+
+// 貸出に伴う破損・紛失の記録について、弁償の方法を記録し直す。None の場合は弁償なしにする
+pub struct UpdateConditionCharge {
+    pub book_condition_id: BookConditionId,
+    pub book_id: BookId,
+    pub charge: Option<CheckoutCharge>,
+}
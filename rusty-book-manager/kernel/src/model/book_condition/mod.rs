@@ -0,0 +1,43 @@
+use crate::model::id::{BookConditionId, BookId, CheckoutId, UserId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+
+pub mod event;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BookCondition {
+    #[default]
+    Good,
+    // 破損しているが貸し出せる
+    Damaged,
+    // 紛失中。見つかるまで貸し出せない
+    Lost,
+}
+
+// 破損・紛失に伴う弁償の方法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckoutCharge {
+    Fee { amount: i32 },
+    // 同じ本を用意して弁償する
+    Replacement,
+}
+
+// 蔵書の状態の変更の記録
+#[derive(Debug)]
+pub struct BookConditionRecord {
+    pub id: BookConditionId,
+    pub book_id: BookId,
+    pub condition: BookCondition,
+    // 破損・紛失のきっかけになった貸出
+    pub checkout_id: Option<CheckoutId>,
+    pub note: String,
+    pub charge: Option<CheckoutCharge>,
+    // 記録した利用者が削除された場合は None
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: DateTime<Utc>,
+}
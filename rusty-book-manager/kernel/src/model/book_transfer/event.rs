@@ -0,0 +1,40 @@
+use crate::model::id::{BookId, BookTransferId, UserId};
+
+// 所有者が別の利用者に蔵書を譲ることを申し出る
+#[derive(Debug)]
+pub struct OfferBookTransfer {
+    pub book_id: BookId,
+    pub to_user_id: UserId,
+    pub requested_user: UserId,
+}
+
+// 申し出への返事は、譲り受ける利用者だけができる
+#[derive(Debug)]
+pub struct AcceptBookTransfer {
+    pub book_transfer_id: BookTransferId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
+
+#[derive(Debug)]
+pub struct DeclineBookTransfer {
+    pub book_transfer_id: BookTransferId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
+
+// 返事を待っている申し出は、申し出た所有者だけが取り下げられる
+#[derive(Debug)]
+pub struct CancelBookTransfer {
+    pub book_transfer_id: BookTransferId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
+
+// 管理者が申し出を経ずに所有者を変更する。返事を待っている申し出は取り消す
+#[derive(Debug)]
+pub struct ForceBookTransfer {
+    pub book_id: BookId,
+    pub to_user_id: UserId,
+    pub requested_user: UserId,
+}
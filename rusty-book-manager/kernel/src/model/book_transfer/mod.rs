@@ -0,0 +1,37 @@
+use crate::model::{
+    id::{BookId, BookTransferId, UserId},
+    user::TransferUser,
+};
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumString};
+
+pub mod event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum BookTransferStatus {
+    // 譲り受ける利用者の返事を待っている
+    Pending,
+    Accepted,
+    Declined,
+    // 返事の前に、申し出た所有者が取り下げたか、管理者が所有者を変更したため取り消された
+    Cancelled,
+    // 管理者が所有者を変更した
+    Forced,
+}
+
+// 蔵書の所有者を変更する申し出と、その結果
+#[derive(Debug)]
+pub struct BookTransfer {
+    pub id: BookTransferId,
+    pub book_id: BookId,
+    pub book_title: String,
+    // 譲渡を申し出た時点の所有者
+    pub from_user: TransferUser,
+    pub to_user: TransferUser,
+    pub status: BookTransferStatus,
+    // 管理者が所有者を変更した場合の管理者
+    pub forced_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
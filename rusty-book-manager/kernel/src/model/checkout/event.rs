@@ -1,4 +1,7 @@
-use crate::model::id::{BookId, CheckoutId, UserId};
+use crate::model::{
+    book_condition::CheckoutCharge,
+    id::{BookId, CheckoutId, UserId},
+};
 use chrono::{DateTime, Utc};
 use derive_new::new;
 
@@ -16,3 +19,44 @@ pub struct UpdateReturned {
     pub returned_by: UserId,
     pub returned_at: DateTime<Utc>,
 }
+
+// 窓口で管理者が利用者に代わって貸し出す
+#[derive(new)]
+pub struct CreateCheckoutOnBehalf {
+    pub book_id: BookId,
+    pub checked_out_by: UserId,
+    pub requested_user: UserId,
+    pub checked_out_at: DateTime<Utc>,
+}
+
+// 退職などで返却できなくなった貸出を、管理者が借りた利用者に代わって返却する
+#[derive(new)]
+pub struct ForceReturned {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    pub reason: String,
+    pub returned_at: DateTime<Utc>,
+}
+
+// 蔵書を破損した状態で返却する。弁償の方法は管理者のみ指定できる
+#[derive(new)]
+pub struct ReturnDamaged {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    pub note: String,
+    pub charge: Option<CheckoutCharge>,
+    pub returned_at: DateTime<Utc>,
+}
+
+// 貸出中の蔵書を紛失したことを届け出て、貸出を終える
+#[derive(new)]
+pub struct ReportLost {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    pub note: String,
+    pub charge: Option<CheckoutCharge>,
+    pub reported_at: DateTime<Utc>,
+}
@@ -1,7 +1,9 @@
 use crate::model::id::{BookId, CheckoutId, UserId};
 use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumString};
 
 pub mod event;
+pub mod reminder;
 
 #[derive(Debug)]
 pub struct Checkout {
@@ -9,9 +11,35 @@ pub struct Checkout {
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
     pub returned_at: Option<DateTime<Utc>>,
+    // 貸出の終わり方。未返却の場合は None
+    pub outcome: Option<CheckoutOutcome>,
+    // 管理者が利用者に代わって貸出・返却した場合の管理者と、返却した理由
+    pub checked_out_on_behalf_by: Option<UserId>,
+    pub returned_on_behalf_by: Option<UserId>,
+    pub return_reason: Option<String>,
     pub book: CheckoutBook,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum CheckoutOutcome {
+    Returned,
+    // 破損した状態で返却された
+    Damaged,
+    // 紛失したため返却されずに終わった
+    Lost,
+}
+
+// 利用者の返却履歴の絞り込みとページネーション。
+// 期間は返却日時で絞り込み、returned_from は含み returned_to は含まない
+#[derive(Debug, Clone, Default)]
+pub struct CheckoutHistoryOptions {
+    pub limit: i64,
+    pub offset: i64,
+    pub returned_from: Option<DateTime<Utc>>,
+    pub returned_to: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug)]
 pub struct CheckoutBook {
     pub book_id: BookId,
@@ -0,0 +1,22 @@
+use crate::model::id::{BookId, CheckoutId, UserId};
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumString};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum CheckoutReminderKind {
+    // 返却期限が近い
+    DueSoon,
+    // 返却期限を過ぎた
+    Overdue,
+}
+
+// 返却を促す通知の宛先と内容
+#[derive(Debug, Clone)]
+pub struct CheckoutReminder {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub title: String,
+    pub user_id: UserId,
+    pub due_at: DateTime<Utc>,
+}
@@ -0,0 +1,113 @@
+use crate::model::{
+    book_condition::BookCondition,
+    id::{BookId, BookTransferId, CheckoutId, DomainEventId, UserId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 蔵書・貸出・利用者の変更を表すイベント。変更と同じトランザクションで outbox に書き込み、
+// 通知などの後続の処理はコミット後に outbox から受け取って行う
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+    #[serde(rename = "book.created")]
+    BookCreated { book_id: BookId, owner_id: UserId },
+    #[serde(rename = "book.updated")]
+    BookUpdated { book_id: BookId },
+    #[serde(rename = "book.deleted")]
+    BookDeleted { book_id: BookId },
+    #[serde(rename = "book.transfer_offered")]
+    BookTransferOffered {
+        book_transfer_id: BookTransferId,
+        book_id: BookId,
+        from_user_id: UserId,
+        to_user_id: UserId,
+    },
+    #[serde(rename = "book.transfer_declined")]
+    BookTransferDeclined {
+        book_transfer_id: BookTransferId,
+        book_id: BookId,
+        from_user_id: UserId,
+        to_user_id: UserId,
+    },
+    // 返事の前に、申し出た所有者が申し出を取り下げた
+    #[serde(rename = "book.transfer_cancelled")]
+    BookTransferCancelled {
+        book_transfer_id: BookTransferId,
+        book_id: BookId,
+        from_user_id: UserId,
+        to_user_id: UserId,
+    },
+    // 申し出が受け入れられたか、管理者が変更したことで所有者が変わった
+    #[serde(rename = "book.transferred")]
+    BookTransferred {
+        book_transfer_id: BookTransferId,
+        book_id: BookId,
+        from_user_id: UserId,
+        to_user_id: UserId,
+        forced: bool,
+    },
+    // 破損・紛失の届け出や、紛失中の蔵書が見つかったことで蔵書の状態が変わった。
+    // 紛失した場合の貸出は返却されずに終わるため、checkout.returned は出さない
+    #[serde(rename = "book.condition_changed")]
+    BookConditionChanged {
+        book_id: BookId,
+        condition: BookCondition,
+        checkout_id: Option<CheckoutId>,
+        user_id: UserId,
+    },
+    #[serde(rename = "checkout.created")]
+    CheckoutCreated {
+        checkout_id: CheckoutId,
+        book_id: BookId,
+        user_id: UserId,
+        checked_out_at: DateTime<Utc>,
+        // 管理者が利用者に代わって操作した場合の管理者
+        #[serde(default)]
+        on_behalf_by: Option<UserId>,
+    },
+    #[serde(rename = "checkout.returned")]
+    CheckoutReturned {
+        checkout_id: CheckoutId,
+        book_id: BookId,
+        user_id: UserId,
+        returned_at: DateTime<Utc>,
+        #[serde(default)]
+        on_behalf_by: Option<UserId>,
+    },
+    #[serde(rename = "user.created")]
+    UserCreated { user_id: UserId },
+    // 名前・メールアドレス・ロールの変更
+    #[serde(rename = "user.updated")]
+    UserUpdated { user_id: UserId },
+    #[serde(rename = "user.deleted")]
+    UserDeleted { user_id: UserId },
+}
+
+impl DomainEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::BookCreated { .. } => "book.created",
+            Self::BookUpdated { .. } => "book.updated",
+            Self::BookDeleted { .. } => "book.deleted",
+            Self::BookTransferOffered { .. } => "book.transfer_offered",
+            Self::BookTransferDeclined { .. } => "book.transfer_declined",
+            Self::BookTransferCancelled { .. } => "book.transfer_cancelled",
+            Self::BookTransferred { .. } => "book.transferred",
+            Self::BookConditionChanged { .. } => "book.condition_changed",
+            Self::CheckoutCreated { .. } => "checkout.created",
+            Self::CheckoutReturned { .. } => "checkout.returned",
+            Self::UserCreated { .. } => "user.created",
+            Self::UserUpdated { .. } => "user.updated",
+            Self::UserDeleted { .. } => "user.deleted",
+        }
+    }
+}
+
+// outbox から取り出したイベント。id はハンドラが重複を除くための冪等キーに使う
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxEvent {
+    pub id: DomainEventId,
+    pub event: DomainEvent,
+    pub occurred_at: DateTime<Utc>,
+}
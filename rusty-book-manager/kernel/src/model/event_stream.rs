@@ -0,0 +1,56 @@
+use std::{fmt, str::FromStr};
+
+use crate::model::domain_event::DomainEvent;
+use chrono::{DateTime, Utc};
+use shared::error::AppError;
+
+// 配信の履歴上のイベントの位置。Redis の Stream が振る「ミリ秒-連番」の形式で、
+// クライアントは最後に受け取った位置を Last-Event-ID に指定して続きから受け取り直せる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventStreamId {
+    millis: u64,
+    sequence: u64,
+}
+
+impl FromStr for EventStreamId {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::UnprocessableEntity(format!("イベントの ID が不正です: {s}"));
+        let (millis, sequence) = s.split_once('-').ok_or_else(invalid)?;
+        Ok(Self {
+            millis: millis.parse().map_err(|_| invalid())?,
+            sequence: sequence.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for EventStreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.millis, self.sequence)
+    }
+}
+
+// 接続中のクライアントに配信するドメインイベント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamedEvent {
+    pub id: EventStreamId,
+    pub event: DomainEvent,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_stream_id_ordering() -> anyhow::Result<()> {
+        let id: EventStreamId = "1712345678901-2".parse()?;
+        assert_eq!(id.to_string(), "1712345678901-2");
+        assert!(id < "1712345678901-10".parse()?);
+        assert!(id < "1712345678902-0".parse()?);
+        assert!("1712345678901".parse::<EventStreamId>().is_err());
+        assert!("abc-0".parse::<EventStreamId>().is_err());
+        Ok(())
+    }
+}
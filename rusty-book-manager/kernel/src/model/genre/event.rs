@@ -0,0 +1,25 @@
+use crate::model::id::{BookId, GenreId};
+
+#[derive(Debug)]
+pub struct CreateGenre {
+    pub name: String,
+    pub parent_id: Option<GenreId>,
+}
+
+// 蔵書や下位のジャンルから参照されているジャンルは削除できない
+#[derive(Debug)]
+pub struct DeleteGenre {
+    pub genre_id: GenreId,
+}
+
+#[derive(Debug)]
+pub struct AddBookGenre {
+    pub book_id: BookId,
+    pub genre_id: GenreId,
+}
+
+#[derive(Debug)]
+pub struct RemoveBookGenre {
+    pub book_id: BookId,
+    pub genre_id: GenreId,
+}
@@ -0,0 +1,12 @@
+use crate::model::id::GenreId;
+use serde::{Deserialize, Serialize};
+
+pub mod event;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Genre {
+    pub id: GenreId,
+    pub name: String,
+    // 上位のジャンル。最上位の場合は None
+    pub parent_id: Option<GenreId>,
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Up,
+    // 応答はあるが余裕がない状態（コネクションプールの枯渇など）
+    Degraded,
+    Down,
+}
+
+#[derive(Debug)]
+pub struct DependencyHealth {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    // false の依存先は Down でも Readiness を落とさない
+    pub required: bool,
+    pub latency: Duration,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct HealthReport {
+    pub checks: Vec<DependencyHealth>,
+}
+
+impl HealthReport {
+    pub fn is_ready(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|c| !c.required || c.status != HealthStatus::Down)
+    }
+
+    pub fn status(&self) -> HealthStatus {
+        if !self.is_ready() {
+            HealthStatus::Down
+        } else if self.checks.iter().all(|c| c.status == HealthStatus::Up) {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Degraded
+        }
+    }
+}
@@ -54,3 +54,14 @@ macro_rules! define_id {
 define_id!(UserId);
 define_id!(BookId);
 define_id!(CheckoutId);
+define_id!(GenreId);
+define_id!(ShelfId);
+define_id!(AuthorId);
+define_id!(ReviewId);
+define_id!(WebhookSubscriptionId);
+define_id!(WebhookDeliveryId);
+define_id!(DomainEventId);
+define_id!(JobRunId);
+define_id!(NotificationId);
+define_id!(BookTransferId);
+define_id!(BookConditionId);
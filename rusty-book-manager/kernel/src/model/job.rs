@@ -0,0 +1,62 @@
+use crate::model::id::JobRunId;
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+// スケジューラが実行するジョブ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum JobName {
+    // 返却期限が近い・過ぎた貸出の利用者に知らせる
+    CheckoutReminders,
+    // 有効期限の切れたアクセストークンを利用者ごとの一覧から取り除く
+    TokenCleanup,
+    // 保存期間を過ぎた配信済みのイベントや実行履歴を削除する
+    AuditPurge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum JobRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug)]
+pub struct JobRun {
+    pub id: JobRunId,
+    pub job_name: JobName,
+    pub status: JobRunStatus,
+    pub scheduled_at: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    // 成功した場合に処理した件数と、処理できずに飛ばした件数
+    pub processed: Option<i64>,
+    pub failed: Option<i64>,
+    pub error: Option<String>,
+}
+
+// ジョブを最後まで実行した結果。一部の処理に失敗しても続けた場合は failed に数える
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobOutcome {
+    pub processed: u64,
+    pub failed: u64,
+}
+
+impl JobOutcome {
+    pub fn processed(processed: u64) -> Self {
+        Self {
+            processed,
+            failed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JobRunListOptions {
+    pub limit: i64,
+    pub offset: i64,
+    // None の場合はすべてのジョブ・状態を返す
+    pub job_name: Option<JobName>,
+    pub status: Option<JobRunStatus>,
+}
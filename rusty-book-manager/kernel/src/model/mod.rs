@@ -1,7 +1,24 @@
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod book_condition;
+pub mod book_transfer;
 pub mod checkout;
+pub mod domain_event;
+pub mod event_stream;
+pub mod genre;
+pub mod health;
 pub mod id;
+pub mod job;
 pub mod list;
+pub mod mail;
+pub mod notification;
+pub mod rate_limit;
+pub mod recommendation;
+pub mod report;
+pub mod review;
 pub mod role;
+pub mod shelf;
+pub mod tag;
 pub mod user;
+pub mod webhook;
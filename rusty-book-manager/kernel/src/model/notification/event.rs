@@ -0,0 +1,35 @@
+use crate::model::{
+    id::{BookId, NotificationId, UserId},
+    notification::{NotificationKind, NotificationPreference},
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateNotification {
+    pub user_id: UserId,
+    pub kind: NotificationKind,
+    // 通知のもとになった outbox のイベントや貸出の ID。同じ種類の通知は同じものから一度しか作らない
+    pub source_id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub book_id: Option<BookId>,
+}
+
+// 既読にする操作はいずれも、通知を受け取った利用者のものだけを対象にする
+#[derive(Debug)]
+pub struct MarkNotificationRead {
+    pub notification_id: NotificationId,
+    pub user_id: UserId,
+}
+
+#[derive(Debug)]
+pub struct MarkAllNotificationsRead {
+    pub user_id: UserId,
+}
+
+// 指定しなかった種類の設定は変えない
+#[derive(Debug)]
+pub struct UpdateNotificationPreferences {
+    pub user_id: UserId,
+    pub preferences: Vec<NotificationPreference>,
+}
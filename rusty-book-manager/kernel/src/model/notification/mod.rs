@@ -0,0 +1,69 @@
+use crate::model::id::{BookId, NotificationId, UserId};
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+pub mod event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationKind {
+    // 所有する蔵書が貸し出された
+    BookCheckedOut,
+    // 所有する蔵書が返却された
+    BookReturned,
+    // 借りている蔵書の返却期限が近い
+    CheckoutDueSoon,
+    // 借りている蔵書の返却期限を過ぎた
+    CheckoutOverdue,
+    // 名前・メールアドレス・ロールが変更された
+    AccountUpdated,
+    // 蔵書の譲渡を申し出られた
+    BookTransferOffered,
+    // 申し出た譲渡が断られた
+    BookTransferDeclined,
+    // 蔵書の所有者が変わった
+    BookTransferred,
+    // 所有する蔵書の破損・紛失が届け出られたか、紛失中の蔵書が見つかった
+    BookConditionChanged,
+}
+
+impl NotificationKind {
+    // 利用者が設定していない場合に、メールでも知らせるかどうか
+    pub fn email_by_default(&self) -> bool {
+        match self {
+            Self::BookCheckedOut | Self::BookReturned | Self::BookTransferDeclined => false,
+            Self::CheckoutDueSoon
+            | Self::CheckoutOverdue
+            | Self::AccountUpdated
+            | Self::BookTransferOffered
+            | Self::BookTransferred
+            | Self::BookConditionChanged => true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Notification {
+    pub id: NotificationId,
+    pub user_id: UserId,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    pub book_id: Option<BookId>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NotificationListOptions {
+    pub limit: i64,
+    pub offset: i64,
+    // true の場合は未読の通知だけを返す
+    pub unread_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationPreference {
+    pub kind: NotificationKind,
+    pub email: bool,
+}
@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+// window の間に limit 件までリクエストを許可する
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitQuota {
+    pub limit: u64,
+    pub window: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u64,
+    pub remaining: u64,
+    // ウィンドウ内で最も古いリクエストが枠から外れ、次のリクエストが可能になるまでの時間
+    pub reset_after: Duration,
+}
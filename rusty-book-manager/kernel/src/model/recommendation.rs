@@ -0,0 +1,17 @@
+use crate::model::id::BookId;
+
+// 利用者におすすめする蔵書。各件数は推薦の根拠で、score はその重み付きの合計
+#[derive(Debug)]
+pub struct Recommendation {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub score: i64,
+    // 利用者が読んだ蔵書を借りた人が、この蔵書も借りた人数の合計
+    pub co_checkout_count: i64,
+    // 利用者が読んだ蔵書のうち、この蔵書と著者が共通するものの数
+    pub shared_author_count: i64,
+    // 利用者が読んだ蔵書のうち、この蔵書とタグが共通するものの数
+    pub shared_tag_count: i64,
+}
@@ -0,0 +1,120 @@
+use crate::model::id::{AuthorId, BookId, UserId};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use strum::AsRefStr;
+
+// 集計の対象期間。from は含み to は含まない。None の場合はその側を制限しない
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportPeriod {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+// 貸出数を集計する単位。週は月曜始まり
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    // 一度に集計できる単位の数の上限（日は約 2 年、週は約 10 年、月は 20 年分）
+    pub fn max_buckets(self) -> i64 {
+        match self {
+            Self::Day => 731,
+            Self::Week => 522,
+            Self::Month => 240,
+        }
+    }
+
+    // first から last まで（いずれも含む）にかかる集計単位の数
+    pub fn bucket_count(self, first: DateTime<Utc>, last: DateTime<Utc>) -> i64 {
+        if first > last {
+            return 0;
+        }
+        let (first, last) = (first.date_naive(), last.date_naive());
+        match self {
+            Self::Day => (last - first).num_days() + 1,
+            Self::Week => (week_start(last) - week_start(first)).num_days() / 7 + 1,
+            Self::Month => {
+                let months = |d: NaiveDate| i64::from(d.year()) * 12 + i64::from(d.month0());
+                months(last) - months(first) + 1
+            }
+        }
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+#[derive(Debug)]
+pub struct CheckoutCount {
+    // 集計単位の始まり（UTC）
+    pub period_start: DateTime<Utc>,
+    pub count: i64,
+}
+
+#[derive(Debug)]
+pub struct BookCheckoutRanking {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub checkout_count: i64,
+}
+
+#[derive(Debug)]
+pub struct AuthorCheckoutRanking {
+    pub author_id: AuthorId,
+    pub name: String,
+    pub checkout_count: i64,
+}
+
+#[derive(Debug)]
+pub struct BorrowerRanking {
+    pub user_id: UserId,
+    pub name: String,
+    pub checkout_count: i64,
+}
+
+#[derive(Debug)]
+pub struct LoanDuration {
+    pub returned_count: i64,
+    // 返却済みの貸出がない場合は None
+    pub average_days: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct IdleBook {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub registered_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_bucket_count() {
+        let at = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap();
+        // 2025-03-02 は日曜日、2025-03-03 は月曜日
+        let (first, last) = (at(2025, 3, 2), at(2025, 3, 3));
+        assert_eq!(Granularity::Day.bucket_count(first, last), 2);
+        assert_eq!(Granularity::Week.bucket_count(first, last), 2);
+        assert_eq!(Granularity::Month.bucket_count(first, last), 1);
+        assert_eq!(
+            Granularity::Month.bucket_count(at(2024, 12, 31), at(2025, 1, 1)),
+            2
+        );
+        assert_eq!(Granularity::Day.bucket_count(last, first), 0);
+        assert_eq!(
+            Granularity::Day.bucket_count(at(2024, 1, 1), at(2025, 12, 31)),
+            731
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use crate::model::id::{BookId, ReviewId, UserId};
+
+// 返却済みの貸出がある利用者だけが、蔵書ごとに 1 件投稿できる
+#[derive(Debug)]
+pub struct CreateReview {
+    pub book_id: BookId,
+    pub reviewed_by: UserId,
+    pub rating: i16,
+    pub body: String,
+}
+
+// 投稿者のみ編集できる
+#[derive(Debug)]
+pub struct UpdateReview {
+    pub review_id: ReviewId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    pub rating: i16,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub struct DeleteReview {
+    pub review_id: ReviewId,
+    pub book_id: BookId,
+    // None の場合は管理者による削除で、投稿者を問わない
+    pub requested_user: Option<UserId>,
+}
+
+// 管理者がレビューを非表示にする（または非表示を解除する）
+#[derive(Debug)]
+pub struct ModerateReview {
+    pub review_id: ReviewId,
+    pub book_id: BookId,
+    pub hidden: bool,
+}
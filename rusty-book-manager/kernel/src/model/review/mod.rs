@@ -0,0 +1,30 @@
+use crate::model::{
+    id::{BookId, ReviewId},
+    user::ReviewUser,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub mod event;
+
+#[derive(Debug)]
+pub struct Review {
+    pub id: ReviewId,
+    pub book_id: BookId,
+    pub reviewed_by: ReviewUser,
+    // 1〜5 の星の数
+    pub rating: i16,
+    pub body: String,
+    // 管理者が非表示にしたレビュー
+    pub hidden: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 蔵書の評価の集計。非表示のレビューは含めない
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatingSummary {
+    // レビューがない場合は None
+    pub average: Option<f64>,
+    pub count: i64,
+}
@@ -0,0 +1,28 @@
+use crate::model::id::{BookId, ShelfId, UserId};
+
+#[derive(Debug)]
+pub struct CreateShelf {
+    pub user_id: UserId,
+    pub name: String,
+}
+
+// 本棚の操作はいずれも、本棚の持ち主であるユーザーのものだけを対象にする
+#[derive(Debug)]
+pub struct DeleteShelf {
+    pub shelf_id: ShelfId,
+    pub user_id: UserId,
+}
+
+#[derive(Debug)]
+pub struct AddShelfBook {
+    pub shelf_id: ShelfId,
+    pub user_id: UserId,
+    pub book_id: BookId,
+}
+
+#[derive(Debug)]
+pub struct RemoveShelfBook {
+    pub shelf_id: ShelfId,
+    pub user_id: UserId,
+    pub book_id: BookId,
+}
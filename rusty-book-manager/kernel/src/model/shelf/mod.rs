@@ -0,0 +1,11 @@
+use crate::model::id::ShelfId;
+
+pub mod event;
+
+// 利用者ごとの本棚と、そこに入っている蔵書の数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shelf {
+    pub id: ShelfId,
+    pub name: String,
+    pub book_count: i64,
+}
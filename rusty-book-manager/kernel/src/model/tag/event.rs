@@ -0,0 +1,14 @@
+use crate::model::id::BookId;
+
+// 蔵書にタグを付ける。タグがなければ作成する
+#[derive(Debug)]
+pub struct AddBookTag {
+    pub book_id: BookId,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct RemoveBookTag {
+    pub book_id: BookId,
+    pub name: String,
+}
@@ -0,0 +1,45 @@
+pub mod event;
+
+// タグと、そのタグが付いている蔵書の数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub book_count: i64,
+}
+
+const MAX_TAG_LENGTH: usize = 64;
+
+// 前後の空白を除いて小文字にし、途中の空白はハイフンにする。
+// 使えるのは文字・数字と "-_+#." のみで、それ以外を含む場合は None を返す
+pub fn normalize_tag(name: &str) -> Option<String> {
+    let name = name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_TAG_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_+#.".contains(c));
+    valid.then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag(" Rust ").as_deref(), Some("rust"));
+        assert_eq!(
+            normalize_tag("Domain  Driven Design").as_deref(),
+            Some("domain-driven-design")
+        );
+        assert_eq!(normalize_tag("C++").as_deref(), Some("c++"));
+        assert_eq!(normalize_tag("設計").as_deref(), Some("設計"));
+        assert_eq!(normalize_tag("a:b"), None);
+        assert_eq!(normalize_tag("  "), None);
+        assert_eq!(normalize_tag(&"a".repeat(65)), None);
+    }
+}
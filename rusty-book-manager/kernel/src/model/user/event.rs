@@ -1,4 +1,5 @@
 use crate::model::{id::UserId, role::Role};
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct CreateUser {
@@ -20,6 +21,45 @@ pub struct UpdateUserPassword {
     pub new_password: String,
 }
 
+#[derive(Debug)]
+pub struct UpdateUserName {
+    pub user_id: UserId,
+    pub name: String,
+}
+
+// 確認済みのメールアドレスに変更する
+#[derive(Debug)]
+pub struct UpdateUserEmail {
+    pub user_id: UserId,
+    pub email: String,
+}
+
+// メールアドレスの変更を申請する。確認用のトークンが使われるまで変更は反映しない
+#[derive(Debug)]
+pub struct RequestEmailChange {
+    pub user_id: UserId,
+    pub new_email: String,
+    pub token: String,
+}
+
+impl RequestEmailChange {
+    pub fn new(user_id: UserId, new_email: String) -> Self {
+        let token = Uuid::new_v4().simple().to_string();
+        Self {
+            user_id,
+            new_email,
+            token,
+        }
+    }
+}
+
+// 管理者による再設定なので現在のパスワードは確認しない
+#[derive(Debug)]
+pub struct ResetUserPassword {
+    pub user_id: UserId,
+    pub new_password: String,
+}
+
 #[derive(Debug)]
 pub struct DeleteUser {
     pub user_id: UserId,
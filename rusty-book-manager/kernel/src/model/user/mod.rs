@@ -1,4 +1,5 @@
 use crate::model::{id::UserId, role::Role};
+use serde::{Deserialize, Serialize};
 pub mod event;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -9,14 +10,36 @@ pub struct User {
     pub role: Role,
 }
 
-#[derive(Debug)]
+pub struct EmailVerificationToken(pub String);
+
+// 確認待ちのメールアドレス変更
+#[derive(Debug, PartialEq, Eq)]
+pub struct EmailVerification {
+    pub user_id: UserId,
+    pub new_email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct BookOwner {
     pub id: UserId,
     pub name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CheckoutUser {
     pub id: UserId,
     pub name: String,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewUser {
+    pub id: UserId,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferUser {
+    // 利用者が削除された後の履歴では None になる（名前は譲渡の時点のものが残る）
+    pub id: Option<UserId>,
+    pub name: String,
+}
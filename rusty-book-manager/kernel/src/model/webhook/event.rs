@@ -0,0 +1,20 @@
+use crate::model::{
+    id::{WebhookDeliveryId, WebhookSubscriptionId},
+    webhook::WebhookEventType,
+};
+
+pub struct CreateWebhookSubscription {
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<WebhookEventType>,
+}
+
+pub struct DeleteWebhookSubscription {
+    pub subscription_id: WebhookSubscriptionId,
+}
+
+// 送信に失敗した配信の記録。上限回数に達していれば dead にし、そうでなければ再送を予約する
+pub struct RecordWebhookFailure {
+    pub delivery_id: WebhookDeliveryId,
+    pub error: String,
+}
@@ -0,0 +1,226 @@
+use crate::model::{
+    book::Book,
+    id::{BookId, CheckoutId, UserId, WebhookDeliveryId, WebhookSubscriptionId},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+pub mod event;
+
+// 購読できるイベントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+pub enum WebhookEventType {
+    #[strum(serialize = "book.created")]
+    BookCreated,
+    #[strum(serialize = "book.updated")]
+    BookUpdated,
+    #[strum(serialize = "checkout.created")]
+    CheckoutCreated,
+    #[strum(serialize = "checkout.returned")]
+    CheckoutReturned,
+    #[strum(serialize = "user.created")]
+    UserCreated,
+}
+
+// 署名に使う共有鍵は登録時にしか受け取らず、読み出すことはできない
+#[derive(Debug)]
+pub struct WebhookSubscription {
+    pub id: WebhookSubscriptionId,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    // 送信待ち（再送待ちを含む）
+    Pending,
+    Succeeded,
+    // 再送の上限まで失敗した
+    Dead,
+}
+
+#[derive(Debug)]
+pub struct WebhookDelivery {
+    pub id: WebhookDeliveryId,
+    pub subscription_id: WebhookSubscriptionId,
+    pub event_type: WebhookEventType,
+    // 送信する JSON の本文
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WebhookDeliveryListOptions {
+    pub limit: i64,
+    pub offset: i64,
+    // None の場合はすべての状態を返す
+    pub status: Option<WebhookDeliveryStatus>,
+}
+
+// 送信処理が取り出した配信。送信先と署名に使う共有鍵を含む
+#[derive(Debug, Clone)]
+pub struct PendingWebhookDelivery {
+    pub id: WebhookDeliveryId,
+    pub url: String,
+    pub secret: String,
+    pub event_type: WebhookEventType,
+    pub payload: String,
+    // これまでに失敗した回数
+    pub attempts: i32,
+}
+
+// 購読先に送るイベント。JSON では type と data に分けて表す
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum WebhookEvent {
+    #[serde(rename = "book.created")]
+    BookCreated(WebhookBook),
+    #[serde(rename = "book.updated")]
+    BookUpdated(WebhookBook),
+    #[serde(rename = "checkout.created")]
+    CheckoutCreated(WebhookCheckout),
+    #[serde(rename = "checkout.returned")]
+    CheckoutReturned(WebhookReturn),
+    #[serde(rename = "user.created")]
+    UserCreated(WebhookUser),
+}
+
+impl WebhookEvent {
+    pub fn event_type(&self) -> WebhookEventType {
+        match self {
+            Self::BookCreated(_) => WebhookEventType::BookCreated,
+            Self::BookUpdated(_) => WebhookEventType::BookUpdated,
+            Self::CheckoutCreated(_) => WebhookEventType::CheckoutCreated,
+            Self::CheckoutReturned(_) => WebhookEventType::CheckoutReturned,
+            Self::UserCreated(_) => WebhookEventType::UserCreated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookBook {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub owner_id: UserId,
+}
+
+impl From<Book> for WebhookBook {
+    fn from(value: Book) -> Self {
+        Self {
+            book_id: value.id,
+            title: value.title,
+            author: value.author,
+            isbn: value.isbn,
+            owner_id: value.owner.id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookCheckout {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub checked_out_by: UserId,
+    pub checked_out_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookReturn {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub returned_by: UserId,
+    pub returned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookUser {
+    pub user_id: UserId,
+    pub name: String,
+}
+
+// 購読先として送信してよいアドレスか。ループバック・リンクローカル・プライベートなど、
+// 内部のネットワークに届くアドレスへは送らない（SSRF 対策）
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // 0.0.0.0/8、キャリアグレード NAT（100.64.0.0/10）、IETF 予約（192.0.0.0/24）、
+        // ベンチマーク（198.18.0.0/15）、将来の予約（240.0.0.0/4）
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // ユニークローカル（fc00::/7）、リンクローカル（fe80::/10）、サイトローカル（fec0::/10）、
+        // ドキュメント用（2001:db8::/32）
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first & 0xffc0) == 0xfec0
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_address() {
+        for ip in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public_address(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{ip}");
+        }
+    }
+}
@@ -15,4 +15,8 @@ pub trait AuthRepository: Send + Sync {
     async fn verify_user(&self, email: &str, password: &str) -> AppResult<UserId>;
     async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken>;
     async fn delete_token(&self, access_token: AccessToken) -> AppResult<()>;
+    // 指定ユーザーのアクセストークンをすべて失効させ、失効させた件数を返す
+    async fn delete_all_tokens(&self, user_id: UserId) -> AppResult<u64>;
+    // 有効期限の切れたトークンを利用者ごとの発行済みトークンの一覧から取り除き、取り除いた件数を返す
+    async fn delete_expired_tokens(&self) -> AppResult<u64>;
 }
@@ -0,0 +1,14 @@
+use crate::model::{
+    author::{Author, AuthorDetail},
+    id::AuthorId,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait AuthorRepository: Send + Sync {
+    // 蔵書に関わっている著者を名前順に返す
+    async fn find_all(&self) -> AppResult<Vec<Author>>;
+    async fn find_by_id(&self, author_id: AuthorId) -> AppResult<Option<AuthorDetail>>;
+}
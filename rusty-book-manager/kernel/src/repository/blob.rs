@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// 画像などのバイナリをキーで保存するストレージ。キーは "/" で区切った相対パスとする
+#[mockall::automock]
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> AppResult<()>;
+    // 存在しない場合は None
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>>;
+    // 存在しない場合も成功とする
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
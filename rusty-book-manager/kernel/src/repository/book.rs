@@ -3,7 +3,7 @@ use shared::error::AppResult;
 
 use crate::model::{
     book::{
-        event::{CreateBook, DeleteBook, UpdateBook},
+        event::{BookPatch, CreateBook, DeleteBook, UpdateBook, UpdateBookCover},
         Book, BookListOptions,
     },
     id::{BookId, UserId},
@@ -13,9 +13,13 @@ use crate::model::{
 #[mockall::automock]
 #[async_trait]
 pub trait BookRepository: Send + Sync {
-    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<()>;
+    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<BookId>;
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>>;
     async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>>;
     async fn update(&self, event: UpdateBook) -> AppResult<()>;
-    async fn delete(&self, event: DeleteBook) -> AppResult<()>;
+    async fn patch(&self, event: BookPatch) -> AppResult<()>;
+    // 差し替える前の表紙のキーを返す
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<Option<String>>;
+    // 削除した蔵書の表紙のキーを返す
+    async fn delete(&self, event: DeleteBook) -> AppResult<Option<String>>;
 }
@@ -0,0 +1,21 @@
+use crate::model::{
+    book_condition::{
+        event::{MarkBookFound, UpdateBookCondition, UpdateConditionCharge},
+        BookConditionRecord,
+    },
+    id::BookId,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// 破損・紛失の記録は貸出の操作（CheckoutRepository）で行う
+#[mockall::automock]
+#[async_trait]
+pub trait BookConditionRepository: Send + Sync {
+    async fn mark_found(&self, event: MarkBookFound) -> AppResult<()>;
+    async fn update_condition(&self, event: UpdateBookCondition) -> AppResult<()>;
+    async fn update_charge(&self, event: UpdateConditionCharge) -> AppResult<()>;
+    // 蔵書の状態の履歴を新しい順に返す
+    async fn find_history_by_book_id(&self, book_id: BookId)
+        -> AppResult<Vec<BookConditionRecord>>;
+}
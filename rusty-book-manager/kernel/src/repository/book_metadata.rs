@@ -0,0 +1,10 @@
+use crate::model::book::metadata::BookMetadata;
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait BookMetadataProvider: Send + Sync {
+    // ISBN に対応する書誌情報を取得する。見つからない場合は None
+    async fn lookup(&self, isbn: &str) -> AppResult<Option<BookMetadata>>;
+}
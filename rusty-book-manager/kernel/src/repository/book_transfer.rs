@@ -0,0 +1,27 @@
+use crate::model::{
+    book_transfer::{
+        event::{
+            AcceptBookTransfer, CancelBookTransfer, DeclineBookTransfer, ForceBookTransfer,
+            OfferBookTransfer,
+        },
+        BookTransfer,
+    },
+    id::{BookId, UserId},
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// 所有者の変更は、貸出中の蔵書に対しては行えない
+#[mockall::automock]
+#[async_trait]
+pub trait BookTransferRepository: Send + Sync {
+    async fn offer(&self, event: OfferBookTransfer) -> AppResult<BookTransfer>;
+    async fn accept(&self, event: AcceptBookTransfer) -> AppResult<BookTransfer>;
+    async fn decline(&self, event: DeclineBookTransfer) -> AppResult<BookTransfer>;
+    async fn cancel(&self, event: CancelBookTransfer) -> AppResult<BookTransfer>;
+    async fn force(&self, event: ForceBookTransfer) -> AppResult<BookTransfer>;
+    // 利用者が申し出た、または申し出を受けた返事待ちの譲渡を新しい順に返す
+    async fn find_pending_by_user_id(&self, user_id: UserId) -> AppResult<Vec<BookTransfer>>;
+    // 蔵書の所有者が変わった譲渡を古い順に返す
+    async fn find_history(&self, book_id: BookId) -> AppResult<Vec<BookTransfer>>;
+}
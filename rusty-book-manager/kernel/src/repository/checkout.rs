@@ -1,9 +1,13 @@
 use crate::model::{
     checkout::{
-        event::{CreateCheckout, UpdateReturned},
-        Checkout,
+        event::{
+            CreateCheckout, CreateCheckoutOnBehalf, ForceReturned, ReportLost, ReturnDamaged,
+            UpdateReturned,
+        },
+        Checkout, CheckoutHistoryOptions,
     },
     id::{BookId, UserId},
+    list::PaginatedList,
 };
 use async_trait::async_trait;
 use shared::error::AppResult;
@@ -15,10 +19,24 @@ pub trait CheckoutRepository: Send + Sync {
     async fn create(&self, event: CreateCheckout) -> AppResult<()>;
     // update returned_at -> 返却
     async fn update_returned(&self, event: UpdateReturned) -> AppResult<()>;
+    // 管理者が利用者に代わって貸し出す
+    async fn create_on_behalf(&self, event: CreateCheckoutOnBehalf) -> AppResult<()>;
+    // 管理者が借りた利用者に代わって返却する
+    async fn force_returned(&self, event: ForceReturned) -> AppResult<()>;
+    // 破損した状態で返却し、蔵書を破損ありにする
+    async fn return_damaged(&self, event: ReturnDamaged) -> AppResult<()>;
+    // 紛失を届け出て貸出を終え、蔵書を見つかるまで貸し出せないようにする
+    async fn report_lost(&self, event: ReportLost) -> AppResult<()>;
     // すべての貸出中の情報を取得
     async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>>;
     // 特定のユーザーの貸出中の情報を取得
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>>;
     // 特定の蔵書の貸出履歴を取得
     async fn find_history_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>>;
+    // 特定のユーザーの返却済みの貸出履歴を、返却日時の新しい順に取得
+    async fn find_history_by_user_id(
+        &self,
+        user_id: UserId,
+        options: CheckoutHistoryOptions,
+    ) -> AppResult<PaginatedList<Checkout>>;
 }
@@ -0,0 +1,18 @@
+use crate::model::{
+    checkout::reminder::{CheckoutReminder, CheckoutReminderKind},
+    id::CheckoutId,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait CheckoutReminderRepository: Send + Sync {
+    // 現時点で kind の催促を送るべきで、まだ送っていない貸出を返却期限の早い順に返す
+    async fn find_unsent(&self, kind: CheckoutReminderKind) -> AppResult<Vec<CheckoutReminder>>;
+    async fn record_sent(
+        &self,
+        checkout_id: CheckoutId,
+        kind: CheckoutReminderKind,
+    ) -> AppResult<()>;
+}
@@ -0,0 +1,13 @@
+use crate::model::user::{event::RequestEmailChange, EmailVerification, EmailVerificationToken};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait EmailVerificationRepository: Send + Sync {
+    // 確認用のトークンを有効期限付きで保存する
+    async fn create(&self, event: RequestEmailChange) -> AppResult<EmailVerificationToken>;
+    // トークンを使用済みにして申請内容を返す。期限切れ・使用済みの場合は None
+    async fn consume(&self, token: &EmailVerificationToken)
+        -> AppResult<Option<EmailVerification>>;
+}
@@ -0,0 +1,21 @@
+use crate::model::{
+    domain_event::OutboxEvent,
+    event_stream::{EventStreamId, StreamedEvent},
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+use tokio::sync::broadcast;
+
+#[mockall::automock]
+#[async_trait]
+pub trait EventStreamRepository: Send + Sync {
+    // イベントを配信の履歴に追加し、すべてのレプリカに知らせる。同じイベントは一度しか追加しない
+    async fn publish(&self, event: &OutboxEvent) -> AppResult<()>;
+    // after より後のイベントを古い順に返す。after が履歴に残っていないほど古い場合は None
+    async fn find_after(&self, after: EventStreamId) -> AppResult<Option<Vec<StreamedEvent>>>;
+    // このレプリカに届いたイベントを受け取る
+    fn subscribe(&self) -> broadcast::Receiver<StreamedEvent>;
+    // ほかのレプリカを含めて追加されたイベントを受信し、subscribe の受け手に流す。
+    // 受信の接続が切れるまで戻らない
+    async fn listen(&self) -> AppResult<()>;
+}
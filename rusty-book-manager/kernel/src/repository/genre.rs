@@ -0,0 +1,20 @@
+use crate::model::{
+    genre::{
+        event::{AddBookGenre, CreateGenre, DeleteGenre, RemoveBookGenre},
+        Genre,
+    },
+    id::GenreId,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait GenreRepository: Send + Sync {
+    async fn find_all(&self) -> AppResult<Vec<Genre>>;
+    async fn find_by_id(&self, genre_id: GenreId) -> AppResult<Option<Genre>>;
+    async fn create(&self, event: CreateGenre) -> AppResult<Genre>;
+    async fn delete(&self, event: DeleteGenre) -> AppResult<()>;
+    async fn add_to_book(&self, event: AddBookGenre) -> AppResult<()>;
+    async fn remove_from_book(&self, event: RemoveBookGenre) -> AppResult<()>;
+}
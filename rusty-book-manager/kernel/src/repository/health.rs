@@ -1,7 +1,14 @@
 use async_trait::async_trait;
 
+use crate::model::health::DependencyHealth;
+
 #[mockall::automock]
 #[async_trait]
 pub trait HealthCheckRepository: Send + Sync {
     async fn check_db(&self) -> bool;
+    // 以下は Readiness 用に、依存先ごとの状態とレイテンシを返す
+    async fn check_database(&self) -> DependencyHealth;
+    async fn check_redis(&self) -> DependencyHealth;
+    async fn check_migrations(&self) -> DependencyHealth;
+    async fn check_pool(&self) -> DependencyHealth;
 }
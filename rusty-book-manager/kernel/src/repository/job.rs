@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use crate::model::{
+    id::JobRunId,
+    job::{JobName, JobOutcome, JobRun, JobRunListOptions},
+    list::PaginatedList,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait JobRunRepository: Send + Sync {
+    // 実行の開始を記録する
+    async fn start(&self, job_name: JobName, scheduled_at: DateTime<Utc>) -> AppResult<JobRunId>;
+    async fn record_success(&self, run_id: JobRunId, outcome: JobOutcome) -> AppResult<()>;
+    async fn record_failure(&self, run_id: JobRunId, error: String) -> AppResult<()>;
+    // 実行中のまま終わらなかった job_name の実行のうち、scheduled_at より前に予定されたものを
+    // 失敗として記録し、記録した件数を返す
+    async fn fail_stale(&self, job_name: JobName, scheduled_at: DateTime<Utc>) -> AppResult<u64>;
+    // 開始日時の新しい順に返す
+    async fn find_all(&self, options: JobRunListOptions) -> AppResult<PaginatedList<JobRun>>;
+    // before より前に終わった実行の履歴を削除し、削除した件数を返す
+    async fn purge_finished(&self, before: DateTime<Utc>) -> AppResult<u64>;
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait JobLockRepository: Send + Sync {
+    // scheduled_at に予定された job_name の実行のロックを ttl の間取る。
+    // すでにほかのレプリカが取っていれば false を返す
+    async fn try_lock(
+        &self,
+        job_name: JobName,
+        scheduled_at: DateTime<Utc>,
+        ttl: Duration,
+    ) -> AppResult<bool>;
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait Job: Send + Sync {
+    fn name(&self) -> JobName;
+    // 処理した件数と失敗した件数を返す
+    async fn run(&self) -> AppResult<JobOutcome>;
+}
@@ -0,0 +1,11 @@
+use crate::model::mail::Mail;
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait MailRepository: Send + Sync {
+    // 実際にメールを届ける送信手段が設定されているか
+    fn is_deliverable(&self) -> bool;
+    async fn send(&self, mail: Mail) -> AppResult<()>;
+}
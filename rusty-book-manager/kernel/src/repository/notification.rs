@@ -0,0 +1,40 @@
+use crate::model::{
+    id::UserId,
+    list::PaginatedList,
+    notification::{
+        event::{
+            CreateNotification, MarkAllNotificationsRead, MarkNotificationRead,
+            UpdateNotificationPreferences,
+        },
+        Notification, NotificationListOptions, NotificationPreference,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    // 同じ種類の通知がすでに同じものから作られていれば、何もせずに None を返す
+    async fn create(&self, event: CreateNotification) -> AppResult<Option<Notification>>;
+    // 新しい順に返す
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+        options: NotificationListOptions,
+    ) -> AppResult<PaginatedList<Notification>>;
+    async fn count_unread(&self, user_id: UserId) -> AppResult<i64>;
+    async fn mark_read(&self, event: MarkNotificationRead) -> AppResult<()>;
+    // 既読にした件数を返す
+    async fn mark_all_read(&self, event: MarkAllNotificationsRead) -> AppResult<u64>;
+    // すべての種類の設定を返す。利用者が設定していない種類は既定の設定を返す
+    async fn find_preferences(&self, user_id: UserId) -> AppResult<Vec<NotificationPreference>>;
+    async fn update_preferences(&self, event: UpdateNotificationPreferences) -> AppResult<()>;
+}
+
+// 利用者に通知を届ける。受信箱に追加し、利用者がその種類をメールでも受け取る設定にしていればメールも送る
+#[mockall::automock]
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, event: CreateNotification) -> AppResult<()>;
+}
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use crate::model::{domain_event::OutboxEvent, id::DomainEventId};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    // 未配信のイベントを古い順に最大 limit 件取り出す。取り出したイベントは lease の間ほかの配信処理から見えない
+    async fn claim_due(&self, limit: i64, lease: Duration) -> AppResult<Vec<OutboxEvent>>;
+    // イベントを処理し終えたハンドラの名前
+    async fn find_handled(&self, event_id: DomainEventId) -> AppResult<Vec<String>>;
+    async fn record_handled(&self, event_id: DomainEventId, handler: &str) -> AppResult<()>;
+    // すべてのハンドラが処理し終えたイベントを配信済みにする
+    async fn record_published(&self, event_id: DomainEventId) -> AppResult<()>;
+    // 処理に失敗したハンドラがあるイベントの再送を予約する
+    async fn record_failure(&self, event_id: DomainEventId, error: String) -> AppResult<()>;
+    // before より前に配信済みになったイベントを削除し、削除した件数を返す
+    async fn purge_published(&self, before: DateTime<Utc>) -> AppResult<u64>;
+}
+
+// outbox のイベントを受け取る処理。同じイベントが複数回渡されることがあるので、
+// OutboxEvent::id を使って冪等に処理する
+#[mockall::automock]
+#[async_trait]
+pub trait DomainEventHandler: Send + Sync {
+    // 処理済みの記録に使う名前。変えると処理済みのイベントも再び渡される
+    fn name(&self) -> &'static str;
+    async fn handle(&self, event: &OutboxEvent) -> AppResult<()>;
+}
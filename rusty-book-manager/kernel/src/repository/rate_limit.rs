@@ -0,0 +1,11 @@
+use crate::model::rate_limit::{RateLimitDecision, RateLimitQuota};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait RateLimitRepository: Send + Sync {
+    // key に対するリクエストを 1 件数え、quota の範囲内に収まるかを返す。
+    // 範囲を超えた場合、そのリクエストは数えない
+    async fn hit(&self, key: &str, quota: RateLimitQuota) -> AppResult<RateLimitDecision>;
+}
@@ -0,0 +1,12 @@
+use crate::model::{id::UserId, recommendation::Recommendation};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait RecommendationRepository: Send + Sync {
+    // 利用者が読んだ（借りた）蔵書と自分の蔵書は除き、score の高い順に返す
+    async fn find_by_user_id(&self, user_id: UserId, limit: i64) -> AppResult<Vec<Recommendation>>;
+    // 貸出履歴の集計を最新にする。定期的に呼び出す
+    async fn refresh(&self) -> AppResult<()>;
+}
@@ -0,0 +1,38 @@
+use crate::model::report::{
+    AuthorCheckoutRanking, BookCheckoutRanking, BorrowerRanking, CheckoutCount, Granularity,
+    IdleBook, LoanDuration, ReportPeriod,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// 貸出の統計。特に断りがなければ、貸出中・返却済みの貸出を貸出日時で期間に絞り込んで集計する
+#[mockall::automock]
+#[async_trait]
+pub trait ReportRepository: Send + Sync {
+    // 集計単位ごとの貸出数。貸出のない単位も 0 件として返す
+    async fn checkout_counts(
+        &self,
+        period: ReportPeriod,
+        granularity: Granularity,
+    ) -> AppResult<Vec<CheckoutCount>>;
+    async fn top_books(
+        &self,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> AppResult<Vec<BookCheckoutRanking>>;
+    // 著者（翻訳者・編者は除く）ごとの貸出数
+    async fn top_authors(
+        &self,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> AppResult<Vec<AuthorCheckoutRanking>>;
+    async fn top_borrowers(
+        &self,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> AppResult<Vec<BorrowerRanking>>;
+    // 期間内に返却された貸出の平均の貸出日数
+    async fn loan_duration(&self, period: ReportPeriod) -> AppResult<LoanDuration>;
+    // 期間内に一度も貸し出されていない蔵書を登録の古い順に返す
+    async fn idle_books(&self, period: ReportPeriod, limit: i64) -> AppResult<Vec<IdleBook>>;
+}
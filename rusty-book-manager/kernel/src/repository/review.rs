@@ -0,0 +1,24 @@
+use crate::model::{
+    id::BookId,
+    review::{
+        event::{CreateReview, DeleteReview, ModerateReview, UpdateReview},
+        Review,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait ReviewRepository: Send + Sync {
+    async fn create(&self, event: CreateReview) -> AppResult<Review>;
+    // 新しい順に返す。非表示のレビューは include_hidden が true の場合のみ含める
+    async fn find_by_book_id(
+        &self,
+        book_id: BookId,
+        include_hidden: bool,
+    ) -> AppResult<Vec<Review>>;
+    async fn update(&self, event: UpdateReview) -> AppResult<()>;
+    async fn delete(&self, event: DeleteReview) -> AppResult<()>;
+    async fn moderate(&self, event: ModerateReview) -> AppResult<()>;
+}
@@ -0,0 +1,19 @@
+use crate::model::{
+    id::UserId,
+    shelf::{
+        event::{AddShelfBook, CreateShelf, DeleteShelf, RemoveShelfBook},
+        Shelf,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait ShelfRepository: Send + Sync {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Shelf>>;
+    async fn create(&self, event: CreateShelf) -> AppResult<Shelf>;
+    async fn delete(&self, event: DeleteShelf) -> AppResult<()>;
+    async fn add_book(&self, event: AddShelfBook) -> AppResult<()>;
+    async fn remove_book(&self, event: RemoveShelfBook) -> AppResult<()>;
+}
@@ -0,0 +1,15 @@
+use crate::model::tag::{
+    event::{AddBookTag, RemoveBookTag},
+    Tag,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    // 蔵書に付いているタグを、付いている蔵書の多い順に返す
+    async fn find_all(&self) -> AppResult<Vec<Tag>>;
+    async fn add_to_book(&self, event: AddBookTag) -> AppResult<()>;
+    async fn remove_from_book(&self, event: RemoveBookTag) -> AppResult<()>;
+}
@@ -1,7 +1,10 @@
 use crate::model::{
     id::UserId,
     user::{
-        event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+        event::{
+            CreateUser, DeleteUser, ResetUserPassword, UpdateUserEmail, UpdateUserName,
+            UpdateUserPassword, UpdateUserRole,
+        },
         User,
     },
 };
@@ -13,8 +16,13 @@ use shared::error::AppResult;
 pub trait UserRepository: Send + Sync {
     async fn find_current_user(&self, current_user_id: UserId) -> AppResult<Option<User>>;
     async fn find_all(&self) -> AppResult<Vec<User>>;
+    async fn find_by_email(&self, email: &str) -> AppResult<Option<User>>;
     async fn create(&self, event: CreateUser) -> AppResult<User>;
     async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
+    async fn reset_password(&self, event: ResetUserPassword) -> AppResult<()>;
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()>;
-    async fn delete(&self, event: DeleteUser) -> AppResult<()>;
+    async fn update_name(&self, event: UpdateUserName) -> AppResult<()>;
+    async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()>;
+    // 所有する蔵書も削除されるため、それらの表紙のキーを返す
+    async fn delete(&self, event: DeleteUser) -> AppResult<Vec<String>>;
 }
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use crate::model::{
+    id::{DomainEventId, WebhookDeliveryId},
+    list::PaginatedList,
+    webhook::{
+        event::{CreateWebhookSubscription, DeleteWebhookSubscription, RecordWebhookFailure},
+        PendingWebhookDelivery, WebhookDelivery, WebhookDeliveryListOptions, WebhookEvent,
+        WebhookSubscription,
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn create_subscription(
+        &self,
+        event: CreateWebhookSubscription,
+    ) -> AppResult<WebhookSubscription>;
+    async fn find_subscriptions(&self) -> AppResult<Vec<WebhookSubscription>>;
+    async fn delete_subscription(&self, event: DeleteWebhookSubscription) -> AppResult<()>;
+    // イベントを購読しているすべての宛先について配信をキューに積む。
+    // 同じ event_id のイベントを再び積んでも、同じ宛先への配信は増えない
+    async fn enqueue(
+        &self,
+        event_id: DomainEventId,
+        occurred_at: DateTime<Utc>,
+        event: WebhookEvent,
+    ) -> AppResult<()>;
+    // 新しい順に返す
+    async fn find_deliveries(
+        &self,
+        options: WebhookDeliveryListOptions,
+    ) -> AppResult<PaginatedList<WebhookDelivery>>;
+    // 送信を諦めた（dead の）配信の失敗回数を数え直して、すぐに送り直す
+    async fn redeliver(&self, delivery_id: WebhookDeliveryId) -> AppResult<()>;
+    // 送信時刻を過ぎた配信を最大 limit 件取り出す。取り出した配信は lease の間ほかの送信処理から見えない
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease: Duration,
+    ) -> AppResult<Vec<PendingWebhookDelivery>>;
+    async fn record_success(&self, delivery_id: WebhookDeliveryId) -> AppResult<()>;
+    async fn record_failure(&self, event: RecordWebhookFailure) -> AppResult<()>;
+    // before より前に送信済み・送信を諦めた配信を削除し、削除した件数を返す
+    async fn purge_finished(&self, before: DateTime<Utc>) -> AppResult<u64>;
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    // 購読先に署名したペイロードを送る。2xx 以外の応答はエラーとする
+    async fn send(&self, delivery: &PendingWebhookDelivery) -> AppResult<()>;
+}
@@ -2,16 +2,66 @@ use std::sync::Arc;
 
 use adapter::redis::RedisClient;
 use adapter::repository::auth::AuthRepositoryImpl;
+use adapter::repository::author::AuthorRepositoryImpl;
+use adapter::repository::blob::{filesystem::FilesystemBlobStore, s3::S3BlobStore};
 use adapter::repository::book::BookRepositoryImpl;
+use adapter::repository::book_cache::{
+    BookCache, BookCacheInvalidatingBookConditionRepository,
+    BookCacheInvalidatingBookTransferRepository, BookCacheInvalidatingCheckoutRepository,
+    BookCacheInvalidatingGenreRepository, BookCacheInvalidatingReviewRepository,
+    BookCacheInvalidatingTagRepository, BookCacheInvalidatingUserRepository, CachedBookRepository,
+};
+use adapter::repository::book_condition::BookConditionRepositoryImpl;
+use adapter::repository::book_metadata::OpenLibraryMetadataProvider;
+use adapter::repository::book_transfer::BookTransferRepositoryImpl;
 use adapter::repository::checkout::CheckoutRepositoryImpl;
+use adapter::repository::checkout_reminder::CheckoutReminderRepositoryImpl;
+use adapter::repository::email_verification::EmailVerificationRepositoryImpl;
+use adapter::repository::event_stream::{EventStreamHandler, EventStreamRepositoryImpl};
+use adapter::repository::genre::GenreRepositoryImpl;
+use adapter::repository::job::{JobLockRepositoryImpl, JobRunRepositoryImpl};
+use adapter::repository::mail::{LoggingMailRepository, SmtpMailRepository};
+use adapter::repository::notification::{NotificationRepositoryImpl, NotifierImpl};
+use adapter::repository::notification_event::NotificationEventHandler;
+use adapter::repository::outbox::OutboxRepositoryImpl;
+use adapter::repository::rate_limit::RateLimitRepositoryImpl;
+use adapter::repository::recommendation::RecommendationRepositoryImpl;
+use adapter::repository::report::ReportRepositoryImpl;
+use adapter::repository::review::ReviewRepositoryImpl;
+use adapter::repository::scheduled_job::{AuditPurgeJob, CheckoutReminderJob, TokenCleanupJob};
+use adapter::repository::shelf::ShelfRepositoryImpl;
+use adapter::repository::tag::TagRepositoryImpl;
 use adapter::repository::user::UserRepositoryImpl;
+use adapter::repository::webhook::WebhookRepositoryImpl;
+use adapter::repository::webhook_event::WebhookEventHandler;
+use adapter::repository::webhook_sender::HttpWebhookSender;
 use adapter::{database::ConnectionPool, repository::health::HealthCheckRepositoryImpl};
 use kernel::repository::auth::AuthRepository;
+use kernel::repository::author::AuthorRepository;
+use kernel::repository::blob::BlobStore;
 use kernel::repository::book::BookRepository;
+use kernel::repository::book_condition::BookConditionRepository;
+use kernel::repository::book_metadata::BookMetadataProvider;
+use kernel::repository::book_transfer::BookTransferRepository;
 use kernel::repository::checkout::CheckoutRepository;
+use kernel::repository::email_verification::EmailVerificationRepository;
+use kernel::repository::event_stream::EventStreamRepository;
+use kernel::repository::genre::GenreRepository;
 use kernel::repository::health::HealthCheckRepository;
+use kernel::repository::job::{Job, JobLockRepository, JobRunRepository};
+use kernel::repository::mail::MailRepository;
+use kernel::repository::notification::{NotificationRepository, Notifier};
+use kernel::repository::outbox::{DomainEventHandler, OutboxRepository};
+use kernel::repository::rate_limit::RateLimitRepository;
+use kernel::repository::recommendation::RecommendationRepository;
+use kernel::repository::report::ReportRepository;
+use kernel::repository::review::ReviewRepository;
+use kernel::repository::shelf::ShelfRepository;
+use kernel::repository::tag::TagRepository;
 use kernel::repository::user::UserRepository;
-use shared::config::AppConfig;
+use kernel::repository::webhook::{WebhookRepository, WebhookSender};
+use shared::config::{AppConfig, StorageConfig};
+use shared::error::AppResult;
 
 #[derive(Clone)]
 pub struct AppRegistryImpl {
@@ -20,30 +70,204 @@ pub struct AppRegistryImpl {
     auth_repository: Arc<dyn AuthRepository>,
     user_repository: Arc<dyn UserRepository>,
     checkout_repository: Arc<dyn CheckoutRepository>,
+    book_transfer_repository: Arc<dyn BookTransferRepository>,
+    book_condition_repository: Arc<dyn BookConditionRepository>,
+    rate_limit_repository: Arc<dyn RateLimitRepository>,
+    email_verification_repository: Arc<dyn EmailVerificationRepository>,
+    mail_repository: Arc<dyn MailRepository>,
+    notification_repository: Arc<dyn NotificationRepository>,
+    book_metadata_provider: Arc<dyn BookMetadataProvider>,
+    blob_store: Arc<dyn BlobStore>,
+    tag_repository: Arc<dyn TagRepository>,
+    genre_repository: Arc<dyn GenreRepository>,
+    shelf_repository: Arc<dyn ShelfRepository>,
+    author_repository: Arc<dyn AuthorRepository>,
+    review_repository: Arc<dyn ReviewRepository>,
+    recommendation_repository: Arc<dyn RecommendationRepository>,
+    report_repository: Arc<dyn ReportRepository>,
+    webhook_repository: Arc<dyn WebhookRepository>,
+    webhook_sender: Arc<dyn WebhookSender>,
+    outbox_repository: Arc<dyn OutboxRepository>,
+    event_stream_repository: Arc<dyn EventStreamRepository>,
+    domain_event_handlers: Vec<Arc<dyn DomainEventHandler>>,
+    job_run_repository: Arc<dyn JobRunRepository>,
+    job_lock_repository: Arc<dyn JobLockRepository>,
+    scheduled_jobs: Vec<Arc<dyn Job>>,
 }
 
 impl AppRegistryImpl {
     pub fn new(
         pool: ConnectionPool,
         redis_client: Arc<RedisClient>,
-        app_config: AppConfig,
-    ) -> Self {
-        let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(pool.clone()));
-        let book_repository = Arc::new(BookRepositoryImpl::new(pool.clone()));
-        let auth_repository = Arc::new(AuthRepositoryImpl::new(
+        app_config: &AppConfig,
+    ) -> AppResult<Self> {
+        let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(
+            pool.clone(),
+            redis_client.clone(),
+        ));
+        let mut book_repository: Arc<dyn BookRepository> =
+            Arc::new(BookRepositoryImpl::new(pool.clone()));
+        let mut checkout_repository: Arc<dyn CheckoutRepository> =
+            Arc::new(CheckoutRepositoryImpl::new(pool.clone()));
+        let mut tag_repository: Arc<dyn TagRepository> =
+            Arc::new(TagRepositoryImpl::new(pool.clone()));
+        let mut genre_repository: Arc<dyn GenreRepository> =
+            Arc::new(GenreRepositoryImpl::new(pool.clone()));
+        let mut review_repository: Arc<dyn ReviewRepository> =
+            Arc::new(ReviewRepositoryImpl::new(pool.clone()));
+        let mut book_transfer_repository: Arc<dyn BookTransferRepository> =
+            Arc::new(BookTransferRepositoryImpl::new(pool.clone()));
+        let mut book_condition_repository: Arc<dyn BookConditionRepository> =
+            Arc::new(BookConditionRepositoryImpl::new(pool.clone()));
+        let mut user_repository: Arc<dyn UserRepository> =
+            Arc::new(UserRepositoryImpl::new(pool.clone()));
+        // キャッシュを有効にする場合は、蔵書の読み込みをキャッシュし、貸出・返却やタグ・ジャンルの付け外し、
+        // レビューの変更、譲渡、紛失した蔵書の発見、ユーザーの変更・削除でキャッシュを破棄するデコレータで包む
+        if app_config.cache.enabled {
+            let cache = Arc::new(BookCache::new(redis_client.clone(), app_config.cache.ttl));
+            book_repository = Arc::new(CachedBookRepository::new(book_repository, cache.clone()));
+            checkout_repository = Arc::new(BookCacheInvalidatingCheckoutRepository::new(
+                checkout_repository,
+                cache.clone(),
+            ));
+            tag_repository = Arc::new(BookCacheInvalidatingTagRepository::new(
+                tag_repository,
+                cache.clone(),
+            ));
+            genre_repository = Arc::new(BookCacheInvalidatingGenreRepository::new(
+                genre_repository,
+                cache.clone(),
+            ));
+            review_repository = Arc::new(BookCacheInvalidatingReviewRepository::new(
+                review_repository,
+                cache.clone(),
+            ));
+            book_transfer_repository = Arc::new(BookCacheInvalidatingBookTransferRepository::new(
+                book_transfer_repository,
+                cache.clone(),
+            ));
+            book_condition_repository =
+                Arc::new(BookCacheInvalidatingBookConditionRepository::new(
+                    book_condition_repository,
+                    cache.clone(),
+                ));
+            user_repository = Arc::new(BookCacheInvalidatingUserRepository::new(
+                user_repository,
+                pool.clone(),
+                cache,
+            ));
+        }
+        let auth_repository: Arc<dyn AuthRepository> = Arc::new(AuthRepositoryImpl::new(
             pool.clone(),
             redis_client.clone(),
             app_config.auth.ttl,
         ));
-        let user_repository = Arc::new(UserRepositoryImpl::new(pool.clone()));
-        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(pool.clone()));
-        Self {
+        let shelf_repository = Arc::new(ShelfRepositoryImpl::new(pool.clone()));
+        let author_repository = Arc::new(AuthorRepositoryImpl::new(pool.clone()));
+        let recommendation_repository = Arc::new(RecommendationRepositoryImpl::new(pool.clone()));
+        let report_repository = Arc::new(ReportRepositoryImpl::new(pool.clone()));
+        let rate_limit_repository = Arc::new(RateLimitRepositoryImpl::new(redis_client.clone()));
+        let email_verification_repository = Arc::new(EmailVerificationRepositoryImpl::new(
+            redis_client.clone(),
+            app_config.auth.email_verification_ttl,
+        ));
+        let mail_repository: Arc<dyn MailRepository> = match &app_config.mail.smtp {
+            Some(smtp) => Arc::new(SmtpMailRepository::new(smtp)?),
+            None => Arc::new(LoggingMailRepository),
+        };
+        let notification_repository: Arc<dyn NotificationRepository> =
+            Arc::new(NotificationRepositoryImpl::new(pool.clone()));
+        let notifier: Arc<dyn Notifier> = Arc::new(NotifierImpl::new(
+            notification_repository.clone(),
+            user_repository.clone(),
+            mail_repository.clone(),
+        ));
+        let book_metadata_provider = Arc::new(OpenLibraryMetadataProvider::new(
+            app_config.metadata.base_url.clone(),
+            app_config.metadata.timeout,
+        )?);
+        let webhook_repository: Arc<dyn WebhookRepository> = Arc::new(WebhookRepositoryImpl::new(
+            pool.clone(),
+            app_config.webhook.max_attempts,
+            app_config.webhook.retry_base_delay,
+        ));
+        let webhook_sender = Arc::new(HttpWebhookSender::new(app_config.webhook.timeout)?);
+        let outbox_repository: Arc<dyn OutboxRepository> = Arc::new(OutboxRepositoryImpl::new(
+            pool.clone(),
+            app_config.outbox.retry_base_delay,
+            app_config.outbox.retry_max_delay,
+        ));
+        let event_stream_repository: Arc<dyn EventStreamRepository> =
+            Arc::new(EventStreamRepositoryImpl::new(redis_client.clone()));
+        // outbox のイベントを受け取る処理
+        let domain_event_handlers: Vec<Arc<dyn DomainEventHandler>> = vec![
+            Arc::new(WebhookEventHandler::new(
+                book_repository.clone(),
+                user_repository.clone(),
+                webhook_repository.clone(),
+            )),
+            Arc::new(EventStreamHandler::new(event_stream_repository.clone())),
+            Arc::new(NotificationEventHandler::new(
+                book_repository.clone(),
+                user_repository.clone(),
+                notifier.clone(),
+            )),
+        ];
+        let job_run_repository: Arc<dyn JobRunRepository> =
+            Arc::new(JobRunRepositoryImpl::new(pool.clone()));
+        let job_lock_repository = Arc::new(JobLockRepositoryImpl::new(redis_client.clone()));
+        // スケジューラが実行するジョブ。実行の予定は設定ファイルで指定する
+        let scheduled_jobs: Vec<Arc<dyn Job>> = vec![
+            Arc::new(CheckoutReminderJob::new(
+                Arc::new(CheckoutReminderRepositoryImpl::new(
+                    pool.clone(),
+                    app_config.scheduler.loan_period,
+                    app_config.scheduler.due_soon,
+                )),
+                notifier,
+            )),
+            Arc::new(TokenCleanupJob::new(auth_repository.clone())),
+            Arc::new(AuditPurgeJob::new(
+                outbox_repository.clone(),
+                webhook_repository.clone(),
+                job_run_repository.clone(),
+                app_config.scheduler.audit_retention,
+            )),
+        ];
+        let blob_store: Arc<dyn BlobStore> = match &app_config.storage {
+            StorageConfig::Filesystem { path } => Arc::new(FilesystemBlobStore::new(path.clone())),
+            StorageConfig::S3(s3) => Arc::new(S3BlobStore::new(s3)?),
+        };
+        Ok(Self {
             health_check_repository,
             book_repository,
             auth_repository,
             user_repository,
             checkout_repository,
-        }
+            book_transfer_repository,
+            book_condition_repository,
+            rate_limit_repository,
+            email_verification_repository,
+            mail_repository,
+            notification_repository,
+            book_metadata_provider,
+            blob_store,
+            tag_repository,
+            genre_repository,
+            shelf_repository,
+            author_repository,
+            review_repository,
+            recommendation_repository,
+            report_repository,
+            webhook_repository,
+            webhook_sender,
+            outbox_repository,
+            event_stream_repository,
+            domain_event_handlers,
+            job_run_repository,
+            job_lock_repository,
+            scheduled_jobs,
+        })
     }
 }
 
@@ -54,6 +278,29 @@ pub trait AppRegistryExt {
     fn auth_repository(&self) -> Arc<dyn AuthRepository>;
     fn user_repository(&self) -> Arc<dyn UserRepository>;
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository>;
+    fn book_transfer_repository(&self) -> Arc<dyn BookTransferRepository>;
+    fn book_condition_repository(&self) -> Arc<dyn BookConditionRepository>;
+    fn rate_limit_repository(&self) -> Arc<dyn RateLimitRepository>;
+    fn email_verification_repository(&self) -> Arc<dyn EmailVerificationRepository>;
+    fn mail_repository(&self) -> Arc<dyn MailRepository>;
+    fn notification_repository(&self) -> Arc<dyn NotificationRepository>;
+    fn book_metadata_provider(&self) -> Arc<dyn BookMetadataProvider>;
+    fn blob_store(&self) -> Arc<dyn BlobStore>;
+    fn tag_repository(&self) -> Arc<dyn TagRepository>;
+    fn genre_repository(&self) -> Arc<dyn GenreRepository>;
+    fn shelf_repository(&self) -> Arc<dyn ShelfRepository>;
+    fn author_repository(&self) -> Arc<dyn AuthorRepository>;
+    fn review_repository(&self) -> Arc<dyn ReviewRepository>;
+    fn recommendation_repository(&self) -> Arc<dyn RecommendationRepository>;
+    fn report_repository(&self) -> Arc<dyn ReportRepository>;
+    fn webhook_repository(&self) -> Arc<dyn WebhookRepository>;
+    fn webhook_sender(&self) -> Arc<dyn WebhookSender>;
+    fn outbox_repository(&self) -> Arc<dyn OutboxRepository>;
+    fn event_stream_repository(&self) -> Arc<dyn EventStreamRepository>;
+    fn domain_event_handlers(&self) -> Vec<Arc<dyn DomainEventHandler>>;
+    fn job_run_repository(&self) -> Arc<dyn JobRunRepository>;
+    fn job_lock_repository(&self) -> Arc<dyn JobLockRepository>;
+    fn scheduled_jobs(&self) -> Vec<Arc<dyn Job>>;
 }
 impl AppRegistryExt for AppRegistryImpl {
     fn health_check_repository(&self) -> Arc<dyn HealthCheckRepository> {
@@ -75,6 +322,98 @@ impl AppRegistryExt for AppRegistryImpl {
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository> {
         self.checkout_repository.clone()
     }
+
+    fn book_transfer_repository(&self) -> Arc<dyn BookTransferRepository> {
+        self.book_transfer_repository.clone()
+    }
+
+    fn book_condition_repository(&self) -> Arc<dyn BookConditionRepository> {
+        self.book_condition_repository.clone()
+    }
+
+    fn rate_limit_repository(&self) -> Arc<dyn RateLimitRepository> {
+        self.rate_limit_repository.clone()
+    }
+
+    fn email_verification_repository(&self) -> Arc<dyn EmailVerificationRepository> {
+        self.email_verification_repository.clone()
+    }
+
+    fn mail_repository(&self) -> Arc<dyn MailRepository> {
+        self.mail_repository.clone()
+    }
+
+    fn notification_repository(&self) -> Arc<dyn NotificationRepository> {
+        self.notification_repository.clone()
+    }
+
+    fn book_metadata_provider(&self) -> Arc<dyn BookMetadataProvider> {
+        self.book_metadata_provider.clone()
+    }
+
+    fn blob_store(&self) -> Arc<dyn BlobStore> {
+        self.blob_store.clone()
+    }
+
+    fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    fn genre_repository(&self) -> Arc<dyn GenreRepository> {
+        self.genre_repository.clone()
+    }
+
+    fn shelf_repository(&self) -> Arc<dyn ShelfRepository> {
+        self.shelf_repository.clone()
+    }
+
+    fn author_repository(&self) -> Arc<dyn AuthorRepository> {
+        self.author_repository.clone()
+    }
+
+    fn review_repository(&self) -> Arc<dyn ReviewRepository> {
+        self.review_repository.clone()
+    }
+
+    fn recommendation_repository(&self) -> Arc<dyn RecommendationRepository> {
+        self.recommendation_repository.clone()
+    }
+
+    fn report_repository(&self) -> Arc<dyn ReportRepository> {
+        self.report_repository.clone()
+    }
+
+    fn webhook_repository(&self) -> Arc<dyn WebhookRepository> {
+        self.webhook_repository.clone()
+    }
+
+    fn webhook_sender(&self) -> Arc<dyn WebhookSender> {
+        self.webhook_sender.clone()
+    }
+
+    fn outbox_repository(&self) -> Arc<dyn OutboxRepository> {
+        self.outbox_repository.clone()
+    }
+
+    fn event_stream_repository(&self) -> Arc<dyn EventStreamRepository> {
+        self.event_stream_repository.clone()
+    }
+
+    fn domain_event_handlers(&self) -> Vec<Arc<dyn DomainEventHandler>> {
+        self.domain_event_handlers.clone()
+    }
+
+    fn job_run_repository(&self) -> Arc<dyn JobRunRepository> {
+        self.job_run_repository.clone()
+    }
+
+    fn job_lock_repository(&self) -> Arc<dyn JobLockRepository> {
+        self.job_lock_repository.clone()
+    }
+
+    fn scheduled_jobs(&self) -> Vec<Arc<dyn Job>> {
+        self.scheduled_jobs.clone()
+    }
 }
 
 pub type AppRegistry = Arc<dyn AppRegistryExt + Send + Sync + 'static>;
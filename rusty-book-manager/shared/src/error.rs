@@ -3,6 +3,8 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum AppError {
+    #[error("{0}")]
+    BadRequest(String),
     #[error("{0}")]
     UnprocessableEntity(String),
     #[error("{0}")]
@@ -13,6 +15,8 @@ pub enum AppError {
     TransactionError(#[source] sqlx::Error),
     #[error("データベース処理実行中にエラーが発生しました。")]
     SpecificOperationError(#[source] sqlx::Error),
+    #[error("マイグレーションを適用できませんでした。")]
+    MigrationError(#[source] sqlx::migrate::MigrateError),
     #[error("No rows affected: {0}")]
     NoRowsAffectedError(String),
     #[error("{0}")]
@@ -28,6 +32,20 @@ pub enum AppError {
     #[error("許可されていない操作です")]
     ForbiddenOperation,
     #[error("{0}")]
+    PreconditionFailed(String),
+    #[error("If-Match ヘッダーを指定してください")]
+    PreconditionRequired,
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("外部サービスの呼び出しに失敗しました: {0}")]
+    ExternalServiceError(String),
+    #[error("{0}")]
+    ServiceUnavailable(String),
+    #[error("ファイルの保存・読み込みに失敗しました: {0}")]
+    BlobStoreError(String),
+    #[error("{0}")]
     ConversionEntityError(String),
 }
 
@@ -36,16 +54,27 @@ impl IntoResponse for AppError {
         let status_code = match self {
             AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::EntityNotFound(_) => StatusCode::NOT_FOUND,
-            AppError::ValidationError(_) | AppError::ConvertToUuidError(_) => {
-                StatusCode::BAD_REQUEST
-            }
+            AppError::BadRequest(_)
+            | AppError::ValidationError(_)
+            | AppError::ConvertToUuidError(_) => StatusCode::BAD_REQUEST,
             AppError::UnauthenticatedError | AppError::ForbiddenOperation => StatusCode::FORBIDDEN,
             AppError::UnauthorizedError => StatusCode::UNAUTHORIZED,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            e @ AppError::ExternalServiceError(_) => {
+                tracing::warn!(error.message = %e, "External service is unavailable");
+                StatusCode::BAD_GATEWAY
+            }
             e @ (AppError::TransactionError(_)
             | AppError::SpecificOperationError(_)
+            | AppError::MigrationError(_)
             | AppError::NoRowsAffectedError(_)
             | AppError::KeyValueStoreError(_)
             | AppError::BcryptError(_)
+            | AppError::BlobStoreError(_)
             | AppError::ConversionEntityError(_)) => {
                 tracing::error!(
                 error.cause_chain = ?e,
@@ -0,0 +1,218 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Days, TimeDelta, TimeZone, Timelike, Utc};
+use serde::Deserialize;
+
+// 「分 時 日 月 曜日」の 5 つのフィールドからなる cron 形式のスケジュール。時刻は UTC で解釈する。
+// 各フィールドには *、数値、範囲 (1-5)、間隔 (*/15, 0-30/10) とそれらのカンマ区切りを書ける。
+// 曜日は 0 (または 7) が日曜日
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct CronSchedule {
+    expression: String,
+    // 各フィールドで一致する値をビットで表す
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // 日と曜日の両方を指定した場合は、どちらかに一致すれば実行する（cron と同じ）
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<u64, String> {
+    let invalid = || format!("invalid {name} field {field:?}");
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| invalid())?)),
+            None => (part, None),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse().map_err(|_| invalid())?,
+                end.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let value = range.parse().map_err(|_| invalid())?;
+            // 「5/15」は 5 から最大値まで 15 おき
+            (value, if step.is_some() { max } else { value })
+        };
+        if step == Some(0) || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+impl CronSchedule {
+    // after より後で最初に一致する時刻（分単位）。一致する時刻がなければ None
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        // 最も間隔が空くのは 2 月 29 日（最大 8 年おき）なので、それ以上先は探さない
+        let last_year = after.year() + 8;
+        while t.year() <= last_year {
+            if !contains(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.matches_day(t) {
+                t = (t.date_naive() + Days::new(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if !contains(self.hours, t.hour()) {
+                t = t.with_minute(0)? + TimeDelta::hours(1);
+            } else if !contains(self.minutes, t.minute()) {
+                t += TimeDelta::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, t: DateTime<Utc>) -> bool {
+        let day = contains(self.days, t.day());
+        let weekday = contains(self.weekdays, t.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (minute hour day month weekday) but got {}",
+                fields.len()
+            ));
+        };
+        let mut weekdays = parse_field(weekday, "weekday", 0, 7)?;
+        // 7 も日曜日として扱う
+        if contains(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        let schedule = Self {
+            expression: fields.join(" "),
+            minutes: parse_field(minute, "minute", 0, 59)?,
+            hours: parse_field(hour, "hour", 0, 23)?,
+            days: parse_field(day, "day", 1, 31)?,
+            months: parse_field(month, "month", 1, 12)?,
+            weekdays,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        };
+        // 2 月 30 日のように一致する日がない指定を弾く
+        let origin = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        if schedule.next_after(origin).is_none() {
+            return Err(format!("{s:?} never matches any date"));
+        }
+        Ok(schedule)
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_next_after() {
+        let cases = [
+            (
+                "*/30 * * * *",
+                "2025-04-01T10:15:42Z",
+                "2025-04-01T10:30:00Z",
+            ),
+            (
+                "*/30 * * * *",
+                "2025-04-01T10:30:00Z",
+                "2025-04-01T11:00:00Z",
+            ),
+            ("30 3 * * *", "2025-04-01T03:30:00Z", "2025-04-02T03:30:00Z"),
+            (
+                "0 9 * * 1-5",
+                "2025-04-04T10:00:00Z",
+                "2025-04-07T09:00:00Z",
+            ),
+            (
+                "0 0 1 */3 *",
+                "2025-04-15T00:00:00Z",
+                "2025-07-01T00:00:00Z",
+            ),
+            (
+                "0 0 31 12 *",
+                "2025-04-01T00:00:00Z",
+                "2025-12-31T00:00:00Z",
+            ),
+            ("0 0 29 2 *", "2025-03-01T00:00:00Z", "2028-02-29T00:00:00Z"),
+            // 日と曜日の両方を指定した場合はどちらかに一致すればよい
+            ("0 0 15 * 0", "2025-04-01T00:00:00Z", "2025-04-06T00:00:00Z"),
+            ("0 0 * * 7", "2025-04-01T00:00:00Z", "2025-04-06T00:00:00Z"),
+        ];
+        for (expression, after, expected) in cases {
+            let schedule: CronSchedule = expression.parse().unwrap();
+            assert_eq!(
+                schedule.next_after(at(after)),
+                Some(at(expected)),
+                "{expression} after {after}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "0 0 30 2 *",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "{expression} should be rejected"
+            );
+        }
+    }
+}
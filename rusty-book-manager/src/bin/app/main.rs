@@ -0,0 +1,288 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use adapter::database::{connect_database_with, run_migrations};
+use adapter::redis::RedisClient;
+use adapter::repository::job::JobRunner;
+use adapter::repository::outbox::OutboxDispatcher;
+use anyhow::Result;
+use api::{
+    middleware::rate_limit::RateLimitLayer,
+    route::{auth, v1},
+};
+use axum::{
+    http::{header, Method},
+    Router,
+};
+use chrono::Utc;
+use kernel::model::job::JobName;
+use kernel::model::webhook::{event::RecordWebhookFailure, PendingWebhookDelivery};
+use registry::{AppRegistry, AppRegistryImpl};
+use shared::config::{AppConfig, OutboxConfig, SchedulerConfig, WebhookConfig};
+use shared::schedule::CronSchedule;
+
+use shared::env::{which, Environment};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use anyhow::Context;
+use tower_http::cors::{self, CorsLayer};
+use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
+use tower_http::LatencyUnit;
+use tracing::Level;
+
+mod server;
+
+fn cors() -> CorsLayer {
+    CorsLayer::new()
+        .allow_headers(cors::Any)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_origin(cors::Any)
+        // 条件付きリクエストのため、ブラウザのクライアントから ETag を読めるようにする
+        .expose_headers([header::ETAG])
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_logger()?;
+    bootstrap().await
+}
+
+fn init_logger() -> Result<()> {
+    let log_level = match which() {
+        Environment::Development => "debug",
+        Environment::Production => "info",
+    };
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| log_level.into());
+    let subscriber = tracing_subscriber::fmt::layer()
+        .with_file(true)
+        .with_line_number(true)
+        .with_target(false);
+    #[cfg(not(debug_assertions))]
+    let subscriber = subscriber.json();
+
+    tracing_subscriber::registry()
+        .with(subscriber)
+        .with(env_filter)
+        .try_init()?;
+
+    Ok(())
+}
+
+async fn bootstrap() -> Result<()> {
+    let app_config = AppConfig::new()?;
+    tracing::debug!(config = ?app_config, "Loaded configuration");
+    let pool = connect_database_with(&app_config.database)?;
+    if app_config.database.run_migrations {
+        run_migrations(&pool).await?;
+        tracing::info!("Applied pending migrations");
+    }
+    let kv = Arc::new(RedisClient::new(&app_config.redis)?);
+    let registry: AppRegistry = Arc::new(AppRegistryImpl::new(pool, kv, &app_config)?);
+    tokio::spawn(refresh_recommendations(
+        registry.clone(),
+        app_config.recommendation.refresh_interval,
+    ));
+    tokio::spawn(deliver_webhooks(
+        registry.clone(),
+        app_config.webhook.clone(),
+    ));
+    tokio::spawn(dispatch_outbox(
+        OutboxDispatcher::new(
+            registry.outbox_repository(),
+            registry.domain_event_handlers(),
+        ),
+        app_config.outbox.clone(),
+    ));
+    tokio::spawn(relay_event_stream(registry.clone()));
+    if app_config.scheduler.enabled {
+        start_scheduler(&registry, &app_config.scheduler);
+    }
+
+    let app = Router::new()
+        .merge(v1::routes())
+        .merge(auth::routes())
+        .layer(RateLimitLayer::new(
+            registry.clone(),
+            app_config.rate_limit.clone(),
+        ))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+                .on_request(DefaultOnRequest::new().level(Level::INFO))
+                .on_response(
+                    DefaultOnResponse::new()
+                        .level(Level::INFO)
+                        .latency_unit(LatencyUnit::Millis),
+                ),
+        )
+        .layer(cors())
+        .with_state(registry);
+
+    server::serve(app, &app_config.server)
+        .await
+        .context("Unexpected error happened in server")
+        .inspect_err(|e| {
+            tracing::error!(
+                error.cause_chain = ?e,error.message = %e, "Unexpected error"
+            )
+        })
+}
+
+// 推薦に使う貸出履歴の集計を定期的に作り直す。失敗しても次の周期で再試行する
+async fn refresh_recommendations(registry: AppRegistry, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match registry.recommendation_repository().refresh().await {
+            Ok(()) => tracing::debug!("Refreshed recommendation statistics"),
+            Err(e) => {
+                tracing::warn!(error.message = %e, "Failed to refresh recommendation statistics")
+            }
+        }
+    }
+}
+
+// 一度に取り出す outbox のイベントの数
+const OUTBOX_BATCH_SIZE: i64 = 100;
+// 取り出したイベントをほかのレプリカから隠しておく時間。この間に処理を終えなければ再び取り出される
+const OUTBOX_LEASE: Duration = Duration::from_secs(60);
+
+// outbox に書き込まれたドメインイベントを登録されたハンドラに渡す。
+// 取り出せるイベントが残っている間は待たずに続けて処理する
+async fn dispatch_outbox(dispatcher: OutboxDispatcher, config: OutboxConfig) {
+    let mut interval = tokio::time::interval(config.poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        loop {
+            match dispatcher
+                .dispatch_due(OUTBOX_BATCH_SIZE, OUTBOX_LEASE)
+                .await
+            {
+                Ok(count) if count as i64 == OUTBOX_BATCH_SIZE => continue,
+                Ok(_) => break,
+                Err(e) => {
+                    tracing::warn!(error.message = %e, "Failed to dispatch domain events");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// 各ジョブを設定された予定で実行するタスクを起動する
+fn start_scheduler(registry: &AppRegistry, config: &SchedulerConfig) {
+    for job in registry.scheduled_jobs() {
+        let schedule = match job.name() {
+            JobName::CheckoutReminders => config.checkout_reminders.clone(),
+            JobName::TokenCleanup => config.token_cleanup.clone(),
+            JobName::AuditPurge => config.audit_purge.clone(),
+        };
+        let runner = JobRunner::new(
+            job,
+            registry.job_lock_repository(),
+            registry.job_run_repository(),
+            config.lock_ttl,
+        );
+        tokio::spawn(run_scheduled_job(runner, schedule));
+    }
+}
+
+// 予定の時刻になるたびにジョブを実行する。実行中に過ぎた予定は飛ばす
+async fn run_scheduled_job(runner: JobRunner, schedule: CronSchedule) {
+    let job = runner.name();
+    while let Some(scheduled_at) = schedule.next_after(Utc::now()) {
+        let wait = (scheduled_at - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+        match runner.run(scheduled_at).await {
+            Ok(Some(run_id)) => {
+                tracing::info!(job = job.as_ref(), %run_id, "Ran scheduled job")
+            }
+            Ok(None) => {
+                tracing::debug!(
+                    job = job.as_ref(),
+                    "Scheduled job is run by another replica"
+                )
+            }
+            Err(e) => {
+                tracing::warn!(job = job.as_ref(), error.message = %e, "Failed to run scheduled job")
+            }
+        }
+    }
+}
+
+// Redis との接続が切れたときに受信をやり直すまでの時間
+const EVENT_STREAM_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// ほかのレプリカを含めて追加されたイベントを受信し、このレプリカの SSE の接続に流す
+async fn relay_event_stream(registry: AppRegistry) {
+    loop {
+        match registry.event_stream_repository().listen().await {
+            Ok(()) => tracing::warn!("Event stream subscription closed"),
+            Err(e) => tracing::warn!(error.message = %e, "Failed to subscribe to event stream"),
+        }
+        tokio::time::sleep(EVENT_STREAM_RECONNECT_DELAY).await;
+    }
+}
+
+// 一度に取り出す Webhook の配信の数
+const WEBHOOK_BATCH_SIZE: i64 = 20;
+
+// 送信時刻を過ぎた Webhook の配信を取り出して並行に送る。
+// 取り出した配信は送信の待ち時間の倍だけほかのレプリカから見えなくなる
+async fn deliver_webhooks(registry: AppRegistry, config: WebhookConfig) {
+    let mut interval = tokio::time::interval(config.poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let deliveries = match registry
+            .webhook_repository()
+            .claim_due(WEBHOOK_BATCH_SIZE, config.timeout * 2)
+            .await
+        {
+            Ok(deliveries) => deliveries,
+            Err(e) => {
+                tracing::warn!(error.message = %e, "Failed to claim webhook deliveries");
+                continue;
+            }
+        };
+        let mut tasks = tokio::task::JoinSet::new();
+        for delivery in deliveries {
+            tasks.spawn(deliver_webhook(registry.clone(), delivery));
+        }
+        while tasks.join_next().await.is_some() {}
+    }
+}
+
+async fn deliver_webhook(registry: AppRegistry, delivery: PendingWebhookDelivery) {
+    let webhooks = registry.webhook_repository();
+    let result = match registry.webhook_sender().send(&delivery).await {
+        Ok(()) => webhooks.record_success(delivery.id).await,
+        Err(e) => {
+            tracing::info!(
+                error.message = %e,
+                delivery_id = %delivery.id,
+                attempts = delivery.attempts + 1,
+                "Failed to deliver webhook"
+            );
+            webhooks
+                .record_failure(RecordWebhookFailure {
+                    delivery_id: delivery.id,
+                    error: e.to_string(),
+                })
+                .await
+        }
+    };
+    if let Err(e) = result {
+        tracing::warn!(error.message = %e, delivery_id = %delivery.id, "Failed to record webhook delivery");
+    }
+}
@@ -0,0 +1,167 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use shared::config::{ListenAddr, ServerConfig, TlsConfig};
+
+pub async fn serve(app: Router, config: &ServerConfig) -> Result<()> {
+    match &config.listen {
+        ListenAddr::Tcp(addr) => serve_tcp(app, *addr, config).await,
+        ListenAddr::Unix(path) => serve_unix(app, path, config.shutdown_timeout).await,
+    }
+}
+
+async fn serve_tcp(app: Router, addr: SocketAddr, config: &ServerConfig) -> Result<()> {
+    let handle = Handle::new();
+    tokio::spawn(graceful_shutdown(handle.clone(), config.shutdown_timeout));
+
+    // レートリミットなどでクライアントの IP アドレスを参照できるようにする
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    match &config.tls {
+        Some(tls) => {
+            // 暗号ライブラリは ring を使う（既にインストール済みならエラーになるが問題ない）
+            let _ = rustls::crypto::ring::default_provider().install_default();
+            let rustls = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+                .await
+                .context("Failed to load TLS certificate")?;
+            tokio::spawn(watch_certificate(rustls.clone(), tls.clone()));
+
+            tracing::info!("Listening on https://{}", addr);
+            axum_server::bind_rustls(addr, rustls)
+                .handle(handle)
+                .serve(app)
+                .await?;
+        }
+        None => {
+            tracing::info!("Listening on http://{}", addr);
+            axum_server::bind(addr).handle(handle).serve(app).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn serve_unix(app: Router, path: &Path, shutdown_timeout: Duration) -> Result<()> {
+    use hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::{conn::auto::Builder, graceful::GracefulShutdown},
+        service::TowerToHyperService,
+    };
+    use tokio::net::UnixListener;
+
+    // 前回の異常終了で残ったソケットファイルがあると bind できないので削除しておく
+    if path.exists() {
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind unix socket {}", path.display()))?;
+    tracing::info!("Listening on unix:{}", path.display());
+
+    let builder = Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+    let mut signal = std::pin::pin!(shutdown_signal());
+
+    loop {
+        tokio::select! {
+            conn = listener.accept() => {
+                let stream = match conn {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!(error.message = %e, "Failed to accept connection");
+                        continue;
+                    }
+                };
+                let service = TowerToHyperService::new(app.clone());
+                let conn = builder
+                    .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                    .into_owned();
+                let conn = graceful.watch(conn);
+                tokio::spawn(async move {
+                    if let Err(e) = conn.await {
+                        tracing::debug!(error.message = %e, "Connection closed with error");
+                    }
+                });
+            }
+            _ = &mut signal => break,
+        }
+    }
+
+    drop(listener);
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = tokio::time::sleep(shutdown_timeout) => {
+            tracing::warn!("Shutdown timed out before all connections were closed");
+        }
+    }
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn serve_unix(_app: Router, _path: &Path, _shutdown_timeout: Duration) -> Result<()> {
+    anyhow::bail!("Unix domain sockets are not supported on this platform")
+}
+
+async fn graceful_shutdown(handle: Handle, timeout: Duration) {
+    shutdown_signal().await;
+    // 新規接続の受付を止め、処理中のリクエストは timeout まで完了を待つ
+    handle.graceful_shutdown(Some(timeout));
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("Shutdown signal received, draining in-flight requests");
+}
+
+// 証明書ファイルの更新日時を定期的に確認し、変わっていれば再読み込みする
+async fn watch_certificate(rustls: RustlsConfig, tls: TlsConfig) {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    let snapshot = || -> (Option<SystemTime>, Option<SystemTime>) {
+        (modified(&tls.cert_path), modified(&tls.key_path))
+    };
+
+    let mut last = snapshot();
+    let mut interval = tokio::time::interval(tls.reload_interval);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let current = snapshot();
+        if current == last {
+            continue;
+        }
+        match rustls
+            .reload_from_pem_file(&tls.cert_path, &tls.key_path)
+            .await
+        {
+            Ok(()) => {
+                tracing::info!("Reloaded TLS certificate");
+                last = current;
+            }
+            // 書き込み途中の可能性があるので、古い証明書のまま次回再試行する
+            Err(e) => tracing::warn!(error.message = %e, "Failed to reload TLS certificate"),
+        }
+    }
+}
@@ -0,0 +1,470 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use adapter::database::{connect_database_with, run_migrations, ConnectionPool};
+use adapter::redis::RedisClient;
+use anyhow::{bail, Context, Result};
+use api::model::{
+    checkout::{CheckoutResponse, CheckoutsResponse},
+    user::{UserResponse, UsersResponse},
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use kernel::model::{
+    checkout::event::ForceReturned,
+    id::{CheckoutId, UserId},
+    role::Role,
+    user::{
+        event::{CreateUser, ResetUserPassword, UpdateUserRole},
+        User,
+    },
+};
+use registry::{AppRegistryExt, AppRegistryImpl};
+use serde::Serialize;
+use shared::config::AppConfig;
+
+mod seed;
+
+#[derive(Parser)]
+#[command(name = "bookctl", about = "蔵書管理アプリケーションの運用コマンド")]
+struct Cli {
+    /// 出力形式
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human, global = true)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Human,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// ユーザーの管理
+    #[command(subcommand)]
+    User(UserCommand),
+    /// 貸出の管理
+    #[command(subcommand)]
+    Checkout(CheckoutCommand),
+    /// ログインセッション（アクセストークン）の管理
+    #[command(subcommand)]
+    Session(SessionCommand),
+    /// 未適用のマイグレーションを適用する
+    Migrate,
+    /// デモ用のユーザーと蔵書を投入する（開発環境専用。本番環境では実行しないこと）
+    Seed {
+        /// デモ用アカウント共通のパスワード
+        #[arg(long, env = "BOOKCTL_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum UserCommand {
+    /// ユーザーを作成する
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long, env = "BOOKCTL_PASSWORD", hide_env_values = true)]
+        password: String,
+        /// 管理者として作成する
+        #[arg(long)]
+        admin: bool,
+    },
+    /// ユーザーの一覧を表示する
+    List,
+    /// パスワードを再設定し、発行済みのアクセストークンをすべて失効させる
+    ResetPassword {
+        /// ユーザー ID またはメールアドレス
+        user: String,
+        #[arg(long, env = "BOOKCTL_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+    /// ロールを変更する
+    SetRole {
+        /// ユーザー ID またはメールアドレス
+        user: String,
+        #[arg(long, value_enum)]
+        role: RoleArg,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RoleArg {
+    Admin,
+    User,
+}
+
+impl From<RoleArg> for Role {
+    fn from(value: RoleArg) -> Self {
+        match value {
+            RoleArg::Admin => Role::Admin,
+            RoleArg::User => Role::User,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum CheckoutCommand {
+    /// 貸出中の一覧を表示する
+    List {
+        /// 指定したユーザー（ID またはメールアドレス）の貸出のみ表示する
+        #[arg(long)]
+        user: Option<String>,
+    },
+    /// 借りているユーザーに代わって返却済みにする（管理者が返却したものとして記録する）
+    ForceReturn {
+        checkout_id: CheckoutId,
+        /// 操作する管理者（ID またはメールアドレス）
+        #[arg(long)]
+        operator: String,
+        /// 代わりに返却する理由
+        #[arg(long)]
+        reason: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionCommand {
+    /// 指定したユーザーのアクセストークンをすべて失効させる
+    Revoke {
+        /// ユーザー ID またはメールアドレス
+        user: String,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PasswordResetResponse {
+    #[serde(flatten)]
+    user: UserResponse,
+    revoked_sessions: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RevokedSessionsResponse {
+    user_id: UserId,
+    revoked: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let app_config = AppConfig::new()?;
+    let pool = connect_database_with(&app_config.database)?;
+    let kv = Arc::new(RedisClient::new(&app_config.redis)?);
+    let registry = AppRegistryImpl::new(pool.clone(), kv, &app_config)?;
+
+    match cli.command {
+        Command::User(cmd) => run_user(&registry, cli.output, cmd).await,
+        Command::Checkout(cmd) => run_checkout(&registry, cli.output, cmd).await,
+        Command::Session(SessionCommand::Revoke { user }) => {
+            let user = find_user(&registry, &user).await?;
+            let revoked = registry
+                .auth_repository()
+                .delete_all_tokens(user.id)
+                .await?;
+            print(
+                cli.output,
+                &RevokedSessionsResponse {
+                    user_id: user.id,
+                    revoked,
+                },
+                |r| println!("Revoked {} session(s) of {}", r.revoked, user.email),
+            )
+        }
+        Command::Migrate => migrate(&pool).await,
+        Command::Seed { password } => seed::run(&registry, cli.output, password).await,
+    }
+}
+
+async fn run_user(
+    registry: &AppRegistryImpl,
+    output: OutputFormat,
+    cmd: UserCommand,
+) -> Result<()> {
+    let repo = registry.user_repository();
+    match cmd {
+        UserCommand::Create {
+            name,
+            email,
+            password,
+            admin,
+        } => {
+            let mut user = repo
+                .create(CreateUser {
+                    name,
+                    email,
+                    password,
+                })
+                .await?;
+            if admin {
+                repo.update_role(UpdateUserRole {
+                    user_id: user.id,
+                    role: Role::Admin,
+                })
+                .await?;
+                user.role = Role::Admin;
+            }
+            print_user(output, user)
+        }
+        UserCommand::List => {
+            let items = repo
+                .find_all()
+                .await?
+                .into_iter()
+                .map(UserResponse::from)
+                .collect();
+            print(output, &UsersResponse { items }, |users| {
+                for u in &users.items {
+                    println!("{}", format_user(u));
+                }
+            })
+        }
+        UserCommand::ResetPassword { user, password } => {
+            let user = find_user(registry, &user).await?;
+            repo.reset_password(ResetUserPassword {
+                user_id: user.id,
+                new_password: password,
+            })
+            .await?;
+            // 乗っ取られたアカウントを締め出せるよう、発行済みのアクセストークンも失効させる
+            let revoked_sessions = registry
+                .auth_repository()
+                .delete_all_tokens(user.id)
+                .await?;
+            let res = PasswordResetResponse {
+                user: user.into(),
+                revoked_sessions,
+            };
+            print(output, &res, |r| {
+                println!("{}", format_user(&r.user));
+                println!("Revoked {} session(s)", r.revoked_sessions);
+            })
+        }
+        UserCommand::SetRole { user, role } => {
+            let mut user = find_user(registry, &user).await?;
+            repo.update_role(UpdateUserRole {
+                user_id: user.id,
+                role: role.into(),
+            })
+            .await?;
+            user.role = role.into();
+            print_user(output, user)
+        }
+    }
+}
+
+async fn run_checkout(
+    registry: &AppRegistryImpl,
+    output: OutputFormat,
+    cmd: CheckoutCommand,
+) -> Result<()> {
+    let repo = registry.checkout_repository();
+    match cmd {
+        CheckoutCommand::List { user } => {
+            let checkouts = match user {
+                Some(user) => {
+                    let user = find_user(registry, &user).await?;
+                    repo.find_unreturned_by_user_id(user.id).await?
+                }
+                None => repo.find_unreturned_all().await?,
+            };
+            print(output, &CheckoutsResponse::from(checkouts), |res| {
+                for c in &res.items {
+                    println!("{}", format_checkout(c));
+                }
+            })
+        }
+        CheckoutCommand::ForceReturn {
+            checkout_id,
+            operator,
+            reason,
+        } => {
+            let operator = find_user(registry, &operator).await?;
+            if operator.role != Role::Admin {
+                bail!("User {} is not an admin", operator.email);
+            }
+            let reason = reason.trim().to_string();
+            // API の ForceReturnRequest と同じ制約
+            if reason.is_empty() || reason.chars().count() > 1000 {
+                bail!("--reason must be between 1 and 1000 characters");
+            }
+            let checkout = repo
+                .find_unreturned_all()
+                .await?
+                .into_iter()
+                .find(|c| c.id == checkout_id)
+                .with_context(|| {
+                    format!("Checkout {checkout_id} is not found or already returned")
+                })?;
+            let returned_at = chrono::Utc::now();
+            // API から管理者が代わりに返却した場合と同じく、操作した管理者と理由を記録する
+            repo.force_returned(ForceReturned::new(
+                checkout.id,
+                checkout.book.book_id,
+                operator.id,
+                reason.clone(),
+                returned_at,
+            ))
+            .await?;
+            let mut returned = CheckoutResponse::from(checkout);
+            returned.returned_at = Some(returned_at);
+            returned.returned_on_behalf_by = Some(operator.id);
+            returned.return_reason = Some(reason);
+            print(output, &returned, |c| {
+                println!("Returned {}", format_checkout(c))
+            })
+        }
+    }
+}
+
+async fn migrate(pool: &ConnectionPool) -> Result<()> {
+    run_migrations(pool).await?;
+    eprintln!("Applied pending migrations");
+    Ok(())
+}
+
+// ユーザー ID とメールアドレスのどちらでも指定できるようにする
+async fn find_user(registry: &AppRegistryImpl, user: &str) -> Result<User> {
+    let repo = registry.user_repository();
+    if let Ok(user_id) = UserId::from_str(user) {
+        return repo
+            .find_current_user(user_id)
+            .await?
+            .with_context(|| format!("User {user} is not found"));
+    }
+    match repo.find_all().await?.into_iter().find(|u| u.email == user) {
+        Some(u) => Ok(u),
+        None => bail!("User {user} is not found"),
+    }
+}
+
+fn print_user(output: OutputFormat, user: User) -> Result<()> {
+    print(output, &UserResponse::from(user), |u| {
+        println!("{}", format_user(u))
+    })
+}
+
+fn print<T: Serialize>(output: OutputFormat, value: &T, human: impl FnOnce(&T)) -> Result<()> {
+    match output {
+        OutputFormat::Human => human(value),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+fn format_user(u: &UserResponse) -> String {
+    let role = serde_json::to_value(&u.role)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    format!("{}\t{}\t{}\t{}", u.id, role, u.email, u.name)
+}
+
+fn format_checkout(c: &CheckoutResponse) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}",
+        c.id,
+        c.checked_out_by,
+        c.checked_out_at.format("%Y-%m-%d %H:%M"),
+        c.book.id,
+        c.book.title
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parse_user_create() {
+        let cli = Cli::try_parse_from([
+            "bookctl",
+            "user",
+            "create",
+            "--name",
+            "Alice",
+            "--email",
+            "alice@example.com",
+            "--password",
+            "secret",
+            "--admin",
+            "--output",
+            "json",
+        ])
+        .unwrap();
+        assert!(matches!(cli.output, OutputFormat::Json));
+        assert!(matches!(
+            cli.command,
+            Command::User(UserCommand::Create { name, email, password, admin: true })
+                if name == "Alice" && email == "alice@example.com" && password == "secret"
+        ));
+    }
+
+    #[test]
+    fn seed_requires_password() {
+        // 環境変数 BOOKCTL_PASSWORD が設定されていると省略できてしまうため、未設定の場合のみ確認する
+        if std::env::var_os("BOOKCTL_PASSWORD").is_none() {
+            assert!(Cli::try_parse_from(["bookctl", "seed"]).is_err());
+        }
+        let cli = Cli::try_parse_from(["bookctl", "seed", "--password", "secret"]).unwrap();
+        assert!(matches!(cli.command, Command::Seed { password } if password == "secret"));
+    }
+
+    #[test]
+    fn force_return_requires_operator_and_reason() {
+        let checkout_id = CheckoutId::new();
+        let args = [
+            "bookctl",
+            "checkout",
+            "force-return",
+            &checkout_id.to_string(),
+        ];
+        assert!(Cli::try_parse_from(args).is_err());
+        assert!(Cli::try_parse_from(
+            args.iter()
+                .copied()
+                .chain(["--operator", "admin@example.com"])
+        )
+        .is_err());
+
+        let cli = Cli::try_parse_from(args.iter().copied().chain([
+            "--operator",
+            "admin@example.com",
+            "--reason",
+            "退職者の蔵書を回収した",
+        ]))
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Checkout(CheckoutCommand::ForceReturn { checkout_id: id, operator, reason })
+                if id == checkout_id && operator == "admin@example.com" && reason == "退職者の蔵書を回収した"
+        ));
+    }
+
+    #[test]
+    fn format_user_line() {
+        let user = UserResponse {
+            id: UserId::new(),
+            name: "Alice".into(),
+            email: "alice@example.com".into(),
+            role: Role::Admin.into(),
+        };
+        assert_eq!(
+            format_user(&user),
+            format!("{}\tAdmin\talice@example.com\tAlice", user.id)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use api::model::user::UserResponse;
+use kernel::model::{
+    author::AuthorName,
+    book::{event::CreateBook, BookListOptions},
+    role::Role,
+    user::event::{CreateUser, UpdateUserRole},
+};
+use registry::{AppRegistryExt, AppRegistryImpl};
+use serde::Serialize;
+
+use crate::{format_user, print, OutputFormat};
+
+const DEMO_USERS: [(&str, &str, Role); 3] = [
+    ("Demo Admin", "demo-admin@example.com", Role::Admin),
+    ("Alice Demo", "alice@example.com", Role::User),
+    ("Bob Demo", "bob@example.com", Role::User),
+];
+
+const DEMO_BOOKS: [(&str, &str, &str, &str); 3] = [
+    (
+        "実践Rustプログラミング入門",
+        "初田直也他",
+        "978-4798061702",
+        "C/C++の代わりとなるべき最新言語その独特な仕様をわかりやすく解説。",
+    ),
+    (
+        "ゼロから学ぶRust",
+        "高野祐輝",
+        "978-4065301951",
+        "システムプログラミングの基礎から線形型システムまで。",
+    ),
+    (
+        "RustによるWebアプリケーション開発",
+        "豊田優貴他",
+        "978-4065369579",
+        "設計からリリース・運用まで。",
+    ),
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SeedResponse {
+    created_users: Vec<UserResponse>,
+    created_books: usize,
+}
+
+// 開発環境向けのデモデータ投入。管理者アカウントも作成されるため、
+// パスワードは固定値にせず実行時に指定させる。
+// 何度実行しても重複して投入しないよう、既存のデータがあればスキップする
+pub async fn run(registry: &AppRegistryImpl, output: OutputFormat, password: String) -> Result<()> {
+    let user_repo = registry.user_repository();
+    let existing = user_repo.find_all().await?;
+
+    let mut created_users = Vec::new();
+    for (name, email, role) in DEMO_USERS {
+        if existing.iter().any(|u| u.email == email) {
+            continue;
+        }
+        let mut user = user_repo
+            .create(CreateUser {
+                name: name.into(),
+                email: email.into(),
+                password: password.clone(),
+            })
+            .await?;
+        if role == Role::Admin {
+            user_repo
+                .update_role(UpdateUserRole {
+                    user_id: user.id,
+                    role: Role::Admin,
+                })
+                .await?;
+        }
+        user.role = role;
+        created_users.push(user);
+    }
+
+    let book_repo = registry.book_repository();
+    let has_books = book_repo
+        .find_all(BookListOptions {
+            limit: 1,
+            offset: 0,
+            filter: Default::default(),
+            sort: Default::default(),
+        })
+        .await?
+        .total
+        > 0;
+    let owner = user_repo
+        .find_all()
+        .await?
+        .into_iter()
+        .find(|u| u.email == DEMO_USERS[0].1);
+    let mut created_books = 0;
+    if let (false, Some(owner)) = (has_books, owner) {
+        for (title, author, isbn, description) in DEMO_BOOKS {
+            book_repo
+                .create(
+                    CreateBook {
+                        title: title.into(),
+                        authors: AuthorName::parse_list(author),
+                        isbn: isbn.into(),
+                        description: description.into(),
+                        cover_url: None,
+                        bibliography: Default::default(),
+                    },
+                    owner.id,
+                )
+                .await?;
+            created_books += 1;
+        }
+    }
+
+    let res = SeedResponse {
+        created_users: created_users.into_iter().map(UserResponse::from).collect(),
+        created_books,
+    };
+    print(output, &res, |res| {
+        for u in &res.created_users {
+            println!("Created user {}", format_user(u));
+        }
+        println!("Created {} book(s)", res.created_books);
+        if !res.created_users.is_empty() {
+            println!("Demo users can log in with the given password");
+        }
+    })
+}